@@ -0,0 +1,117 @@
+// Birdeye API Integration - optional premium source for new tokens and prices
+use crate::settings::BotSettings;
+
+use serde::Deserialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use anyhow::Result;
+use reqwest::StatusCode;
+
+const BIRDEYE_API_URL: &str = "https://public-api.birdeye.so";
+// Most listings the new_listing endpoint returns per request
+const MAX_NEW_LISTINGS: u32 = 20;
+
+#[derive(Debug, Deserialize)]
+struct BirdeyeResponse<T> {
+    success: bool,
+    data: Option<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BirdeyeNewListingData {
+    items: Vec<BirdeyeNewToken>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BirdeyeNewToken {
+    pub address: String,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub liquidity: Option<f64>, // USD
+    #[serde(rename = "liquidityAddedAt")]
+    pub liquidity_added_at: Option<String>, // RFC 3339
+}
+
+#[derive(Debug, Deserialize)]
+struct BirdeyePriceData {
+    value: f64,
+}
+
+pub struct BirdeyeClient {
+    client: reqwest::Client,
+    api_key: String,
+    disabled: AtomicBool, // Set after an auth failure so a bad key isn't retried every scan
+}
+
+impl BirdeyeClient {
+    pub fn new(api_key: &str) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .unwrap_or_default(),
+            api_key: api_key.to_string(),
+            disabled: AtomicBool::new(false),
+        }
+    }
+
+    /// Client for the configured key, or None when Birdeye is off or has no key
+    pub fn from_settings(settings: &BotSettings) -> Option<Self> {
+        let premium = &settings.apis.premium_endpoints;
+        if premium.birdeye_enabled && !premium.birdeye_api_key.is_empty() {
+            Some(Self::new(&premium.birdeye_api_key))
+        } else {
+            None
+        }
+    }
+
+    /// Newest Solana token listings, newest first (at most one page of MAX_NEW_LISTINGS)
+    pub async fn new_listings(&self, limit: u32) -> Result<Vec<BirdeyeNewToken>> {
+        let url = format!("{}/defi/v2/tokens/new_listing?limit={}&meme_platform_enabled=true",
+            BIRDEYE_API_URL, limit.clamp(1, MAX_NEW_LISTINGS));
+        let data: BirdeyeNewListingData = self.get(&url).await?;
+        Ok(data.items)
+    }
+
+    /// Current USD price for a mint
+    pub async fn token_price(&self, token_address: &str) -> Result<f64> {
+        let url = format!("{}/defi/price?address={}", BIRDEYE_API_URL, token_address);
+        let data: BirdeyePriceData = self.get(&url).await?;
+        Ok(data.value)
+    }
+
+    async fn get<T: for<'de> Deserialize<'de>>(&self, url: &str) -> Result<T> {
+        if self.disabled.load(Ordering::Relaxed) {
+            return Err(anyhow::anyhow!("Birdeye disabled after an authentication failure"));
+        }
+
+        let response = self.client
+            .get(url)
+            .header("X-API-KEY", &self.api_key)
+            .header("x-chain", "solana")
+            .header("Accept", "application/json")
+            .send()
+            .await?;
+
+        match response.status() {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                self.disabled.store(true, Ordering::Relaxed);
+                println!("🔑 Birdeye API key rejected - disabling Birdeye for this session");
+                return Err(anyhow::anyhow!("Birdeye API key invalid ({})", response.status()));
+            }
+            StatusCode::TOO_MANY_REQUESTS => {
+                return Err(anyhow::anyhow!("Birdeye quota exceeded"));
+            }
+            status if !status.is_success() => {
+                return Err(anyhow::anyhow!("Birdeye API returned status: {}", status));
+            }
+            _ => {}
+        }
+
+        let body: BirdeyeResponse<T> = response.json().await?;
+        match body.data {
+            Some(data) if body.success => Ok(data),
+            _ => Err(anyhow::anyhow!("Birdeye API returned no data")),
+        }
+    }
+}
@@ -0,0 +1,180 @@
+// Bot Context - one startup path for every long-running command
+use crate::settings::BotSettings;
+use crate::wallet::SolanaWallet;
+use crate::telegram::{MessageTemplates, TelegramNotifier};
+use crate::jupiter_trader::JupiterTrader;
+use crate::pool_scanner::PoolScanner;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::{Result, anyhow};
+
+/// Which long-running command the context was built for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunMode {
+    Monitoring, // `start`
+    Scanning,   // `scan`
+}
+
+/// Create any missing directories, returning the ones that didn't exist yet
+pub fn ensure_directories(directories: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut created = Vec::new();
+    for directory in directories {
+        if directory.is_dir() {
+            continue;
+        }
+        std::fs::create_dir_all(directory)
+            .map_err(|e| anyhow!("Cannot create directory {}: {} - create it or fix its permissions, or point the path setting elsewhere", directory.display(), e))?;
+        created.push(directory.clone());
+    }
+    Ok(created)
+}
+
+/// Everything a run needs, constructed and checked once by `init`
+pub struct BotContext {
+    pub settings: BotSettings,
+    pub telegram: TelegramNotifier,
+    pub jupiter_trader: Arc<JupiterTrader>, // Shared with the scanner
+    pub scanner: PoolScanner,
+}
+
+impl BotContext {
+    /// Build the wallet, Telegram notifier, trader and scanner, then run the startup checks.
+    /// The balance check doubles as the RPC connectivity check; an underfunded wallet is an
+    /// error unless `dry_run` (never spends) or `allow_empty` is set.
+    pub async fn init(settings: &BotSettings, dry_run: bool, allow_empty: bool) -> Result<Self> {
+        if dry_run {
+            println!("🧪 DRY RUN enabled - no transactions will be sent");
+        }
+
+        // Fresh installs have no ./data or ./logs yet - create them before anything writes
+        if settings.monitoring.create_data_dirs {
+            for directory in ensure_directories(&settings.data_directories())? {
+                println!("📁 Created {}", directory.display());
+            }
+        }
+
+        let wallet = SolanaWallet::from_env()
+            .map_err(|e| anyhow!("Wallet initialization failed: {}", e))?;
+        let telegram = TelegramNotifier::new(&settings.telegram.bot_token, &settings.telegram.chat_id)
+            .with_error_dedup_window(Duration::from_secs(settings.telegram.error_alert_dedup_window_secs))
+            .with_templates(MessageTemplates::from_settings(&settings.telegram))
+            .with_explorer_links(settings.telegram.explorer_links.clone());
+
+        // Fail fast if the wallet can't fund even one trade
+        let required_sol = settings.wallet.min_balance_sol + settings.trading.position_size_sol;
+        match wallet.get_sol_balance().await {
+            Ok(balance) if balance < required_sol && !dry_run && !allow_empty => {
+                println!("❌ Wallet {} has {:.4} SOL - needs at least {:.4} SOL ({:.4} reserve + {:.4} position)",
+                    wallet.get_address(), balance, required_sol, settings.wallet.min_balance_sol, settings.trading.position_size_sol);
+                println!("💡 Fund the wallet, or use --allow-empty / --dry-run for an observe-only run.");
+
+                if settings.telegram.notifications_enabled {
+                    let msg = format!("❌ BOT NOT STARTED - wallet underfunded\n💰 Balance: {:.4} SOL\n📏 Required: {:.4} SOL\n👛 Wallet: `{}`",
+                        balance, required_sol, wallet.get_address());
+                    if let Err(e) = telegram.send_message(&msg).await {
+                        println!("⚠️  Failed to send Telegram alert: {}", e);
+                    }
+                }
+                return Err(anyhow!("Wallet underfunded: {:.4} SOL, need {:.4} SOL", balance, required_sol));
+            }
+            Ok(balance) => println!("💰 Wallet balance: {:.4} SOL", balance),
+            Err(e) => println!("⚠️  Could not check wallet balance, continuing: {}", e),
+        }
+
+        println!("📊 Settings automatically loaded from global configuration");
+        settings.display_summary();
+
+        let scanner = PoolScanner::new(settings.clone(), telegram.clone(), wallet, dry_run)?;
+        Ok(Self {
+            settings: settings.clone(),
+            telegram,
+            jupiter_trader: scanner.jupiter_trader(),
+            scanner,
+        })
+    }
+
+    /// Run the command until shutdown, alerting if the scan loop dies
+    pub async fn run_mode(mut self, mode: RunMode) -> Result<()> {
+        match mode {
+            RunMode::Monitoring => println!("🔄 Starting continuous monitoring..."),
+            RunMode::Scanning => println!("🔍 Starting pool scanning..."),
+        }
+        let result = self.scanner.start_continuous_scan().await;
+
+        if let Err(e) = &result {
+            if self.settings.telegram.notifications_enabled && self.settings.telegram.send_error_alerts {
+                if let Err(alert_err) = self.telegram.send_error_alert("scanner", &e.to_string()).await {
+                    println!("⚠️  Telegram error alert failed: {}", alert_err);
+                }
+            }
+        }
+        if self.settings.wallet.persistent_wsol {
+            match self.jupiter_trader.close_wsol_account().await {
+                Ok(unwrapped) => println!("🔄 WSOL account closed - unwrapped {}", unwrapped),
+                Err(e) => println!("⚠️  Could not unwrap WSOL account (it stays funded): {}", e),
+            }
+        }
+        match self.jupiter_trader.get_sol_balance().await {
+            Ok(balance) => println!("💰 Wallet balance at shutdown: {:.4} SOL", balance),
+            Err(e) => println!("⚠️  Could not read final wallet balance: {}", e),
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Settings whose data files, logs and analysis all live under `root`
+    fn settings_under(root: &std::path::Path) -> BotSettings {
+        let path = |relative: &str| root.join(relative).to_string_lossy().into_owned();
+        let mut settings = BotSettings::from_env().unwrap();
+        let monitoring = &mut settings.monitoring;
+        monitoring.health_snapshot_path = path("data/health.json");
+        monitoring.trade_journal_path = path("data/journal/trades.json");
+        monitoring.daily_trades_path = path("data/daily.json");
+        monitoring.compound_state_path = path("data/compound.json");
+        monitoring.portfolio_history_path = path("data/history/portfolio.json");
+        monitoring.storage_backend = "json".to_string();
+        monitoring.storage_path = path("data/positions");
+        monitoring.log_to_file = true;
+        monitoring.log_file_path = path("logs/bot.log");
+        monitoring.save_analysis_results = true;
+        monitoring.analysis_results_path = path("data/analysis");
+        monitoring.decision_trace_enabled = false;
+        settings
+    }
+
+    #[test]
+    fn init_creates_the_configured_directories() {
+        let root = std::env::temp_dir().join(format!("bot_context_dirs_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let directories = settings_under(&root).data_directories();
+        let expected: Vec<PathBuf> = ["data", "data/journal", "data/history", "data/positions", "logs", "data/analysis"]
+            .iter()
+            .map(|relative| root.join(relative))
+            .collect();
+        for directory in &expected {
+            assert!(directories.contains(directory), "{} not listed", directory.display());
+        }
+
+        let created = ensure_directories(&directories).unwrap();
+        assert_eq!(created, directories); // None existed yet
+        assert!(expected.iter().all(|directory| directory.is_dir()));
+        // A second start has nothing left to create
+        assert!(ensure_directories(&directories).unwrap().is_empty());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn uncreatable_directory_fails_with_the_path() {
+        let file = std::env::temp_dir().join(format!("bot_context_blocker_{}", std::process::id()));
+        std::fs::write(&file, "not a directory").unwrap();
+        let err = ensure_directories(&[file.join("logs")]).unwrap_err();
+        assert!(err.to_string().starts_with(&format!("Cannot create directory {}", file.join("logs").display())));
+        std::fs::remove_file(&file).unwrap();
+    }
+}
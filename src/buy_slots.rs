@@ -0,0 +1,96 @@
+// Buy Slots - caps how many buys can be in flight at once (MAX_CONCURRENT_BUYS), so buys sent
+// faster than they confirm can't over-commit capital before active_positions catches up.
+// A slot is taken before the buy is built and given back when it resolves, landed or not.
+use std::sync::{Arc, Mutex};
+
+/// Shared in-flight buy counter
+#[derive(Debug, Clone)]
+pub struct BuySlots {
+    in_flight: Arc<Mutex<usize>>,
+    max: usize,
+}
+
+impl BuySlots {
+    pub fn new(max: usize) -> Self {
+        Self { in_flight: Arc::new(Mutex::new(0)), max }
+    }
+
+    /// Take a slot for a buy; None when `max` buys are already in flight
+    pub fn try_acquire(&self) -> Option<BuySlot> {
+        let mut in_flight = self.in_flight.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if *in_flight >= self.max {
+            return None;
+        }
+        *in_flight += 1;
+        Some(BuySlot { slots: self.clone() })
+    }
+
+    pub fn in_flight(&self) -> usize {
+        *self.in_flight.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn max(&self) -> usize {
+        self.max
+    }
+}
+
+/// A buy in flight; hold it until the buy has landed or failed
+pub struct BuySlot {
+    slots: BuySlots,
+}
+
+impl Drop for BuySlot {
+    fn drop(&mut self) {
+        let mut in_flight = self.slots.in_flight.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        *in_flight = in_flight.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn slots_run_out_at_the_cap_and_come_back_on_drop() {
+        let slots = BuySlots::new(2);
+        let first = slots.try_acquire().unwrap();
+        let _second = slots.try_acquire().unwrap();
+        assert!(slots.try_acquire().is_none());
+        assert_eq!(slots.in_flight(), 2);
+
+        drop(first);
+        assert_eq!(slots.in_flight(), 1);
+        assert!(slots.try_acquire().is_some());
+    }
+
+    #[tokio::test]
+    async fn simultaneous_buys_never_exceed_the_cap() {
+        let slots = BuySlots::new(3);
+        let peak = Arc::new(Mutex::new(0));
+
+        // Twenty qualifying pools arrive at once; each buy holds its slot while it "confirms"
+        let buys: Vec<_> = (0..20).map(|_| {
+            let (slots, peak) = (slots.clone(), peak.clone());
+            tokio::spawn(async move {
+                let Some(_slot) = slots.try_acquire() else {
+                    return false;
+                };
+                {
+                    let mut peak = peak.lock().unwrap();
+                    *peak = (*peak).max(slots.in_flight());
+                }
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                true
+            })
+        }).collect();
+
+        let mut sent = 0;
+        for buy in buys {
+            sent += buy.await.unwrap() as usize;
+        }
+        assert!(*peak.lock().unwrap() <= 3);
+        assert!((1..20).contains(&sent)); // Some went through, the overflow was turned away
+        assert_eq!(slots.in_flight(), 0);
+    }
+}
@@ -0,0 +1,71 @@
+// Cancel signal - one flag shared by everything that must stop when the bot shuts down. Buys
+// check it right before sending; the scan loop waits on it instead of on Ctrl-C directly.
+use std::sync::Arc;
+use tokio::sync::watch;
+
+#[derive(Clone)]
+pub struct CancelSignal(Arc<watch::Sender<bool>>);
+
+impl CancelSignal {
+    pub fn new() -> Self {
+        Self(Arc::new(watch::Sender::new(false)))
+    }
+
+    /// Raise the signal; every clone sees it from now on
+    pub fn cancel(&self) {
+        self.0.send_replace(true);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    /// Resolves once the signal is raised (immediately if it already is)
+    pub async fn cancelled(&self) {
+        let mut receiver = self.0.subscribe();
+        let _ = receiver.wait_for(|cancelled| *cancelled).await;
+    }
+}
+
+impl Default for CancelSignal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn starts_lowered() {
+        assert!(!CancelSignal::new().is_cancelled());
+        assert!(!CancelSignal::default().is_cancelled());
+    }
+
+    #[test]
+    fn cancel_is_seen_by_every_clone() {
+        let signal = CancelSignal::new();
+        let clone = signal.clone();
+        clone.cancel();
+        assert!(signal.is_cancelled());
+        assert!(clone.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn cancelled_resolves_once_raised() {
+        let signal = CancelSignal::new();
+        let waiter = tokio::spawn({
+            let signal = signal.clone();
+            async move { signal.cancelled().await }
+        });
+        signal.cancel();
+        tokio::time::timeout(Duration::from_secs(5), waiter).await
+            .expect("cancelled() never resolved").unwrap();
+
+        // Already raised - resolves straight away
+        tokio::time::timeout(Duration::from_secs(5), signal.cancelled()).await
+            .expect("cancelled() never resolved");
+    }
+}
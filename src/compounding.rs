@@ -0,0 +1,201 @@
+// Auto-Compounding - the buy size steps up after a run of profitable closes and steps back down
+// once realized P&L falls below where a step was earned, persisted so a restart keeps the size
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use anyhow::Result;
+use crate::settings::BotSettings;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompoundPolicy {
+    pub base_size_sol: f64, // POSITION_SIZE_SOL - the size never steps below it
+    pub max_size_sol: f64,  // MAX_POSITION_SIZE_SOL
+    pub step_percent: f64,  // Growth per step
+    pub wins_per_step: u32, // Profitable closes that earn a step
+}
+
+impl CompoundPolicy {
+    pub fn from_settings(settings: &BotSettings) -> Self {
+        Self {
+            base_size_sol: settings.trading.position_size_sol,
+            max_size_sol: settings.trading.max_position_size_sol,
+            step_percent: settings.trading.compound_step_percent,
+            wins_per_step: settings.trading.compound_wins_per_step,
+        }
+    }
+
+    /// Buy size after `steps` step-ups, capped at the maximum
+    pub fn size_sol(&self, steps: usize) -> f64 {
+        let grown = self.base_size_sol * (1.0 + self.step_percent / 100.0).powi(steps as i32);
+        grown.min(self.max_size_sol.max(self.base_size_sol))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompoundState {
+    pub step_floors_pnl_sol: Vec<f64>, // Realized P&L each step's run began at - falling below the last undoes it
+    pub run_start_pnl_sol: f64,        // Realized P&L the current run began at
+    pub wins: u32,                     // Profitable closes in the current run
+    #[serde(default)]
+    pub realized_pnl_sol: f64,         // Every close's P&L since compounding began - the floors' own ledger, kept across restarts
+    #[serde(default)]
+    pub position_size_sol: f64,        // Size when saved, for reading the file; recomputed from the steps on load
+}
+
+impl CompoundState {
+    pub fn steps(&self) -> usize {
+        self.step_floors_pnl_sol.len()
+    }
+
+    /// The state after a close that realized `pnl_sol`. Steps are undone first, then a run of
+    /// wins that grew realized P&L earns a new one (while below the cap).
+    pub fn after_close(&self, pnl_sol: f64, policy: &CompoundPolicy) -> Self {
+        let mut next = self.clone();
+        next.realized_pnl_sol += pnl_sol;
+        let realized_pnl_sol = next.realized_pnl_sol;
+        if pnl_sol > 0.0 {
+            next.wins += 1;
+        }
+        while next.step_floors_pnl_sol.last().is_some_and(|&floor| realized_pnl_sol < floor) {
+            next.step_floors_pnl_sol.pop();
+            next.run_start_pnl_sol = realized_pnl_sol;
+            next.wins = 0;
+        }
+        let below_cap = policy.size_sol(next.steps()) < policy.max_size_sol;
+        if next.wins >= policy.wins_per_step.max(1) && realized_pnl_sol > next.run_start_pnl_sol && below_cap {
+            next.step_floors_pnl_sol.push(next.run_start_pnl_sol);
+            next.run_start_pnl_sol = realized_pnl_sol;
+            next.wins = 0;
+        }
+        next.position_size_sol = policy.size_sol(next.steps());
+        next
+    }
+}
+
+/// The persisted compounding state; every change is written through
+#[derive(Debug, Clone)]
+pub struct PositionSizer {
+    file_path: String,
+    policy: CompoundPolicy,
+    state: CompoundState,
+}
+
+impl PositionSizer {
+    /// Load the saved state (a missing or unreadable file starts at the base size)
+    pub fn load(file_path: &str, policy: CompoundPolicy) -> Self {
+        let state = std::fs::read_to_string(file_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { file_path: file_path.to_string(), policy, state }
+    }
+
+    pub fn position_size_sol(&self) -> f64 {
+        self.policy.size_sol(self.state.steps())
+    }
+
+    /// Apply closes (oldest first) and persist the result. The floors are measured against the
+    /// state's own P&L ledger, not the position book's, which may restart at zero.
+    pub fn record_closes(&mut self, closes_pnl_sol: &[f64]) -> Result<()> {
+        if closes_pnl_sol.is_empty() {
+            return Ok(());
+        }
+        for &pnl_sol in closes_pnl_sol {
+            self.state = self.state.after_close(pnl_sol, &self.policy);
+        }
+        if let Some(parent) = Path::new(&self.file_path).parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.file_path, serde_json::to_string_pretty(&self.state)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> CompoundPolicy {
+        CompoundPolicy { base_size_sol: 0.1, max_size_sol: 0.2, step_percent: 25.0, wins_per_step: 2 }
+    }
+
+    /// Apply `closes` one by one from a fresh state, returning the size after each
+    fn sizes_after(closes: &[f64]) -> Vec<f64> {
+        let mut state = CompoundState::default();
+        closes.iter()
+            .map(|&pnl_sol| {
+                state = state.after_close(pnl_sol, &policy());
+                state.position_size_sol
+            })
+            .collect()
+    }
+
+    fn assert_sizes(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (actual, expected) in actual.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn size_ratchets_up_after_runs_of_wins_until_the_cap() {
+        // Every second win steps up 25%: 0.1 -> 0.125 -> 0.15625 -> 0.1953125 -> capped at 0.2
+        let sizes = sizes_after(&[0.05; 8]);
+        assert_sizes(&sizes, &[0.1, 0.125, 0.125, 0.15625, 0.15625, 0.1953125, 0.1953125, 0.2]);
+        // At the cap further wins change nothing
+        assert_sizes(&sizes_after(&[0.05; 12])[8..], &[0.2; 4]);
+    }
+
+    #[test]
+    fn a_loss_between_wins_does_not_break_the_run() {
+        assert_sizes(&sizes_after(&[0.05, -0.01, 0.05]), &[0.1, 0.1, 0.125]);
+    }
+
+    #[test]
+    fn size_ratchets_down_when_losses_give_back_a_step() {
+        // Two wins earn a step from a 0.0 floor; losing it all back undoes the step
+        assert_sizes(&sizes_after(&[0.05, 0.05, -0.04, -0.07]), &[0.1, 0.125, 0.125, 0.1]);
+        // Two steps, then one big loss below both floors
+        assert_sizes(&sizes_after(&[0.05, 0.05, 0.05, 0.05, -0.25]), &[0.1, 0.125, 0.125, 0.15625, 0.1]);
+        // Never below the base size
+        assert_sizes(&sizes_after(&[-0.05, -0.05, -0.05]), &[0.1, 0.1, 0.1]);
+    }
+
+    #[test]
+    fn size_survives_a_restart() {
+        let file_path = std::env::temp_dir().join(format!("compound_state_{}.json", std::process::id()));
+        let file_path = file_path.to_str().unwrap();
+        let _ = std::fs::remove_file(file_path);
+
+        let mut sizer = PositionSizer::load(file_path, policy());
+        assert_eq!(sizer.position_size_sol(), 0.1);
+        sizer.record_closes(&[0.05, 0.05]).unwrap();
+        assert!((sizer.position_size_sol() - 0.125).abs() < 1e-9);
+
+        let reloaded = PositionSizer::load(file_path, policy());
+        assert!((reloaded.position_size_sol() - 0.125).abs() < 1e-9);
+        assert_eq!(reloaded.state, sizer.state);
+        std::fs::remove_file(file_path).unwrap();
+    }
+
+    #[test]
+    fn steps_survive_a_restart_that_resets_the_book() {
+        let file_path = std::env::temp_dir().join(format!("compound_state_reset_{}.json", std::process::id()));
+        let file_path = file_path.to_str().unwrap();
+        let _ = std::fs::remove_file(file_path);
+
+        // Two runs of wins earn two steps over 0.2 SOL of realized profit
+        let mut sizer = PositionSizer::load(file_path, policy());
+        sizer.record_closes(&[0.05; 4]).unwrap();
+        assert!((sizer.position_size_sol() - 0.15625).abs() < 1e-9);
+
+        // After a restart the book's realized P&L starts from zero again: a small loss only gives
+        // back what it lost, not every step earned before the restart
+        let mut reloaded = PositionSizer::load(file_path, policy());
+        reloaded.record_closes(&[-0.01]).unwrap();
+        assert!((reloaded.position_size_sol() - 0.15625).abs() < 1e-9);
+        // Losing back below the last step's floor still undoes it
+        reloaded.record_closes(&[-0.1]).unwrap();
+        assert!((reloaded.position_size_sol() - 0.125).abs() < 1e-9);
+        std::fs::remove_file(file_path).unwrap();
+    }
+}
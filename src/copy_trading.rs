@@ -0,0 +1,381 @@
+// Copy trading: follow known wallets and turn their token buys into detected pools
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_client::{rpc_client::RpcClient, rpc_request::RpcRequest};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use crate::rpc_limiter::RpcRateLimiter;
+use crate::wsol::WRAPPED_SOL_MINT;
+use crate::units::lamports_to_sol;
+
+/// Detection source prefix for copied buys: "copy:<wallet>"
+pub const COPY_SOURCE_PREFIX: &str = "copy:";
+
+// Signatures fetched per getSignaturesForAddress page
+const SIGNATURES_PER_POLL: usize = 25;
+// Pages read back per wallet per poll; a burst beyond this only copies the newest buys
+const MAX_POLL_PAGES: usize = 8;
+// Longest wait before retrying a wallet whose RPC calls keep failing
+const MAX_FOLLOW_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Programs whose swaps count as buys: Jupiter v6, Raydium AMM v4 / CPMM / CLMM, Orca Whirlpool, pump.fun
+const SWAP_PROGRAMS: &[&str] = &[
+    "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4",
+    "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8",
+    "CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C",
+    "CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK",
+    "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc",
+    "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P",
+];
+
+/// A wallet to copy and how its buys are sized relative to POSITION_SIZE_SOL
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CopyTarget {
+    pub wallet: String,
+    pub size_multiplier: f64,
+}
+
+/// Parse COPY_TRADE_WALLETS: comma-separated `wallet` or `wallet:multiplier` (default 1.0).
+/// An unparseable multiplier becomes NaN so validation reports it instead of silently using 1.0.
+pub fn parse_copy_targets(raw: &str) -> Vec<CopyTarget> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| match entry.split_once(':') {
+            Some((wallet, multiplier)) => CopyTarget {
+                wallet: wallet.trim().to_string(),
+                size_multiplier: multiplier.trim().parse().unwrap_or(f64::NAN),
+            },
+            None => CopyTarget { wallet: entry.to_string(), size_multiplier: 1.0 },
+        })
+        .collect()
+}
+
+/// Size multiplier for a pool's detection source: the copied wallet's, or 1.0 for every other source
+pub fn copy_size_multiplier(source: &str, targets: &[CopyTarget]) -> f64 {
+    source.strip_prefix(COPY_SOURCE_PREFIX)
+        .and_then(|wallet| targets.iter().find(|target| target.wallet == wallet))
+        .map_or(1.0, |target| target.size_multiplier)
+}
+
+/// A followed wallet's buy, decoded from a confirmed swap
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedBuy {
+    pub mint: String,
+    pub sol_spent: f64,
+    pub tokens_received: u64,
+}
+
+/// A buy to copy
+#[derive(Debug, Clone, PartialEq)]
+pub struct CopySignal {
+    pub wallet: String,
+    pub signature: String,
+    pub buy: DecodedBuy,
+}
+
+/// Account keys of a jsonParsed transaction, including v0 lookup-table addresses
+fn account_keys(transaction: &Value) -> Vec<&str> {
+    let static_keys = transaction["transaction"]["message"]["accountKeys"].as_array().into_iter().flatten()
+        .filter_map(|key| key["pubkey"].as_str().or_else(|| key.as_str()));
+    let loaded = &transaction["meta"]["loadedAddresses"];
+    let loaded_keys = ["writable", "readonly"].into_iter()
+        .flat_map(|kind| loaded[kind].as_array().into_iter().flatten())
+        .filter_map(Value::as_str);
+    static_keys.chain(loaded_keys).collect()
+}
+
+/// Raw token balance change per mint for accounts owned by `owner`
+fn token_deltas(meta: &Value, owner: &str) -> HashMap<String, i128> {
+    let mut deltas: HashMap<String, i128> = HashMap::new();
+    for (key, sign) in [("preTokenBalances", -1i128), ("postTokenBalances", 1i128)] {
+        for balance in meta[key].as_array().into_iter().flatten().filter(|balance| balance["owner"] == owner) {
+            let (Some(mint), Some(amount)) = (
+                balance["mint"].as_str(),
+                balance["uiTokenAmount"]["amount"].as_str().and_then(|amount| amount.parse::<i128>().ok()),
+            ) else {
+                continue;
+            };
+            *deltas.entry(mint.to_string()).or_default() += sign * amount;
+        }
+    }
+    deltas
+}
+
+/// Decode a wallet's token buy from a jsonParsed transaction: a successful swap through a known
+/// program in which the wallet's SOL (native or wrapped) went down and a token balance went up.
+/// Sells, token-to-token swaps and plain transfers decode to None. Fees are not counted as spend.
+pub fn decode_swap_buy(transaction: &Value, wallet: &str) -> Option<DecodedBuy> {
+    let meta = &transaction["meta"];
+    if !meta["err"].is_null() {
+        return None;
+    }
+    let keys = account_keys(transaction);
+    if !keys.iter().any(|key| SWAP_PROGRAMS.contains(key)) {
+        return None;
+    }
+
+    let wallet_index = keys.iter().position(|key| *key == wallet)?;
+    let lamport_at = |key: &str| meta[key].get(wallet_index).and_then(Value::as_i64);
+    let fee = if wallet_index == 0 { meta["fee"].as_i64().unwrap_or(0) } else { 0 };
+    let native_spent = lamport_at("preBalances")? - lamport_at("postBalances")? - fee;
+
+    let deltas = token_deltas(meta, wallet);
+    let wsol_mint = WRAPPED_SOL_MINT.to_string();
+    let wrapped_spent = -deltas.get(&wsol_mint).copied().unwrap_or(0);
+    let sol_spent_lamports = native_spent as i128 + wrapped_spent;
+    if sol_spent_lamports <= 0 {
+        return None;
+    }
+
+    let (mint, received) = deltas.iter()
+        .filter(|(mint, delta)| **mint != wsol_mint && **delta > 0)
+        .max_by_key(|(_, delta)| **delta)?;
+    Some(DecodedBuy {
+        mint: mint.clone(),
+        sol_spent: lamports_to_sol(u64::try_from(sol_spent_lamports).ok()?),
+        tokens_received: u64::try_from(*received).ok()?,
+    })
+}
+
+/// `before` cursor for the next (older) page: the oldest signature of a full page. A short page
+/// means `until` or the start of the wallet's history was reached.
+fn next_page_before(page: &[Value], page_size: usize) -> Option<String> {
+    if page.len() < page_size {
+        return None;
+    }
+    page.last().and_then(|entry| entry["signature"].as_str()).map(str::to_string)
+}
+
+/// Wait before retrying a wallet after `failures` consecutive failed polls (doubling, capped)
+pub fn follow_backoff(base: Duration, failures: u32) -> Duration {
+    base.saturating_mul(2u32.saturating_pow(failures.min(16))).min(MAX_FOLLOW_BACKOFF)
+}
+
+/// Per-wallet position in its transaction history, so a failed poll resumes where it stopped
+#[derive(Debug, Default)]
+struct FollowCursor {
+    last_signature: Option<String>, // Newest signature already handled
+    started: bool,                  // The first poll only sets the cursor - history is never copied
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+/// Polls the followed wallets' signatures and decodes their new buys
+pub struct WalletFollower {
+    rpc_client: RpcClient,
+    rpc_limiter: RpcRateLimiter,
+    targets: Vec<CopyTarget>,
+    cursors: HashMap<String, FollowCursor>,
+    retry_base: Duration,
+}
+
+impl WalletFollower {
+    pub fn new(rpc_url: &str, targets: Vec<CopyTarget>, retry_base: Duration) -> Self {
+        Self {
+            rpc_client: RpcClient::new(rpc_url.to_string()),
+            rpc_limiter: RpcRateLimiter::unlimited(),
+            targets,
+            cursors: HashMap::new(),
+            retry_base,
+        }
+    }
+
+    pub fn with_rpc_limiter(mut self, rpc_limiter: RpcRateLimiter) -> Self {
+        self.rpc_limiter = rpc_limiter;
+        self
+    }
+
+    /// New buys from every followed wallet since the last poll, oldest first per wallet.
+    /// A wallet whose RPC calls fail keeps its cursor and is retried with backoff.
+    pub async fn poll(&mut self) -> Vec<CopySignal> {
+        let mut signals = Vec::new();
+        let wallets: Vec<String> = self.targets.iter().map(|target| target.wallet.clone()).collect();
+        for wallet in wallets {
+            let cursor = self.cursors.entry(wallet.clone()).or_default();
+            if cursor.retry_at.is_some_and(|retry_at| Instant::now() < retry_at) {
+                continue;
+            }
+            match self.poll_wallet(&wallet, &mut signals).await {
+                Ok(()) => {
+                    let cursor = self.cursors.entry(wallet.clone()).or_default();
+                    if cursor.failures > 0 {
+                        println!("✅ Copy-trading: {} reachable again after {} failed polls", wallet, cursor.failures);
+                    }
+                    cursor.failures = 0;
+                    cursor.retry_at = None;
+                }
+                Err(e) => {
+                    let cursor = self.cursors.entry(wallet.clone()).or_default();
+                    cursor.failures += 1;
+                    let delay = follow_backoff(self.retry_base, cursor.failures);
+                    cursor.retry_at = Some(Instant::now() + delay);
+                    println!("⚠️  Copy-trading: polling {} failed ({} in a row), retrying in {:?}: {}", wallet, cursor.failures, delay, e);
+                }
+            }
+        }
+        signals
+    }
+
+    async fn poll_wallet(&mut self, wallet: &str, signals: &mut Vec<CopySignal>) -> Result<()> {
+        let (started, until) = {
+            let cursor = self.cursors.entry(wallet.to_string()).or_default();
+            (cursor.started, cursor.last_signature.clone())
+        };
+        // Page back to the cursor so a burst between polls isn't cut off at one page
+        let mut entries: Vec<Value> = Vec::new();
+        let mut before: Option<String> = None;
+        for page_number in 1..=MAX_POLL_PAGES {
+            let mut config = serde_json::json!({ "limit": SIGNATURES_PER_POLL, "commitment": "confirmed" });
+            if let Some(until) = &until {
+                config["until"] = Value::String(until.clone());
+            }
+            if let Some(before) = &before {
+                config["before"] = Value::String(before.clone());
+            }
+
+            self.rpc_limiter.acquire().await;
+            let page: Value = self.rpc_client.send(RpcRequest::GetSignaturesForAddress, serde_json::json!([wallet, config]))?;
+            let page = page.as_array().ok_or_else(|| anyhow!("unexpected getSignaturesForAddress response"))?;
+            before = next_page_before(page, SIGNATURES_PER_POLL).filter(|_| started);
+            entries.extend(page.iter().cloned());
+            if before.is_none() {
+                break;
+            }
+            if page_number == MAX_POLL_PAGES {
+                println!("⚠️  Copy-trading: {} made over {} transactions since the last poll - copying only the newest",
+                    wallet, entries.len());
+            }
+        }
+
+        if !started {
+            let cursor = self.cursors.entry(wallet.to_string()).or_default();
+            cursor.started = true;
+            cursor.last_signature = entries.first().and_then(|entry| entry["signature"].as_str()).map(str::to_string);
+            println!("👀 Copy-trading: following {} from its latest transaction", wallet);
+            return Ok(());
+        }
+
+        // Newest first from the RPC; handle oldest first so the cursor only ever moves forward
+        for entry in entries.iter().rev() {
+            let Some(signature) = entry["signature"].as_str() else {
+                continue;
+            };
+            if entry["err"].is_null() {
+                if let Some(buy) = self.fetch_buy(wallet, signature).await? {
+                    println!("🪞 Copy-trading: {} bought {} for {:.4} SOL ({})", wallet, buy.mint, buy.sol_spent, signature);
+                    signals.push(CopySignal { wallet: wallet.to_string(), signature: signature.to_string(), buy });
+                }
+            }
+            self.cursors.entry(wallet.to_string()).or_default().last_signature = Some(signature.to_string());
+        }
+        Ok(())
+    }
+
+    async fn fetch_buy(&self, wallet: &str, signature: &str) -> Result<Option<DecodedBuy>> {
+        self.rpc_limiter.acquire().await;
+        let transaction: Value = self.rpc_client.send(
+            RpcRequest::GetTransaction,
+            serde_json::json!([
+                signature,
+                { "encoding": "jsonParsed", "commitment": "confirmed", "maxSupportedTransactionVersion": 0 }
+            ]),
+        )?;
+        if transaction.is_null() {
+            return Err(anyhow!("transaction {} not available yet", signature));
+        }
+        Ok(decode_swap_buy(&transaction, wallet))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const WALLET: &str = "Wa11et1111111111111111111111111111111111111";
+    const MINT: &str = "TokenMint1111111111111111111111111111111111";
+    const JUPITER: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+
+    fn token_balance(mint: &str, amount: u64) -> Value {
+        json!({ "accountIndex": 2, "mint": mint, "owner": WALLET, "uiTokenAmount": { "amount": amount.to_string(), "decimals": 6 } })
+    }
+
+    /// A jsonParsed swap: the wallet (fee payer) goes from `pre_lamports` to `post_lamports`
+    /// and its token balances from `pre_tokens` to `post_tokens`
+    fn swap(program: &str, pre_lamports: i64, post_lamports: i64, pre_tokens: Vec<Value>, post_tokens: Vec<Value>) -> Value {
+        json!({
+            "transaction": { "message": { "accountKeys": [
+                { "pubkey": WALLET, "signer": true },
+                { "pubkey": program, "signer": false },
+            ] } },
+            "meta": {
+                "err": null,
+                "fee": 5_000,
+                "preBalances": [pre_lamports, 1],
+                "postBalances": [post_lamports, 1],
+                "preTokenBalances": pre_tokens,
+                "postTokenBalances": post_tokens,
+            },
+        })
+    }
+
+    #[test]
+    fn decodes_a_native_sol_buy() {
+        let transaction = swap(JUPITER, 2_000_000_000, 1_499_995_000, vec![], vec![token_balance(MINT, 1_000_000)]);
+        assert_eq!(decode_swap_buy(&transaction, WALLET), Some(DecodedBuy {
+            mint: MINT.to_string(),
+            sol_spent: 0.5,
+            tokens_received: 1_000_000,
+        }));
+    }
+
+    #[test]
+    fn decodes_a_wrapped_sol_buy() {
+        let wsol = WRAPPED_SOL_MINT.to_string();
+        let transaction = swap(JUPITER, 1_000_000_000, 999_995_000,
+            vec![token_balance(&wsol, 300_000_000), token_balance(MINT, 500)],
+            vec![token_balance(&wsol, 50_000_000), token_balance(MINT, 2_500)]);
+        let buy = decode_swap_buy(&transaction, WALLET).unwrap();
+        assert_eq!(buy.mint, MINT);
+        assert_eq!(buy.tokens_received, 2_000);
+        assert!((buy.sol_spent - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn sells_transfers_and_failed_swaps_are_not_buys() {
+        // Sell: SOL up, token down
+        let sell = swap(JUPITER, 1_000_000_000, 1_400_000_000, vec![token_balance(MINT, 1_000)], vec![]);
+        assert!(decode_swap_buy(&sell, WALLET).is_none());
+
+        // Token arrives without a swap program involved
+        let transfer = swap("11111111111111111111111111111111", 1_000_000_000, 999_995_000, vec![], vec![token_balance(MINT, 1_000)]);
+        assert!(decode_swap_buy(&transfer, WALLET).is_none());
+
+        let mut failed = swap(JUPITER, 2_000_000_000, 1_499_995_000, vec![], vec![token_balance(MINT, 1_000)]);
+        failed["meta"]["err"] = json!({ "InstructionError": [0, "Custom"] });
+        assert!(decode_swap_buy(&failed, WALLET).is_none());
+
+        let other_wallet = swap(JUPITER, 2_000_000_000, 1_499_995_000, vec![], vec![token_balance(MINT, 1_000)]);
+        assert!(decode_swap_buy(&other_wallet, "Someone1111111111111111111111111111111111111").is_none());
+    }
+
+    #[test]
+    fn only_full_signature_pages_are_paged_back() {
+        let page: Vec<Value> = (0..3).map(|i| json!({ "signature": format!("sig{}", i) })).collect();
+        assert_eq!(next_page_before(&page, 3).as_deref(), Some("sig2"));
+        assert_eq!(next_page_before(&page, 25), None);
+        assert_eq!(next_page_before(&[], 25), None);
+    }
+
+    #[test]
+    fn copy_targets_and_multipliers() {
+        let targets = parse_copy_targets("walletA, walletB:0.5 ,walletC:oops");
+        assert_eq!(targets[0], CopyTarget { wallet: "walletA".to_string(), size_multiplier: 1.0 });
+        assert_eq!(targets[1], CopyTarget { wallet: "walletB".to_string(), size_multiplier: 0.5 });
+        assert!(targets[2].size_multiplier.is_nan());
+        assert_eq!(copy_size_multiplier("copy:walletB", &targets), 0.5);
+        assert_eq!(copy_size_multiplier("raydium", &targets), 1.0);
+    }
+}
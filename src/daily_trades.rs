@@ -0,0 +1,126 @@
+// Daily Trade Cap - buys per UTC day, persisted so a restart doesn't reset the count
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use anyhow::Result;
+
+const SECS_PER_DAY: u64 = 86_400;
+
+/// UTC day number (days since the Unix epoch) - rolls over at UTC midnight
+pub fn utc_day(unix_secs: u64) -> u64 {
+    unix_secs / SECS_PER_DAY
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyTradeCount {
+    pub utc_day: u64,
+    pub buys: u32,
+}
+
+impl DailyTradeCount {
+    /// Buys made on `day` - a count from an earlier day no longer applies
+    pub fn buys_on(&self, day: u64) -> u32 {
+        if self.utc_day == day { self.buys } else { 0 }
+    }
+
+    /// Whether `max_daily_trades` (0 = unlimited) blocks another buy on `day`
+    pub fn cap_reached(&self, day: u64, max_daily_trades: u32) -> bool {
+        max_daily_trades > 0 && self.buys_on(day) >= max_daily_trades
+    }
+
+    /// The count after one more buy on `day`
+    pub fn with_buy(&self, day: u64) -> Self {
+        Self { utc_day: day, buys: self.buys_on(day) + 1 }
+    }
+}
+
+/// The persisted count; every buy is written through so a crash can't lose it
+#[derive(Debug, Clone)]
+pub struct DailyTradeCounter {
+    file_path: String,
+    count: DailyTradeCount,
+}
+
+impl DailyTradeCounter {
+    /// Load the saved count (a missing or unreadable file starts from zero)
+    pub fn load(file_path: &str) -> Self {
+        let count = std::fs::read_to_string(file_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { file_path: file_path.to_string(), count }
+    }
+
+    pub fn buys_on(&self, day: u64) -> u32 {
+        self.count.buys_on(day)
+    }
+
+    pub fn cap_reached(&self, day: u64, max_daily_trades: u32) -> bool {
+        self.count.cap_reached(day, max_daily_trades)
+    }
+
+    /// Count a buy and persist the new total
+    pub fn record_buy(&mut self, day: u64) -> Result<()> {
+        self.count = self.count.with_buy(day);
+        if let Some(parent) = Path::new(&self.file_path).parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.file_path, serde_json::to_string_pretty(&self.count)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counter_file(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("daily_trades_{}_{}.json", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn day_rolls_over_at_utc_midnight() {
+        let midnight = 20_000 * SECS_PER_DAY;
+        assert_eq!(utc_day(midnight - 1), 19_999);
+        assert_eq!(utc_day(midnight), 20_000);
+    }
+
+    #[test]
+    fn buys_past_the_cap_are_blocked_until_the_next_day() {
+        let mut counter = DailyTradeCounter::load(&counter_file("cap"));
+        let today = 20_000;
+        for _ in 0..3 {
+            assert!(!counter.cap_reached(today, 3));
+            counter.record_buy(today).unwrap();
+        }
+        assert!(counter.cap_reached(today, 3));
+        assert_eq!(counter.buys_on(today), 3);
+        // Tomorrow starts from zero
+        assert!(!counter.cap_reached(today + 1, 3));
+        counter.record_buy(today + 1).unwrap();
+        assert_eq!(counter.buys_on(today + 1), 1);
+    }
+
+    #[test]
+    fn the_count_survives_a_restart() {
+        let path = counter_file("restart");
+        let mut counter = DailyTradeCounter::load(&path);
+        counter.record_buy(20_000).unwrap();
+        counter.record_buy(20_000).unwrap();
+        assert!(DailyTradeCounter::load(&path).cap_reached(20_000, 2));
+    }
+
+    #[test]
+    fn no_cap_never_blocks() {
+        let count = DailyTradeCount { utc_day: 20_000, buys: 500 };
+        assert!(!count.cap_reached(20_000, 0));
+    }
+
+    #[test]
+    fn unreadable_file_starts_from_zero() {
+        let path = counter_file("corrupt");
+        std::fs::write(&path, "not json").unwrap();
+        assert_eq!(DailyTradeCounter::load(&path).buys_on(20_000), 0);
+    }
+}
@@ -0,0 +1,193 @@
+// Decision Trace - optional per-pool log of why each token was or wasn't bought
+use crate::settings::BotSettings;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use anyhow::{Result, anyhow};
+
+// Source of the records for watched mints no detector returned
+const LOOKUP_SOURCE: &str = "dexscreener-lookup";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceRecord {
+    pub timestamp_unix_secs: u64,
+    pub mint: String,
+    pub source: String, // Detecting DEX/feed, or "dexscreener-lookup" for never-detected mints
+    pub decision: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LookupPair {
+    dex_id: String,
+    pair_created_at: Option<u64>, // Unix millis
+    liquidity: Option<LookupLiquidity>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LookupLiquidity {
+    usd: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LookupResponse {
+    pairs: Option<Vec<LookupPair>>,
+}
+
+pub struct DecisionTrace {
+    client: reqwest::Client,
+    file_path: String,
+    watched_mints: HashSet<String>, // Mints to cross-check against DexScreener when never seen
+    seen_mints: HashSet<String>,
+    reported_missing: HashSet<String>, // Watched mints already logged as never detected
+}
+
+impl DecisionTrace {
+    /// Trace writer for the configured path, or None when DECISION_TRACE is off
+    pub fn from_settings(settings: &BotSettings) -> Option<Self> {
+        if !settings.monitoring.decision_trace_enabled {
+            return None;
+        }
+        Some(Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .unwrap_or_default(),
+            file_path: settings.monitoring.decision_trace_path.clone(),
+            watched_mints: settings.monitoring.decision_trace_mints.iter().cloned().collect(),
+            seen_mints: HashSet::new(),
+            reported_missing: HashSet::new(),
+        })
+    }
+
+    /// Note mints a source returned this scan, whether or not they get as far as a decision
+    /// (already processed, sell cooldown, deferred in the backlog)
+    pub fn mark_detected<'a>(&mut self, mints: impl IntoIterator<Item = &'a str>) {
+        self.seen_mints.extend(mints.into_iter().map(str::to_string));
+    }
+
+    /// Log the decision for one pool to the console and the trace file
+    pub fn record(&mut self, mint: &str, source: &str, decision: &str) {
+        if source != LOOKUP_SOURCE {
+            self.seen_mints.insert(mint.to_string());
+        }
+        let watched = if self.watched_mints.contains(mint) { " 🎯" } else { "" };
+        println!("🧭 TRACE{} {} ({}): {}", watched, mint, source, decision);
+
+        let record = TraceRecord {
+            timestamp_unix_secs: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs(),
+            mint: mint.to_string(),
+            source: source.to_string(),
+            decision: decision.to_string(),
+        };
+        if let Err(e) = append_record(&self.file_path, &record) {
+            println!("⚠️  Failed to write decision trace: {}", e);
+        }
+    }
+
+    /// Look up watched mints no source has reported yet. Any that already trade on DexScreener
+    /// were missed by every detector and get a "never detected" record (once per mint).
+    pub async fn check_watched_mints(&mut self) {
+        for mint in unseen_watched_mints(&self.watched_mints, &self.seen_mints, &self.reported_missing) {
+            match self.lookup_listing(&mint).await {
+                Ok(Some(listing)) => {
+                    self.record(&mint, LOOKUP_SOURCE, &format!("never detected by any source - {}", listing));
+                    self.reported_missing.insert(mint);
+                }
+                Ok(None) => {} // Not launched yet - check again next time
+                Err(e) => println!("⚠️  Decision trace lookup failed for {}: {}", mint, e),
+            }
+        }
+    }
+
+    /// Where the mint trades according to DexScreener, or None if it isn't listed
+    async fn lookup_listing(&self, mint: &str) -> Result<Option<String>> {
+        let response = self.client
+            .get(format!("https://api.dexscreener.com/latest/dex/tokens/{}", mint))
+            .header("Accept", "application/json")
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("DexScreener API returned status: {}", response.status()));
+        }
+        let lookup: LookupResponse = response.json().await?;
+        let Some(pair) = lookup.pairs.unwrap_or_default().into_iter()
+            .max_by(|a, b| liquidity_usd(a).total_cmp(&liquidity_usd(b)))
+        else {
+            return Ok(None);
+        };
+        let listed_at = pair.pair_created_at
+            .and_then(|millis| chrono::DateTime::from_timestamp((millis / 1000) as i64, 0))
+            .map(|time| time.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+            .unwrap_or_else(|| "unknown time".to_string());
+        Ok(Some(format!("listed on {} since {} (${:.0} liquidity)", pair.dex_id, listed_at, liquidity_usd(&pair))))
+    }
+}
+
+fn liquidity_usd(pair: &LookupPair) -> f64 {
+    pair.liquidity.as_ref().and_then(|liquidity| liquidity.usd).unwrap_or(0.0)
+}
+
+/// Watched mints that no scan has seen and that haven't been reported missing yet
+pub fn unseen_watched_mints(
+    watched: &HashSet<String>,
+    seen: &HashSet<String>,
+    reported: &HashSet<String>,
+) -> Vec<String> {
+    let mut unseen: Vec<String> = watched.iter()
+        .filter(|mint| !seen.contains(*mint) && !reported.contains(*mint))
+        .cloned()
+        .collect();
+    unseen.sort();
+    unseen
+}
+
+/// Append one record as a JSON line, creating the file and parent directory if needed
+fn append_record(file_path: &str, record: &TraceRecord) -> Result<()> {
+    if let Some(parent) = Path::new(file_path).parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file_path)?;
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trace(watched: &[&str]) -> DecisionTrace {
+        DecisionTrace {
+            client: reqwest::Client::new(),
+            file_path: std::env::temp_dir().join("decision_trace_test.jsonl").to_string_lossy().into_owned(),
+            watched_mints: watched.iter().map(|mint| mint.to_string()).collect(),
+            seen_mints: HashSet::new(),
+            reported_missing: HashSet::new(),
+        }
+    }
+
+    fn unseen(trace: &DecisionTrace) -> Vec<String> {
+        unseen_watched_mints(&trace.watched_mints, &trace.seen_mints, &trace.reported_missing)
+    }
+
+    #[test]
+    fn mints_a_source_returned_are_never_reported_missing() {
+        let mut trace = trace(&["Deferred", "Decided", "Missed"]);
+        trace.mark_detected(["Deferred", "Other"]);
+        trace.record("Decided", "Raydium", "skipped: low liquidity");
+        assert_eq!(unseen(&trace), vec!["Missed".to_string()]);
+    }
+
+    #[test]
+    fn a_never_detected_record_does_not_count_as_a_detection() {
+        let mut trace = trace(&["Missed"]);
+        trace.record("Missed", LOOKUP_SOURCE, "never detected by any source");
+        assert!(!trace.seen_mints.contains("Missed"));
+    }
+}
@@ -0,0 +1,47 @@
+// Health Snapshot - persisted bot state for alerting continuity across restarts
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use anyhow::Result;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HealthSnapshot {
+    pub last_scan_unix_secs: u64,
+    pub saved_at_unix_secs: u64,
+    pub consecutive_errors: HashMap<String, u32>, // Keyed by API/error category
+    pub active_positions: usize,
+    pub realized_pnl_sol: f64,
+}
+
+impl HealthSnapshot {
+    /// Load the last persisted snapshot, if any
+    pub fn load(file_path: &str) -> Result<Option<Self>> {
+        if !Path::new(file_path).exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(file_path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    /// Write the snapshot as pretty JSON, creating the parent directory if needed
+    pub fn save(&self, file_path: &str) -> Result<()> {
+        if let Some(parent) = Path::new(file_path).parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(file_path, json)?;
+        Ok(())
+    }
+
+    /// Time between the last recorded scan and `now`
+    pub fn downtime(&self, now: SystemTime) -> Duration {
+        let last_scan = UNIX_EPOCH + Duration::from_secs(self.last_scan_unix_secs);
+        now.duration_since(last_scan).unwrap_or_default()
+    }
+}
+
+/// Seconds since the Unix epoch for a SystemTime (0 if before the epoch)
+pub fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
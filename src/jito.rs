@@ -0,0 +1,114 @@
+// Jito Block Engine Integration - submit swaps as tipped bundles for better landing odds
+use crate::settings::BotSettings;
+use crate::lamports::Lamports;
+
+use serde::Deserialize;
+use solana_sdk::{
+    hash::Hash,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::Transaction,
+};
+use base64::{engine::general_purpose, Engine as _};
+use rand::seq::SliceRandom;
+use std::time::Duration;
+use anyhow::{Result, anyhow};
+
+/// Jito's published tip accounts - any one of them credits the tip
+const JITO_TIP_ACCOUNTS: [&str; 8] = [
+    "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
+    "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe",
+    "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY",
+    "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49",
+    "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh",
+    "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt",
+    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
+    "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT",
+];
+
+#[derive(Debug, Deserialize)]
+struct JitoRpcError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SendBundleResponse {
+    result: Option<String>, // Bundle id
+    error: Option<JitoRpcError>,
+}
+
+pub struct JitoClient {
+    client: reqwest::Client,
+    block_engine_url: String,
+    tip: Lamports,
+}
+
+impl JitoClient {
+    pub fn new(block_engine_url: &str, tip: Lamports) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .unwrap_or_default(),
+            block_engine_url: block_engine_url.trim_end_matches('/').to_string(),
+            tip,
+        }
+    }
+
+    /// Client for the configured block engine, or None when Jito is off
+    pub fn from_settings(settings: &BotSettings) -> Option<Self> {
+        let premium = &settings.apis.premium_endpoints;
+        if premium.jito_enabled {
+            Some(Self::new(&premium.jito_block_engine_url, premium.jito_tip()))
+        } else {
+            None
+        }
+    }
+
+    /// Tip transfer to a random Jito tip account, signed against the swap's blockhash
+    pub fn tip_transaction(&self, payer: &Keypair, recent_blockhash: Hash) -> Result<Transaction> {
+        let tip_account: Pubkey = JITO_TIP_ACCOUNTS
+            .choose(&mut rand::thread_rng())
+            .expect("tip account list is not empty")
+            .parse()
+            .map_err(|e| anyhow!("Invalid Jito tip account: {}", e))?;
+        let instruction = system_instruction::transfer(&payer.pubkey(), &tip_account, self.tip.as_u64());
+        Ok(Transaction::new_signed_with_payer(&[instruction], Some(&payer.pubkey()), &[payer], recent_blockhash))
+    }
+
+    /// Submit signed transactions as one atomic bundle, returning the bundle id
+    pub async fn send_bundle(&self, transactions: &[Transaction]) -> Result<String> {
+        let encoded = transactions.iter()
+            .map(|tx| Ok(general_purpose::STANDARD.encode(bincode::serialize(tx)?)))
+            .collect::<Result<Vec<String>>>()?;
+
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "sendBundle",
+            "params": [encoded, { "encoding": "base64" }],
+        });
+
+        let response = self.client
+            .post(format!("{}/api/v1/bundles", self.block_engine_url))
+            .json(&body)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!("Jito block engine returned status: {}", response.status()));
+        }
+
+        let bundle: SendBundleResponse = response.json().await?;
+        match (bundle.result, bundle.error) {
+            (_, Some(error)) => Err(anyhow!("Jito rejected bundle: {}", error.message)),
+            (Some(bundle_id), None) => Ok(bundle_id),
+            (None, None) => Err(anyhow!("Jito returned no bundle id")),
+        }
+    }
+
+    pub fn tip(&self) -> Lamports {
+        self.tip
+    }
+}
@@ -1,18 +1,46 @@
 // Jupiter V6 Trading Integration for Real Solana Swaps
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder};
 use serde::{Deserialize, Serialize};
 use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    compute_budget::{self, ComputeBudgetInstruction},
+    instruction::CompiledInstruction,
+    pubkey::Pubkey,
     signature::{Keypair, Signature, Signer},
-    transaction::Transaction,
+    system_instruction, system_program,
+    transaction::{Transaction, TransactionError, VersionedTransaction},
 };
-use solana_client::rpc_client::RpcClient;
+use solana_client::{rpc_client::RpcClient, rpc_request::RpcRequest};
 use anyhow::{Result, anyhow};
 use base64::{engine::general_purpose, Engine as _};
-use std::time::Duration;
-use std::env;
+use std::future::Future;
+use std::time::{Duration, Instant, SystemTime};
+use crate::health::unix_secs;
+use crate::rpc_limiter::RpcRateLimiter;
+use crate::jito::JitoClient;
+use crate::private_relay::PrivateRelay;
+use crate::lamports::Lamports;
+use crate::trade_journal::{PendingTrade, TradeJournal};
+use crate::wsol::{self, wsol_top_up};
+use crate::sol_reserve::{ReserveClaim, SolReserve};
+use crate::wallet::parse_keypair;
+use crate::settings::BotSettings;
+use crate::units::{lamports_to_sol, sol_to_lamports};
+use crate::cancel::CancelSignal;
+
+// Jito bundles and relay submissions are checked for ~a blockhash lifetime before falling back to RPC
+const LANDING_POLLS: u32 = 30;
+const LANDING_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// Jupiter API hosts: the free rate-limited lite tier, and the paid tier that needs an API key
+pub const JUPITER_LITE_BASE_URL: &str = "https://lite-api.jup.ag";
+pub const JUPITER_PAID_BASE_URL: &str = "https://api.jup.ag";
+
+// Jupiter's prioritizationFeeLamports for every swap - higher priority fee for faster execution
+const PRIORITIZATION_FEE: Lamports = Lamports::new(15_000);
 
 // Jupiter API Response Structures
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct JupiterQuoteResponse {
     #[serde(rename = "inputMint")]
     pub input_mint: String,
@@ -36,20 +64,72 @@ pub struct JupiterQuoteResponse {
     pub route_plan: Vec<RoutePlan>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+impl JupiterQuoteResponse {
+    /// Number of legs in the best route
+    pub fn route_count(&self) -> usize {
+        self.route_plan.len()
+    }
+
+    /// AMM labels along the route, in order
+    pub fn route_labels(&self) -> Vec<&str> {
+        self.route_plan.iter().map(|leg| leg.swap_info.label.as_str()).collect()
+    }
+
+    /// Price impact in percent (Jupiter's priceImpactPct is a fraction: 0.01 = 1%).
+    /// None when the field doesn't parse.
+    pub fn price_impact_percent(&self) -> Option<f64> {
+        self.price_impact_pct.trim().parse::<f64>().ok()
+            .filter(|fraction| fraction.is_finite())
+            .map(|fraction| fraction.abs() * 100.0)
+    }
+
+    /// The quoted output (raw units) after checking the quote is for `requested_in`, its amounts
+    /// parse and are plausible, the output is non-zero and the slippage threshold doesn't exceed it
+    pub fn checked_out_amount(&self, requested_in: u64) -> Result<u64> {
+        let in_amount = parse_quote_amount("inAmount", &self.in_amount)?;
+        if in_amount != requested_in {
+            return Err(anyhow!("Quote is for {} input units, requested {}", in_amount, requested_in));
+        }
+        let out_amount = parse_quote_amount("outAmount", &self.out_amount)?;
+        if out_amount == 0 {
+            return Err(anyhow!("Quote returned a zero outAmount"));
+        }
+        let threshold = parse_quote_amount("otherAmountThreshold", &self.other_amount_threshold)?;
+        if threshold > out_amount {
+            return Err(anyhow!("Quote otherAmountThreshold {} exceeds outAmount {}", threshold, out_amount));
+        }
+        Ok(out_amount)
+    }
+}
+
+/// Largest raw amount a quote is trusted with: 10^18 is a billion whole tokens at 9 decimals,
+/// far past any real fill, and still leaves the f64 price math meaningful
+const MAX_PLAUSIBLE_QUOTE_AMOUNT: u64 = 1_000_000_000_000_000_000;
+
+/// Parse one of a quote's string amounts, rejecting empty, non-numeric, overflowing and implausible values
+pub fn parse_quote_amount(field: &str, value: &str) -> Result<u64> {
+    let amount: u64 = value.trim().parse()
+        .map_err(|e| anyhow!("Quote {} {:?} is not a valid amount: {}", field, value, e))?;
+    if amount > MAX_PLAUSIBLE_QUOTE_AMOUNT {
+        return Err(anyhow!("Quote {} {} is implausibly large (max {})", field, amount, MAX_PLAUSIBLE_QUOTE_AMOUNT));
+    }
+    Ok(amount)
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct PlatformFee {
     pub amount: String,
     pub fee_bps: i32,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct RoutePlan {
     #[serde(rename = "swapInfo")]
     pub swap_info: SwapInfo,
     pub percent: i32,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SwapInfo {
     #[serde(rename = "ammKey")]
     pub amm_key: String,
@@ -129,9 +209,117 @@ pub struct JupiterTrader {
     client: Client,
     rpc_client: RpcClient,
     keypair: Keypair,
-    quote_url: String,
-    swap_url: String,
-    api_key: String,
+    endpoints: JupiterEndpoints,
+    fee_account: Option<String>, // Referral token account receiving the platform fee
+    platform_fee_bps: u16,
+    fee_account_exists: tokio::sync::OnceCell<bool>, // Checked on-chain on first use
+    compute_unit_limit: Option<u32>, // None = keep Jupiter's estimate
+    compute_unit_price_micro_lamports: u64,      // Buys
+    sell_compute_unit_price_micro_lamports: u64, // Sells (and sell probes)
+    rpc_limiter: RpcRateLimiter,
+    trade_journal: Option<TradeJournal>, // Pending buys, cleared by the caller once the position is tracked
+    min_routes: usize, // Buys whose best quote has fewer route legs are rejected
+    max_price_impact_percent: f64, // Buys whose quote moves the price more are rejected; 0 = no limit
+    requote_min_sol: f64,          // Buys at least this size re-check impact on a fresh quote before sending; 0 = off
+    jito: Option<JitoClient>, // Submit swaps as tipped bundles, falling back to RPC
+    private_relay: Option<PrivateRelay>, // Submit swaps through a private relay, falling back to RPC
+    buy_slippage_percent: f64,      // First buy attempt
+    max_buy_slippage_percent: f64,  // Escalation ceiling
+    slippage_escalation_attempts: u32,
+    confirm_attempts: u32,      // Sends per swap, each with a fresh blockhash
+    confirm_timeout: Duration,  // Wait per send before checking whether its blockhash expired
+    quote_grace: QuoteGrace,    // Re-quoting of failed buy quotes
+    wsol_top_up: Option<Lamports>, // Some = keep a persistent WSOL account, wrapping in batches of this
+    wsol_reserve: Lamports,        // Native SOL top-ups never wrap into
+    sell_mint: Option<String>, // Sell into this mint instead of SOL (the buy funding mint)
+    balance_commitment: CommitmentConfig, // SOL, WSOL and token balance reads
+    buy_cancel: Option<CancelSignal>, // Raised = buys not yet sent are abandoned
+    sol_reserve: Option<SolReserve>,  // Checked right before every SOL-spending send
+    send_overhead: Lamports,          // Fees, tips and rent charged to each such send on top of its amount
+}
+
+/// What a buy spends: `amount` base units of `mint` (lamports for SOL, micro-USDC for USDC, ...),
+/// worth `sol_value` SOL - the position's cost basis
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuyInput {
+    pub mint: String,
+    pub amount: u64,
+    pub sol_value: f64,
+}
+
+impl BuyInput {
+    /// Spend native SOL (wrapped for the swap)
+    pub fn sol(sol_amount: f64) -> Self {
+        Self {
+            mint: wsol::WRAPPED_SOL_MINT.to_string(),
+            amount: sol_to_lamports(sol_amount),
+            sol_value: sol_amount,
+        }
+    }
+
+    pub fn is_sol(&self) -> bool {
+        self.mint == wsol::WRAPPED_SOL_MINT.to_string()
+    }
+}
+
+/// Where quotes and swaps go, and the X-API-KEY header sent with them (None = no header)
+#[derive(Debug, Clone, PartialEq)]
+pub struct JupiterEndpoints {
+    pub quote_url: String,
+    pub fallback_quote_urls: Vec<String>, // Older quote paths tried when the primary fails
+    pub swap_url: String,
+    pub api_key: Option<String>,
+}
+
+impl JupiterEndpoints {
+    /// Pick URLs and header for a tier ("lite" or "paid"). An empty `base_url` means the tier's
+    /// default host; the key is only sent when `send_api_key` is set and a key is configured.
+    pub fn for_tier(tier: &str, base_url: &str, api_key: &str, send_api_key: bool) -> Self {
+        let base_url = if !base_url.is_empty() {
+            base_url.trim_end_matches('/')
+        } else if tier == "paid" {
+            JUPITER_PAID_BASE_URL
+        } else {
+            JUPITER_LITE_BASE_URL
+        };
+        Self {
+            quote_url: format!("{}/v6/quote", base_url),
+            fallback_quote_urls: vec![format!("{}/v4/quote", base_url), format!("{}/quote", base_url)],
+            swap_url: format!("{}/v6/swap", base_url),
+            api_key: (send_api_key && !api_key.is_empty()).then(|| api_key.to_string()),
+        }
+    }
+
+    /// JUPITER_TIER / JUPITER_BASE_URL / JUPITER_API_KEY, with JUPITER_QUOTE_API and
+    /// JUPITER_SWAP_API still overriding the individual paths
+    pub fn from_settings(settings: &BotSettings) -> Self {
+        let apis = &settings.apis;
+        let mut endpoints = Self::for_tier(&apis.jupiter_tier, &apis.jupiter_base_url, &apis.jupiter_api_key, apis.jupiter_send_api_key);
+        if !apis.jupiter_quote_api.is_empty() {
+            endpoints.quote_url = apis.jupiter_quote_api.clone();
+        }
+        if !apis.jupiter_swap_api.is_empty() {
+            endpoints.swap_url = apis.jupiter_swap_api.clone();
+        }
+        endpoints
+    }
+
+    /// Attach the X-API-KEY header when one is configured
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.api_key {
+            Some(api_key) => request.header("X-API-KEY", api_key),
+            None => request,
+        }
+    }
+}
+
+/// getTokenAccountsByOwner params for `owner`'s accounts of `mint`, read at `commitment`
+pub fn token_accounts_params(owner: &str, mint: &str, commitment: CommitmentConfig) -> serde_json::Value {
+    serde_json::json!([
+        owner,
+        { "mint": mint },
+        { "encoding": "jsonParsed", "commitment": commitment.commitment }
+    ])
 }
 
 impl JupiterTrader {
@@ -142,32 +330,228 @@ impl JupiterTrader {
 
         let rpc_client = RpcClient::new(rpc_url.to_string());
         
-        // Parse private key
-        let private_key_bytes = bs58::decode(private_key)
-            .into_vec()
-            .map_err(|e| anyhow!("Invalid private key format: {}", e))?;
-        
-        let keypair = Keypair::from_bytes(&private_key_bytes)
-            .map_err(|e| anyhow!("Failed to create keypair: {}", e))?;
-
-        // Get API endpoints from environment
-        let quote_url = env::var("JUPITER_QUOTE_API")
-            .unwrap_or_else(|_| "https://lite-api.jup.ag/v6/quote".to_string());
-        let swap_url = env::var("JUPITER_SWAP_API")
-            .unwrap_or_else(|_| "https://lite-api.jup.ag/v6/swap".to_string());
-        let api_key = env::var("JUPITER_API_KEY")
-            .unwrap_or_else(|_| "7c815f4f-99b7-4ccc-b8b5-df26f99583f2".to_string());
+        let keypair = parse_keypair(private_key)?;
 
         Ok(Self {
             client,
             rpc_client,
             keypair,
-            quote_url,
-            swap_url,
-            api_key,
+            endpoints: JupiterEndpoints::for_tier("lite", "", "", false),
+            fee_account: None,
+            platform_fee_bps: 0,
+            fee_account_exists: tokio::sync::OnceCell::new(),
+            compute_unit_limit: None,
+            compute_unit_price_micro_lamports: 2000, // 2000 micro-lamports
+            sell_compute_unit_price_micro_lamports: 2000,
+            rpc_limiter: RpcRateLimiter::unlimited(),
+            trade_journal: None,
+            min_routes: 1,
+            max_price_impact_percent: 0.0,
+            requote_min_sol: 0.0,
+            jito: None,
+            private_relay: None,
+            buy_slippage_percent: 15.0, // 15% slippage for low liquidity tokens
+            max_buy_slippage_percent: 15.0,
+            slippage_escalation_attempts: 1,
+            confirm_attempts: 1,
+            confirm_timeout: Duration::from_secs(30),
+            quote_grace: QuoteGrace::default(),
+            wsol_top_up: None,
+            wsol_reserve: Lamports::ZERO,
+            sell_mint: None,
+            balance_commitment: CommitmentConfig::confirmed(),
+            buy_cancel: None,
+            sol_reserve: None,
+            send_overhead: Lamports::ZERO,
         })
     }
 
+    /// Quote/swap URLs and API key (lite tier without a key by default)
+    pub fn with_endpoints(mut self, endpoints: JupiterEndpoints) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Commitment for balance reads. The client's finalized default lags a just-confirmed swap by
+    /// ~30 slots, long enough for a follow-up guard to see the pre-trade balance.
+    pub fn with_balance_commitment(mut self, commitment: CommitmentConfig) -> Self {
+        self.balance_commitment = commitment;
+        self
+    }
+
+    /// Abort buys that haven't been sent once `signal` is raised (shutdown). A buy already
+    /// sent can't be recalled - it completes and the caller decides what to do with it.
+    pub fn with_buy_cancel(mut self, signal: CancelSignal) -> Self {
+        self.buy_cancel = Some(signal);
+        self
+    }
+
+    /// Share an RPC rate limiter with the other components using the same provider
+    pub fn with_rpc_limiter(mut self, rpc_limiter: RpcRateLimiter) -> Self {
+        self.rpc_limiter = rpc_limiter;
+        self
+    }
+
+    /// Journal every buy before it is sent so a restart can reconcile it
+    pub fn with_trade_journal(mut self, trade_journal: TradeJournal) -> Self {
+        self.trade_journal = Some(trade_journal);
+        self
+    }
+
+    /// Reject buys whose best quote routes through fewer than `min_routes` legs
+    pub fn with_min_routes(mut self, min_routes: usize) -> Self {
+        self.min_routes = min_routes;
+        self
+    }
+
+    /// Reject buys whose quote moves the price more than `max_percent` (0 = no limit). Buys worth
+    /// at least `requote_min_sol` (0 = none) fetch a fresh quote right before sending and are
+    /// checked again on it - one more round-trip, in case the price moved since the first quote.
+    pub fn with_price_impact_limit(mut self, max_percent: f64, requote_min_sol: f64) -> Self {
+        self.max_price_impact_percent = max_percent;
+        self.requote_min_sol = requote_min_sol;
+        self
+    }
+
+    /// Submit swaps as Jito bundles (None keeps plain RPC submission)
+    pub fn with_jito(mut self, jito: Option<JitoClient>) -> Self {
+        if let Some(jito) = &jito {
+            println!("🎯 Jito bundles enabled: {} tip per swap", jito.tip());
+        }
+        self.jito = jito;
+        self
+    }
+
+    /// Keep swaps out of the public mempool by sending them through a private relay
+    /// (None keeps plain RPC submission). Each swap carries the relay's tip.
+    pub fn with_private_relay(mut self, private_relay: Option<PrivateRelay>) -> Self {
+        if let Some(relay) = &private_relay {
+            println!("🕶️  Private submission enabled via {}: {} tip per swap", relay.name(), relay.tip());
+        }
+        self.private_relay = private_relay;
+        self
+    }
+
+    /// Start buys at `initial_percent` slippage and widen it on slippage failures,
+    /// never past `max_percent`, for at most `attempts` tries
+    pub fn with_slippage_escalation(mut self, initial_percent: f64, max_percent: f64, attempts: u32) -> Self {
+        self.buy_slippage_percent = initial_percent.min(max_percent);
+        self.max_buy_slippage_percent = max_percent;
+        self.slippage_escalation_attempts = attempts.max(1);
+        self
+    }
+
+    /// Re-send swaps whose blockhash expired unlanded, up to `attempts` sends in total,
+    /// waiting at least `timeout` on each before checking for expiry
+    pub fn with_confirmation(mut self, attempts: u32, timeout: Duration) -> Self {
+        self.confirm_attempts = attempts.max(1);
+        self.confirm_timeout = timeout;
+        self
+    }
+
+    /// Re-quote a buy up to `attempts` times in total while Jupiter is temporarily unavailable,
+    /// waiting `delay`, 2x`delay`, ... in between. "No route" answers follow `with_no_route_retry`.
+    pub fn with_quote_grace(mut self, attempts: u32, delay: Duration) -> Self {
+        self.quote_grace.attempts = attempts.max(1);
+        self.quote_grace.retry_delay = delay;
+        self
+    }
+
+    /// Keep re-quoting a buy Jupiter has no route for every `interval` until `window` has passed
+    /// since the first "no route" answer - routing often lags a fresh launch. Zero = give up at once.
+    pub fn with_no_route_retry(mut self, window: Duration, interval: Duration) -> Self {
+        self.quote_grace.no_route_window = window;
+        self.quote_grace.no_route_interval = interval;
+        self
+    }
+
+    /// Swap from and into a persistent WSOL account instead of wrapping/unwrapping SOL on every
+    /// swap. Buys top the account up in `top_up` batches from native SOL above `reserve`.
+    pub fn with_persistent_wsol(mut self, top_up: Option<Lamports>, reserve: Lamports) -> Self {
+        if let Some(top_up) = top_up {
+            println!("🔄 Persistent WSOL account {} (top-ups of {})", wsol::wsol_account(&self.keypair.pubkey()), top_up);
+        }
+        self.wsol_top_up = top_up;
+        self.wsol_reserve = reserve;
+        self
+    }
+
+    /// Refuse buys and WSOL wraps that would leave native SOL below the reserve's floor, counting
+    /// `send_overhead` (fees, tips, rent) on top of each spend and any spends still in flight
+    pub fn with_sol_reserve(mut self, reserve: SolReserve, send_overhead: Lamports) -> Self {
+        self.sol_reserve = Some(reserve);
+        self.send_overhead = send_overhead;
+        self
+    }
+
+    /// Sell positions into `mint` instead of SOL (None or the SOL mint keeps SOL proceeds).
+    /// Sells are still valued and floored with a SOL quote.
+    pub fn with_sell_mint(mut self, mint: Option<String>) -> Self {
+        self.sell_mint = mint.filter(|mint| *mint != wsol::WRAPPED_SOL_MINT.to_string());
+        if let Some(mint) = &self.sell_mint {
+            println!("💵 Sells return to {}", mint);
+        }
+        self
+    }
+
+    /// Apply configured compute unit limit/price to every swap transaction (sells too, unless
+    /// `with_sell_compute_unit_price` sets their own)
+    pub fn with_compute_budget(mut self, compute_unit_limit: u32, compute_unit_price_micro_lamports: u64) -> Self {
+        self.compute_unit_limit = Some(compute_unit_limit);
+        self.compute_unit_price_micro_lamports = compute_unit_price_micro_lamports;
+        self.sell_compute_unit_price_micro_lamports = compute_unit_price_micro_lamports;
+        self
+    }
+
+    /// Separate compute unit price for sells; 0 keeps the buy price
+    pub fn with_sell_compute_unit_price(mut self, micro_lamports: u64) -> Self {
+        self.sell_compute_unit_price_micro_lamports = swap_compute_unit_price(
+            SwapSide::Sell, self.compute_unit_price_micro_lamports, micro_lamports);
+        self
+    }
+
+    fn compute_unit_price(&self, side: SwapSide) -> u64 {
+        swap_compute_unit_price(side, self.compute_unit_price_micro_lamports, self.sell_compute_unit_price_micro_lamports)
+    }
+
+    /// Route a platform fee to a referral token account on every swap.
+    /// The account must already exist on-chain - checked on the first quote, not here, so
+    /// building the trader makes no RPC call. A missing account leaves fees disabled.
+    pub fn with_platform_fee(mut self, fee_account: Option<String>, platform_fee_bps: u16) -> Self {
+        self.fee_account = fee_account;
+        self.platform_fee_bps = platform_fee_bps;
+        self
+    }
+
+    // The referral account when one is configured and exists on-chain (looked up once)
+    async fn active_fee_account(&self) -> Option<&str> {
+        let fee_account = self.fee_account.as_deref()?;
+        let exists = *self.fee_account_exists.get_or_init(|| async {
+            let Ok(pubkey) = fee_account.parse::<solana_sdk::pubkey::Pubkey>() else {
+                println!("⚠️  Jupiter fee account {} is not a valid address - platform fee disabled", fee_account);
+                return false;
+            };
+            self.rpc_limiter.acquire().await;
+            let exists = self.rpc_client.get_account(&pubkey).is_ok();
+            if exists {
+                println!("💸 Jupiter platform fee: {} bps → {}", self.platform_fee_bps, fee_account);
+            } else {
+                println!("⚠️  Jupiter fee account {} not found on-chain - platform fee disabled", fee_account);
+            }
+            exists
+        }).await;
+        exists.then_some(fee_account)
+    }
+
+    // Extra quote query parameter when a platform fee is active
+    async fn platform_fee_param(&self) -> String {
+        if self.active_fee_account().await.is_some() {
+            format!("&platformFeeBps={}", self.platform_fee_bps)
+        } else {
+            String::new()
+        }
+    }
+
     // Get quote for SOL to Token swap
     pub async fn get_quote(
         &self,
@@ -175,21 +559,29 @@ impl JupiterTrader {
         sol_amount_lamports: u64,
         slippage_bps: u16,
     ) -> Result<JupiterQuoteResponse> {
-        let sol_mint = "So11111111111111111111111111111111111111112"; // Wrapped SOL mint
+        self.get_quote_for(&wsol::WRAPPED_SOL_MINT.to_string(), token_mint, sol_amount_lamports, slippage_bps).await
+    }
+
+    // Get quote for swapping `amount` base units of any input mint into `output_mint`
+    pub async fn get_quote_for(
+        &self,
+        input_mint: &str,
+        output_mint: &str,
+        amount: u64,
+        slippage_bps: u16,
+    ) -> Result<JupiterQuoteResponse> {
+        let fee_param = self.platform_fee_param().await;
         
         // Try multiple potential endpoints
-        let endpoints = vec![
-            format!("{}?inputMint={}&outputMint={}&amount={}&slippageBps={}", self.quote_url, sol_mint, token_mint, sol_amount_lamports, slippage_bps),
-            format!("https://lite-api.jup.ag/v4/quote?inputMint={}&outputMint={}&amount={}&slippageBps={}", sol_mint, token_mint, sol_amount_lamports, slippage_bps),
-            format!("https://lite-api.jup.ag/quote?inputMint={}&outputMint={}&amount={}&slippageBps={}", sol_mint, token_mint, sol_amount_lamports, slippage_bps),
-        ];
+        let endpoints: Vec<String> = std::iter::once(&self.endpoints.quote_url)
+            .chain(&self.endpoints.fallback_quote_urls)
+            .map(|quote_url| format!("{}?inputMint={}&outputMint={}&amount={}&slippageBps={}{}", quote_url, input_mint, output_mint, amount, slippage_bps, fee_param))
+            .collect();
 
         for url in endpoints {
             println!("🌐 Trying Jupiter quote endpoint: {}", url);
             
-            let response = match self.client
-                .get(&url)
-                .header("X-API-KEY", &self.api_key)
+            let response = match self.endpoints.authorize(self.client.get(&url))
                 .send()
                 .await {
                 Ok(resp) => resp,
@@ -214,6 +606,14 @@ impl JupiterTrader {
                 let status = response.status();
                 let error_text = response.text().await.unwrap_or_default();
                 println!("❌ HTTP {}: {}", status, error_text);
+                // Jupiter answered and there is nothing to route through - the other endpoints won't differ
+                if is_no_route_response(&error_text) {
+                    return Err(NoRouteError {
+                        input_mint: input_mint.to_string(),
+                        output_mint: output_mint.to_string(),
+                        detail: error_text,
+                    }.into());
+                }
             }
         }
 
@@ -224,31 +624,81 @@ impl JupiterTrader {
         ))
     }
 
-    // Execute real token purchase
+    /// `get_quote_for` with the re-quote grace period: transient failures (timeouts, 5xx, bad
+    /// responses) are retried up to `quote_attempts` times, a NoRouteError for the no-route window
+    pub async fn get_quote_with_grace(
+        &self,
+        input_mint: &str,
+        output_mint: &str,
+        amount: u64,
+        slippage_bps: u16,
+    ) -> Result<JupiterQuoteResponse> {
+        requote_with_grace(&self.quote_grace, output_mint, || {
+            self.get_quote_for(input_mint, output_mint, amount, slippage_bps)
+        }).await
+    }
+
+    // Execute a swap, rebuilding it with a fresh blockhash if a send expires unlanded
     pub async fn execute_swap(
         &self,
         quote: JupiterQuoteResponse,
-        prioritization_fee_lamports: u64,
-    ) -> Result<Signature> {
+        prioritization_fee: Lamports,
+        side: SwapSide,
+    ) -> Result<(Signature, SubmissionRoute, u32)> {
+        self.confirm_with_retry(
+            || self.sign_swap(quote.clone(), prioritization_fee, side),
+            self.rpc_client.commitment(),
+            self.confirm_timeout,
+        ).await
+    }
+
+    /// Build, send and confirm a transaction, re-building (fresh blockhash, new signature) and
+    /// re-sending when a send's blockhash expires before it lands, up to `confirm_attempts` sends.
+    /// A send is only retried once its blockhash is expired, so two attempts can never both land.
+    /// Returns the landed signature, its route and the number of sends it took.
+    pub async fn confirm_with_retry<F, Fut>(
+        &self,
+        mut build_transaction: F,
+        commitment: CommitmentConfig,
+        timeout: Duration,
+    ) -> Result<(Signature, SubmissionRoute, u32)>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<Transaction>>,
+    {
+        let ((signature, route), sends) = resend_on_expiry(self.confirm_attempts, || {
+            let built = build_transaction();
+            async move { self.send_swap(&built.await?, commitment, timeout).await }
+        }).await?;
+        Ok((signature, route, sends))
+    }
+
+    // Build and sign the Jupiter swap transaction without sending it
+    async fn sign_swap(
+        &self,
+        quote: JupiterQuoteResponse,
+        prioritization_fee: Lamports,
+        side: SwapSide,
+    ) -> Result<Transaction> {
         println!("🔄 Preparing Jupiter swap transaction...");
+        let compute_unit_price_micro_lamports = self.compute_unit_price(side);
 
         let swap_request = JupiterSwapRequest {
             quote_response: quote,
             user_public_key: self.keypair.pubkey().to_string(),
-            wrap_and_unwrap_sol: true,
+            wrap_and_unwrap_sol: self.wsol_top_up.is_none(),
             use_shared_accounts: true,
-            fee_account: None,
+            fee_account: self.active_fee_account().await.map(String::from),
             tracking_account: None,
-            compute_unit_price_micro_lamports: Some(2000), // 2000 micro-lamports
-            prioritization_fee_lamports: Some(prioritization_fee_lamports),
-            as_legacy_transaction: false,
+            compute_unit_price_micro_lamports: Some(compute_unit_price_micro_lamports),
+            prioritization_fee_lamports: Some(prioritization_fee.as_u64()),
+            // The compute budget and relay tip are spliced into the message, which needs a legacy transaction
+            as_legacy_transaction: true,
             use_token_ledger: false,
             destination_token_account: None,
         };
 
-        let response = self.client
-            .post(&self.swap_url)
-            .header("X-API-KEY", &self.api_key)
+        let response = self.endpoints.authorize(self.client.post(&self.endpoints.swap_url))
             .json(&swap_request)
             .send()
             .await?;
@@ -263,100 +713,407 @@ impl JupiterTrader {
 
         // Check for simulation errors
         if let Some(sim_error) = swap_response.simulation_error {
-            return Err(anyhow!("Transaction simulation failed: {}", sim_error.error));
+            return Err(SwapSimulationError(sim_error.error).into());
         }
 
         // Decode and sign transaction
         let transaction_bytes = general_purpose::STANDARD.decode(&swap_response.swap_transaction)?;
-        let mut transaction: Transaction = bincode::deserialize(&transaction_bytes)?;
+        let mut transaction = legacy_swap_transaction(&transaction_bytes)?;
+
+        // Enforce configured compute budget (Jupiter only takes a price, not a limit)
+        apply_compute_budget(&mut transaction, self.compute_unit_limit, compute_unit_price_micro_lamports);
+        
+        // Relays only forward transactions that tip them
+        if let Some(relay) = &self.private_relay {
+            append_tip_transfer(&mut transaction, relay.tip_account(), relay.tip());
+        }
 
         // Sign the transaction
+        self.rpc_limiter.acquire().await;
         transaction.sign(&[&self.keypair], self.rpc_client.get_latest_blockhash()?);
 
+        // Last point a buy can still be called off - nothing has been sent yet
+        check_not_cancelled(side, self.buy_cancel.as_ref())?;
+
+        Ok(transaction)
+    }
+
+    // Send a signed swap and wait for confirmation - via a Jito bundle or private relay first when enabled
+    async fn send_swap(
+        &self,
+        transaction: &Transaction,
+        commitment: CommitmentConfig,
+        timeout: Duration,
+    ) -> Result<(Signature, SubmissionRoute)> {
+        if let Some(jito) = &self.jito {
+            match self.send_swap_bundle(jito, transaction).await {
+                Ok(signature) => {
+                    println!("✅ Transaction landed via Jito bundle! Signature: {}", signature);
+                    return Ok((signature, SubmissionRoute::JitoBundle));
+                }
+                Err(e) => println!("⚠️  Jito bundle failed ({}) - falling back to RPC", e),
+            }
+        }
+
+        if let Some(relay) = &self.private_relay {
+            match self.send_swap_private(relay, transaction).await {
+                Ok(signature) => {
+                    println!("✅ Transaction landed via private relay {}! Signature: {}", relay.name(), signature);
+                    return Ok((signature, SubmissionRoute::PrivateRelay));
+                }
+                Err(e) => println!("⚠️  Private relay {} failed ({}) - falling back to PUBLIC RPC submission", relay.name(), e),
+            }
+        }
+
         println!("📡 Sending transaction to Solana network...");
 
-        // Send transaction with confirmation
-        let signature = self.rpc_client.send_and_confirm_transaction_with_spinner(&transaction)?;
+        // Resending the same signed transaction is safe: if the bundle or relay submission
+        // landed after all, the duplicate signature is rejected.
+        self.rpc_limiter.acquire().await;
+        self.rpc_client.send_transaction(transaction)?;
+        let signature = self.await_confirmation(transaction, commitment, timeout).await?;
 
-        println!("✅ Transaction confirmed! Signature: {}", signature);
+        println!("✅ Transaction confirmed via RPC! Signature: {}", signature);
 
-        Ok(signature)
+        Ok((signature, SubmissionRoute::Rpc))
     }
 
-    // Execute complete SOL to Token purchase (wrapper for pool_scanner)
-    pub async fn buy_token(&self, token_mint: &str, sol_amount: f64) -> Result<TradeResult> {
-        let max_slippage_percent = 15.0; // 15% slippage for low liquidity tokens
-        let prioritization_fee_lamports = 15000; // Higher priority fee for faster execution
+    // Bundle the swap with a tip and wait until it lands (or its blockhash expires)
+    async fn send_swap_bundle(&self, jito: &JitoClient, transaction: &Transaction) -> Result<Signature> {
+        let signature = transaction.signatures[0];
+        let tip = jito.tip_transaction(&self.keypair, transaction.message.recent_blockhash)?;
+
+        println!("🎯 Sending Jito bundle (swap + {} tip)...", jito.tip());
+        let bundle_id = jito.send_bundle(&[transaction.clone(), tip]).await?;
+        println!("📦 Bundle accepted: {}", bundle_id);
+
+        self.await_landing(&signature).await
+            .map_err(|e| anyhow!("bundle {}: {}", bundle_id, e))
+    }
+
+    // Hand the swap to a private relay and wait until it lands (or its blockhash expires)
+    async fn send_swap_private(&self, relay: &PrivateRelay, transaction: &Transaction) -> Result<Signature> {
+        println!("🕶️  Sending transaction privately via {} ({} tip)...", relay.name(), relay.tip());
+        let signature = relay.send_transaction(transaction).await?;
+        println!("📨 Relay accepted: {}", signature);
+
+        self.await_landing(&signature).await
+    }
+
+    // Poll until a transaction submitted off the public RPC path executes
+    async fn await_landing(&self, signature: &Signature) -> Result<Signature> {
+        for _ in 0..LANDING_POLLS {
+            tokio::time::sleep(LANDING_POLL_INTERVAL).await;
+            self.rpc_limiter.acquire().await;
+            match self.rpc_client.get_signature_status(signature)? {
+                Some(Ok(())) => return Ok(*signature),
+                Some(Err(e)) => return Err(anyhow!("swap failed on-chain: {}", e)),
+                None => {}
+            }
+        }
+
+        Err(anyhow!("not landed after {}s", LANDING_POLLS as u64 * LANDING_POLL_INTERVAL.as_secs()))
+    }
+
+    // Poll a sent transaction until it reaches `commitment`. Past `timeout` it keeps polling until
+    // the blockhash expires, then reports BlockhashExpiredError - the transaction can no longer land.
+    async fn await_confirmation(
+        &self,
+        transaction: &Transaction,
+        commitment: CommitmentConfig,
+        timeout: Duration,
+    ) -> Result<Signature> {
+        let signature = transaction.signatures[0];
+        let started = Instant::now();
+        loop {
+            tokio::time::sleep(LANDING_POLL_INTERVAL).await;
+            self.rpc_limiter.acquire().await;
+            match self.rpc_client.get_signature_status_with_commitment(&signature, commitment)? {
+                Some(Ok(())) => return Ok(signature),
+                Some(Err(e)) => return Err(anyhow!("swap failed on-chain: {}", e)),
+                None if started.elapsed() < timeout => {}
+                None => {
+                    self.rpc_limiter.acquire().await;
+                    if self.rpc_client.is_blockhash_valid(&transaction.message.recent_blockhash, commitment)? {
+                        continue;
+                    }
+                    // One last look - it may have landed just before the blockhash expired
+                    self.rpc_limiter.acquire().await;
+                    return match self.rpc_client.get_signature_status_with_commitment(&signature, commitment)? {
+                        Some(Ok(())) => Ok(signature),
+                        Some(Err(e)) => Err(anyhow!("swap failed on-chain: {}", e)),
+                        None => Err(BlockhashExpiredError(signature).into()),
+                    };
+                }
+            }
+        }
+    }
+
+    // Execute complete purchase of a token with SOL or another funding mint (wrapper for pool_scanner)
+    pub async fn buy_token(&self, token_mint: &str, input: &BuyInput, min_tokens_out: Option<u64>) -> Result<TradeResult> {
+        let schedule = slippage_schedule(
+            self.buy_slippage_percent,
+            self.max_buy_slippage_percent,
+            self.slippage_escalation_attempts,
+        );
         
-        let (signature, tokens_received, _effective_price) = self.buy_token_full(
-            token_mint,
-            sol_amount,
-            max_slippage_percent,
-            prioritization_fee_lamports,
-        ).await?;
+        let (signature, quoted_tokens, _effective_price, landed_via, time_to_fill) = escalate_slippage(&schedule, |max_slippage_percent| async move {
+            let started = Instant::now(); // Quote request through confirmation of this attempt
+            let (signature, quoted, price, route) = self.buy_token_full(
+                token_mint,
+                input,
+                max_slippage_percent,
+                PRIORITIZATION_FEE,
+                min_tokens_out,
+            ).await?;
+            Ok((signature, quoted, price, route, started.elapsed()))
+        }).await?;
+        
+        // The quote is only an estimate - read what actually arrived from the confirmed transaction
+        let tokens_received = match self.confirmed_token_delta(&signature, token_mint).await {
+            Ok(received) => received,
+            Err(e) => {
+                println!("⚠️  Could not read received tokens from transaction - using quote: {}", e);
+                quoted_tokens
+            }
+        };
         
         Ok(TradeResult {
             transaction_signature: signature.to_string(),
             tokens_received,
             sol_received: 0.0, // Not applicable for buy operations
+            landed_via: Some(landed_via),
+            time_to_fill: Some(time_to_fill),
+            realized_slippage_percent: realized_slippage_percent(quoted_tokens, tokens_received),
         })
     }
 
     // Execute complete Token to SOL sale (wrapper for pool_scanner)
-    pub async fn sell_token(&self, token_mint: &str, token_amount: u64) -> Result<TradeResult> {
+    pub async fn sell_token(&self, token_mint: &str, token_amount: u64, min_sol_out: Option<f64>) -> Result<TradeResult> {
         let max_slippage_percent = 15.0; // 15% slippage for low liquidity tokens
-        let prioritization_fee_lamports = 15000; // Higher priority fee for faster execution
         
-        let (signature, sol_received) = self.sell_token_full(
+        let started = Instant::now();
+        let (signature, sol_received, landed_via) = self.sell_token_full(
             token_mint,
             token_amount,
             max_slippage_percent,
-            prioritization_fee_lamports,
+            PRIORITIZATION_FEE,
+            min_sol_out,
         ).await?;
         
         Ok(TradeResult {
             transaction_signature: signature.to_string(),
             tokens_received: 0, // Not applicable for sell operations
             sol_received,
+            landed_via: Some(landed_via),
+            time_to_fill: Some(started.elapsed()),
+            realized_slippage_percent: None, // SOL delta is muddied by fees and rent refunds
         })
     }
 
-    // Execute complete SOL to Token purchase
+    // Sell roughly `target_sol` worth of a token (e.g. "take 2 SOL off the table"), clamped to the held balance.
+    // Sells everything when the whole position is worth no more than the target.
+    pub async fn sell_token_for_sol(&self, token_mint: &str, target_sol: f64) -> Result<TradeResult> {
+        let held_tokens = self.get_token_balance(token_mint).await?;
+        if held_tokens == 0 {
+            return Err(anyhow!("No {} tokens held", token_mint));
+        }
+        let target_lamports = sol_to_lamports(target_sol);
+        let slippage_bps = 1500; // Same 15% as sell_token - only the out amounts matter here
+
+        // Price the whole position first: if it can't reach the target, the ladder step takes everything
+        let full_quote = self.get_quote_sell(token_mint, held_tokens, slippage_bps).await?;
+        let estimate = tokens_for_sol_target(&full_quote, held_tokens, target_lamports, held_tokens)?;
+        let token_amount = if estimate == held_tokens {
+            println!("💸 Position worth {:.6} SOL, not above the {:.6} SOL target - selling everything",
+                lamports_to_sol(full_quote.checked_out_amount(held_tokens)?), target_sol);
+            held_tokens
+        } else {
+            // Smaller sells get a better rate than the full position, so refine the estimate on its own quote
+            let estimate_quote = self.get_quote_sell(token_mint, estimate, slippage_bps).await?;
+            let refined = tokens_for_sol_target(&estimate_quote, estimate, target_lamports, held_tokens)?;
+            println!("💸 Selling {} of {} tokens for ~{:.6} SOL", refined, held_tokens, target_sol);
+            refined
+        };
+
+        self.sell_token(token_mint, token_amount, None).await
+    }
+
+    // SOL selling `token_amount` would return at the current quote (price impact included, fees not)
+    pub async fn quote_sell_sol(&self, token_mint: &str, token_amount: u64) -> Result<f64> {
+        let quote = self.get_quote_sell(token_mint, token_amount, 1500).await?; // Same 15% as sell_token
+        Ok(lamports_to_sol(quote.checked_out_amount(token_amount)?))
+    }
+
+    // Build a sell of `token_amount` and simulate it without sending - a token that can be bought
+    // but not sold is a honeypot. Errors only when the probe itself couldn't run.
+    pub async fn probe_sell(&self, token_mint: &str, token_amount: u64) -> Result<SellProbe> {
+        let quote = self.get_quote_sell(token_mint, token_amount, 1500).await?; // Same 15% as sell_token
+        let transaction = match self.sign_swap(quote, PRIORITIZATION_FEE, SwapSide::Sell).await {
+            Ok(transaction) => transaction,
+            Err(e) => return match e.downcast::<SwapSimulationError>() {
+                Ok(SwapSimulationError(reason)) => Ok(SellProbe::Honeypot(reason)),
+                Err(e) => Err(e),
+            },
+        };
+
+        self.rpc_limiter.acquire().await;
+        let simulation = self.rpc_client.simulate_transaction(&transaction)?.value;
+        Ok(sell_probe_from_simulation(simulation.err, simulation.logs))
+    }
+
+    // Raw token balance the wallet holds of a mint, summed over its token accounts
+    pub async fn get_token_balance(&self, token_mint: &str) -> Result<u64> {
+        self.get_token_balance_with_commitment(token_mint, self.balance_commitment).await
+    }
+
+    // Raw token balance at an explicit commitment
+    pub async fn get_token_balance_with_commitment(&self, token_mint: &str, commitment: CommitmentConfig) -> Result<u64> {
+        self.rpc_limiter.acquire().await;
+        let accounts: serde_json::Value = self.rpc_client.send(
+            RpcRequest::GetTokenAccountsByOwner,
+            token_accounts_params(&self.keypair.pubkey().to_string(), token_mint, commitment),
+        )?;
+        Ok(accounts["value"].as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|account| account["account"]["data"]["parsed"]["info"]["tokenAmount"]["amount"].as_str()?.parse::<u64>().ok())
+            .sum())
+    }
+
+    // Quote buying `lamports` of SOL worth of a token, then selling every token straight back.
+    // Returns the SOL that would come back, in lamports.
+    pub async fn round_trip_quote(&self, token_mint: &str, lamports: u64, slippage_bps: u16) -> Result<u64> {
+        let sol_mint = wsol::WRAPPED_SOL_MINT.to_string();
+        let buy_quote = self.get_quote_for(&sol_mint, token_mint, lamports, slippage_bps).await?;
+        let tokens = buy_quote.checked_out_amount(lamports)?;
+        let sell_quote = self.get_quote_for(token_mint, &sol_mint, tokens, slippage_bps).await?;
+        sell_quote.checked_out_amount(tokens)
+    }
+
+    // SOL held in WSOL token accounts owned by a pool. None when the pool keeps its vaults under a
+    // separate authority (e.g. Raydium AMM v4), so its reserves can't be read this way.
+    pub async fn get_pool_sol_reserves(&self, pool_address: &str) -> Result<Option<f64>> {
+        self.rpc_limiter.acquire().await;
+        let accounts: serde_json::Value = self.rpc_client.send(
+            RpcRequest::GetTokenAccountsByOwner,
+            serde_json::json!([
+                pool_address,
+                { "mint": wsol::WRAPPED_SOL_MINT.to_string() },
+                { "encoding": "jsonParsed" }
+            ]),
+        )?;
+        let balances: Vec<u64> = accounts["value"].as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|account| account["account"]["data"]["parsed"]["info"]["tokenAmount"]["amount"].as_str()?.parse::<u64>().ok())
+            .collect();
+        Ok((!balances.is_empty()).then(|| Lamports::new(balances.iter().sum()).as_sol()))
+    }
+
+    // Execute complete purchase, spending `input` (SOL or another funding mint)
     pub async fn buy_token_full(
         &self,
         token_mint: &str,
-        sol_amount: f64,
+        input: &BuyInput,
         max_slippage_percent: f64,
-        prioritization_fee_lamports: u64,
-    ) -> Result<(Signature, u64, f64)> {
-        // Convert SOL to lamports
-        let sol_amount_lamports = (sol_amount * 1_000_000_000.0) as u64;
+        prioritization_fee: Lamports,
+        min_tokens_out: Option<u64>,
+    ) -> Result<(Signature, u64, f64, SubmissionRoute)> {
+        let sol_amount = input.sol_value;
         let slippage_bps = (max_slippage_percent * 100.0) as u16; // Convert % to basis points
 
         println!("💰 Initiating purchase:");
         println!("   Token: {}", token_mint);
-        println!("   Amount: {} SOL ({} lamports)", sol_amount, sol_amount_lamports);
+        if input.is_sol() {
+            println!("   Amount: {} SOL ({} lamports)", sol_amount, input.amount);
+        } else {
+            println!("   Amount: {} base units of {} (~{:.4} SOL)", input.amount, input.mint, sol_amount);
+        }
         println!("   Max Slippage: {}%", max_slippage_percent);
 
-        // Get quote
-        let quote = self.get_quote(token_mint, sol_amount_lamports, slippage_bps).await?;
+        // Get quote, re-quoting through brief Jupiter outages
+        let quote = self.get_quote_with_grace(&input.mint, token_mint, input.amount, slippage_bps).await?;
 
-        // Calculate expected tokens
-        let expected_tokens: u64 = quote.out_amount.parse()
-            .map_err(|e| anyhow!("Invalid token amount in quote: {}", e))?;
+        // Calculate expected tokens - an unparseable, zero or implausible quote is never acted on
+        let expected_tokens = quote.checked_out_amount(input.amount)
+            .map_err(|e| anyhow!("Rejected quote for {}: {}", token_mint, e))?;
 
-        // Calculate effective price
+        // Calculate effective price (SOL per token, whatever the funding mint)
         let effective_price = sol_amount / expected_tokens as f64;
 
         println!("📊 Quote details:");
         println!("   Expected tokens: {}", expected_tokens);
         println!("   Effective price: {} SOL per token", effective_price);
-        println!("   Price impact: {}%", quote.price_impact_pct);
+        println!("   Price impact: {}%", quote.price_impact_percent().map_or(quote.price_impact_pct.clone(), |impact| format!("{:.2}", impact)));
+        println!("   Route: {} ({} legs)", quote.route_labels().join(" → "), quote.route_count());
 
-        // Execute the swap
-        let signature = self.execute_swap(quote, prioritization_fee_lamports).await?;
+        check_route_count(&quote, self.min_routes)?;
+        check_min_out(&quote, min_tokens_out)?;
+        check_price_impact(&quote, self.max_price_impact_percent, false)?;
+
+        if let Some(batch) = self.wsol_top_up.filter(|_| input.is_sol()) {
+            self.ensure_wsol(Lamports::new(input.amount), batch).await?;
+        }
+
+        // Held until the swap has landed or failed; the WSOL wrap above already claimed its SOL
+        let native_spend = if input.is_sol() && self.wsol_top_up.is_none() { Lamports::new(input.amount) } else { Lamports::ZERO };
+        let _reserve_claim = self.claim_reserve(native_spend).await?;
+
+        // Large buys swap on a fresh quote, so a price that moved since the first one can't slip through
+        let (quote, expected_tokens, effective_price) = if requote_before_send(sol_amount, self.requote_min_sol) {
+            let fresh = self.get_quote_for(&input.mint, token_mint, input.amount, slippage_bps).await
+                .map_err(|e| anyhow!("Re-quote before sending {} failed: {}", token_mint, e))?;
+            let fresh_tokens = check_fresh_quote(&fresh, input.amount, min_tokens_out, self.max_price_impact_percent)
+                .map_err(|e| if e.is::<PriceImpactError>() || e.is::<MinOutError>() { e } else {
+                    anyhow!("Rejected re-quote for {}: {}", token_mint, e)
+                })?;
+            println!("🔁 Re-quoted before sending: {} tokens, {}% impact", fresh_tokens,
+                fresh.price_impact_percent().map_or(fresh.price_impact_pct.clone(), |impact| format!("{:.2}", impact)));
+            (fresh, fresh_tokens, sol_amount / fresh_tokens as f64)
+        } else {
+            (quote, expected_tokens, effective_price)
+        };
+
+        let Some(journal) = &self.trade_journal else {
+            let (signature, route, _) = self.execute_swap(quote, prioritization_fee, SwapSide::Buy).await?;
+            return Ok((signature, expected_tokens, effective_price, route));
+        };
+
+        // Journal the intent first so a crash from here on can be reconciled at startup
+        journal.record_intent(PendingTrade {
+            token_address: token_mint.to_string(),
+            sol_amount,
+            expected_tokens,
+            signature: None,
+            created_at_unix_secs: unix_secs(SystemTime::now()),
+        })?;
+
+        // Each send is journaled under its own signature. On a send error the entry stays
+        // journaled - the transaction may still land.
+        let (signature, route, _) = self.confirm_with_retry(
+            || {
+                let quote = quote.clone();
+                async move {
+                    let transaction = match self.sign_swap(quote, prioritization_fee, SwapSide::Buy).await {
+                        Ok(transaction) => transaction,
+                        Err(e) => {
+                            // Nothing unexpired was sent, so there is nothing to reconcile
+                            if let Err(journal_err) = journal.clear(token_mint) {
+                                println!("⚠️  Failed to clear trade journal entry: {}", journal_err);
+                            }
+                            return Err(e);
+                        }
+                    };
+                    journal.attach_signature(token_mint, &transaction.signatures[0].to_string())?;
+                    Ok(transaction)
+                }
+            },
+            self.rpc_client.commitment(),
+            self.confirm_timeout,
+        ).await?;
 
-        Ok((signature, expected_tokens, effective_price))
+        Ok((signature, expected_tokens, effective_price, route))
     }
 
     // Execute complete Token to SOL sale
@@ -365,8 +1122,9 @@ impl JupiterTrader {
         token_mint: &str,
         token_amount: u64,
         max_slippage_percent: f64,
-        prioritization_fee_lamports: u64,
-    ) -> Result<(Signature, f64)> {
+        prioritization_fee: Lamports,
+        min_sol_out: Option<f64>,
+    ) -> Result<(Signature, f64, SubmissionRoute)> {
         let slippage_bps = (max_slippage_percent * 100.0) as u16; // Convert % to basis points
 
         println!("💸 Initiating sale:");
@@ -374,22 +1132,45 @@ impl JupiterTrader {
         println!("   Amount: {} tokens", token_amount);
         println!("   Max Slippage: {}%", max_slippage_percent);
 
-        // Get quote for selling tokens to SOL
+        // Get quote for selling tokens to SOL - also the valuation when selling into another mint
         let quote = self.get_quote_sell(token_mint, token_amount, slippage_bps).await?;
 
         // Calculate expected SOL
-        let expected_sol_lamports: u64 = quote.out_amount.parse()
-            .map_err(|e| anyhow!("Invalid SOL amount in quote: {}", e))?;
-        let expected_sol = expected_sol_lamports as f64 / 1_000_000_000.0;
+        let expected_sol_lamports = quote.checked_out_amount(token_amount)
+            .map_err(|e| anyhow!("Rejected sell quote for {}: {}", token_mint, e))?;
+        let expected_sol = lamports_to_sol(expected_sol_lamports);
 
         println!("📊 Quote details:");
         println!("   Expected SOL: {:.6}", expected_sol);
         println!("   Price impact: {}%", quote.price_impact_pct);
 
+        check_min_out(&quote, min_sol_out.map(sol_to_lamports))?;
+
+        let quote = match &self.sell_mint {
+            Some(sell_mint) => {
+                let quote = self.get_quote_for(token_mint, sell_mint, token_amount, slippage_bps).await?;
+                let mint_out = quote.checked_out_amount(token_amount)
+                    .map_err(|e| anyhow!("Rejected sell quote into {}: {}", sell_mint, e))?;
+                println!("   Selling into {}: {} base units expected", sell_mint, quote.out_amount);
+                // This is the quote that gets executed, so it has to clear the same floor
+                let min_mint_out = min_sol_out
+                    .and_then(|min_sol| floor_in_mint(sol_to_lamports(min_sol), expected_sol_lamports, mint_out));
+                check_min_out(&quote, min_mint_out)?;
+                quote
+            }
+            None => {
+                // Proceeds land in the WSOL account, so it has to exist
+                if let Some(batch) = self.wsol_top_up {
+                    self.ensure_wsol(Lamports::ZERO, batch).await?;
+                }
+                quote
+            }
+        };
+
         // Execute the swap
-        let signature = self.execute_swap(quote, prioritization_fee_lamports).await?;
+        let (signature, route, _) = self.execute_swap(quote, prioritization_fee, SwapSide::Sell).await?;
 
-        Ok((signature, expected_sol))
+        Ok((signature, expected_sol, route))
     }
 
     // Get quote for selling tokens to SOL
@@ -402,15 +1183,13 @@ impl JupiterTrader {
         let sol_mint = "So11111111111111111111111111111111111111112"; // Wrapped SOL mint
         
         let url = format!(
-            "{}?inputMint={}&outputMint={}&amount={}&slippageBps={}",
-            self.quote_url, token_mint, sol_mint, token_amount, slippage_bps
+            "{}?inputMint={}&outputMint={}&amount={}&slippageBps={}{}",
+            self.endpoints.quote_url, token_mint, sol_mint, token_amount, slippage_bps, self.platform_fee_param().await
         );
 
         println!("🌐 Requesting Jupiter sell quote: {}", url);
 
-        let response = self.client
-            .get(&url)
-            .header("X-API-KEY", &self.api_key)
+        let response = self.endpoints.authorize(self.client.get(&url))
             .send()
             .await?;
 
@@ -426,10 +1205,154 @@ impl JupiterTrader {
         Ok(quote)
     }
 
-    // Get wallet SOL balance
+    // Get a mint's decimals, needed to turn a per-token price into raw token units
+    pub async fn get_token_decimals(&self, token_mint: &str) -> Result<u8> {
+        let mint = token_mint.parse()
+            .map_err(|e| anyhow!("Invalid token mint {}: {}", token_mint, e))?;
+        self.rpc_limiter.acquire().await;
+        Ok(self.rpc_client.get_token_supply(&mint)?.decimals)
+    }
+
+    /// Current mint and freeze authorities of a token, read straight from its mint account
+    pub async fn get_mint_authorities(&self, token_mint: &str) -> Result<MintAuthorities> {
+        let mint: Pubkey = token_mint.parse()
+            .map_err(|e| anyhow!("Invalid token mint {}: {}", token_mint, e))?;
+        self.rpc_limiter.acquire().await;
+        let data = self.rpc_client.get_account_data(&mint)?;
+        mint_authorities(&data).ok_or_else(|| anyhow!("{} is not an SPL token mint", token_mint))
+    }
+
+    // Look up a transaction on-chain: None if unknown, Some(true) if it executed, Some(false) if it failed
+    pub async fn get_signature_outcome(&self, signature: &str) -> Result<Option<bool>> {
+        let signature: Signature = signature.parse()
+            .map_err(|e| anyhow!("Invalid signature {}: {}", signature, e))?;
+        self.rpc_limiter.acquire().await;
+        let status = self.rpc_client.get_signature_status_with_commitment_and_history(
+            &signature,
+            self.rpc_client.commitment(),
+            true,
+        )?;
+        Ok(status.map(|result| result.is_ok()))
+    }
+
+    // Tokens of `token_mint` the wallet gained in a confirmed transaction (post minus pre balance)
+    async fn confirmed_token_delta(&self, signature: &Signature, token_mint: &str) -> Result<u64> {
+        self.rpc_limiter.acquire().await;
+        let transaction: serde_json::Value = self.rpc_client.send(
+            RpcRequest::GetTransaction,
+            serde_json::json!([
+                signature.to_string(),
+                { "encoding": "json", "commitment": "confirmed", "maxSupportedTransactionVersion": 0 }
+            ]),
+        )?;
+        token_balance_delta(&transaction["meta"], &self.keypair.pubkey().to_string(), token_mint)
+            .ok_or_else(|| anyhow!("no token balance change for {} in {}", token_mint, signature))
+    }
+
+    // Wrapped balance of the persistent WSOL account; None if the account doesn't exist
+    async fn wsol_account_balance(&self) -> Result<Option<Lamports>> {
+        self.rpc_limiter.acquire().await;
+        let account = self.rpc_client
+            .get_account_with_commitment(&wsol::wsol_account(&self.keypair.pubkey()), self.balance_commitment)?
+            .value;
+        match account {
+            Some(account) => wsol::token_account_amount(&account.data)
+                .map(|amount| Some(Lamports::new(amount)))
+                .ok_or_else(|| anyhow!("WSOL account data too short")),
+            None => Ok(None),
+        }
+    }
+
+    // SOL held as WSOL in the persistent account (zero when WSOL isn't persistent or the account is gone)
+    pub async fn wsol_balance(&self) -> Result<Lamports> {
+        if self.wsol_top_up.is_none() {
+            return Ok(Lamports::ZERO);
+        }
+        Ok(self.wsol_account_balance().await?.unwrap_or(Lamports::ZERO))
+    }
+
+    // Claim `spend` plus the per-send overhead against the SOL reserve (None without one).
+    // A refusal is logged and returned as a ReserveBreachError.
+    async fn claim_reserve(&self, spend: Lamports) -> Result<Option<ReserveClaim>> {
+        let Some(reserve) = &self.sol_reserve else {
+            return Ok(None);
+        };
+        self.rpc_limiter.acquire().await;
+        let balance = Lamports::new(self.rpc_client.get_balance_with_commitment(&self.keypair.pubkey(), self.balance_commitment)?.value);
+        match reserve.claim(balance, spend + self.send_overhead) {
+            Ok(claim) => Ok(Some(claim)),
+            Err(breach) => {
+                println!("🛡️  SOL reserve refused a send: {}", breach);
+                Err(breach.into())
+            }
+        }
+    }
+
+    // Make sure the WSOL account exists and holds at least `needed`, wrapping a batch if it doesn't
+    async fn ensure_wsol(&self, needed: Lamports, batch: Lamports) -> Result<()> {
+        let owner = self.keypair.pubkey();
+        let existing = self.wsol_account_balance().await?;
+        let wsol_balance = existing.unwrap_or(Lamports::ZERO);
+
+        self.rpc_limiter.acquire().await;
+        let native = Lamports::new(self.rpc_client.get_balance_with_commitment(&owner, self.balance_commitment)?.value);
+        let available = native.saturating_sub(self.wsol_reserve);
+
+        let amount = wsol_top_up(wsol_balance, needed, batch, available)
+            .ok_or_else(|| anyhow!("WSOL {} plus {} wrappable SOL can't cover {}", wsol_balance, available, needed))?;
+        if existing.is_some() && amount == Lamports::ZERO {
+            return Ok(());
+        }
+
+        let _reserve_claim = self.claim_reserve(amount).await?;
+        println!("🔄 Wrapping {} into WSOL (balance {}, need {})", amount, wsol_balance, needed);
+        self.rpc_limiter.acquire().await;
+        let blockhash = self.rpc_client.get_latest_blockhash()?;
+        let transaction = Transaction::new_signed_with_payer(
+            &wsol::wrap_instructions(&owner, amount),
+            Some(&owner),
+            &[&self.keypair],
+            blockhash,
+        );
+        self.rpc_limiter.acquire().await;
+        self.rpc_client.send_and_confirm_transaction(&transaction)?;
+        Ok(())
+    }
+
+    // Unwrap the persistent WSOL account back to native SOL (closing it refunds its rent too).
+    // Returns the unwrapped amount; the next buy recreates the account.
+    pub async fn close_wsol_account(&self) -> Result<Lamports> {
+        if self.wsol_top_up.is_none() {
+            return Ok(Lamports::ZERO);
+        }
+        let Some(wsol_balance) = self.wsol_account_balance().await? else {
+            return Ok(Lamports::ZERO);
+        };
+
+        let owner = self.keypair.pubkey();
+        self.rpc_limiter.acquire().await;
+        let blockhash = self.rpc_client.get_latest_blockhash()?;
+        let transaction = Transaction::new_signed_with_payer(
+            &[wsol::unwrap_instruction(&owner)],
+            Some(&owner),
+            &[&self.keypair],
+            blockhash,
+        );
+        self.rpc_limiter.acquire().await;
+        self.rpc_client.send_and_confirm_transaction(&transaction)?;
+        Ok(wsol_balance)
+    }
+
+    // Get wallet SOL balance at the balance commitment
     pub async fn get_sol_balance(&self) -> Result<f64> {
-        let balance_lamports = self.rpc_client.get_balance(&self.keypair.pubkey())?;
-        Ok(balance_lamports as f64 / 1_000_000_000.0)
+        self.get_sol_balance_with_commitment(self.balance_commitment).await
+    }
+
+    // Get wallet SOL balance at an explicit commitment
+    pub async fn get_sol_balance_with_commitment(&self, commitment: CommitmentConfig) -> Result<f64> {
+        self.rpc_limiter.acquire().await;
+        let balance_lamports = self.rpc_client.get_balance_with_commitment(&self.keypair.pubkey(), commitment)?.value;
+        Ok(lamports_to_sol(balance_lamports))
     }
 
     // Get wallet address
@@ -438,11 +1361,1184 @@ impl JupiterTrader {
     }
 }
 
-// Real trading result
-#[derive(Debug, Clone)]
-pub struct TradeResult {
-    pub transaction_signature: String,
-    pub tokens_received: u64,
-    pub sol_received: f64,
+/// Which way a swap goes - sells can pay a different compute unit price than buys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapSide {
+    Buy,
+    Sell,
+}
+
+/// Compute unit price (micro-lamports) for a swap: sells use `sell_price` when set (non-zero)
+pub fn swap_compute_unit_price(side: SwapSide, buy_price: u64, sell_price: u64) -> u64 {
+    match side {
+        SwapSide::Sell if sell_price > 0 => sell_price,
+        _ => buy_price,
+    }
+}
+
+/// Fail with `BuyCancelledError` for a buy once `cancel` is raised; sells always go through
+pub fn check_not_cancelled(side: SwapSide, cancel: Option<&CancelSignal>) -> Result<()> {
+    if side == SwapSide::Buy && cancel.is_some_and(CancelSignal::is_cancelled) {
+        return Err(BuyCancelledError.into());
+    }
+    Ok(())
 }
 
+/// Set the compute unit limit/price on an unsigned legacy transaction.
+/// Existing ComputeBudget instructions are rewritten in place; missing ones are prepended,
+/// appending the ComputeBudget program as a read-only unsigned account if needed.
+fn apply_compute_budget(transaction: &mut Transaction, compute_unit_limit: Option<u32>, compute_unit_price_micro_lamports: u64) {
+    let message = &mut transaction.message;
+    let program_index = match message.account_keys.iter().position(|key| *key == compute_budget::id()) {
+        Some(index) => index,
+        None => {
+            // Read-only unsigned accounts sit at the end, so appending keeps existing indexes valid
+            message.account_keys.push(compute_budget::id());
+            message.header.num_readonly_unsigned_accounts += 1;
+            message.account_keys.len() - 1
+        }
+    } as u8;
+
+    let mut budget_instructions = vec![ComputeBudgetInstruction::set_compute_unit_price(compute_unit_price_micro_lamports)];
+    if let Some(limit) = compute_unit_limit {
+        budget_instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(limit));
+    }
+
+    for instruction in budget_instructions {
+        let tag = instruction.data[0]; // Borsh variant index identifies limit vs price
+        let existing = message.instructions.iter_mut()
+            .find(|ix| ix.program_id_index == program_index && ix.data.first() == Some(&tag));
+
+        match existing {
+            Some(ix) => ix.data = instruction.data,
+            None => message.instructions.insert(0, CompiledInstruction::new_from_raw_parts(program_index, instruction.data, vec![])),
+        }
+    }
+}
+
+/// Append a SOL transfer from the fee payer to `recipient` on an unsigned legacy transaction.
+/// A new recipient joins the writable unsigned accounts, shifting the read-only accounts behind it.
+fn append_tip_transfer(transaction: &mut Transaction, recipient: Pubkey, tip: Lamports) {
+    let message = &mut transaction.message;
+    let recipient_index = match message.account_keys.iter().position(|key| *key == recipient) {
+        Some(index) => index,
+        None => {
+            let index = message.account_keys.len() - message.header.num_readonly_unsigned_accounts as usize;
+            message.account_keys.insert(index, recipient);
+            for instruction in message.instructions.iter_mut() {
+                if instruction.program_id_index as usize >= index {
+                    instruction.program_id_index += 1;
+                }
+                for account in instruction.accounts.iter_mut() {
+                    if *account as usize >= index {
+                        *account += 1;
+                    }
+                }
+            }
+            index
+        }
+    };
+    let program_index = match message.account_keys.iter().position(|key| *key == system_program::id()) {
+        Some(index) => index,
+        None => {
+            message.account_keys.push(system_program::id());
+            message.header.num_readonly_unsigned_accounts += 1;
+            message.account_keys.len() - 1
+        }
+    };
+
+    let payer = message.account_keys[0];
+    let transfer = system_instruction::transfer(&payer, &recipient, tip.as_u64());
+    message.instructions.push(CompiledInstruction::new_from_raw_parts(
+        program_index as u8,
+        transfer.data,
+        vec![0, recipient_index as u8],
+    ));
+}
+
+/// Decode Jupiter's swap transaction. The compute budget and relay tip are spliced into a legacy
+/// message, so a v0 transaction is refused rather than signed half-edited.
+fn legacy_swap_transaction(bytes: &[u8]) -> Result<Transaction> {
+    let versioned: VersionedTransaction = bincode::deserialize(bytes)?;
+    versioned.into_legacy_transaction()
+        .ok_or_else(|| anyhow!("Jupiter returned a v0 swap transaction - the compute budget and relay tip need a legacy one"))
+}
+
+/// Jupiter answered the quote request but has no route for the pair (not tradable yet, or no pool)
+#[derive(Debug)]
+pub struct NoRouteError {
+    pub input_mint: String,
+    pub output_mint: String,
+    pub detail: String, // Jupiter's error body
+}
+
+impl std::fmt::Display for NoRouteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "No route from {} to {}: {}", self.input_mint, self.output_mint, self.detail)
+    }
+}
+
+impl std::error::Error for NoRouteError {}
+
+/// Every buy quote attempt failed without Jupiter saying there is no route (timeouts, 5xx, bad
+/// responses) - nothing was built or sent
+#[derive(Debug)]
+pub struct QuoteUnavailableError {
+    pub attempts: u32,
+    pub detail: String, // The last attempt's error
+}
+
+impl std::fmt::Display for QuoteUnavailableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.attempts > 1 {
+            write!(f, "{} (after {} quote attempts)", self.detail, self.attempts)
+        } else {
+            write!(f, "{}", self.detail)
+        }
+    }
+}
+
+impl std::error::Error for QuoteUnavailableError {}
+
+/// Whether a failed quote's body says no route exists, as opposed to a transient outage
+pub fn is_no_route_response(body: &str) -> bool {
+    let body = body.to_uppercase();
+    ["COULD_NOT_FIND_ANY_ROUTE", "NO_ROUTES_FOUND", "TOKEN_NOT_TRADABLE", "NO ROUTE"]
+        .iter()
+        .any(|marker| body.contains(marker))
+}
+
+/// Wait before re-quote number `attempt` (1-based): linear so the whole grace period stays short
+pub fn requote_delay(base: Duration, attempt: u32) -> Duration {
+    base * attempt
+}
+
+/// How failed buy quotes are retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuoteGrace {
+    pub attempts: u32,               // Quotes in total while Jupiter is temporarily unavailable
+    pub retry_delay: Duration,       // First transient re-quote wait, growing linearly per attempt
+    pub no_route_window: Duration,   // How long "no route" keeps being re-quoted; zero = never
+    pub no_route_interval: Duration, // Wait between "no route" re-quotes
+}
+
+impl Default for QuoteGrace {
+    fn default() -> Self {
+        Self { attempts: 1, retry_delay: Duration::ZERO, no_route_window: Duration::ZERO, no_route_interval: Duration::ZERO }
+    }
+}
+
+/// What a failed buy quote does next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequoteAction {
+    Requote(Duration), // Wait this long, then quote again
+    GiveUp,
+}
+
+/// Decide a failed buy quote. `no_route_for` is Some(time since the first "no route" answer)
+/// when Jupiter has no route - re-quoted every interval while a next try still fits in the
+/// window. Anything else is transient: re-quoted until `attempt` (1-based) reaches the limit.
+pub fn requote_action(no_route_for: Option<Duration>, attempt: u32, grace: &QuoteGrace) -> RequoteAction {
+    match no_route_for {
+        Some(waited) if waited + grace.no_route_interval < grace.no_route_window => RequoteAction::Requote(grace.no_route_interval),
+        Some(_) => RequoteAction::GiveUp,
+        None if attempt < grace.attempts => RequoteAction::Requote(requote_delay(grace.retry_delay, attempt)),
+        None => RequoteAction::GiveUp,
+    }
+}
+
+/// Run `quote` under the re-quote grace period (see `get_quote_with_grace`)
+async fn requote_with_grace<T, F, Fut>(grace: &QuoteGrace, output_mint: &str, mut quote: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 1;
+    let mut first_no_route: Option<Instant> = None;
+    loop {
+        let e = match quote().await {
+            Ok(found) => return Ok(found),
+            Err(e) => e,
+        };
+        let no_route_for = e.downcast_ref::<NoRouteError>()
+            .map(|_| first_no_route.get_or_insert_with(Instant::now).elapsed());
+        match (requote_action(no_route_for, attempt, grace), no_route_for) {
+            (RequoteAction::Requote(delay), Some(waited)) => {
+                println!("⏳ No route yet for {} - re-quoting in {}ms ({}s of {}s waited)",
+                    output_mint, delay.as_millis(), waited.as_secs(), grace.no_route_window.as_secs());
+                tokio::time::sleep(delay).await;
+            }
+            (RequoteAction::Requote(delay), None) => {
+                println!("⏳ Quote temporarily unavailable - re-quoting in {}ms (attempt {}/{})",
+                    delay.as_millis(), attempt + 1, grace.attempts);
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            (RequoteAction::GiveUp, Some(waited)) if !grace.no_route_window.is_zero() => {
+                println!("❌ No route for {} after {}s - giving up", output_mint, waited.as_secs());
+                return Err(e);
+            }
+            (RequoteAction::GiveUp, None) => {
+                return Err(QuoteUnavailableError { attempts: grace.attempts, detail: e.to_string() }.into());
+            }
+            (RequoteAction::GiveUp, Some(_)) => return Err(e),
+        }
+    }
+}
+
+/// A quote's guaranteed output (otherAmountThreshold) fell below the caller's hard minimum
+#[derive(Debug)]
+pub struct MinOutError {
+    pub guaranteed_out: u64,
+    pub min_out: u64,
+}
+
+impl std::fmt::Display for MinOutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Quote guarantees only {} out, below the minimum of {}", self.guaranteed_out, self.min_out)
+    }
+}
+
+impl std::error::Error for MinOutError {}
+
+/// Reject a quote whose worst-case fill after slippage is below `min_out` (raw units)
+fn check_min_out(quote: &JupiterQuoteResponse, min_out: Option<u64>) -> Result<()> {
+    let Some(min_out) = min_out else {
+        return Ok(());
+    };
+    let guaranteed_out = parse_quote_amount("otherAmountThreshold", &quote.other_amount_threshold)?;
+    if guaranteed_out < min_out {
+        return Err(MinOutError { guaranteed_out, min_out }.into());
+    }
+    Ok(())
+}
+
+/// A SOL floor restated in another output mint's base units, at the rate two quotes for the same
+/// input give (`sol_out` lamports vs `mint_out` base units). None when the SOL quote is empty.
+fn floor_in_mint(min_lamports: u64, sol_out: u64, mint_out: u64) -> Option<u64> {
+    (sol_out > 0).then(|| (min_lamports as u128 * mint_out as u128 / sol_out as u128) as u64)
+}
+
+/// A buy quote moves the price more than the configured maximum
+#[derive(Debug)]
+pub struct PriceImpactError {
+    pub impact_percent: Option<f64>, // None = the quote's priceImpactPct didn't parse
+    pub max_percent: f64,
+    pub requoted: bool, // Found on the fresh quote fetched right before sending
+}
+
+impl std::fmt::Display for PriceImpactError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let quote = if self.requoted { "Fresh quote" } else { "Quote" };
+        match self.impact_percent {
+            Some(impact) => write!(f, "{} moves the price {:.2}%, above the maximum of {}%", quote, impact, self.max_percent),
+            None => write!(f, "{} has an unreadable price impact (maximum {}%)", quote, self.max_percent),
+        }
+    }
+}
+
+impl std::error::Error for PriceImpactError {}
+
+/// Reject a quote whose price impact exceeds `max_percent` (0 = no limit). An unreadable
+/// impact is rejected too while a limit is set.
+fn check_price_impact(quote: &JupiterQuoteResponse, max_percent: f64, requoted: bool) -> Result<()> {
+    if max_percent <= 0.0 {
+        return Ok(());
+    }
+    let impact_percent = quote.price_impact_percent();
+    if !impact_percent.is_some_and(|impact| impact <= max_percent) {
+        return Err(PriceImpactError { impact_percent, max_percent, requoted }.into());
+    }
+    Ok(())
+}
+
+/// Check the quote fetched right before sending as strictly as the first one; the tokens it
+/// promises, or the error aborting the send
+fn check_fresh_quote(fresh: &JupiterQuoteResponse, requested_in: u64, min_out: Option<u64>, max_impact_percent: f64) -> Result<u64> {
+    let fresh_tokens = fresh.checked_out_amount(requested_in)?;
+    check_min_out(fresh, min_out)?;
+    check_price_impact(fresh, max_impact_percent, true)?;
+    Ok(fresh_tokens)
+}
+
+/// Whether a buy worth `sol_value` is re-quoted right before sending (`min_sol` 0 = never)
+fn requote_before_send(sol_value: f64, min_sol: f64) -> bool {
+    min_sol > 0.0 && sol_value >= min_sol
+}
+
+/// Slippage tolerances to try in order: doubling from `initial_percent`, capped at
+/// `max_percent`, stopping once the cap is reached or `attempts` entries are listed
+fn slippage_schedule(initial_percent: f64, max_percent: f64, attempts: u32) -> Vec<f64> {
+    let mut schedule = vec![initial_percent.min(max_percent)];
+    while schedule.len() < attempts as usize {
+        let last = schedule[schedule.len() - 1];
+        if last >= max_percent {
+            break;
+        }
+        schedule.push((last * 2.0).min(max_percent));
+    }
+    schedule
+}
+
+/// Run `attempt` at each tolerance of `schedule` in turn until it succeeds. Only slippage
+/// failures move on to the next tolerance; any other error, or one at the last, is returned.
+async fn escalate_slippage<T, F, Fut>(schedule: &[f64], mut attempt: F) -> Result<T>
+where
+    F: FnMut(f64) -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    let mut index = 0;
+    loop {
+        let max_slippage_percent = schedule[index];
+        match attempt(max_slippage_percent).await {
+            Ok(value) => return Ok(value),
+            Err(e) if is_slippage_error(&e) && index + 1 < schedule.len() => {
+                index += 1;
+                println!("📈 Slippage exceeded at {}% - retrying at {}% (attempt {}/{})",
+                    max_slippage_percent, schedule[index], index + 1, schedule.len());
+            }
+            Err(e) if is_slippage_error(&e) => {
+                println!("🛑 Slippage exceeded at the {}% ceiling - giving up", max_slippage_percent);
+                return Err(e);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Run `send` (build, sign and send one transaction) until it lands, again after each
+/// BlockhashExpiredError up to `max_sends` sends in total. Any other error is returned at once.
+/// Returns the landed value and the number of sends it took.
+async fn resend_on_expiry<T, F, Fut>(max_sends: u32, mut send: F) -> Result<(T, u32)>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 1;
+    loop {
+        match send().await {
+            Ok(landed) => {
+                if attempt > 1 {
+                    println!("✅ Landed on send {}/{}", attempt, max_sends);
+                }
+                return Ok((landed, attempt));
+            }
+            Err(e) if e.downcast_ref::<BlockhashExpiredError>().is_some() && attempt < max_sends => {
+                attempt += 1;
+                println!("🔁 {} - rebuilding with a fresh blockhash (send {}/{})", e, attempt, max_sends);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Whether a swap failed because the price moved past the slippage tolerance
+/// (Jupiter's SlippageToleranceExceeded, custom program error 0x1771)
+fn is_slippage_error(error: &anyhow::Error) -> bool {
+    let message = error.to_string().to_lowercase();
+    message.contains("0x1771") || message.contains("slippagetoleranceexceeded") || message.contains("slippage tolerance exceeded")
+}
+
+/// Result of simulating a sell of a position right after buying it
+#[derive(Debug, Clone, PartialEq)]
+pub enum SellProbe {
+    Sellable,
+    Honeypot(String),     // The sell would fail - why
+    Inconclusive(String), // The probe couldn't run (quote or RPC error)
+}
+
+/// Read a simulated sell: any error is a honeypot, described by the error and the last program log
+pub fn sell_probe_from_simulation(err: Option<TransactionError>, logs: Option<Vec<String>>) -> SellProbe {
+    match err {
+        None => SellProbe::Sellable,
+        Some(err) => {
+            let last_log = logs.unwrap_or_default().pop().unwrap_or_default();
+            SellProbe::Honeypot(format!("{} {}", err, last_log).trim_end().to_string())
+        }
+    }
+}
+
+/// Jupiter's own simulation of a swap transaction failed
+#[derive(Debug)]
+pub struct SwapSimulationError(pub String);
+
+impl std::fmt::Display for SwapSimulationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Transaction simulation failed: {}", self.0)
+    }
+}
+
+impl std::error::Error for SwapSimulationError {}
+
+/// A buy was called off (shutdown) before its transaction was sent
+#[derive(Debug)]
+pub struct BuyCancelledError;
+
+impl std::fmt::Display for BuyCancelledError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Buy cancelled before sending")
+    }
+}
+
+impl std::error::Error for BuyCancelledError {}
+
+/// A sent transaction's blockhash expired before it landed, so it never will - safe to rebuild and resend
+#[derive(Debug)]
+pub struct BlockhashExpiredError(pub Signature);
+
+impl std::fmt::Display for BlockhashExpiredError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Transaction {} expired before landing", self.0)
+    }
+}
+
+impl std::error::Error for BlockhashExpiredError {}
+
+/// A buy quote's best route had fewer legs than the configured minimum
+#[derive(Debug)]
+pub struct RouteCountError {
+    pub routes: usize,
+    pub min_routes: usize,
+    pub labels: Vec<String>,
+}
+
+impl std::fmt::Display for RouteCountError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Quote routes through {} leg(s) [{}], below the minimum of {}",
+            self.routes, self.labels.join(", "), self.min_routes)
+    }
+}
+
+impl std::error::Error for RouteCountError {}
+
+/// Reject a quote whose route has fewer than `min_routes` legs (a lone obscure AMM is a honeypot signal)
+fn check_route_count(quote: &JupiterQuoteResponse, min_routes: usize) -> Result<()> {
+    if quote.route_count() < min_routes {
+        return Err(RouteCountError {
+            routes: quote.route_count(),
+            min_routes,
+            labels: quote.route_labels().into_iter().map(String::from).collect(),
+        }.into());
+    }
+    Ok(())
+}
+
+/// Raw token amount `owner` gained of `mint`, from a transaction's meta pre/post token balances.
+/// None when the transaction shows no gain (or the meta is missing).
+fn token_balance_delta(meta: &serde_json::Value, owner: &str, mint: &str) -> Option<u64> {
+    let total = |key: &str| -> u64 {
+        meta[key].as_array()
+            .into_iter()
+            .flatten()
+            .filter(|balance| balance["owner"] == owner && balance["mint"] == mint)
+            .filter_map(|balance| balance["uiTokenAmount"]["amount"].as_str()?.parse::<u64>().ok())
+            .sum()
+    };
+    let gained = total("postTokenBalances").checked_sub(total("preTokenBalances"))?;
+    (gained > 0).then_some(gained)
+}
+
+/// Tokens to sell for `target_lamports`, scaled from a sell quote of `quoted_tokens`: rounded up so
+/// the sale doesn't land just short, and never past `held_tokens` (all of them when the quote can't
+/// reach the target, or returns nothing)
+pub fn tokens_for_sol_target(quote: &JupiterQuoteResponse, quoted_tokens: u64, target_lamports: u64, held_tokens: u64) -> Result<u64> {
+    let quoted_lamports = match quote.checked_out_amount(quoted_tokens) {
+        Ok(lamports) => lamports,
+        Err(_) if quote.out_amount.trim() == "0" => return Ok(held_tokens),
+        Err(e) => return Err(e),
+    };
+    let needed = (target_lamports as u128 * quoted_tokens as u128).div_ceil(quoted_lamports as u128);
+    Ok(needed.min(held_tokens as u128) as u64)
+}
+
+/// Share of the SOL put in that a round trip loses to spread, taxes, fees and price impact, in
+/// percent (0 when it comes back whole or better)
+pub fn round_trip_cost_percent(lamports_in: u64, lamports_back: u64) -> f64 {
+    if lamports_in == 0 {
+        return 100.0;
+    }
+    ((1.0 - lamports_back as f64 / lamports_in as f64) * 100.0).max(0.0)
+}
+
+/// How far the fill fell short of the quote, in percent (negative = better than quoted)
+pub fn realized_slippage_percent(quoted: u64, received: u64) -> Option<f64> {
+    if quoted == 0 {
+        return None;
+    }
+    Some((quoted as f64 - received as f64) / quoted as f64 * 100.0)
+}
+
+/// Who can still mint more supply or freeze holders' accounts (None = revoked)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MintAuthorities {
+    pub mint_authority: Option<Pubkey>,
+    pub freeze_authority: Option<Pubkey>,
+}
+
+/// Authorities from SPL mint account data: COption<Pubkey> at bytes 0..36 (mint) and 46..82 (freeze)
+pub fn mint_authorities(data: &[u8]) -> Option<MintAuthorities> {
+    let coption_pubkey = |bytes: &[u8]| -> Option<Option<Pubkey>> {
+        match bytes.get(0..4)? {
+            [0, 0, 0, 0] => Some(None),
+            [1, 0, 0, 0] => Some(Some(Pubkey::try_from(bytes.get(4..36)?).ok()?)),
+            _ => None,
+        }
+    };
+    Some(MintAuthorities {
+        mint_authority: coption_pubkey(data.get(0..36)?)?,
+        freeze_authority: coption_pubkey(data.get(46..82)?)?,
+    })
+}
+
+// Real trading result
+#[derive(Debug, Clone)]
+pub struct TradeResult {
+    pub transaction_signature: String,
+    pub tokens_received: u64,
+    pub sol_received: f64,
+    pub landed_via: Option<SubmissionRoute>, // None when unknown (e.g. reconciled after a restart)
+    pub time_to_fill: Option<Duration>,      // Quote request to confirmation
+    pub realized_slippage_percent: Option<f64>, // Received vs quoted out amount (buys only)
+}
+
+/// How a swap transaction reached the chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmissionRoute {
+    Rpc,
+    JitoBundle,
+    PrivateRelay,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+    const TOKEN_MINT: &str = "TokenMint1111111111111111111111111111111111";
+
+    /// A SOL -> token quote fixture: `in_amount` lamports for `out_amount` tokens, at least
+    /// `threshold` after slippage, routed through `labels`
+    fn quote(in_amount: u64, out_amount: u64, threshold: u64, labels: &[&str]) -> JupiterQuoteResponse {
+        JupiterQuoteResponse {
+            input_mint: SOL_MINT.to_string(),
+            in_amount: in_amount.to_string(),
+            output_mint: TOKEN_MINT.to_string(),
+            out_amount: out_amount.to_string(),
+            other_amount_threshold: threshold.to_string(),
+            swap_mode: "ExactIn".to_string(),
+            slippage_bps: 1500,
+            platform_fee: None,
+            price_impact_pct: "0.01".to_string(),
+            route_plan: labels.iter().map(|label| RoutePlan {
+                swap_info: SwapInfo {
+                    amm_key: format!("{}Amm", label),
+                    label: label.to_string(),
+                    input_mint: SOL_MINT.to_string(),
+                    output_mint: TOKEN_MINT.to_string(),
+                    in_amount: in_amount.to_string(),
+                    out_amount: out_amount.to_string(),
+                    fee_amount: "0".to_string(),
+                    fee_mint: SOL_MINT.to_string(),
+                },
+                percent: 100,
+            }).collect(),
+        }
+    }
+
+    #[test]
+    fn quote_within_slippage_but_below_the_hard_floor_is_rejected() {
+        // 15% slippage guarantees 850_000 of 1_000_000 - fine for Jupiter, under our 900_000 floor
+        let fixture = quote(100_000_000, 1_000_000, 850_000, &["Raydium"]);
+        let error = check_min_out(&fixture, Some(900_000)).unwrap_err();
+        let min_out = error.downcast_ref::<MinOutError>().expect("typed min-out error");
+        assert_eq!((min_out.guaranteed_out, min_out.min_out), (850_000, 900_000));
+    }
+
+    #[test]
+    fn quote_guaranteeing_the_floor_passes() {
+        let fixture = quote(100_000_000, 1_000_000, 900_000, &["Raydium"]);
+        assert!(check_min_out(&fixture, Some(900_000)).is_ok());
+    }
+
+    #[test]
+    fn no_floor_accepts_any_guaranteed_output() {
+        let fixture = quote(100_000_000, 1_000_000, 1, &["Raydium"]);
+        assert!(check_min_out(&fixture, None).is_ok());
+    }
+
+    #[test]
+    fn slippage_schedule_doubles_up_to_the_ceiling() {
+        assert_eq!(slippage_schedule(2.0, 15.0, 5), vec![2.0, 4.0, 8.0, 15.0]);
+        assert_eq!(slippage_schedule(5.0, 15.0, 2), vec![5.0, 10.0]);
+        // Default: the old fixed 15%, nothing to widen to
+        assert_eq!(slippage_schedule(15.0, 15.0, 3), vec![15.0]);
+    }
+
+    #[tokio::test]
+    async fn slippage_failure_retries_at_a_wider_tolerance() {
+        let mut tried = Vec::new();
+        let result = escalate_slippage(&[5.0, 10.0], |slippage| {
+            tried.push(slippage);
+            async move {
+                if slippage < 10.0 {
+                    Err(anyhow!("custom program error: 0x1771"))
+                } else {
+                    Ok(slippage)
+                }
+            }
+        }).await;
+        assert_eq!(result.unwrap(), 10.0);
+        assert_eq!(tried, vec![5.0, 10.0]);
+    }
+
+    #[tokio::test]
+    async fn slippage_failure_at_the_ceiling_gives_up() {
+        let mut attempts = 0;
+        let result: Result<()> = escalate_slippage(&[5.0, 10.0], |_| {
+            attempts += 1;
+            async { Err(anyhow!("SlippageToleranceExceeded")) }
+        }).await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn other_failures_are_not_retried() {
+        let mut attempts = 0;
+        let result: Result<()> = escalate_slippage(&[5.0, 10.0], |_| {
+            attempts += 1;
+            async { Err(anyhow!("insufficient funds")) }
+        }).await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+
+    /// A signed-over legacy swap: payer, a writable pool account, the swap program
+    fn legacy_swap() -> Transaction {
+        let payer = Pubkey::new_unique();
+        let pool = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        let instruction = solana_sdk::instruction::Instruction::new_with_bytes(
+            program,
+            &[1, 2, 3],
+            vec![
+                solana_sdk::instruction::AccountMeta::new(payer, true),
+                solana_sdk::instruction::AccountMeta::new(pool, false),
+            ],
+        );
+        Transaction::new_with_payer(&[instruction], Some(&payer))
+    }
+
+    #[test]
+    fn relay_tip_is_appended_as_a_system_transfer() {
+        let mut transaction = legacy_swap();
+        let tip_account = Pubkey::new_unique();
+        append_tip_transfer(&mut transaction, tip_account, Lamports::new(5_000));
+
+        let message = &transaction.message;
+        let tip = message.instructions.last().unwrap();
+        assert_eq!(message.account_keys[tip.program_id_index as usize], system_program::id());
+        assert_eq!(message.account_keys[tip.accounts[0] as usize], message.account_keys[0]);
+        assert_eq!(message.account_keys[tip.accounts[1] as usize], tip_account);
+        assert!(message.is_writable(tip.accounts[1] as usize));
+        // The swap instruction still points at its own program and accounts
+        let swap = &message.instructions[0];
+        assert_eq!(swap.data, vec![1, 2, 3]);
+        assert!(!message.is_writable(swap.program_id_index as usize));
+    }
+
+    #[test]
+    fn legacy_swap_transactions_decode() {
+        let transaction = legacy_swap();
+        let bytes = bincode::serialize(&transaction).unwrap();
+        assert_eq!(legacy_swap_transaction(&bytes).unwrap(), transaction);
+    }
+
+    #[test]
+    fn v0_swap_transactions_are_refused() {
+        let payer = Pubkey::new_unique();
+        let message = solana_sdk::message::v0::Message::try_compile(&payer, &[], &[], solana_sdk::hash::Hash::default()).unwrap();
+        let versioned = VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: solana_sdk::message::VersionedMessage::V0(message),
+        };
+        let bytes = bincode::serialize(&versioned).unwrap();
+        assert!(legacy_swap_transaction(&bytes).is_err());
+    }
+
+    #[test]
+    fn sol_floor_is_restated_in_the_sell_mint() {
+        // 1 SOL quoted as 150 USDC (6 decimals): a 0.8 SOL floor is 120 USDC
+        assert_eq!(floor_in_mint(800_000_000, 1_000_000_000, 150_000_000), Some(120_000_000));
+        assert_eq!(floor_in_mint(800_000_000, 0, 150_000_000), None);
+    }
+
+    #[test]
+    fn sell_into_usdc_below_the_restated_floor_is_rejected() {
+        let floor = floor_in_mint(800_000_000, 1_000_000_000, 150_000_000);
+        let usdc_quote = |threshold| {
+            let mut fixture = quote(5_000_000, 150_000_000, threshold, &["Raydium"]);
+            fixture.output_mint = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string();
+            fixture
+        };
+        assert!(check_min_out(&usdc_quote(110_000_000), floor).unwrap_err().downcast_ref::<MinOutError>().is_some());
+        assert!(check_min_out(&usdc_quote(127_500_000), floor).is_ok());
+    }
+
+    #[test]
+    fn raised_cancel_stops_buys_but_not_sells() {
+        let signal = CancelSignal::new();
+        assert!(check_not_cancelled(SwapSide::Buy, None).is_ok());
+        assert!(check_not_cancelled(SwapSide::Buy, Some(&signal)).is_ok());
+
+        signal.clone().cancel();
+        let err = check_not_cancelled(SwapSide::Buy, Some(&signal)).unwrap_err();
+        assert!(err.downcast_ref::<BuyCancelledError>().is_some());
+        assert!(check_not_cancelled(SwapSide::Sell, Some(&signal)).is_ok());
+    }
+
+    fn no_route_grace(window_secs: u64) -> QuoteGrace {
+        QuoteGrace {
+            attempts: 3,
+            retry_delay: Duration::from_millis(150),
+            no_route_window: Duration::from_secs(window_secs),
+            no_route_interval: Duration::from_secs(2),
+        }
+    }
+
+    #[test]
+    fn no_route_bodies_are_told_apart_from_outages() {
+        assert!(is_no_route_response(r#"{"error":"Could not find any route","errorCode":"COULD_NOT_FIND_ANY_ROUTE"}"#));
+        assert!(is_no_route_response(r#"{"errorCode":"TOKEN_NOT_TRADABLE"}"#));
+        assert!(is_no_route_response("No route found"));
+        assert!(!is_no_route_response("<html>502 Bad Gateway</html>"));
+        assert!(!is_no_route_response(r#"{"error":"Too many requests"}"#));
+        assert!(!is_no_route_response(""));
+    }
+
+    #[test]
+    fn no_route_is_requoted_through_the_window_then_given_up() {
+        let grace = no_route_grace(10);
+        let requote = RequoteAction::Requote(Duration::from_secs(2));
+        assert_eq!(requote_action(Some(Duration::ZERO), 1, &grace), requote);
+        // The transient attempt count doesn't apply to "no route"
+        assert_eq!(requote_action(Some(Duration::from_secs(7)), 9, &grace), requote);
+        // The next try would land past the window
+        assert_eq!(requote_action(Some(Duration::from_secs(8)), 1, &grace), RequoteAction::GiveUp);
+    }
+
+    #[test]
+    fn no_route_gives_up_at_once_without_a_window() {
+        assert_eq!(requote_action(Some(Duration::ZERO), 1, &no_route_grace(0)), RequoteAction::GiveUp);
+    }
+
+    #[test]
+    fn transient_failures_use_the_attempt_limit_not_the_window() {
+        let grace = no_route_grace(0);
+        assert_eq!(requote_action(None, 1, &grace), RequoteAction::Requote(Duration::from_millis(150)));
+        assert_eq!(requote_action(None, 2, &grace), RequoteAction::Requote(Duration::from_millis(300)));
+        assert_eq!(requote_action(None, 3, &grace), RequoteAction::GiveUp);
+    }
+
+    #[test]
+    fn only_a_no_route_error_starts_the_no_route_window() {
+        let no_route: anyhow::Error = NoRouteError {
+            input_mint: SOL_MINT.to_string(),
+            output_mint: TOKEN_MINT.to_string(),
+            detail: "COULD_NOT_FIND_ANY_ROUTE".to_string(),
+        }.into();
+        let transient = anyhow!("All Jupiter API endpoints failed");
+        let grace = no_route_grace(10);
+        let decide = |e: &anyhow::Error| requote_action(e.downcast_ref::<NoRouteError>().map(|_| Duration::ZERO), 3, &grace);
+        assert_eq!(decide(&no_route), RequoteAction::Requote(Duration::from_secs(2)));
+        assert_eq!(decide(&transient), RequoteAction::GiveUp);
+    }
+
+    fn quote_with_impact(price_impact_pct: &str) -> JupiterQuoteResponse {
+        JupiterQuoteResponse { price_impact_pct: price_impact_pct.to_string(), ..quote(1_000_000_000, 5_000_000, 4_000_000, &["Raydium"]) }
+    }
+
+    #[test]
+    fn price_impact_is_read_as_a_fraction() {
+        assert_eq!(quote_with_impact("0.025").price_impact_percent(), Some(2.5));
+        assert_eq!(quote_with_impact("-0.01").price_impact_percent(), Some(1.0));
+        assert_eq!(quote_with_impact("n/a").price_impact_percent(), None);
+    }
+
+    #[test]
+    fn only_buys_at_the_requote_size_are_requoted() {
+        assert!(!requote_before_send(5.0, 0.0));
+        assert!(!requote_before_send(0.4, 0.5));
+        assert!(requote_before_send(0.5, 0.5));
+    }
+
+    #[test]
+    fn requote_showing_higher_impact_aborts_the_send() {
+        // The first quote moved the price 1%, within the 3% maximum
+        assert!(check_price_impact(&quote_with_impact("0.01"), 3.0, false).is_ok());
+        // By send time the fresh quote moves it 6%
+        let err = check_fresh_quote(&quote_with_impact("0.06"), 1_000_000_000, Some(4_000_000), 3.0).unwrap_err();
+        let impact = err.downcast_ref::<PriceImpactError>().expect("a price impact abort");
+        assert!(impact.requoted);
+        assert!((impact.impact_percent.unwrap() - 6.0).abs() < 1e-9);
+        assert!(err.to_string().starts_with("Fresh quote moves the price 6.00%"));
+    }
+
+    #[test]
+    fn requote_still_within_limits_swaps_on_the_fresh_amount() {
+        let fresh = JupiterQuoteResponse { price_impact_pct: "0.02".to_string(), ..quote(1_000_000_000, 4_800_000, 4_100_000, &["Raydium"]) };
+        assert_eq!(check_fresh_quote(&fresh, 1_000_000_000, Some(4_000_000), 3.0).unwrap(), 4_800_000);
+        // A fresh quote whose worst case slid under the floor aborts too
+        let err = check_fresh_quote(&fresh, 1_000_000_000, Some(4_200_000), 3.0).unwrap_err();
+        assert!(err.downcast_ref::<MinOutError>().is_some());
+        // An unreadable impact is refused while a limit is set
+        let err = check_fresh_quote(&quote_with_impact(""), 1_000_000_000, None, 3.0).unwrap_err();
+        assert!(err.downcast_ref::<PriceImpactError>().unwrap().impact_percent.is_none());
+    }
+
+    /// The data of every compute budget instruction in the transaction, in order
+    fn compute_budget_data(transaction: &Transaction) -> Vec<Vec<u8>> {
+        let message = &transaction.message;
+        message.instructions.iter()
+            .filter(|ix| message.account_keys[ix.program_id_index as usize] == compute_budget::id())
+            .map(|ix| ix.data.clone())
+            .collect()
+    }
+
+    #[test]
+    fn configured_compute_budget_is_prepended_to_the_swap() {
+        let mut transaction = legacy_swap();
+        apply_compute_budget(&mut transaction, Some(300_000), 50_000);
+
+        let mut budget = compute_budget_data(&transaction);
+        budget.sort();
+        let mut expected = vec![
+            ComputeBudgetInstruction::set_compute_unit_price(50_000).data,
+            ComputeBudgetInstruction::set_compute_unit_limit(300_000).data,
+        ];
+        expected.sort();
+        assert_eq!(budget, expected);
+        // The swap stays last, untouched, and the budget program is read-only
+        let message = &transaction.message;
+        assert_eq!(message.instructions.last().unwrap().data, vec![1, 2, 3]);
+        let budget_index = message.account_keys.iter().position(|key| *key == compute_budget::id()).unwrap();
+        assert!(!message.is_writable(budget_index));
+    }
+
+    #[test]
+    fn jupiters_compute_unit_price_is_replaced_not_duplicated() {
+        let payer = Pubkey::new_unique();
+        let swap = solana_sdk::instruction::Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[1, 2, 3],
+            vec![solana_sdk::instruction::AccountMeta::new(payer, true)],
+        );
+        let mut transaction = Transaction::new_with_payer(&[ComputeBudgetInstruction::set_compute_unit_price(2_000), swap], Some(&payer));
+        apply_compute_budget(&mut transaction, None, 75_000);
+        assert_eq!(compute_budget_data(&transaction), vec![ComputeBudgetInstruction::set_compute_unit_price(75_000).data]);
+        assert_eq!(transaction.message.instructions.len(), 2);
+    }
+
+    #[test]
+    fn single_route_quote_is_rejected_with_its_label() {
+        let single = quote(1_000_000_000, 5_000_000, 4_000_000, &["ObscureSwap"]);
+        assert_eq!(single.route_count(), 1);
+        let err = check_route_count(&single, 2).unwrap_err();
+        let routes = err.downcast_ref::<RouteCountError>().unwrap();
+        assert_eq!((routes.routes, routes.min_routes), (1, 2));
+        assert_eq!(err.to_string(), "Quote routes through 1 leg(s) [ObscureSwap], below the minimum of 2");
+    }
+
+    #[test]
+    fn multi_route_quote_passes_the_minimum() {
+        let multi = quote(1_000_000_000, 5_000_000, 4_000_000, &["Raydium", "Orca", "Meteora"]);
+        assert_eq!(multi.route_labels(), ["Raydium", "Orca", "Meteora"]);
+        assert!(check_route_count(&multi, 2).is_ok());
+        assert!(check_route_count(&multi, 3).is_ok());
+        assert!(check_route_count(&quote(1_000_000_000, 5_000_000, 4_000_000, &["Raydium"]), 1).is_ok()); // Default minimum
+    }
+
+    /// A send stub: fails with `failures` in order, then lands with its send number
+    fn stub_sends(failures: Vec<anyhow::Error>) -> impl FnMut() -> std::future::Ready<Result<u32>> {
+        let mut failures = failures.into_iter();
+        let mut sends = 0;
+        move || {
+            sends += 1;
+            std::future::ready(failures.next().map_or(Ok(sends), Err))
+        }
+    }
+
+    fn expired() -> anyhow::Error {
+        BlockhashExpiredError(Signature::default()).into()
+    }
+
+    #[tokio::test]
+    async fn expired_first_send_is_rebuilt_and_lands_on_the_second() {
+        let (landed_on, sends) = resend_on_expiry(3, stub_sends(vec![expired()])).await.unwrap();
+        assert_eq!((landed_on, sends), (2, 2));
+    }
+
+    #[tokio::test]
+    async fn sends_stop_at_the_attempt_limit() {
+        let err = resend_on_expiry(2, stub_sends(vec![expired(), expired(), expired()])).await.unwrap_err();
+        assert!(err.downcast_ref::<BlockhashExpiredError>().is_some());
+    }
+
+    #[tokio::test]
+    async fn other_send_failures_are_never_resent() {
+        let mut sends = 0;
+        let err = resend_on_expiry(3, || {
+            sends += 1;
+            std::future::ready(Err::<(), _>(anyhow!("custom program error: 0x1")))
+        }).await.unwrap_err();
+        assert_eq!(err.to_string(), "custom program error: 0x1");
+        assert_eq!(sends, 1);
+    }
+
+    #[test]
+    fn failed_sell_simulation_flags_a_honeypot() {
+        let err = TransactionError::InstructionError(2, solana_sdk::instruction::InstructionError::Custom(6001));
+        let logs = vec![
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]".to_string(),
+            "Program log: Error: transfers are disabled".to_string(),
+        ];
+        assert_eq!(
+            sell_probe_from_simulation(Some(err.clone()), Some(logs)),
+            SellProbe::Honeypot("Error processing Instruction 2: custom program error: 0x1771 Program log: Error: transfers are disabled".to_string()),
+        );
+        // No logs: the error alone
+        assert_eq!(sell_probe_from_simulation(Some(err), None), SellProbe::Honeypot("Error processing Instruction 2: custom program error: 0x1771".to_string()));
+    }
+
+    #[test]
+    fn clean_sell_simulation_is_sellable() {
+        assert_eq!(sell_probe_from_simulation(None, Some(vec!["Program log: ok".to_string()])), SellProbe::Sellable);
+    }
+
+    #[test]
+    fn quote_amounts_at_the_boundaries() {
+        assert_eq!(parse_quote_amount("outAmount", "0").unwrap(), 0);
+        assert_eq!(parse_quote_amount("outAmount", " 42 ").unwrap(), 42);
+        assert_eq!(parse_quote_amount("outAmount", &MAX_PLAUSIBLE_QUOTE_AMOUNT.to_string()).unwrap(), MAX_PLAUSIBLE_QUOTE_AMOUNT);
+        let err = parse_quote_amount("outAmount", &(MAX_PLAUSIBLE_QUOTE_AMOUNT + 1).to_string()).unwrap_err();
+        assert!(err.to_string().contains("implausibly large"));
+        // Past u64 entirely
+        assert!(parse_quote_amount("outAmount", "18446744073709551616").unwrap_err().to_string().contains("not a valid amount"));
+    }
+
+    #[test]
+    fn malformed_quote_amounts_are_rejected() {
+        for value in ["", "-5", "1.5", "1e9", "abc", "0x10"] {
+            let err = parse_quote_amount("outAmount", value).unwrap_err();
+            assert!(err.to_string().starts_with("Quote outAmount"), "{:?} gave {}", value, err);
+        }
+    }
+
+    #[test]
+    fn quotes_are_checked_against_the_request() {
+        assert_eq!(quote(1_000, 5_000, 4_000, &["Raydium"]).checked_out_amount(1_000).unwrap(), 5_000);
+        assert!(quote(1_000, 5_000, 4_000, &["Raydium"]).checked_out_amount(999).unwrap_err().to_string().contains("requested 999"));
+        assert!(quote(1_000, 0, 0, &["Raydium"]).checked_out_amount(1_000).unwrap_err().to_string().contains("zero outAmount"));
+        assert!(quote(1_000, 5_000, 6_000, &["Raydium"]).checked_out_amount(1_000).unwrap_err().to_string().contains("exceeds outAmount"));
+        let bad = JupiterQuoteResponse { out_amount: "lots".to_string(), ..quote(1_000, 5_000, 4_000, &["Raydium"]) };
+        assert!(bad.checked_out_amount(1_000).is_err());
+    }
+
+    fn quote_outage() -> anyhow::Error {
+        anyhow!("All Jupiter API endpoints failed")
+    }
+
+    fn no_route() -> anyhow::Error {
+        NoRouteError { input_mint: "in".to_string(), output_mint: "out".to_string(), detail: "NO_ROUTES_FOUND".to_string() }.into()
+    }
+
+    fn fast_grace(attempts: u32) -> QuoteGrace {
+        QuoteGrace { attempts, ..QuoteGrace::default() }
+    }
+
+    #[tokio::test]
+    async fn failed_first_quote_is_retried_into_a_buy() {
+        let mut quotes = stub_sends(vec![quote_outage()]);
+        let buy = requote_with_grace(&fast_grace(3), "out", || {
+            let quoted_on = quotes();
+            async move { quoted_on.await.map(|attempt| (attempt, quote(1_000, 5_000, 4_000, &["Raydium"]))) }
+        }).await.unwrap();
+        assert_eq!(buy.0, 2);
+        assert_eq!(buy.1.checked_out_amount(1_000).unwrap(), 5_000);
+    }
+
+    #[tokio::test]
+    async fn quote_outages_give_up_after_the_attempts() {
+        let err = requote_with_grace(&fast_grace(2), "out", stub_sends(vec![quote_outage(), quote_outage(), quote_outage()]))
+            .await.unwrap_err();
+        assert!(err.to_string().ends_with("(after 2 quote attempts)"));
+        assert_eq!(err.downcast_ref::<QuoteUnavailableError>().unwrap().attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn no_route_is_not_retried_as_an_outage() {
+        let err = requote_with_grace(&fast_grace(3), "out", stub_sends(vec![no_route(), quote_outage()])).await.unwrap_err();
+        assert!(err.downcast_ref::<NoRouteError>().is_some());
+    }
+
+    /// The X-API-KEY header `endpoints` puts on a quote request
+    fn sent_api_key(endpoints: &JupiterEndpoints) -> Option<String> {
+        let request = endpoints.authorize(Client::new().get(&endpoints.quote_url)).build().unwrap();
+        request.headers().get("X-API-KEY").map(|value| value.to_str().unwrap().to_string())
+    }
+
+    #[test]
+    fn lite_tier_uses_the_lite_host_without_a_key() {
+        let lite = JupiterEndpoints::for_tier("lite", "", "", false);
+        assert_eq!(lite.quote_url, "https://lite-api.jup.ag/v6/quote");
+        assert_eq!(lite.swap_url, "https://lite-api.jup.ag/v6/swap");
+        assert_eq!(lite.fallback_quote_urls, vec!["https://lite-api.jup.ag/v4/quote", "https://lite-api.jup.ag/quote"]);
+        assert_eq!(sent_api_key(&lite), None);
+        // A key configured for lite is only sent when asked to
+        assert_eq!(sent_api_key(&JupiterEndpoints::for_tier("lite", "", "k", false)), None);
+        assert_eq!(sent_api_key(&JupiterEndpoints::for_tier("lite", "", "k", true)).as_deref(), Some("k"));
+    }
+
+    #[test]
+    fn paid_tier_uses_the_paid_host_with_its_key() {
+        let paid = JupiterEndpoints::for_tier("paid", "", "secret", true);
+        assert_eq!(paid.quote_url, "https://api.jup.ag/v6/quote");
+        assert_eq!(paid.swap_url, "https://api.jup.ag/v6/swap");
+        assert_eq!(sent_api_key(&paid).as_deref(), Some("secret"));
+        // An empty key is never sent as a blank header
+        assert_eq!(sent_api_key(&JupiterEndpoints::for_tier("paid", "", "", true)), None);
+    }
+
+    #[test]
+    fn base_url_overrides_the_tier_host() {
+        let custom = JupiterEndpoints::for_tier("paid", "https://jup.example.com/", "secret", true);
+        assert_eq!(custom.quote_url, "https://jup.example.com/v6/quote");
+        assert_eq!(custom.swap_url, "https://jup.example.com/v6/swap");
+    }
+
+    #[test]
+    fn quote_and_swap_paths_still_override_the_tier() {
+        let mut settings = BotSettings::from_env().unwrap();
+        settings.apis.jupiter_tier = "paid".to_string();
+        settings.apis.jupiter_base_url = String::new();
+        settings.apis.jupiter_api_key = "secret".to_string();
+        settings.apis.jupiter_send_api_key = true;
+        settings.apis.jupiter_quote_api = "https://quotes.example.com/quote".to_string();
+        settings.apis.jupiter_swap_api = String::new();
+        let endpoints = JupiterEndpoints::from_settings(&settings);
+        assert_eq!(endpoints.quote_url, "https://quotes.example.com/quote");
+        assert_eq!(endpoints.swap_url, "https://api.jup.ag/v6/swap");
+        assert_eq!(endpoints.api_key.as_deref(), Some("secret"));
+    }
+
+    /// Round-trip cost of `lamports` through a buy quote and the sell quote for its tokens, read
+    /// the way round_trip_quote reads them
+    fn round_trip_cost(lamports: u64, buy: &JupiterQuoteResponse, sell: &JupiterQuoteResponse) -> Result<f64> {
+        let tokens = buy.checked_out_amount(lamports)?;
+        Ok(round_trip_cost_percent(lamports, sell.checked_out_amount(tokens)?))
+    }
+
+    #[test]
+    fn liquid_pool_round_trips_cheaply() {
+        // 1 SOL buys 5M tokens, which sell back for 0.985 SOL: 1.5% lost to fees and spread
+        let buy = quote(1_000_000_000, 5_000_000, 4_900_000, &["Raydium"]);
+        let sell = quote(5_000_000, 985_000_000, 970_000_000, &["Raydium"]);
+        let cost = round_trip_cost(1_000_000_000, &buy, &sell).unwrap();
+        assert!((cost - 1.5).abs() < 1e-9);
+        assert!(cost <= 10.0);
+    }
+
+    #[test]
+    fn taxed_token_round_trips_expensively() {
+        // A 40% sell tax on top of the fees: the same 5M tokens only bring back 0.59 SOL
+        let buy = quote(1_000_000_000, 5_000_000, 4_900_000, &["Raydium"]);
+        let sell = quote(5_000_000, 590_000_000, 580_000_000, &["Raydium"]);
+        let cost = round_trip_cost(1_000_000_000, &buy, &sell).unwrap();
+        assert!((cost - 41.0).abs() < 1e-9);
+        assert!(cost > 10.0);
+    }
+
+    #[test]
+    fn round_trip_cost_edges() {
+        // Coming back with more than went in is free, not negative
+        assert_eq!(round_trip_cost_percent(1_000, 1_200), 0.0);
+        assert_eq!(round_trip_cost_percent(1_000, 0), 100.0);
+        assert_eq!(round_trip_cost_percent(0, 0), 100.0);
+        // A sell quote for a different token amount than the buy yields is refused
+        let buy = quote(1_000_000_000, 5_000_000, 4_900_000, &["Raydium"]);
+        let sell = quote(4_000_000, 985_000_000, 970_000_000, &["Raydium"]);
+        assert!(round_trip_cost(1_000_000_000, &buy, &sell).is_err());
+    }
+
+    #[test]
+    fn token_balance_reads_carry_their_commitment() {
+        let params = token_accounts_params("Owner111", TOKEN_MINT, CommitmentConfig::processed());
+        assert_eq!(params[0], "Owner111");
+        assert_eq!(params[1]["mint"], TOKEN_MINT);
+        assert_eq!(params[2]["encoding"], "jsonParsed");
+        assert_eq!(params[2]["commitment"], "processed");
+        assert_eq!(token_accounts_params("Owner111", TOKEN_MINT, CommitmentConfig::finalized())[2]["commitment"], "finalized");
+    }
+
+    /// A trader wired the way the scanner builds it, at `buy_price` / `sell_price` micro-lamports
+    fn priced_trader(buy_price: u64, sell_price: u64) -> JupiterTrader {
+        JupiterTrader::new("http://127.0.0.1:8899", &Keypair::new().to_base58_string())
+            .unwrap()
+            .with_compute_budget(300_000, buy_price)
+            .with_sell_compute_unit_price(sell_price)
+    }
+
+    #[test]
+    fn sells_pay_the_sell_fee_and_buys_the_buy_fee() {
+        let trader = priced_trader(2_000, 50_000);
+        assert_eq!(trader.compute_unit_price(SwapSide::Buy), 2_000);
+        assert_eq!(trader.compute_unit_price(SwapSide::Sell), 50_000);
+    }
+
+    #[test]
+    fn unset_sell_fee_keeps_the_buy_fee() {
+        let trader = priced_trader(2_000, 0);
+        assert_eq!(trader.compute_unit_price(SwapSide::Sell), 2_000);
+        assert_eq!(swap_compute_unit_price(SwapSide::Sell, 2_000, 0), 2_000);
+        assert_eq!(swap_compute_unit_price(SwapSide::Buy, 2_000, 50_000), 2_000);
+    }
+
+    #[test]
+    fn sol_target_picks_tokens_worth_about_the_target() {
+        // 1,000,000 tokens quote at 5 SOL: 2 SOL takes 400,000
+        let quote = quote(1_000_000, 5_000_000_000, 4_900_000_000, &["Raydium"]);
+        let tokens = tokens_for_sol_target(&quote, 1_000_000, 2_000_000_000, 1_000_000).unwrap();
+        assert_eq!(tokens, 400_000);
+        // At the quoted rate those tokens return the target
+        assert_eq!(tokens as u128 * 5_000_000_000 / 1_000_000, 2_000_000_000);
+    }
+
+    #[test]
+    fn sol_target_rounds_up_so_the_sale_is_not_short() {
+        // 3 tokens for 10 lamports: 5 lamports need 1.5 tokens, so 2
+        let quote = quote(3, 10, 9, &["Raydium"]);
+        assert_eq!(tokens_for_sol_target(&quote, 3, 5, 3).unwrap(), 2);
+    }
+
+    #[test]
+    fn position_worth_less_than_the_target_sells_everything() {
+        let full = quote(1_000_000, 1_500_000_000, 1_400_000_000, &["Raydium"]);
+        assert_eq!(tokens_for_sol_target(&full, 1_000_000, 2_000_000_000, 1_000_000).unwrap(), 1_000_000);
+        // Exactly worth the target: all of it too
+        assert_eq!(tokens_for_sol_target(&full, 1_000_000, 1_500_000_000, 1_000_000).unwrap(), 1_000_000);
+        // A quote for nothing can't be scaled
+        let worthless = quote(1_000_000, 0, 0, &["Raydium"]);
+        assert_eq!(tokens_for_sol_target(&worthless, 1_000_000, 1, 1_000_000).unwrap(), 1_000_000);
+    }
+
+    #[test]
+    fn refined_estimate_stays_within_the_held_balance() {
+        // The smaller estimate sells at a better rate; the refined amount still never exceeds what's held
+        let estimate_quote = quote(400_000, 2_100_000_000, 2_000_000_000, &["Raydium"]);
+        let refined = tokens_for_sol_target(&estimate_quote, 400_000, 2_000_000_000, 1_000_000).unwrap();
+        assert_eq!(refined, 380_953);
+        assert_eq!(tokens_for_sol_target(&estimate_quote, 400_000, 2_000_000_000, 300_000).unwrap(), 300_000);
+        // A quote for a different amount than asked is refused
+        assert!(tokens_for_sol_target(&estimate_quote, 500_000, 2_000_000_000, 1_000_000).is_err());
+    }
+}
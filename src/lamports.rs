@@ -0,0 +1,93 @@
+// Lamports - the single unit for fees and tips. Config values arrive in SOL, lamports or
+// micro-lamports; converting through the named constructors keeps them from being mixed up.
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Add;
+use crate::units::{lamports_to_sol, sol_to_lamports};
+
+const MICRO_LAMPORTS_PER_LAMPORT: u64 = 1_000_000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Lamports(u64);
+
+impl Lamports {
+    pub const ZERO: Lamports = Lamports(0);
+
+    pub const fn new(lamports: u64) -> Self {
+        Self(lamports)
+    }
+
+    /// From a SOL amount (e.g. a 0.0015 SOL tip = 1_500_000 lamports), rounded like `sol_to_lamports`
+    pub fn from_sol(sol: f64) -> Self {
+        Self(sol_to_lamports(sol))
+    }
+
+    /// From micro-lamports (1/1_000_000 lamport), rounded up like the runtime's priority fee
+    pub fn from_micro_lamports(micro_lamports: u64) -> Self {
+        Self(micro_lamports.div_ceil(MICRO_LAMPORTS_PER_LAMPORT))
+    }
+
+    /// Priority fee for `compute_units` at `micro_lamports_per_unit` (the ComputeBudget price)
+    pub fn priority_fee(compute_units: u32, micro_lamports_per_unit: u64) -> Self {
+        Self::from_micro_lamports((compute_units as u64).saturating_mul(micro_lamports_per_unit))
+    }
+
+    pub fn saturating_sub(self, other: Lamports) -> Lamports {
+        Lamports(self.0.saturating_sub(other.0))
+    }
+
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+
+    pub fn as_sol(self) -> f64 {
+        lamports_to_sol(self.0)
+    }
+}
+
+impl Add for Lamports {
+    type Output = Lamports;
+
+    fn add(self, other: Lamports) -> Lamports {
+        Lamports(self.0.saturating_add(other.0))
+    }
+}
+
+impl fmt::Display for Lamports {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} lamports ({:.6} SOL)", self.0, self.as_sol())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sol_amounts_convert_at_a_billion_lamports() {
+        assert_eq!(Lamports::from_sol(0.0015).as_u64(), 1_500_000); // ZERO_SLOT_TIP_VALUE default
+        assert_eq!(Lamports::from_sol(0.001).as_u64(), 1_000_000); // NOZOMI_TIP_AMOUNT default
+        assert_eq!(Lamports::from_sol(1.0).as_u64(), 1_000_000_000);
+    }
+
+    #[test]
+    fn micro_lamports_round_up_to_whole_lamports() {
+        assert_eq!(Lamports::from_micro_lamports(100_000).as_u64(), 1); // PRIORITY_FEE_MICRO_LAMPORTS default
+        assert_eq!(Lamports::from_micro_lamports(1_000_000).as_u64(), 1);
+        assert_eq!(Lamports::from_micro_lamports(1_000_001).as_u64(), 2);
+        assert_eq!(Lamports::from_micro_lamports(0), Lamports::ZERO);
+    }
+
+    #[test]
+    fn priority_fee_is_units_times_the_unit_price() {
+        // COMPUTE_UNIT_LIMIT=300000 at COMPUTE_UNIT_PRICE=2000 micro-lamports
+        assert_eq!(Lamports::priority_fee(300_000, 2_000).as_u64(), 600);
+        assert_eq!(Lamports::priority_fee(200_000, 1_000_000).as_u64(), 200_000);
+    }
+
+    #[test]
+    fn arithmetic_saturates() {
+        assert_eq!(Lamports::new(5).saturating_sub(Lamports::new(9)), Lamports::ZERO);
+        assert_eq!(Lamports::new(u64::MAX) + Lamports::new(1), Lamports::new(u64::MAX));
+    }
+}
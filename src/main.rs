@@ -12,15 +12,47 @@ mod wallet;
 mod pool_scanner;
 mod jupiter_trader;
 mod profit_monitor;
+mod health;
+mod rpc_limiter;
+mod birdeye;
+mod trade_journal;
+mod portfolio_history;
+mod jito;
+mod private_relay;
+mod position_monitor;
+mod daily_trades;
+mod lamports;
+mod bot_context;
+mod wsol;
+mod decision_trace;
+mod storage;
+mod webhook;
+mod copy_trading;
+mod session_report;
+mod units;
+mod cancel;
+mod compounding;
+mod trading_hours;
+mod settings_schema;
+mod sol_reserve;
+mod buy_slots;
 
 use settings::BotSettings;
 use wallet::SolanaWallet;
 use telegram::TelegramNotifier;
 use pool_scanner::PoolScanner;
+use rugcheck::{RugCheckClient, RugCheckCriteria};
+use bot_context::{BotContext, RunMode};
 
 #[tokio::main]
 async fn main() -> Result<()> {
     println!("🚀 Solana Token Sniper Bot - Production Ready Version");
+    
+    let args: Vec<String> = env::args().collect();
+    if args.get(1).map(String::as_str) == Some("config") && args.get(2).map(String::as_str) == Some("schema") {
+        return handle_schema_command(&args);
+    }
+    
     println!("🔧 Initializing centralized settings system...");
     
     // Initialize global settings (loads from .env and validates)
@@ -33,18 +65,28 @@ async fn main() -> Result<()> {
         }
     };
     
-    let args: Vec<String> = env::args().collect();
-    
     if args.len() < 2 {
         print_usage();
         return Ok(());
     }
     
     match args[1].as_str() {
-        "start" => start_monitoring_mode(&settings).await?,
-        "scan" => start_pool_scanning(&settings).await?,
+        "start" => {
+            let dry_run = args.iter().skip(2).any(|arg| arg == "--dry-run");
+            let allow_empty = args.iter().skip(2).any(|arg| arg == "--allow-empty");
+            println!("🚀 Starting monitoring mode with centralized settings...");
+            BotContext::init(&settings, dry_run, allow_empty).await?
+                .run_mode(RunMode::Monitoring).await?
+        }
+        "scan" => {
+            println!("🔍 Starting pool scanning mode...");
+            BotContext::init(&settings, false, false).await?
+                .run_mode(RunMode::Scanning).await?
+        }
         "test" => handle_test_commands(&args, &settings).await?,
         "config" => handle_config_commands(&args, &settings).await?,
+        "history" => handle_history_commands(&args, &settings)?,
+        "positions" => show_saved_positions(&settings)?,
         "--help" | "-h" => print_usage(),
         _ => {
             println!("❌ Unknown command: {}", args[1]);
@@ -60,77 +102,48 @@ fn print_usage() {
     println!("");
     println!("COMMANDS:");
     println!("  start                    🚀 Start the bot in monitoring mode");
+    println!("  start --dry-run          🧪 Scan and evaluate live, log would-be buys only");
+    println!("  start --allow-empty      👀 Start even if the wallet can't fund a trade");
     println!("  scan                     🔍 Start pool scanning mode");
     println!("  test <command>          🧪 Run test commands");
     println!("  config <action>         ⚙️  Configuration management");
+    println!("  history <action>        📈 Portfolio and trade history");
+    println!("  positions               🎯 Open positions as last saved");
     println!("");
     println!("TEST COMMANDS:");
     println!("  test wallet             💰 Test wallet connection");
     println!("  test telegram           📱 Test Telegram notifications");
     println!("  test endpoints          🌐 Test API endpoints");
     println!("  test speed              ⚡ Test speed optimization");
+    println!("  test rugcheck <mint>    🛡️  Re-run security analysis for one token");
+    println!("  test pipeline <mint>    🔬 Run the full buy pipeline on one token (never sends)");
     println!("");
     println!("CONFIG COMMANDS:");
     println!("  config show             📊 Show current configuration");
     println!("  config validate         ✅ Validate configuration");
     println!("  config export <file>    💾 Export settings to file");
     println!("  config import <file>    📥 Import settings from file");
+    println!("  config schema [file]    📋 List every setting, or write an annotated .env template");
     println!("");
+    println!("HISTORY COMMANDS:");
+    println!("  history equity <file>   📈 Export the equity curve as CSV");
+    println!("  history trades [mint] [n] 🧾 Last n trades (default 20), optionally for one token");
+    println!();
     println!("EXAMPLES:");
     println!("  ./solana-token-sniper start");
+    println!("  ./solana-token-sniper start --dry-run");
     println!("  ./solana-token-sniper test wallet");
+    println!("  ./solana-token-sniper test rugcheck <mint>");
+    println!("  ./solana-token-sniper test pipeline <mint>");
     println!("  ./solana-token-sniper config show");
     println!("  ./solana-token-sniper config export my-settings.json");
-}
-
-async fn start_monitoring_mode(settings: &BotSettings) -> Result<()> {
-    println!("🚀 Starting monitoring mode with centralized settings...");
-    
-    // All components automatically use global settings
-    let wallet = match SolanaWallet::from_env() {
-        Ok(w) => w,
-        Err(e) => {
-            println!("❌ Wallet initialization failed: {}", e);
-            return Ok(());
-        }
-    };
-    let telegram = TelegramNotifier::new(&settings.telegram.bot_token, &settings.telegram.chat_id);
-    
-    println!("📊 Settings automatically loaded from global configuration");
-    settings.display_summary();
-    
-    println!("🔄 Starting continuous monitoring...");
-    let mut scanner = PoolScanner::new(settings.clone(), telegram, wallet)?;
-    scanner.start_continuous_scan().await?;
-    
-    Ok(())
-}
-
-async fn start_pool_scanning(settings: &BotSettings) -> Result<()> {
-    println!("🔍 Starting pool scanning mode...");
-    
-    let wallet = match SolanaWallet::from_env() {
-        Ok(w) => w,
-        Err(e) => {
-            println!("❌ Wallet initialization failed: {}", e);
-            return Ok(());
-        }
-    };
-    
-    println!("📊 Using centralized settings for pool scanning");
-    settings.display_summary();
-    
-    let telegram = TelegramNotifier::new(&settings.telegram.bot_token, &settings.telegram.chat_id);
-    let mut scanner = PoolScanner::new(settings.clone(), telegram, wallet)?;
-    scanner.start_continuous_scan().await?;
-    
-    Ok(())
+    println!("  ./solana-token-sniper history equity equity.csv");
 }
 
 async fn handle_test_commands(args: &[String], settings: &BotSettings) -> Result<()> {
     if args.len() < 3 {
         println!("❌ Test command required. Available tests:");
-        println!("  test wallet, test telegram, test endpoints, test speed");
+        println!("  test wallet, test telegram, test endpoints, test speed, test rugcheck <mint>, test pipeline <mint>");
         return Ok(());
     }
     
@@ -146,6 +159,10 @@ async fn handle_test_commands(args: &[String], settings: &BotSettings) -> Result
             };
             println!("✅ Wallet connection successful");
             println!("   Address: {}", wallet.get_address());
+            match wallet.get_sol_balance().await {
+                Ok(balance) => println!("   Balance: {:.4} SOL", balance),
+                Err(e) => println!("⚠️  Could not fetch balance: {}", e),
+            }
             Ok(())
         }
         "telegram" => {
@@ -154,8 +171,12 @@ async fn handle_test_commands(args: &[String], settings: &BotSettings) -> Result
             
             match telegram.test_connection().await {
                 Ok(msg) => println!("{}", msg),
+                Err(e) if telegram::is_transient_error(&e) => {
+                    println!("❌ Telegram test failed: {:#}", e);
+                    println!("💡 This looks like a temporary Telegram/network problem - your setup is probably fine, try again shortly.");
+                }
                 Err(e) => {
-                    println!("❌ Telegram test failed: {}", e);
+                    println!("❌ Telegram test failed: {:#}", e);
                     println!("\n📱 To fix Telegram notifications:");
                     println!("1. Open Telegram and search for @snipercheck_bot");
                     println!("2. Start a chat by clicking 'START' or sending /start");
@@ -217,6 +238,69 @@ async fn handle_test_commands(args: &[String], settings: &BotSettings) -> Result
             
             Ok(())
         }
+        "rugcheck" => {
+            let Some(mint) = args.get(3) else {
+                println!("❌ Usage: test rugcheck <mint>");
+                return Ok(());
+            };
+            println!("🧪 Running security analysis for {}...", mint);
+            
+            let rugcheck = RugCheckClient::new()
+                .with_auto_buy_criteria(RugCheckCriteria::from_env())
+                .with_fetch_retries(settings.security.rugcheck_fetch_max_attempts, settings.security.rugcheck_fetch_retry_delay_ms)
+                .with_production_mode(settings.security.production_mode);
+            
+            // check_token prints the per-criterion breakdown as it evaluates
+            let response = match rugcheck.check_token(mint).await {
+                Ok(response) => response,
+                Err(e) => {
+                    println!("❌ Security analysis failed: {}", e);
+                    return Ok(());
+                }
+            };
+            
+            println!("\n📦 EXTRACTED DATA:");
+            match &response.liquidity_info {
+                Some(info) => println!("   💧 Liquidity: ${:.2} (locked: {:?}%, until: {})",
+                    info.total_liquidity_usd.unwrap_or(0.0), info.locked_liquidity_percentage, info.locked_until.as_deref().unwrap_or("n/a")),
+                None => println!("   💧 Liquidity: n/a"),
+            }
+            match &response.mint_info {
+                Some(info) => println!("   🪙 Mint authority: {} | Freeze authority: {}",
+                    info.mint_authority.as_deref().unwrap_or("revoked"), info.freeze_authority.as_deref().unwrap_or("revoked")),
+                None => println!("   🪙 Mint info: n/a"),
+            }
+            match &response.market_info {
+                Some(info) => println!("   📈 Market cap: ${:.0} | 24h volume: ${:.0} | Holders: {}",
+                    info.market_cap_usd.unwrap_or(0.0), info.volume_24h_usd.unwrap_or(0.0),
+                    info.holders_count.map(|h| h.to_string()).unwrap_or_else(|| "n/a".to_string())),
+                None => println!("   📈 Market info: n/a"),
+            }
+            println!("   🛡️  Score: {}/100 (needs {})", response.score,
+                rugcheck::security_bar_description(settings.security.min_acceptable_score, settings.security.require_all_criteria));
+            println!();
+            
+            rugcheck.analyze_security_risks(&response).display();
+            Ok(())
+        }
+        "pipeline" => {
+            let Some(mint) = args.get(3) else {
+                println!("❌ Usage: test pipeline <mint>");
+                return Ok(());
+            };
+            println!("🧪 Running the buy pipeline for {} (no transaction will be sent)...", mint);
+            
+            let wallet = match SolanaWallet::from_env() {
+                Ok(w) => w,
+                Err(e) => {
+                    println!("❌ Wallet setup failed (needed for the Jupiter trader): {}", e);
+                    return Ok(());
+                }
+            };
+            let telegram = TelegramNotifier::new(&settings.telegram.bot_token, &settings.telegram.chat_id);
+            let mut scanner = PoolScanner::new(settings.clone(), telegram, wallet, true)?;
+            scanner.test_pipeline(mint).await
+        }
         _ => {
             println!("❌ Unknown test command: {}", args[2]);
             Ok(())
@@ -226,7 +310,7 @@ async fn handle_test_commands(args: &[String], settings: &BotSettings) -> Result
 
 async fn handle_config_commands(args: &[String], settings: &BotSettings) -> Result<()> {
     if args.len() < 3 {
-        println!("❌ Config action required: show, validate, export, import");
+        println!("❌ Config action required: show, validate, export, import, schema");
         return Ok(());
     }
     
@@ -281,3 +365,108 @@ async fn handle_config_commands(args: &[String], settings: &BotSettings) -> Resu
         }
     }
 }
+
+/// `config schema [file]` - runs before settings are validated, so it works without a .env
+fn handle_schema_command(args: &[String]) -> Result<()> {
+    let settings = BotSettings::from_env().map_err(|e| anyhow::anyhow!("{}", e))?;
+    if let Some(filename) = args.get(3) {
+        if let Err(e) = std::fs::write(filename, settings_schema::env_template()) {
+            println!("❌ Template export failed: {}", e);
+            return Ok(());
+        }
+        println!("✅ Annotated .env template written to {}", filename);
+    } else {
+        println!("📋 Settings Schema (env var, type, default):");
+        for section in settings_schema::SETTINGS_SCHEMA {
+            println!("\n[{}]", section.name);
+            for setting in section.settings {
+                let default = if setting.default.is_empty() { "-" } else { setting.default };
+                println!("  {} ({}, default: {})", setting.env_var, setting.kind, default);
+                println!("      {}", setting.description);
+            }
+        }
+    }
+    for field in settings_schema::undocumented_settings(&settings) {
+        println!("⚠️  Settings schema is out of date: {}", field);
+    }
+    Ok(())
+}
+
+fn handle_history_commands(args: &[String], settings: &BotSettings) -> Result<()> {
+    if args.len() < 3 {
+        println!("❌ History action required: equity, trades");
+        return Ok(());
+    }
+    
+    match args[2].as_str() {
+        "equity" => {
+            if args.len() < 4 {
+                println!("❌ Filename required for equity export");
+                return Ok(());
+            }
+            let filename = &args[3];
+            let snapshots = portfolio_history::load_snapshots(&settings.monitoring.portfolio_history_path)?;
+            if snapshots.is_empty() {
+                println!("⚠️  No portfolio history recorded yet at {}", settings.monitoring.portfolio_history_path);
+                return Ok(());
+            }
+            std::fs::write(filename, portfolio_history::equity_csv(&snapshots))?;
+            println!("✅ Exported {} snapshots to {}", snapshots.len(), filename);
+            Ok(())
+        }
+        "trades" => {
+            // Optional mint and count, in either order
+            let mut mint = None;
+            let mut limit = 20;
+            for arg in args.iter().skip(3) {
+                match arg.parse::<usize>() {
+                    Ok(n) => limit = n,
+                    Err(_) => mint = Some(arg.as_str()),
+                }
+            }
+            let storage = storage::open_storage(settings)?;
+            let trades = storage.recent_trades(mint, limit)?;
+            if trades.is_empty() {
+                println!("⚠️  No trades recorded yet in {} storage at {}", storage.backend_name(), settings.monitoring.storage_path);
+                return Ok(());
+            }
+            for trade in &trades {
+                let time = chrono::DateTime::from_timestamp(trade.timestamp_unix_secs as i64, 0)
+                    .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+                    .unwrap_or_default();
+                let pnl = trade.pnl_sol.map(|pnl| format!(" ({:+.4} SOL)", pnl)).unwrap_or_default();
+                println!("   {} {:?} {:?} {} - {:.4} SOL, {} tokens{}",
+                    time, trade.kind, trade.side, trade.token_address, trade.sol_amount, trade.token_amount, pnl);
+            }
+            let stats = storage::trade_stats(&trades);
+            println!("🧾 {} buys, {} closed ({} winners) | realized P&L {:+.4} SOL",
+                stats.buys, stats.closes, stats.wins, stats.realized_pnl_sol);
+            Ok(())
+        }
+        _ => {
+            println!("❌ Unknown history action: {}", args[2]);
+            Ok(())
+        }
+    }
+}
+
+fn show_saved_positions(settings: &BotSettings) -> Result<()> {
+    let storage = storage::open_storage(settings)?;
+    let positions = storage.load_positions()?;
+    if positions.is_empty() {
+        println!("📭 No open positions saved in {} storage at {}", storage.backend_name(), settings.monitoring.storage_path);
+        return Ok(());
+    }
+    
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    let total_sol: f64 = positions.iter().map(|position| position.sol_amount).sum();
+    println!("🎯 {} open position(s), {:.4} SOL invested:", positions.len(), total_sol);
+    for position in &positions {
+        let held_secs = now.saturating_sub(position.purchase_unix_secs);
+        let remaining_secs = position.max_hold_secs.saturating_sub(held_secs);
+        println!("   💎 {} {:?} - {:.4} SOL for {} tokens, held {}m, auto-sell in {}m",
+            position.token_address, position.kind, position.sol_amount, position.estimated_tokens,
+            held_secs / 60, remaining_secs / 60);
+    }
+    Ok(())
+}
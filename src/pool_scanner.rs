@@ -1,16 +1,63 @@
 // Pool Scanner Module - Continuously scans for new Raydium/Orca pools
 use crate::settings::BotSettings;
-use crate::rugcheck::RugCheckClient;
-use crate::telegram::TelegramNotifier;
-use crate::wallet::SolanaWallet;
-use crate::jupiter_trader::{JupiterTrader, TradeResult};
-use crate::profit_monitor::ProfitMonitor;
+use crate::rugcheck::{creator_risk, RugCheckCriteria, load_creator_blacklist, RugCheckClient, RugCheckXyzClient, RugChecker, SecondaryCheckMode, secondary_check_passes, NameBlacklist, impersonated_ticker, is_avoided_pair_mint, meets_security_bar, security_bar_description, SecurityReport};
+use crate::telegram::{TelegramNotifier, ApprovalDecision, escape_html};
+use crate::wallet::{parse_commitment, SolanaWallet};
+use solana_sdk::commitment_config::CommitmentConfig;
+use crate::jupiter_trader::{JupiterTrader, JupiterEndpoints, round_trip_cost_percent, TradeResult, MinOutError, RouteCountError, NoRouteError, PriceImpactError, SellProbe, BuyInput, BuyCancelledError, QuoteUnavailableError};
+use crate::profit_monitor::{format_duration, safe_ratio};
+use crate::position_monitor::PositionMonitor;
+use crate::health::{HealthSnapshot, unix_secs};
+use crate::rpc_limiter::{RpcRateLimiter, RpcUsage};
+use crate::birdeye::BirdeyeClient;
+use crate::jito::JitoClient;
+use crate::private_relay::PrivateRelay;
+use crate::trade_journal::{TradeJournal, ReconcileAction, reconcile_action};
+use crate::daily_trades::{DailyTradeCounter, utc_day};
+use crate::compounding::{CompoundPolicy, PositionSizer};
+use crate::buy_slots::BuySlots;
+use crate::trading_hours::{HourRange, parse_trading_hours, within_trading_hours};
+use crate::lamports::Lamports;
+use crate::decision_trace::DecisionTrace;
+use crate::storage::{Storage, StoredPosition, TradeSide, open_storage};
+use crate::webhook::{WebhookEvent, WebhookNotifier};
+use crate::session_report::session_report;
+use crate::copy_trading::{WalletFollower, CopySignal, COPY_SOURCE_PREFIX, copy_size_multiplier};
+use crate::cancel::CancelSignal;
+use crate::sol_reserve::{ReserveBreachError, SolReserve};
+use crate::units::{sol_to_lamports, sol_to_usd, usd_to_sol};
 
 use serde::{Deserialize, Serialize};
-use std::collections::{HashSet, HashMap};
-use std::time::{Duration, Instant, SystemTime};
+use std::collections::{HashSet, HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use anyhow::Result;
 
+/// How a position came to exist - decides whether it is priced from the market and sold on-chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PositionKind {
+    Real,      // Bought on-chain via Jupiter
+    Paper,     // Dry run with DRY_RUN_PAPER_POSITIONS: real quote and market prices, no transaction
+    Simulated, // Jupiter unavailable: synthetic fill, priced at entry
+}
+
+impl PositionKind {
+    /// Priced from live market data (Simulated positions stay at their entry price)
+    pub fn market_priced(self) -> bool {
+        matches!(self, PositionKind::Real | PositionKind::Paper)
+    }
+
+    /// Holds tokens in the wallet, so exits send real sells
+    pub fn on_chain(self) -> bool {
+        self == PositionKind::Real
+    }
+}
+
+/// Saved positions a run restores: live runs take back their real positions, dry runs touch none
+fn restored_position_kind(dry_run: bool) -> Option<PositionKind> {
+    (!dry_run).then_some(PositionKind::Real)
+}
+
 #[derive(Debug, Clone)]
 pub struct TokenPosition {
     pub token_address: String,
@@ -18,16 +65,24 @@ pub struct TokenPosition {
     pub sol_amount: f64,
     pub estimated_tokens: u64,
     pub entry_price: f64,
-    pub trade_result: Option<TradeResult>, // Real trading result
+    pub kind: PositionKind,
+    pub trade_result: Option<TradeResult>, // Only set for PositionKind::Real
+    pub max_hold: Duration, // Auto-sell once held this long
+    pub timeout_extension: Duration, // Grace max_hold has gained from deferred timeout sells so far
+    pub sell_probe: Option<SellProbe>, // Post-buy sell simulation; None = not probed
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NewPool {
     pub token_address: String,
+    pub token_name: String,
+    pub token_symbol: String,
     pub pool_address: String,
     pub base_mint: String,
     pub quote_mint: String,
     pub liquidity_sol: f64,
+    pub price_usd: f64, // 0.0 when the source doesn't report a price
+    pub price_sol: f64, // SOL per whole token, 0.0 when unknown
     pub detected_at: std::time::SystemTime,
     pub dex: String, // "Raydium", "Orca", "Pump.fun/Jupiter", or "Sample/Testing"
 }
@@ -65,7 +120,7 @@ struct JupiterRecentResponse {
 struct DexScreenerResponse {
     #[serde(rename = "schemaVersion")]
     schema_version: String,
-    pairs: Vec<DexScreenerPair>,
+    pairs: Option<Vec<serde_json::Value>>, // Parsed one by one so a malformed pair can't sink the scan
 }
 
 #[derive(Debug, Deserialize)]
@@ -83,11 +138,11 @@ struct DexScreenerPair {
     #[serde(rename = "quoteToken")]
     quote_token: DexScreenerToken,
     #[serde(rename = "priceNative")]
-    price_native: String,
+    price_native: Option<String>, // Missing/null on brand-new pairs
     #[serde(rename = "priceUsd")]
-    price_usd: String,
-    liquidity: DexScreenerLiquidity,
-    volume: DexScreenerVolume,
+    price_usd: Option<String>,
+    liquidity: Option<DexScreenerLiquidity>,
+    volume: Option<DexScreenerVolume>,
     #[serde(rename = "pairCreatedAt")]
     pair_created_at: Option<u64>,
 }
@@ -101,30 +156,595 @@ struct DexScreenerToken {
 
 #[derive(Debug, Deserialize)]
 struct DexScreenerLiquidity {
-    usd: f64,
-    base: f64,
-    quote: f64,
+    usd: Option<f64>,
+    base: Option<f64>,
+    quote: Option<f64>,
 }
 
 #[derive(Debug, Deserialize)]
 struct DexScreenerVolume {
-    h24: f64,
-    h6: f64,
-    h1: f64,
-    m5: f64,
+    h24: Option<f64>,
+    h6: Option<f64>,
+    h1: Option<f64>,
+    m5: Option<f64>,
+}
+
+/// Deserialize each raw DexScreener pair on its own, keeping the well-formed ones.
+/// Returns the parsed pairs and how many were skipped as malformed.
+fn parse_dexscreener_pairs(raw_pairs: Vec<serde_json::Value>) -> (Vec<DexScreenerPair>, usize) {
+    let total = raw_pairs.len();
+    let pairs: Vec<DexScreenerPair> = raw_pairs
+        .into_iter()
+        .filter_map(|raw| serde_json::from_value(raw).ok())
+        .collect();
+    let skipped = total - pairs.len();
+    (pairs, skipped)
+}
+
+impl DexScreenerPair {
+    /// Pool liquidity in USD (0.0 when DexScreener doesn't report it)
+    fn liquidity_usd(&self) -> f64 {
+        self.liquidity.as_ref().and_then(|liquidity| liquidity.usd).unwrap_or(0.0)
+    }
+}
+
+/// Wrapped SOL mint - the only quote asset the buy route supports
+const WRAPPED_SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+/// Pick the token we'd be sniping out of a DexScreener pair.
+/// SOL is matched by mint address, not symbol, since anyone can name a token "SOL".
+/// Returns None when neither side (or both sides) is wrapped SOL.
+fn sniped_token(pair: &DexScreenerPair) -> Option<&DexScreenerToken> {
+    let base_is_sol = pair.base_token.address == WRAPPED_SOL_MINT;
+    let quote_is_sol = pair.quote_token.address == WRAPPED_SOL_MINT;
+
+    match (base_is_sol, quote_is_sol) {
+        (false, true) => Some(&pair.base_token),
+        (true, false) => Some(&pair.quote_token),
+        _ => None,
+    }
+}
+
+/// Whether a pair looks abandoned: zero volume over both the last 5 minutes and the last hour
+/// although it has been listed at least `min_age` (pairs of unknown age are judged on volume alone)
+fn pool_inactive(pair: &DexScreenerPair, age: Option<Duration>, min_age: Duration) -> bool {
+    if age.is_some_and(|age| age < min_age) {
+        return false; // Too new to have traded yet
+    }
+    let volume = pair.volume.as_ref();
+    let m5 = volume.and_then(|volume| volume.m5).unwrap_or(0.0);
+    let h1 = volume.and_then(|volume| volume.h1).unwrap_or(0.0);
+    m5 <= 0.0 && h1 <= 0.0
+}
+
+/// Pairs older than this are never candidates
+const MAX_CANDIDATE_AGE: Duration = Duration::from_secs(24 * 3600);
+
+/// A DexScreener search response -> this scan's candidate pools, with no network access.
+/// Malformed pairs are skipped (and counted in the log); `sol_price_usd` converts liquidity.
+fn dexscreener_pools(response: DexScreenerResponse, settings: &BotSettings, sol_price_usd: f64, now: SystemTime) -> Vec<NewPool> {
+    let (pairs, skipped) = parse_dexscreener_pairs(response.pairs.unwrap_or_default());
+    if skipped > 0 {
+        println!("⚠️  Skipped {} malformed DexScreener pair(s), processing {}", skipped, pairs.len());
+    }
+    dexscreener_candidates(pairs, settings, sol_price_usd, now)
+}
+
+/// Turn parsed DexScreener pairs into this scan's candidate pools. In order:
+/// 1. keep Solana pairs created within MAX_CANDIDATE_AGE of `now`; pairs with no creation time
+///    only when ALLOW_MISSING_CREATION_TIME is set, as unknown age
+/// 2. keep pairs with exactly one wrapped SOL side (the other side is the sniped token)
+/// 3. keep pairs with at least MIN_LIQUIDITY_SOL (at `sol_price_usd`) and, when SKIP_INACTIVE_POOLS
+///    is set, some trading
+/// 4. sort newest first (unknown ages last) and take MAX_CANDIDATES_PER_SCAN
+fn dexscreener_candidates(pairs: Vec<DexScreenerPair>, settings: &BotSettings, sol_price_usd: f64, now: SystemTime) -> Vec<NewPool> {
+    let max_age = MAX_CANDIDATE_AGE;
+    let min_liquidity_usd = sol_to_usd(settings.trading.min_liquidity_sol, sol_price_usd);
+    let max_candidates = settings.monitoring.max_candidates_per_scan as usize;
+    let allow_missing_creation_time = settings.monitoring.allow_missing_creation_time;
+    let inactive_after = settings.monitoring.skip_inactive_pools
+        .then(|| Duration::from_secs(settings.monitoring.inactive_pool_min_age_minutes * 60));
+
+    let mut qualifying: Vec<(DexScreenerPair, Option<SystemTime>)> = pairs.into_iter()
+        .filter(|pair| pair.chain_id == "solana")
+        .filter_map(|pair| match pair.pair_created_at {
+            Some(created_millis) => {
+                let created_at = UNIX_EPOCH + Duration::from_millis(created_millis);
+                (now.duration_since(created_at).unwrap_or_default() <= max_age).then_some((pair, Some(created_at)))
+            }
+            None if allow_missing_creation_time => {
+                println!("🕳️  Pair {} has no creation time - treating its age as unknown", pair.pair_address);
+                Some((pair, None))
+            }
+            None => None,
+        })
+        .filter(|(pair, _)| {
+            let routable = sniped_token(pair).is_some();
+            if !routable {
+                println!("⚠️  Skipping pair {} - no wrapped SOL side ({} / {})",
+                    pair.pair_address, pair.base_token.symbol, pair.quote_token.symbol);
+            }
+            routable
+        })
+        .filter(|(pair, _)| pair.liquidity_usd() >= min_liquidity_usd)
+        .filter(|(pair, created_at)| {
+            let age = created_at.map(|created_at| now.duration_since(created_at).unwrap_or_default());
+            let inactive = inactive_after.is_some_and(|min_age| pool_inactive(pair, age, min_age));
+            if inactive {
+                println!("💤 Skipping pair {} - no trades in the last hour despite {:.0} USD liquidity",
+                    pair.pair_address, pair.liquidity_usd());
+            }
+            !inactive
+        })
+        .collect();
+    qualifying.sort_by_key(|(_, created_at)| std::cmp::Reverse(*created_at));
+    qualifying.truncate(max_candidates);
+
+    qualifying.into_iter()
+        .map(|(pair, created_at)| {
+            let token = sniped_token(&pair).expect("pair was filtered on its sniped token");
+            let (token_address, token_name, token_symbol) = (token.address.clone(), token.name.clone(), token.symbol.clone());
+            let liquidity_usd = pair.liquidity_usd();
+            NewPool {
+                token_address,
+                token_name,
+                token_symbol,
+                price_sol: sniped_token_price_sol(&pair),
+                price_usd: pair.price_usd.as_deref().and_then(|price| price.parse().ok()).unwrap_or(0.0),
+                pool_address: pair.pair_address,
+                base_mint: pair.base_token.address,
+                quote_mint: pair.quote_token.address,
+                liquidity_sol: usd_to_sol(liquidity_usd, sol_price_usd),
+                detected_at: created_at.unwrap_or(now), // Unknown age counts from first sight, like Birdeye
+                dex: format!("DexScreener/{}", pair.dex_id),
+            }
+        })
+        .collect()
+}
+
+/// One Solana DexScreener pair trading a token
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TokenPool {
+    liquidity_usd: f64,
+    sol_paired: bool, // The other side is wrapped SOL
+}
+
+/// Every pair the DexScreener tokens endpoint lists for a token (malformed pairs dropped).
+/// The one fetch behind the pool spread checks, copy-trade pools and exit liquidity.
+async fn fetch_token_pairs(token_address: &str) -> Result<Vec<DexScreenerPair>> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()?;
+    let response = client
+        .get(format!("https://api.dexscreener.com/latest/dex/tokens/{}", token_address))
+        .header("Accept", "application/json")
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(anyhow::anyhow!("DexScreener API returned status: {}", response.status()));
+    }
+    let dex_response: DexScreenerResponse = response.json().await?;
+    let (pairs, skipped) = parse_dexscreener_pairs(dex_response.pairs.unwrap_or_default());
+    if skipped > 0 {
+        println!("   ⚠️  Skipped {} malformed pair(s)", skipped);
+    }
+    Ok(pairs)
+}
+
+/// Every Solana DexScreener pair trading the token, whatever it's quoted in
+async fn token_pools(token_address: &str) -> Result<Vec<TokenPool>> {
+    Ok(token_pools_in(&fetch_token_pairs(token_address).await?, token_address))
+}
+
+/// USD liquidity of the token's deepest Solana pool; Err when DexScreener lists none
+pub async fn deepest_pool_liquidity_usd(token_address: &str) -> Result<f64> {
+    token_pools(token_address).await?.iter()
+        .map(|pool| pool.liquidity_usd)
+        .max_by(f64::total_cmp)
+        .ok_or_else(|| anyhow::anyhow!("No DexScreener pools for {}", token_address))
+}
+
+fn token_pools_in(pairs: &[DexScreenerPair], token_address: &str) -> Vec<TokenPool> {
+    pairs.iter()
+        .filter(|pair| pair.chain_id == "solana")
+        .filter(|pair| pair.base_token.address == token_address || pair.quote_token.address == token_address)
+        .map(|pair| TokenPool {
+            liquidity_usd: pair.liquidity_usd(),
+            sol_paired: sniped_token(pair).is_some_and(|token| token.address == token_address),
+        })
+        .collect()
+}
+
+/// Why a token has too few SOL-paired pools to enter and exit through: Some when fewer than
+/// `min_pairs` SOL pairs hold any liquidity and none holds `primary_floor_usd` (0 = no such exemption)
+fn sol_pair_shortfall(pools: &[TokenPool], min_pairs: usize, primary_floor_usd: f64) -> Option<String> {
+    let sol_pairs: Vec<f64> = pools.iter()
+        .filter(|pool| pool.sol_paired && pool.liquidity_usd > 0.0)
+        .map(|pool| pool.liquidity_usd)
+        .collect();
+    if sol_pairs.len() >= min_pairs {
+        return None;
+    }
+    let deepest = sol_pairs.iter().copied().fold(0.0, f64::max);
+    if primary_floor_usd > 0.0 && deepest >= primary_floor_usd {
+        return None;
+    }
+    Some(format!("{} SOL pair(s), deepest ${:.0} (needs {} or one holding ${:.0})",
+        sol_pairs.len(), deepest, min_pairs, primary_floor_usd))
+}
+
+/// Why a token's liquidity is too fragmented to trade: Some when it's spread over more than
+/// `max_pools` pools and none of them holds `min_pool_usd`. A max of 0 disables the check.
+fn liquidity_fragmentation(pool_liquidities_usd: &[f64], max_pools: usize, min_pool_usd: f64) -> Option<String> {
+    if max_pools == 0 || pool_liquidities_usd.len() <= max_pools {
+        return None;
+    }
+    let deepest = pool_liquidities_usd.iter().copied().fold(0.0, f64::max);
+    (deepest < min_pool_usd).then(|| format!("{} pools, deepest ${:.0} (max {} pools unless one holds ${:.0})",
+        pool_liquidities_usd.len(), deepest, max_pools, min_pool_usd))
+}
+
+/// The token's deepest Solana SOL pair on DexScreener, shaped like a detected pool, with its USD liquidity
+async fn deepest_sol_pool(token_address: &str, sol_price_usd: f64) -> Result<Option<(NewPool, f64)>> {
+    let Some(pair) = fetch_token_pairs(token_address).await?.into_iter()
+        .filter(|pair| pair.chain_id == "solana" && sniped_token(pair).is_some_and(|token| token.address == token_address))
+        .max_by(|a, b| a.liquidity_usd().total_cmp(&b.liquidity_usd()))
+    else {
+        return Ok(None);
+    };
+    let token = sniped_token(&pair).expect("pair was filtered on its sniped token");
+    let liquidity_usd = pair.liquidity_usd();
+    let pool = NewPool {
+        token_address: token.address.clone(),
+        token_name: token.name.clone(),
+        token_symbol: token.symbol.clone(),
+        pool_address: pair.pair_address.clone(),
+        base_mint: pair.base_token.address.clone(),
+        quote_mint: pair.quote_token.address.clone(),
+        liquidity_sol: usd_to_sol(liquidity_usd, sol_price_usd),
+        price_usd: pair.price_usd.as_deref().and_then(|price| price.parse().ok()).unwrap_or(0.0),
+        price_sol: sniped_token_price_sol(&pair),
+        detected_at: pair.pair_created_at
+            .map(|created| UNIX_EPOCH + Duration::from_millis(created))
+            .unwrap_or_else(SystemTime::now),
+        dex: format!("DexScreener/{}", pair.dex_id),
+    };
+    Ok(Some((pool, liquidity_usd)))
+}
+
+/// Buys kept for the rolling time-to-fill / slippage averages
+const FILL_STATS_WINDOW: usize = 20;
+
+/// Average time-to-fill and realized slippage over recent buys; None before the first fill
+fn rolling_fill_averages(fills: &VecDeque<(Duration, f64)>) -> Option<(Duration, f64)> {
+    if fills.is_empty() {
+        return None;
+    }
+    let total_time: Duration = fills.iter().map(|(time, _)| *time).sum();
+    let total_slippage: f64 = fills.iter().map(|(_, slippage)| slippage).sum();
+    Some((total_time / fills.len() as u32, total_slippage / fills.len() as f64))
+}
+
+/// Pending pools are kept for at most this many scans' worth of processing
+const MAX_PENDING_SCANS: usize = 3;
+
+/// Split pools into the batch to process now (highest liquidity first, at most `cap`)
+/// and the remainder. A cap of 0 means unlimited.
+fn prioritize_pools(mut pools: Vec<NewPool>, cap: usize) -> (Vec<NewPool>, Vec<NewPool>) {
+    pools.sort_by(|a, b| b.liquidity_sol.total_cmp(&a.liquidity_sol));
+    if cap == 0 || pools.len() <= cap {
+        return (pools, Vec::new());
+    }
+    let remainder = pools.split_off(cap);
+    (pools, remainder)
+}
+
+/// Put a deferred pool back in the backlog at its liquidity rank, so the backlog stays
+/// highest-liquidity first and the deepest deferred pool is retried first
+fn defer_pool(pending: &mut Vec<NewPool>, pool: NewPool) {
+    let index = pending.partition_point(|queued| queued.liquidity_sol >= pool.liquidity_sol);
+    pending.insert(index, pool);
+}
+
+/// Hard minimum output for a trade: `expected` reduced by `tolerance_percent`.
+/// None when the floor is disabled (tolerance 0) or there's no usable expectation.
+pub fn min_out_floor(expected: f64, tolerance_percent: f64) -> Option<f64> {
+    if tolerance_percent <= 0.0 || !expected.is_finite() || expected <= 0.0 {
+        return None;
+    }
+    Some(expected * (1.0 - tolerance_percent.min(100.0) / 100.0))
+}
+
+/// Rent-exempt deposit for the token account a first buy creates
+const TOKEN_ACCOUNT_RENT: Lamports = Lamports::new(2_039_280);
+const SIGNATURE_FEE: Lamports = Lamports::new(5_000);
+
+/// Worst-case SOL a buy costs on top of the swap itself: signature fees, priority fee, Jito/relay tip and token account rent
+fn estimated_buy_overhead_sol(settings: &BotSettings) -> f64 {
+    let premium = &settings.apis.premium_endpoints;
+    let (signature_fees, jito_tip) = if premium.jito_enabled {
+        (SIGNATURE_FEE + SIGNATURE_FEE, premium.jito_tip()) // Swap + tip transaction
+    } else {
+        (SIGNATURE_FEE, Lamports::ZERO)
+    };
+    let overhead = signature_fees + settings.wallet.compute_budget_fee() + jito_tip + premium.private_tx_tip() + TOKEN_ACCOUNT_RENT;
+    overhead.as_sol()
+}
+
+/// Fees one swap pays on chain: the buy overhead without the token account rent
+pub fn estimated_swap_fee_sol(settings: &BotSettings) -> f64 {
+    estimated_buy_overhead_sol(settings) - TOKEN_ACCOUNT_RENT.as_sol()
+}
+
+/// How long until another buy is allowed, given the last one; None once `min_gap` has passed
+/// (or there was no buy yet)
+fn buy_spacing_wait(last_buy: Option<Instant>, now: Instant, min_gap: Duration) -> Option<Duration> {
+    let elapsed = now.saturating_duration_since(last_buy?);
+    (elapsed < min_gap).then(|| min_gap - elapsed)
+}
+
+/// Why an indexer's SOL-side liquidity and the pool's on-chain SOL reserves can't both be right:
+/// Some when they differ by more than `max_factor` either way (a zero reserve always disagrees)
+fn liquidity_disagreement(reported_sol: f64, onchain_sol: f64, max_factor: f64) -> Option<String> {
+    if onchain_sol <= 0.0 {
+        return Some(format!("indexer reports {:.2} SOL but the pool holds none", reported_sol));
+    }
+    let factor = reported_sol.max(onchain_sol) / reported_sol.min(onchain_sol).max(f64::MIN_POSITIVE);
+    (factor > max_factor).then(|| format!("indexer reports {:.2} SOL, pool holds {:.2} SOL ({:.1}x apart, max {}x)",
+        reported_sol, onchain_sol, factor, max_factor))
+}
+
+/// Buy size that leaves `reserve` plus `overhead` in the wallet: `desired` shrunk to what's spendable.
+/// None when even `floor` doesn't fit.
+fn fit_position_size(desired: f64, balance: f64, reserve: f64, overhead: f64, floor: f64) -> Option<f64> {
+    let size = desired.min(balance - reserve - overhead);
+    if size < floor || size <= 0.0 {
+        None
+    } else {
+        Some(size)
+    }
+}
+
+/// SOL price of the sniped token, from DexScreener's priceNative (quoted in the pair's quote token).
+/// Inverted when the sniped token sits on the quote side. 0.0 when unknown.
+fn sniped_token_price_sol(pair: &DexScreenerPair) -> f64 {
+    let price_native: f64 = pair.price_native.as_deref().and_then(|price| price.parse().ok()).unwrap_or(0.0);
+    if price_native <= 0.0 {
+        return 0.0;
+    }
+    if pair.quote_token.address == WRAPPED_SOL_MINT {
+        price_native
+    } else {
+        1.0 / price_native
+    }
+}
+
+/// Decide whether a pool changed too much during the entry delay to still buy.
+/// Any liquidity drop aborts; a price rise aborts once it exceeds `max_price_rise_percent`.
+/// A missing initial price (0.0) skips the price check.
+fn entry_delay_abort_reason(
+    initial_liquidity_sol: f64,
+    initial_price_usd: f64,
+    current_liquidity_sol: f64,
+    current_price_usd: f64,
+    max_price_rise_percent: f64,
+) -> Option<String> {
+    if current_liquidity_sol < initial_liquidity_sol {
+        return Some(format!("liquidity dropped {:.2} → {:.2} SOL", initial_liquidity_sol, current_liquidity_sol));
+    }
+    
+    if initial_price_usd > 0.0 {
+        let price_rise_percent = (current_price_usd - initial_price_usd) / initial_price_usd * 100.0;
+        if price_rise_percent > max_price_rise_percent {
+            return Some(format!("price rose {:.1}% (max: {:.1}%)", price_rise_percent, max_price_rise_percent));
+        }
+    }
+    
+    None
+}
+
+/// Entry price limit for a mint: its own TOKEN_MAX_ENTRY_PRICES_USD entry, else MAX_ENTRY_PRICE_USD
+/// (None when neither is set)
+fn max_entry_price_for(token_address: &str, per_token: &HashMap<String, f64>, global_usd: f64) -> Option<f64> {
+    per_token.get(token_address)
+        .copied()
+        .or((global_usd > 0.0).then_some(global_usd))
+}
+
+/// Why a buy at `price_usd` breaks the entry price limit, if it does. An unknown price (0.0)
+/// can't be shown to be under the limit, so it is rejected too.
+fn entry_price_rejection(price_usd: f64, max_entry_price_usd: f64) -> Option<String> {
+    if price_usd <= 0.0 {
+        return Some(format!("price unknown (limit ${})", max_entry_price_usd));
+    }
+    (price_usd > max_entry_price_usd)
+        .then(|| format!("price ${} is above the ${} limit", price_usd, max_entry_price_usd))
+}
+
+/// Why a token's true age (since its first mint transaction) is out of range, if it is.
+/// Limits of 0 are disabled.
+fn token_age_rejection(token_age: Duration, min_age_minutes: u64, max_age_minutes: u64) -> Option<String> {
+    let age_minutes = token_age.as_secs() / 60;
+    if min_age_minutes > 0 && age_minutes < min_age_minutes {
+        return Some(format!("token is {}m old (min: {}m)", age_minutes, min_age_minutes));
+    }
+    if max_age_minutes > 0 && age_minutes > max_age_minutes {
+        return Some(format!("token is {}m old (max: {}m) - new pool for an old token", age_minutes, max_age_minutes));
+    }
+    None
+}
+
+/// Signals-only alert for a token that passed every check. Names and symbols come from the token's
+/// creator, so every value is escaped for the HTML message; `links_html` is already markup.
+fn signal_message(pool: &NewPool, security_summary: &str, links_html: &str) -> String {
+    format!(
+        "📡 <b>BUY SIGNAL</b>\n💎 {} ({})\n📍 <code>{}</code>\n🏊 {} ({:.2} SOL liquidity)\n💲 Price: ${}\n{}\n{}",
+        escape_html(&pool.token_name),
+        escape_html(&pool.token_symbol),
+        escape_html(&pool.token_address),
+        escape_html(&pool.dex),
+        pool.liquidity_sol,
+        pool.price_usd,
+        escape_html(security_summary),
+        links_html
+    )
+}
+
+/// Manual approval prompt for a buy of `sol_amount` SOL. Like `signal_message`, every token value
+/// is escaped: a name with `<` or `&` would otherwise make Telegram refuse the prompt.
+fn approval_message(pool: &NewPool, security_summary: &str, sol_amount: f64, timeout: Duration) -> String {
+    format!(
+        "🙋 <b>APPROVE BUY?</b>\n💎 {} ({})\n📍 <code>{}</code>\n🏊 {} ({:.2} SOL liquidity)\n💰 Amount: {:.4} SOL\n{}\n⏳ Auto-reject in {}s",
+        escape_html(&pool.token_name),
+        escape_html(&pool.token_symbol),
+        escape_html(&pool.token_address),
+        escape_html(&pool.dex),
+        pool.liquidity_sol,
+        sol_amount,
+        escape_html(security_summary),
+        timeout.as_secs()
+    )
+}
+
+/// Alert for a token reusing a known ticker; the ticker is the creator's, so it's escaped
+fn impersonator_message(pool: &NewPool, canonical_mint: &str) -> String {
+    format!(
+        "🎭 <b>IMPERSONATOR REJECTED</b>\n💎 Token: <code>{}</code>\n🏷️ Ticker: {}\n✅ Canonical mint: <code>{}</code>",
+        escape_html(&pool.token_address),
+        escape_html(&pool.token_symbol),
+        escape_html(canonical_mint)
+    )
+}
+
+/// What the RugCheck step makes of a token's score and report
+#[derive(Debug, Clone, PartialEq)]
+enum SecurityVerdict {
+    Pass,
+    BelowBar(String), // The bar it missed, in words
+    CriticalRisks(Vec<String>),
+}
+
+/// Judge a RugCheck result: the score must clear the security bar, then no critical risk may remain
+fn security_verdict(score: u8, report: &SecurityReport, min_acceptable_score: u8, require_all_criteria: bool) -> SecurityVerdict {
+    if !meets_security_bar(score, min_acceptable_score, require_all_criteria) {
+        return SecurityVerdict::BelowBar(security_bar_description(min_acceptable_score, require_all_criteria));
+    }
+    if !report.critical_risks.is_empty() {
+        return SecurityVerdict::CriticalRisks(report.critical_risks.clone());
+    }
+    SecurityVerdict::Pass
+}
+
+/// The primary provider's bar as (min score, all criteria) - waived in combined mode, where
+/// SECONDARY_RUGCHECK_MIN_SCORE on the two providers' average takes its place
+fn primary_security_bar(min_acceptable_score: u8, require_all_criteria: bool, secondary_mode: Option<SecondaryCheckMode>) -> (u8, bool) {
+    if secondary_mode.is_some_and(SecondaryCheckMode::replaces_primary_bar) {
+        (0, false)
+    } else {
+        (min_acceptable_score, require_all_criteria)
+    }
+}
+
+/// What the second security provider made of a token
+#[derive(Debug, Clone, PartialEq)]
+enum SecondOpinion {
+    Agree(u8),
+    Disagree { score: u8, high_risks: Vec<String> },
+    Unavailable(String),
+}
+
+/// Ask `secondary` about a token the primary provider scored `primary_score`
+async fn second_opinion(secondary: &dyn RugChecker, token_address: &str, mode: SecondaryCheckMode, primary_score: u8, min_score: u8) -> SecondOpinion {
+    match secondary.check_token(token_address).await {
+        Ok(response) if secondary_check_passes(mode, primary_score, response.score, min_score) => SecondOpinion::Agree(response.score),
+        Ok(response) => SecondOpinion::Disagree {
+            score: response.score,
+            high_risks: response.risks.into_iter()
+                .filter(|risk| risk.severity == "HIGH")
+                .map(|risk| risk.description)
+                .collect(),
+        },
+        Err(e) => SecondOpinion::Unavailable(e.to_string()),
+    }
+}
+
+/// Hold time for a position from a detection source: the override with the longest source
+/// prefix matching `dex` (case-insensitive), otherwise the global `max_hold_time_hours`
+fn max_hold_for_source(dex: &str, overrides: &HashMap<String, u64>, default_hours: u32) -> Duration {
+    let dex = dex.to_lowercase();
+    overrides.iter()
+        .map(|(source, minutes)| (source.to_lowercase(), minutes))
+        .filter(|(source, _)| dex.starts_with(source.as_str()))
+        // Ties (same prefix in different case) go to the smaller hold, whatever the map order
+        .max_by(|(a, a_minutes), (b, b_minutes)| a.len().cmp(&b.len()).then(b_minutes.cmp(a_minutes)))
+        .map(|(_, minutes)| Duration::from_secs(minutes * 60))
+        .unwrap_or(Duration::from_secs(default_hours as u64 * 3600))
+}
+
+/// Delay before the next scan after `consecutive_failures` errors in a row:
+/// base, 2x base, 4x base, ... capped at `max_ms`
+fn scan_error_backoff(consecutive_failures: u32, base_ms: u64, max_ms: u64) -> Duration {
+    let exponent = consecutive_failures.saturating_sub(1).min(16);
+    Duration::from_millis(base_ms.saturating_mul(1 << exponent).min(max_ms))
+}
+
+/// Why a token with `holders` holders is skipped, if it has fewer than `min_holders`
+fn too_few_holders(holders: u32, min_holders: u32) -> Option<String> {
+    (holders < min_holders).then(|| format!("{} holders below {} minimum", holders, min_holders))
+}
+
+/// Whether a polled source is due, given when it last ran (None = never ran)
+fn source_due(last_run: Option<Instant>, interval: Duration, now: Instant) -> bool {
+    time_until_due(last_run, interval, now).is_zero()
+}
+
+/// How long until a polled source is due again (zero if already due)
+fn time_until_due(last_run: Option<Instant>, interval: Duration, now: Instant) -> Duration {
+    match last_run {
+        Some(last_run) => interval.saturating_sub(now.saturating_duration_since(last_run)),
+        None => Duration::ZERO,
+    }
 }
 
 pub struct PoolScanner {
     settings: BotSettings,
-    rugcheck_client: RugCheckClient,
+    rugcheck_client: Box<dyn RugChecker>,
+    secondary_rug_checker: Option<(SecondaryCheckMode, Box<dyn RugChecker>)>, // rugcheck.xyz second opinion (SECONDARY_RUGCHECK_MODE)
+    birdeye: Option<BirdeyeClient>,
     telegram: TelegramNotifier,
     wallet: SolanaWallet,
-    jupiter_trader: JupiterTrader,
-    profit_monitor: ProfitMonitor,
-    processed_pools: HashSet<String>,
-    active_positions: HashMap<String, TokenPosition>,
+    jupiter_trader: Arc<JupiterTrader>, // Shared with the position monitor for sells
+    trade_journal: TradeJournal,
+    daily_trades: DailyTradeCounter,
+    position_sizer: Option<PositionSizer>, // COMPOUND_ENABLED: buy size grown from realized profits
+    trading_hours: Vec<HourRange>, // TRADING_HOURS windows; empty = buy at any hour
+    buy_slots: BuySlots, // MAX_CONCURRENT_BUYS: buys in flight at once
+    daily_cap_alerted_day: Option<u64>, // One cap alert per UTC day
+    position_monitor: PositionMonitor, // Open positions, P&L and the auto-sell checks
+    name_blacklist: NameBlacklist,
+    creator_blacklist: HashSet<String>, // Known rugger wallets (CREATOR_BLACKLIST_PATH)
+    decision_trace: Option<DecisionTrace>, // Per-pool buy/skip reasons, when DECISION_TRACE is on
+    storage: Arc<dyn Storage>, // Processed pools, open positions and trade history (STORAGE_BACKEND)
+    processed_pools: HashSet<String>, // Loaded from storage so a restart doesn't re-evaluate old pools
+    webhook: Option<WebhookNotifier>, // WEBHOOK_URL: rejects are POSTed here, trades via the position monitor
+    wallet_follower: Option<WalletFollower>, // COPY_TRADE_WALLETS: followed wallets' buys become pools
+    last_copy_poll: Option<Instant>,
+    pending_pools: Vec<NewPool>, // Over the per-scan cap, highest liquidity first
     last_scan_time: Instant,
+    started_at: SystemTime, // Trades recorded since then make up the shutdown summary
     scan_count: u64,
+    dry_run: bool, // Never send transactions or write storage; would-be buys are logged (paper positions on opt-in)
+    shutdown: CancelSignal, // Raised on Ctrl-C; also aborts buys not yet sent
+    rpc_limiter: RpcRateLimiter,
+    last_pool_scan: Option<Instant>,
+    last_position_check: Option<Instant>,
+    last_health_save: Option<Instant>,
+    last_scan_completed: Option<SystemTime>,
+    consecutive_errors: HashMap<String, u32>,
+    token_creation_times: HashMap<String, u64>, // Mint -> unix secs of first transaction (never changes)
+    recent_fills: VecDeque<(Duration, f64)>, // (time-to-fill, realized slippage %) of the last FILL_STATS_WINDOW buys
+    last_buy_at: Option<Instant>,
+    last_buy_by_source: HashMap<String, Instant>, // Detection source (pool.dex) -> its last buy
 }
 
 impl PoolScanner {
@@ -132,172 +752,681 @@ impl PoolScanner {
         settings: BotSettings,
         telegram: TelegramNotifier,
         wallet: SolanaWallet,
+        dry_run: bool,
+    ) -> Result<Self> {
+        Self::with_rug_checker(settings, telegram, wallet, dry_run, None)
+    }
+
+    /// Like `new`, but with a custom security backend (None = RugCheckClient from settings)
+    pub fn with_rug_checker(
+        settings: BotSettings,
+        telegram: TelegramNotifier,
+        mut wallet: SolanaWallet,
+        dry_run: bool,
+        rug_checker: Option<Box<dyn RugChecker>>,
     ) -> Result<Self> {
+        // One bucket for every RPC consumer so bursts stay inside the provider's quota
+        let rpc_limiter = RpcRateLimiter::new(settings.wallet.rpc_max_requests_per_second);
+        wallet.set_rpc_limiter(rpc_limiter.clone());
+        // Guards read balances at one configurable level so a just-confirmed trade is already visible
+        let balance_commitment = parse_commitment(&settings.wallet.balance_commitment).unwrap_or_else(CommitmentConfig::confirmed);
+        wallet.set_commitment(balance_commitment);
+
+        let trade_journal = TradeJournal::new(&settings.monitoring.trade_journal_path);
+        let daily_trades = DailyTradeCounter::load(&settings.monitoring.daily_trades_path);
+        let trading_hours = parse_trading_hours(&settings.trading.trading_hours)
+            .map_err(|e| anyhow::anyhow!("Invalid TRADING_HOURS: {}", e))?;
+        let buy_slots = BuySlots::new(settings.trading.max_concurrent_buys);
+        let position_sizer = settings.trading.compound_enabled
+            .then(|| PositionSizer::load(&settings.monitoring.compound_state_path, CompoundPolicy::from_settings(&settings)));
+        let storage = open_storage(&settings)?;
+        let processed_pools = storage.load_processed_pools().unwrap_or_else(|e| {
+            println!("⚠️  Failed to load processed pools: {}", e);
+            HashSet::new()
+        });
+
         // Initialize Jupiter trader for real trading
+        let shutdown = CancelSignal::new();
         let jupiter_trader = JupiterTrader::new(
             &settings.wallet.rpc_url,
             &settings.wallet.private_key,
-        )?;
+        )?
+        .with_endpoints(JupiterEndpoints::from_settings(&settings))
+        .with_rpc_limiter(rpc_limiter.clone())
+        .with_balance_commitment(balance_commitment)
+        .with_trade_journal(trade_journal.clone())
+        .with_buy_cancel(shutdown.clone())
+        .with_min_routes(settings.trading.min_routes as usize)
+        .with_price_impact_limit(settings.trading.max_price_impact_percent, settings.trading.requote_before_send_min_sol)
+        .with_jito(JitoClient::from_settings(&settings))
+        .with_private_relay(PrivateRelay::from_settings(&settings))
+        .with_slippage_escalation(
+            settings.trading.initial_slippage_percent,
+            settings.trading.max_slippage_percent,
+            settings.trading.slippage_escalation_attempts,
+        )
+        .with_platform_fee(settings.apis.jupiter_fee_account.clone(), settings.apis.jupiter_platform_fee_bps)
+        .with_compute_budget(settings.wallet.compute_unit_limit, settings.wallet.compute_unit_price)
+        .with_sell_compute_unit_price(settings.wallet.sell_priority_fee_micro_lamports)
+        .with_confirmation(settings.trading.confirm_attempts, Duration::from_millis(settings.wallet.confirmation_timeout_ms))
+        .with_quote_grace(settings.trading.quote_attempts, Duration::from_millis(settings.trading.quote_retry_delay_ms))
+        .with_no_route_retry(
+            Duration::from_secs(settings.trading.no_route_retry_secs),
+            Duration::from_millis(settings.trading.no_route_retry_interval_ms),
+        )
+        .with_sell_mint(settings.trading.sell_to_funding_mint.then(|| settings.trading.funding_mint.clone()))
+        .with_persistent_wsol(
+            settings.wallet.persistent_wsol.then(|| Lamports::from_sol(settings.wallet.wsol_top_up_sol)),
+            // Top-ups leave the reserve plus one buy's fees and rent in native SOL
+            Lamports::from_sol(settings.wallet.reserve_sol() + estimated_buy_overhead_sol(&settings)),
+        )
+        .with_sol_reserve(
+            SolReserve::new(Lamports::from_sol(settings.wallet.reserve_sol())),
+            Lamports::from_sol(estimated_buy_overhead_sol(&settings)),
+        );
+
+        // Position book + profit monitor, shared with the monitoring task when it runs separately
+        let jupiter_trader = Arc::new(jupiter_trader);
+        let webhook = WebhookNotifier::from_settings(&settings);
+        let position_monitor = PositionMonitor::new(settings.clone(), telegram.clone(), jupiter_trader.clone())
+            .with_webhook(webhook.clone());
+        // Dry runs never write positions or trades, so a live run's saved book stays intact
+        let position_monitor = if dry_run { position_monitor } else { position_monitor.with_storage(storage.clone()) };
+
+        let creator_blacklist = if settings.security.enable_creator_checks {
+            load_creator_blacklist(&settings.security.creator_blacklist_path)
+        } else {
+            HashSet::new()
+        };
+
+        let name_blacklist = NameBlacklist::new(&settings.security.name_blacklist_patterns)
+            .map_err(|e| anyhow::anyhow!("Invalid name blacklist pattern: {}", e))?;
+
+        let rugcheck_client = rug_checker.unwrap_or_else(|| Box::new(
+            RugCheckClient::new()
+                .with_auto_buy_criteria(RugCheckCriteria::from_env())
+                .with_fetch_retries(
+                    settings.security.rugcheck_fetch_max_attempts,
+                    settings.security.rugcheck_fetch_retry_delay_ms,
+                )
+                .with_rpc_limiter(rpc_limiter.clone())
+                .with_production_mode(settings.security.production_mode)
+                .with_score_weights(settings.security.score_weights()),
+        ));
+
+        let secondary_rug_checker = SecondaryCheckMode::parse(&settings.security.secondary_rugcheck_mode)
+            .filter(|&mode| mode != SecondaryCheckMode::Off)
+            .map(|mode| {
+                let timeout = Duration::from_millis(settings.apis.rugcheck_timeout_ms);
+                let checker: Box<dyn RugChecker> = Box::new(RugCheckXyzClient::new(&settings.apis.rugcheck_api_url, timeout));
+                (mode, checker)
+            });
 
-        // Initialize profit monitor with Telegram integration
-        let profit_monitor = ProfitMonitor::new(settings.clone(), telegram.clone());
+        let wallet_follower = (!settings.trading.copy_trade_wallets.is_empty()).then(|| {
+            let poll_interval = Duration::from_millis(settings.trading.copy_trade_poll_interval_ms);
+            WalletFollower::new(&settings.wallet.rpc_url, settings.trading.copy_trade_wallets.clone(), poll_interval)
+                .with_rpc_limiter(rpc_limiter.clone())
+        });
 
         Ok(Self {
-            rugcheck_client: RugCheckClient::new(),
+            rugcheck_client,
+            secondary_rug_checker,
+            birdeye: BirdeyeClient::from_settings(&settings),
+            decision_trace: DecisionTrace::from_settings(&settings),
             settings,
             telegram,
             wallet,
             jupiter_trader,
-            profit_monitor,
-            processed_pools: HashSet::new(),
-            active_positions: HashMap::new(),
+            trade_journal,
+            daily_trades,
+            position_sizer,
+            trading_hours,
+            buy_slots,
+            daily_cap_alerted_day: None,
+            position_monitor,
+            name_blacklist,
+            creator_blacklist,
+            storage,
+            processed_pools,
+            webhook,
+            wallet_follower,
+            last_copy_poll: None,
+            pending_pools: Vec::new(),
             last_scan_time: Instant::now(),
+            started_at: SystemTime::now(),
             scan_count: 0,
+            dry_run,
+            shutdown,
+            rpc_limiter,
+            last_pool_scan: None,
+            last_position_check: None,
+            last_health_save: None,
+            last_scan_completed: None,
+            consecutive_errors: HashMap::new(),
+            token_creation_times: HashMap::new(),
+            recent_fills: VecDeque::with_capacity(FILL_STATS_WINDOW),
+            last_buy_at: None,
+            last_buy_by_source: HashMap::new(),
         })
     }
 
     /// Main continuous scanning loop
     pub async fn start_continuous_scan(&mut self) -> Result<()> {
         println!("🔍 Starting continuous pool scanning...");
-        println!("⚙️  Scan interval: {}ms", self.settings.monitoring.price_check_interval_ms);
-        println!("🎯 Target score: {}/100", self.settings.security.min_acceptable_score);
+        println!("⚙️  Scan interval: {}ms (DexScreener), {}ms (positions)",
+            self.settings.monitoring.dexscreener_interval_ms, self.settings.monitoring.price_check_interval_ms);
+        println!("🎯 Security bar: {}", security_bar_description(self.settings.security.min_acceptable_score, self.settings.security.require_all_criteria));
         println!("💰 Position size: {:.4} SOL", self.settings.trading.position_size_sol);
+        if self.dry_run {
+            if self.settings.trading.dry_run_paper_positions {
+                println!("🧪 DRY RUN: no transactions will be sent, would-be buys are tracked as paper positions (not saved)");
+            } else {
+                println!("🧪 DRY RUN: no transactions will be sent, would-be buys are only logged");
+            }
+        }
         
         // Send start notification
         if self.settings.telegram.notifications_enabled {
-            if let Err(e) = self.telegram.send_message("🤖 Pool scanner started! Monitoring for new tokens...").await {
+            let start_msg = if self.dry_run {
+                "🤖 Pool scanner started in DRY RUN mode! Logging would-be buys only..."
+            } else {
+                "🤖 Pool scanner started! Monitoring for new tokens..."
+            };
+            if let Err(e) = self.telegram.send_message(start_msg).await {
                 println!("⚠️  Telegram notification failed: {} (continuing anyway)", e);
             }
         }
 
+        self.restore_health_snapshot().await;
+        self.restore_positions().await;
+        self.position_monitor.restore_sell_cooldowns().await;
+        self.reconcile_trade_journal().await;
+
+        // Either a dedicated task checks positions, or the loop below does between scans
+        let position_interval = Duration::from_millis(self.settings.monitoring.price_check_interval_ms);
+        let monitor_task = self.settings.monitoring.concurrent_position_monitoring
+            .then(|| self.position_monitor.clone().spawn(position_interval));
+
+        // Ctrl-C is heard mid-scan too, so a buy in flight can be called off before it's sent
+        let shutdown = self.shutdown.clone();
+        tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move {
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    shutdown.cancel();
+                }
+            }
+        });
+
+        let mut found_new_pools = false;
+        
         loop {
-            let scan_start = Instant::now();
-            let mut found_new_pools = false;
+            let now = Instant::now();
             
-            match self.scan_for_new_pools().await {
-                Ok(new_pools) => {
-                    found_new_pools = !new_pools.is_empty();
-                    
-                    if found_new_pools {
-                        println!("🆕 Found {} new pools", new_pools.len());
+            // Use faster intervals for real-time token detection
+            let pool_scan_interval = self.pool_scan_interval(found_new_pools);
+            
+            // Pool discovery (DexScreener) runs on its own schedule
+            if source_due(self.last_pool_scan, pool_scan_interval, now) {
+                self.last_pool_scan = Some(now);
+                
+                match self.scan_for_new_pools().await {
+                    Ok(new_pools) => {
+                        found_new_pools = !new_pools.is_empty();
+                        
+                        if found_new_pools {
+                            println!("🆕 Found {} new pools", new_pools.len());
+                        }
+                        
+                        // Outside the trading window the backlog waits for it to open
+                        if self.within_trading_hours() {
+                            let batch = self.next_pool_batch(new_pools);
+                            found_new_pools |= !self.pending_pools.is_empty(); // Stay aggressive while a backlog remains
+                            for pool in batch {
+                                if let Err(e) = self.process_new_pool(pool).await {
+                                    println!("❌ Error processing pool: {}", e);
+                                }
+                            }
+                        }
                         
-                        for pool in new_pools {
-                            if let Err(e) = self.process_new_pool(pool).await {
-                                println!("❌ Error processing pool: {}", e);
+                        self.scan_count += 1;
+                        self.last_scan_completed = Some(SystemTime::now());
+                        self.record_success("scan").await;
+                        if self.scan_count % 100 == 0 {
+                            let rpc_usage = self.rpc_limiter.usage();
+                            println!("📊 Completed {} scans, {} pools processed | RPC: {} requests, {} throttled ({}ms waiting)",
+                                self.scan_count, self.processed_pools.len(),
+                                rpc_usage.total_requests, rpc_usage.throttled_requests, rpc_usage.total_wait_ms);
+                            if let Some((avg_fill, avg_slippage)) = rolling_fill_averages(&self.recent_fills) {
+                                println!("⏱️  Last {} buys: {:.2}s avg time-to-fill, {:.2}% avg slippage vs quote",
+                                    self.recent_fills.len(), avg_fill.as_secs_f64(), avg_slippage);
                             }
                         }
+                        
+                        // Display active positions status periodically
+                        if self.scan_count % 10 == 0 {
+                            self.position_monitor.display_position_status();
+                            if let Some(trace) = self.decision_trace.as_mut() {
+                                trace.check_watched_mints().await;
+                            }
+                        }
+                        
+                        let scan_duration = now.elapsed();
+                        if scan_duration > pool_scan_interval {
+                            // Scan took longer than target - log performance
+                            println!("⚡ Performance: Scan took {:.2}s (target: {:.2}s) - optimizing for speed", 
+                                scan_duration.as_secs_f64(), pool_scan_interval.as_secs_f64());
+                        }
                     }
-                    
-                    self.scan_count += 1;
-                    if self.scan_count % 100 == 0 {
-                        println!("📊 Completed {} scans, {} pools processed", self.scan_count, self.processed_pools.len());
+                    Err(e) => {
+                        println!("⚠️  Scan error: {}", e);
+                        self.record_error("scan", &e.to_string()).await;
+                        
+                        // Back off exponentially (via pool_scan_interval) while positions keep being checked
+                        let failures = self.consecutive_errors.get("scan").copied().unwrap_or(0);
+                        println!("⏳ Next scan in {:.1}s after {} consecutive failures", 
+                            self.pool_scan_interval(false).as_secs_f64(), failures);
+                        
+                        if failures == self.settings.monitoring.scan_degraded_alert_threshold {
+                            self.send_scanner_degraded_alert(failures, &e.to_string()).await;
+                        }
                     }
                 }
-                Err(e) => {
-                    println!("⚠️  Scan error: {}", e);
-                    
-                    // Wait longer on error to avoid spam
-                    tokio::time::sleep(Duration::from_millis(5000)).await;
-                    continue;
-                }
             }
 
-            // Monitor positions for 30-minute auto-sell
-            if let Err(e) = self.monitor_position_timeouts().await {
-                println!("⚠️  Position timeout monitoring error: {}", e);
+            // Inline position monitoring runs on the price-check schedule
+            if monitor_task.is_none() && source_due(self.last_position_check, position_interval, now) {
+                self.last_position_check = Some(now);
+                self.position_monitor.run_checks().await;
             }
 
-            // Update profit monitoring (only runs periodic updates, doesn't restart)
-            if !self.active_positions.is_empty() {
-                if let Err(e) = self.update_profit_monitoring().await {
-                    println!("⚠️  Profit monitoring error: {}", e);
-                }
+            // Copy trading: followed wallets' buys, on their own schedule
+            let copy_interval = Duration::from_millis(self.settings.trading.copy_trade_poll_interval_ms);
+            if self.wallet_follower.is_some() && source_due(self.last_copy_poll, copy_interval, now) {
+                self.last_copy_poll = Some(now);
+                self.process_copy_signals().await;
             }
 
-            // Monitor existing positions
-            if let Err(e) = self.monitor_existing_positions().await {
-                println!("⚠️  Position monitoring error: {}", e);
+            // Persist health snapshot periodically
+            let health_interval = Duration::from_secs(self.settings.monitoring.health_snapshot_interval_secs);
+            if source_due(self.last_health_save, health_interval, now) {
+                self.last_health_save = Some(now);
+                self.save_health_snapshot().await;
             }
 
-            // Calculate sleep time for AGGRESSIVE scanning (real-time focus)
-            let scan_duration = scan_start.elapsed();
-            
-            // Use faster intervals for real-time token detection
-            let base_interval = Duration::from_millis(self.settings.monitoring.price_check_interval_ms);
-            let aggressive_interval = Duration::from_millis(500); // 0.5 second for new pools
-            let target_interval = if found_new_pools {
-                aggressive_interval // Faster scanning when activity detected
-            } else {
-                base_interval // Normal interval when no new pools
+            // Sleep until whichever source is due next
+            let pool_scan_interval = self.pool_scan_interval(found_new_pools);
+            let position_due = match monitor_task {
+                Some(_) => Duration::MAX,
+                None => time_until_due(self.last_position_check, position_interval, Instant::now()),
             };
+            let copy_due = match self.wallet_follower {
+                Some(_) => time_until_due(self.last_copy_poll, copy_interval, Instant::now()),
+                None => Duration::MAX,
+            };
+            let sleep_for = [
+                time_until_due(self.last_pool_scan, pool_scan_interval, Instant::now()),
+                position_due,
+                copy_due,
+                time_until_due(self.last_health_save, health_interval, Instant::now()),
+            ].into_iter().min().unwrap_or_default();
             
-            if scan_duration < target_interval {
-                tokio::time::sleep(target_interval - scan_duration).await;
-            } else {
-                // Scan took longer than target - log performance
-                println!("⚡ Performance: Scan took {:.2}s (target: {:.2}s) - optimizing for speed", 
-                    scan_duration.as_secs_f64(), target_interval.as_secs_f64());
-            }
-            
-            // Display active positions status periodically
-            if self.scan_count % 10 == 0 && !self.active_positions.is_empty() {
-                self.display_position_status();
+            tokio::select! {
+                _ = tokio::time::sleep(sleep_for) => {}
+                _ = shutdown.cancelled() => {
+                    println!("🛑 Shutdown requested - saving health snapshot...");
+                    if let Some(task) = &monitor_task {
+                        task.abort();
+                    }
+                    self.save_health_snapshot().await;
+                    self.send_session_report().await;
+                    return Ok(());
+                }
             }
         }
     }
 
-    /// Scan for new Raydium, Orca, and Pump.fun pools
-    async fn scan_for_new_pools(&mut self) -> Result<Vec<NewPool>> {
-        let mut new_pools = Vec::new();
-        
-        // Scan pump.fun for new token launches (highest priority)
-        let pumpfun_pools = self.scan_pumpfun_pools().await?;
-        new_pools.extend(pumpfun_pools);
-        
-        // Scan Raydium pools
-        let raydium_pools = self.scan_raydium_pools().await?;
-        new_pools.extend(raydium_pools);
+    /// Current health state for persistence
+    async fn health_snapshot(&self) -> HealthSnapshot {
+        let book = self.position_monitor.book().await;
+        let mut consecutive_errors = self.consecutive_errors.clone();
+        if book.auto_sell_failures > 0 {
+            consecutive_errors.insert("auto-sell".to_string(), book.auto_sell_failures);
+        }
+        HealthSnapshot {
+            last_scan_unix_secs: self.last_scan_completed.map(unix_secs).unwrap_or(0),
+            saved_at_unix_secs: unix_secs(SystemTime::now()),
+            consecutive_errors,
+            active_positions: book.active_positions.len(),
+            realized_pnl_sol: book.realized_pnl_sol,
+        }
+    }
+
+    async fn save_health_snapshot(&self) {
+        // Nothing worth persisting until the first scan completes
+        if self.last_scan_completed.is_none() {
+            return;
+        }
+        if let Err(e) = self.health_snapshot().await.save(&self.settings.monitoring.health_snapshot_path) {
+            println!("⚠️  Failed to save health snapshot: {}", e);
+        }
+    }
+
+    /// Load the previous run's snapshot and report how long the bot was down
+    async fn restore_health_snapshot(&mut self) {
+        let snapshot = match HealthSnapshot::load(&self.settings.monitoring.health_snapshot_path) {
+            Ok(Some(snapshot)) => snapshot,
+            Ok(None) => return,
+            Err(e) => {
+                println!("⚠️  Failed to load health snapshot: {}", e);
+                return;
+            }
+        };
         
-        // Scan Orca pools  
-        let orca_pools = self.scan_orca_pools().await?;
-        new_pools.extend(orca_pools);
+        self.position_monitor.book().await.realized_pnl_sol = snapshot.realized_pnl_sol;
+        let downtime = format_duration(snapshot.downtime(SystemTime::now()));
         
-        // Filter out already processed pools
-        let filtered_pools: Vec<NewPool> = new_pools
-            .into_iter()
-            .filter(|pool| !self.processed_pools.contains(&pool.pool_address))
+        let failing: Vec<String> = snapshot.consecutive_errors.iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(category, count)| format!("{} ({} errors)", category, count))
             .collect();
         
-        // Add to processed set
-        for pool in &filtered_pools {
-            self.processed_pools.insert(pool.pool_address.clone());
-        }
+        println!("🔄 Resumed after downtime of {}", downtime);
         
-        Ok(filtered_pools)
+        if self.settings.telegram.notifications_enabled {
+            let msg = format!(
+                "🔄 Resumed after downtime of {}\n🎯 Positions at shutdown: {}\n💰 Realized P&L: {:.4} SOL\n⚠️ Failing at shutdown: {}",
+                downtime,
+                snapshot.active_positions,
+                snapshot.realized_pnl_sol,
+                if failing.is_empty() { "none".to_string() } else { failing.join(", ") }
+            );
+            if let Err(e) = self.telegram.send_message(&msg).await {
+                println!("⚠️  Telegram resume notification failed: {}", e);
+            }
+        }
     }
 
-    /// Scan Raydium for new pools
-    async fn scan_raydium_pools(&self) -> Result<Vec<NewPool>> {
-        // In a real implementation, this would call Raydium's API or monitor on-chain events
-        // For now, return empty as this requires specific Raydium integration
-        
-        // Example of what the real implementation would do:
-        // 1. Call Raydium API: https://api.raydium.io/v2/sdk/liquidity/pool-list
-        // 2. Filter for pools created in last scan interval
-        // 3. Extract token addresses that are paired with SOL/USDC
-        // 4. Return new pools with SOL liquidity > minimum threshold
+    /// Resume the positions the previous run still held: real positions in a live run, none in a
+    /// dry run (paper positions are never saved).
+    async fn restore_positions(&mut self) {
+        let stored = match self.storage.load_positions() {
+            Ok(stored) => stored,
+            Err(e) => {
+                println!("⚠️  Failed to load saved positions: {}", e);
+                return;
+            }
+        };
+        let Some(restored_kind) = restored_position_kind(self.dry_run) else {
+            if !stored.is_empty() {
+                println!("🧪 DRY RUN: leaving {} saved position(s) untouched", stored.len());
+            }
+            return;
+        };
+        let (restore, dropped): (Vec<StoredPosition>, Vec<StoredPosition>) = stored.into_iter()
+            .partition(|position| position.kind == restored_kind);
         
-        Ok(Vec::new())
+        for position in &dropped {
+            println!("🗑️  Not restoring {:?} position {} in this run", position.kind, position.token_address);
+        }
+        if restore.is_empty() {
+            return;
+        }
+        println!("♻️  Restoring {} saved position(s) from {} storage", restore.len(), self.storage.backend_name());
+        for position in restore {
+            self.position_monitor.track_position(position.to_position()).await;
+        }
     }
 
-    /// Scan Orca for new pools
-    async fn scan_orca_pools(&self) -> Result<Vec<NewPool>> {
-        // In a real implementation, this would call Orca's API or monitor on-chain events
-        // For now, return empty as this requires specific Orca integration
-        
-        // Example of what the real implementation would do:
-        // 1. Call Orca API: https://api.orca.so/v1/whirlpools
-        // 2. Filter for pools created in last scan interval  
+    /// Whether TRADING_HOURS allows new buys right now
+    fn within_trading_hours(&self) -> bool {
+        within_trading_hours(&self.trading_hours, unix_secs(SystemTime::now()), self.settings.trading.trading_hours_utc_offset)
+    }
+
+    /// Never look at this pool again - persisted in a live run, remembered in memory in a dry run
+    fn mark_pool_processed(&mut self, pool_address: &str) {
+        self.processed_pools.insert(pool_address.to_string());
+        if self.dry_run {
+            return;
+        }
+        if let Err(e) = self.storage.mark_pool_processed(pool_address) {
+            println!("⚠️  Failed to save processed pool: {}", e);
+        }
+    }
+
+    /// Settle buys left in the trade journal by a previous run: restore the ones that landed, drop the rest
+    async fn reconcile_trade_journal(&mut self) {
+        let pending = match self.trade_journal.load() {
+            Ok(pending) => pending,
+            Err(e) => {
+                println!("⚠️  Failed to load trade journal: {}", e);
+                return;
+            }
+        };
+        if pending.is_empty() {
+            return;
+        }
+        if self.dry_run {
+            println!("🧪 DRY RUN: leaving {} journaled trade(s) for the next live run", pending.len());
+            return;
+        }
+        
+        println!("📒 Reconciling {} journaled trade(s) from the previous run...", pending.len());
+        let now = unix_secs(SystemTime::now());
+        
+        for trade in pending.into_values() {
+            let status = match &trade.signature {
+                Some(signature) => match self.jupiter_trader.get_signature_outcome(signature).await {
+                    Ok(status) => status,
+                    Err(e) => {
+                        println!("⚠️  Could not check {} - keeping it journaled: {}", signature, e);
+                        continue;
+                    }
+                },
+                None => None,
+            };
+            
+            match reconcile_action(&trade, status, now) {
+                ReconcileAction::Confirm => {
+                    let signature = trade.signature.clone().unwrap_or_default();
+                    println!("✅ Journaled buy of {} landed ({}) - restoring position", trade.token_address, signature);
+                    let position = TokenPosition {
+                        token_address: trade.token_address.clone(),
+                        purchase_time: UNIX_EPOCH + Duration::from_secs(trade.created_at_unix_secs),
+                        sol_amount: trade.sol_amount,
+                        estimated_tokens: trade.expected_tokens,
+                        entry_price: safe_ratio(trade.sol_amount, trade.expected_tokens as f64),
+                        kind: PositionKind::Real,
+                        trade_result: Some(TradeResult {
+                            transaction_signature: signature,
+                            tokens_received: trade.expected_tokens,
+                            sol_received: 0.0,
+                            landed_via: None,
+                            time_to_fill: None,
+                            realized_slippage_percent: None,
+                        }),
+                        max_hold: Duration::from_secs(self.settings.trading.max_hold_time_hours as u64 * 3600),
+                        timeout_extension: Duration::ZERO,
+                        sell_probe: None,
+                    };
+                    self.position_monitor.track_position(position).await;
+                }
+                ReconcileAction::Discard => {
+                    println!("🗑️  Journaled buy of {} never landed - discarding", trade.token_address);
+                }
+                ReconcileAction::Keep => {
+                    println!("⏳ Journaled buy of {} not yet visible on-chain - will check again next start", trade.token_address);
+                    continue;
+                }
+            }
+            
+            if let Err(e) = self.trade_journal.clear(&trade.token_address) {
+                println!("⚠️  Failed to clear trade journal entry: {}", e);
+            }
+        }
+    }
+
+    /// Count a failure for a category and send a deduplicated Telegram error alert (if enabled)
+    async fn record_error(&mut self, category: &str, error: &str) {
+        *self.consecutive_errors.entry(category.to_string()).or_insert(0) += 1;
+        
+        if self.settings.telegram.notifications_enabled && self.settings.telegram.send_error_alerts {
+            if let Err(e) = self.telegram.send_error_alert(category, error).await {
+                println!("⚠️  Telegram error alert failed: {}", e);
+            }
+        }
+    }
+
+    /// Reset a category's error streak, sending a suppressed-repeats summary if needed
+    async fn record_success(&mut self, category: &str) {
+        self.consecutive_errors.remove(category);
+        
+        if self.settings.telegram.notifications_enabled && self.settings.telegram.send_error_alerts {
+            if let Err(e) = self.telegram.resolve_error_alerts(category).await {
+                println!("⚠️  Telegram recovery alert failed: {}", e);
+            }
+        }
+    }
+
+    /// One-off alert when the scan error streak reaches the degraded threshold
+    async fn send_scanner_degraded_alert(&self, failures: u32, error: &str) {
+        println!("🚨 Scanner degraded: {} consecutive failures", failures);
+        
+        if self.settings.telegram.notifications_enabled {
+            let msg = format!("🚨 SCANNER DEGRADED\n❌ {} consecutive scan failures\n⏳ Backing off up to {}s between attempts\n📄 Last error: {}",
+                failures, self.settings.monitoring.scan_error_backoff_max_ms / 1000, error);
+            if let Err(e) = self.telegram.send_message(&msg).await {
+                println!("⚠️  Telegram degraded alert failed: {}", e);
+            }
+        }
+    }
+
+    /// Pool discovery interval - tightened to 0.5s while new pools keep appearing,
+    /// backed off exponentially while scans keep failing
+    fn pool_scan_interval(&self, found_new_pools: bool) -> Duration {
+        let failures = self.consecutive_errors.get("scan").copied().unwrap_or(0);
+        if failures > 0 {
+            return scan_error_backoff(
+                failures,
+                self.settings.monitoring.scan_error_backoff_base_ms,
+                self.settings.monitoring.scan_error_backoff_max_ms,
+            );
+        }
+        
+        let base_interval = Duration::from_millis(self.settings.monitoring.dexscreener_interval_ms);
+        let aggressive_interval = Duration::from_millis(500); // 0.5 second for new pools
+        if found_new_pools {
+            base_interval.min(aggressive_interval) // Faster scanning when activity detected
+        } else {
+            base_interval // Normal interval when no new pools
+        }
+    }
+
+    /// Scan for new Raydium, Orca, and Pump.fun pools
+    async fn scan_for_new_pools(&mut self) -> Result<Vec<NewPool>> {
+        let mut new_pools = Vec::new();
+        
+        // Scan pump.fun for new token launches (highest priority)
+        let pumpfun_pools = self.scan_pumpfun_pools().await?;
+        new_pools.extend(pumpfun_pools);
+        
+        // Scan Raydium pools
+        let raydium_pools = self.scan_raydium_pools().await?;
+        new_pools.extend(raydium_pools);
+        
+        // Scan Orca pools  
+        let orca_pools = self.scan_orca_pools().await?;
+        new_pools.extend(orca_pools);
+        
+        // Every mint a source returned counts as detected, even if it's filtered out below
+        if let Some(trace) = self.decision_trace.as_mut() {
+            trace.mark_detected(new_pools.iter().map(|pool| pool.token_address.as_str()));
+        }
+        
+        // Outside the trading window nothing is marked processed, so pools still new once it
+        // opens get evaluated then
+        if !self.within_trading_hours() {
+            if !new_pools.is_empty() {
+                println!("🌙 Outside trading window ({}) - leaving {} pool(s) for later", self.settings.trading.trading_hours, new_pools.len());
+            }
+            return Ok(Vec::new());
+        }
+        
+        // Filter out already processed pools. Pools of a mint sold within SELL_COOLDOWN_SECS are
+        // left unprocessed so they're reconsidered once the cooldown ends.
+        let cooldown = Duration::from_secs(self.settings.trading.sell_cooldown_secs);
+        let filtered_pools: Vec<NewPool> = {
+            let mut book = self.position_monitor.book().await;
+            new_pools
+                .into_iter()
+                .filter(|pool| !self.processed_pools.contains(&pool.pool_address))
+                .filter(|pool| match book.sell_cooldown_remaining(&pool.token_address, cooldown, SystemTime::now()) {
+                    Some(remaining) => {
+                        println!("🧊 {} was sold recently - ignoring it for another {}s", pool.token_address, remaining.as_secs());
+                        false
+                    }
+                    None => true,
+                })
+                .collect()
+        };
+        
+        // Add to processed set
+        for pool in &filtered_pools {
+            self.mark_pool_processed(&pool.pool_address);
+        }
+        
+        Ok(filtered_pools)
+    }
+
+    /// Merge fresh pools with the carried-over backlog and take this scan's batch,
+    /// capped at max_new_tokens_per_scan so bursts don't blow the scan budget
+    fn next_pool_batch(&mut self, new_pools: Vec<NewPool>) -> Vec<NewPool> {
+        let cap = self.settings.monitoring.max_new_tokens_per_scan as usize;
+        let mut candidates = std::mem::take(&mut self.pending_pools);
+        candidates.extend(new_pools);
+        
+        let (batch, mut remainder) = prioritize_pools(candidates, cap);
+        
+        if !remainder.is_empty() {
+            let max_pending = cap * MAX_PENDING_SCANS;
+            if remainder.len() > max_pending {
+                println!("🗑️  Dropping {} lowest-liquidity pools over the backlog limit", remainder.len() - max_pending);
+                let dropped = remainder.split_off(max_pending);
+                if let Some(trace) = self.decision_trace.as_mut() {
+                    for pool in &dropped {
+                        trace.record(&pool.token_address, &pool.dex, "dropped: over the pending backlog limit");
+                    }
+                }
+            }
+            println!("⏭️  Processing {} pools this scan, {} carried to the next", batch.len(), remainder.len());
+        }
+        
+        self.pending_pools = remainder;
+        batch
+    }
+
+    /// Scan Raydium for new pools
+    async fn scan_raydium_pools(&self) -> Result<Vec<NewPool>> {
+        // In a real implementation, this would call Raydium's API or monitor on-chain events
+        // For now, return empty as this requires specific Raydium integration
+        
+        // Example of what the real implementation would do:
+        // 1. Call Raydium API: https://api.raydium.io/v2/sdk/liquidity/pool-list
+        // 2. Filter for pools created in last scan interval
+        // 3. Extract token addresses that are paired with SOL/USDC
+        // 4. Return new pools with SOL liquidity > minimum threshold
+        
+        Ok(Vec::new())
+    }
+
+    /// Scan Orca for new pools
+    async fn scan_orca_pools(&self) -> Result<Vec<NewPool>> {
+        // In a real implementation, this would call Orca's API or monitor on-chain events
+        // For now, return empty as this requires specific Orca integration
+        
+        // Example of what the real implementation would do:
+        // 1. Call Orca API: https://api.orca.so/v1/whirlpools
+        // 2. Filter for pools created in last scan interval  
         // 3. Extract token addresses that are paired with SOL/USDC
         // 4. Return new pools with SOL liquidity > minimum threshold
         
@@ -308,6 +1437,18 @@ impl PoolScanner {
     async fn scan_pumpfun_pools(&self) -> Result<Vec<NewPool>> {
         println!("🔍 Scanning for NEW pump.fun tokens (real-time)...");
         
+        // 0. PREMIUM: Birdeye new listings (indexes faster than DexScreener)
+        if self.birdeye.is_some() {
+            match self.scan_via_birdeye().await {
+                Ok(tokens) if !tokens.is_empty() => {
+                    println!("⚡ PREMIUM: Birdeye found {} new Solana tokens", tokens.len());
+                    return Ok(tokens);
+                }
+                Ok(_) => println!("ℹ️  Birdeye: No new tokens in last scan"),
+                Err(e) => println!("⚠️  Birdeye API error (falling back to DexScreener): {}", e),
+            }
+        }
+        
         // 1. PRIORITY: DexScreener API for Solana new pairs (WORKING!)
         match self.scan_via_dexscreener().await {
             Ok(tokens) if !tokens.is_empty() => {
@@ -343,7 +1484,7 @@ impl PoolScanner {
         }
         
         // 4. TESTING ONLY: Sample data (reduced frequency for real trading focus)
-        if self.settings.trading.enable_auto_trading && rand::random::<f64>() < 0.1 {
+        if !self.settings.security.production_mode && self.settings.trading.enable_auto_trading && rand::random::<f64>() < 0.1 {
             println!("🧪 TESTING: Using sample data (10% chance) - implement real monitoring!");
             return self.generate_sample_tokens().await;
         }
@@ -363,8 +1504,8 @@ impl PoolScanner {
             .timeout(Duration::from_secs(5)) // Faster timeout for real-time
             .build()?;
         
-        // Search for VERY RECENT SOL pairs on DexScreener - optimized for speed
-        let url = "https://api.dexscreener.com/latest/dex/search/?q=SOL&limit=20"; // Reduced limit for speed
+        // Search for recent SOL pairs; dexscreener_pools picks this scan's candidates
+        let url = "https://api.dexscreener.com/latest/dex/search/?q=SOL";
         
         let response = client
             .get(url)
@@ -375,52 +1516,56 @@ impl PoolScanner {
 
         if response.status().is_success() {
             let dex_response: DexScreenerResponse = response.json().await?;
-            let mut new_pools = Vec::new();
-            let _current_time = std::time::SystemTime::now();
+            let sol_price_usd = self.position_monitor.sol_price_usd().await;
+            Ok(dexscreener_pools(dex_response, &self.settings, sol_price_usd, SystemTime::now()))
+        } else {
+            Err(anyhow::anyhow!("DexScreener API returned status: {}", response.status()))
+        }
+    }
+
+    /// Birdeye new listings -> NewPool, with the same liquidity floor and 24h window as DexScreener
+    async fn scan_via_birdeye(&self) -> Result<Vec<NewPool>> {
+        let Some(birdeye) = &self.birdeye else {
+            return Ok(Vec::new());
+        };
+        
+        let sol_price_usd = self.position_monitor.sol_price_usd().await;
+        let mut new_pools = Vec::new();
+        for token in birdeye.new_listings(self.settings.monitoring.max_candidates_per_scan).await? {
+            let liquidity_usd = token.liquidity.unwrap_or(0.0);
+            if liquidity_usd < sol_to_usd(self.settings.trading.min_liquidity_sol, sol_price_usd) {
+                continue;
+            }
             
-            for pair in dex_response.pairs.into_iter().take(10) { // Focus on newest
-                // Filter for Solana chain and VERY RECENT creation (last 10 minutes)
-                if pair.chain_id == "solana" && pair.pair_created_at.is_some() {
-                    let created_timestamp = pair.pair_created_at.unwrap();
-                    let created_time = std::time::UNIX_EPOCH + std::time::Duration::from_millis(created_timestamp);
-                    let time_since_creation = std::time::SystemTime::now().duration_since(created_time).unwrap_or_default();
-                    
-                    // Only process pairs created in the last 24 hours
-                    if time_since_creation <= Duration::from_secs(24 * 3600) {
-                        // Check if it's paired with SOL (indicates new token)
-                        let is_sol_pair = pair.quote_token.symbol == "SOL" || pair.base_token.symbol == "SOL";
-                        
-                        if is_sol_pair && pair.liquidity.usd >= self.settings.trading.min_liquidity_sol * 200.0 {
-                            let token_address = if pair.quote_token.symbol == "SOL" {
-                                pair.base_token.address
-                            } else {
-                                pair.quote_token.address
-                            };
-                            
-                            let pool = NewPool {
-                                token_address: token_address.clone(),
-                                pool_address: pair.pair_address,
-                                base_mint: token_address,
-                                quote_mint: "So11111111111111111111111111111111111111112".to_string(),
-                                liquidity_sol: pair.liquidity.usd / 235.0, // Rough SOL conversion
-                                detected_at: created_time,
-                                dex: format!("DexScreener/{}", pair.dex_id),
-                            };
-                            
-                            new_pools.push(pool);
-                            
-                            if new_pools.len() >= 10 {
-                                break;
-                            }
-                        }
-                    }
-                }
+            let detected_at = token.liquidity_added_at
+                .as_deref()
+                .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+                .map(|t| std::time::UNIX_EPOCH + Duration::from_secs(t.timestamp().max(0) as u64))
+                .unwrap_or_else(SystemTime::now);
+            if SystemTime::now().duration_since(detected_at).unwrap_or_default() > MAX_CANDIDATE_AGE {
+                continue;
             }
             
-            Ok(new_pools)
-        } else {
-            Err(anyhow::anyhow!("DexScreener API returned status: {}", response.status()))
+            new_pools.push(NewPool {
+                pool_address: format!("birdeye-{}", token.address),
+                base_mint: token.address.clone(),
+                quote_mint: WRAPPED_SOL_MINT.to_string(),
+                token_name: token.name.unwrap_or_default(),
+                token_symbol: token.symbol.unwrap_or_default(),
+                token_address: token.address,
+                liquidity_sol: usd_to_sol(liquidity_usd, sol_price_usd),
+                price_usd: 0.0,
+                price_sol: 0.0,
+                detected_at,
+                dex: "Birdeye".to_string(),
+            });
+            
+            if new_pools.len() >= self.settings.monitoring.max_candidates_per_scan as usize {
+                break;
+            }
         }
+        
+        Ok(new_pools)
     }
 
     /// Convert Jupiter tokens to NewPool format
@@ -430,10 +1575,14 @@ impl PoolScanner {
         for token in tokens.into_iter().take(5) { // Limit to 5 for testing
             let pool = NewPool {
                 token_address: token.address.clone(),
+                token_name: token.name.clone(),
+                token_symbol: token.symbol.clone(),
                 pool_address: format!("jupiter-{}", token.address),
                 base_mint: token.address.clone(),
                 quote_mint: "So11111111111111111111111111111111111111112".to_string(),
                 liquidity_sol: 10.0, // Default assumption for new tokens
+                price_usd: 0.0,
+                price_sol: 0.0,
                 detected_at: std::time::SystemTime::now(),
                 dex: "Jupiter/Token-List".to_string(),
             };
@@ -541,21 +1690,25 @@ impl PoolScanner {
         let mut pools = Vec::new();
         let base_time = std::time::SystemTime::now();
         
-        for (i, (symbol, _short, liquidity)) in sample_tokens.iter().enumerate() {
-            // Generate realistic-looking Solana addresses
+        for (i, (symbol, short, liquidity)) in sample_tokens.iter().enumerate() {
+            // Generate realistic-looking Solana addresses, tagged so they're never mistaken for real mints
             let chars = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
-            let mut address = String::new();
-            for _ in 0..44 {
+            let mut address = String::from("Sample");
+            for _ in 0..38 {
                 let idx = (rand::random::<usize>() + i) % chars.len();
                 address.push(chars.chars().nth(idx).unwrap());
             }
             
             let pool = NewPool {
                 token_address: address.clone(),
-                pool_address: format!("sample-{}", &address[..8]),
+                token_name: symbol.to_string(),
+                token_symbol: short.to_string(),
+                pool_address: format!("sample-{}", &address[6..14]),
                 base_mint: address,
                 quote_mint: "So11111111111111111111111111111111111111112".to_string(),
                 liquidity_sol: *liquidity,
+                price_usd: 0.0,
+                price_sol: 0.0,
                 detected_at: base_time,
                 dex: format!("Sample-{}", symbol),
             };
@@ -567,15 +1720,242 @@ impl PoolScanner {
         Ok(pools)
     }
 
-    /// Process a newly discovered pool
+    /// The trader this scanner buys through, for callers that need the same instance
+    pub fn jupiter_trader(&self) -> Arc<JupiterTrader> {
+        Arc::clone(&self.jupiter_trader)
+    }
+
+    /// Process a newly discovered pool, recording the outcome when the decision trace is on
+    /// and POSTing skips and rejections to the webhook
     async fn process_new_pool(&mut self, pool: NewPool) -> Result<()> {
+        let (mint, source) = (pool.token_address.clone(), pool.dex.clone());
+        let decision = self.evaluate_new_pool(pool).await;
+        if let (Some(webhook), Ok(outcome)) = (&self.webhook, &decision) {
+            if let Some(event) = WebhookEvent::from_outcome(&mint, outcome) {
+                webhook.dispatch(event);
+            }
+        }
+        if let Some(trace) = self.decision_trace.as_mut() {
+            match &decision {
+                Ok(outcome) => trace.record(&mint, &source, outcome),
+                Err(e) => trace.record(&mint, &source, &format!("error: {}", e)),
+            }
+        }
+        decision.map(|_| ())
+    }
+
+    /// Run a pool through every buy check and buy it if they all pass.
+    /// Returns what happened - the skip reason or the purchase outcome.
+    async fn evaluate_new_pool(&mut self, pool: NewPool) -> Result<String> {
         println!("🔍 Analyzing new pool: {} ({})", pool.token_address, pool.dex);
         
+        // Trading schedule - positions are still managed outside it, nothing new is bought.
+        // Backlog pools were already marked processed, so they wait for the window to open.
+        if !self.within_trading_hours() {
+            println!("🌙 Outside trading window ({}) - deferring {}", self.settings.trading.trading_hours, pool.token_address);
+            defer_pool(&mut self.pending_pools, pool);
+            return Ok("deferred: outside trading window".to_string());
+        }
+        
+        // Re-entry cooldown - copied buys and the backlog can still bring back a mint just sold
+        let cooldown = Duration::from_secs(self.settings.trading.sell_cooldown_secs);
+        let cooldown_remaining = self.position_monitor.book().await
+            .sell_cooldown_remaining(&pool.token_address, cooldown, SystemTime::now());
+        if let Some(remaining) = cooldown_remaining {
+            println!("🧊 {} was sold recently - skipping, cooldown has {}s left", pool.token_address, remaining.as_secs());
+            return Ok(format!("skipped: sold recently ({}s cooldown left)", remaining.as_secs()));
+        }
+        
+        // Step 0: Daily buy cap - no point analysing what can't be bought today
+        let today = utc_day(unix_secs(SystemTime::now()));
+        if self.daily_trades.cap_reached(today, self.settings.trading.max_daily_trades) {
+            println!("📅 Daily buy cap reached ({}/{}) - skipping {} until UTC midnight",
+                self.daily_trades.buys_on(today), self.settings.trading.max_daily_trades, pool.token_address);
+            if self.daily_cap_alerted_day != Some(today) {
+                self.daily_cap_alerted_day = Some(today);
+                if self.settings.telegram.notifications_enabled {
+                    let msg = format!("📅 DAILY BUY CAP REACHED\n🛒 {} buys today\n⏸️ No new buys until UTC midnight (sells continue)",
+                        self.settings.trading.max_daily_trades);
+                    if let Err(e) = self.telegram.send_message(&msg).await {
+                        println!("⚠️  Telegram notification failed: {}", e);
+                    }
+                }
+            }
+            return Ok(format!("skipped: daily buy cap reached ({} buys)", self.settings.trading.max_daily_trades));
+        }
+        
+        // Portfolio take profit already locked in today's gains
+        if self.position_monitor.buys_paused().await {
+            println!("🏁 Portfolio take profit hit today - skipping {} until UTC midnight", pool.token_address);
+            return Ok("skipped: portfolio take profit hit today".to_string());
+        }
+        
+        // Buy spacing - don't deploy capital back-to-back during a launch frenzy
+        let min_gap = Duration::from_secs(self.settings.trading.min_seconds_between_buys);
+        let last_buy = if self.settings.trading.buy_spacing_per_source {
+            self.last_buy_by_source.get(&pool.dex).copied()
+        } else {
+            self.last_buy_at
+        };
+        if let Some(wait) = buy_spacing_wait(last_buy, Instant::now(), min_gap) {
+            println!("⏱️  Last buy was under {}s ago - deferring {} to a later scan ({:.0}s left)",
+                min_gap.as_secs(), pool.token_address, wait.as_secs_f64());
+            defer_pool(&mut self.pending_pools, pool);
+            return Ok(format!("deferred: buy spacing ({:.0}s left)", wait.as_secs_f64()));
+        }
+        
+        // Step 0a: Stablecoin / wrapped-major pairs (USDC/SOL etc.) - the "token" isn't a launch
+        if is_avoided_pair_mint(&pool.token_address, &self.settings.security.avoided_pair_mints) {
+            println!("💵 Skipping {}/SOL - {} is a stablecoin or wrapped major", pool.token_symbol, pool.token_address);
+            return Ok("skipped: stablecoin or wrapped-major pair".to_string());
+        }
+        
+        // Step 0b: Name/symbol blacklist (no API calls)
+        if let Some(pattern) = self.name_blacklist.matching_pattern(&pool.token_name, &pool.token_symbol) {
+            println!("🚫 Blacklisted name/symbol: {} ({}) matches \"{}\"", pool.token_name, pool.token_symbol, pattern);
+            return Ok(format!("skipped: name/symbol matches blacklist pattern \"{}\"", pattern));
+        }
+        
+        // Step 0c: Known ticker reused under a different mint
+        if let Some(canonical_mint) = impersonated_ticker(&pool.token_symbol, &pool.token_address, &self.settings.security.known_tickers) {
+            println!("🎭 Likely impersonator: {} uses ticker {} but the canonical mint is {}", 
+                pool.token_address, pool.token_symbol, canonical_mint);
+            
+            if self.settings.telegram.notifications_enabled {
+                if let Err(e) = self.telegram.send_message(&impersonator_message(&pool, canonical_mint)).await {
+                    println!("⚠️  Failed to send impersonation alert: {}", e);
+                }
+            }
+            return Ok(format!("skipped: impersonates {} (canonical mint {})", pool.token_symbol, canonical_mint));
+        }
+        
         // Step 1: Basic validation
         if pool.liquidity_sol < self.settings.trading.min_liquidity_sol {
             println!("⚠️  Pool liquidity too low: {:.4} SOL (min: {:.4})", 
                 pool.liquidity_sol, self.settings.trading.min_liquidity_sol);
-            return Ok(());
+            return Ok(format!("skipped: liquidity {:.4} SOL below {:.4} minimum", pool.liquidity_sol, self.settings.trading.min_liquidity_sol));
+        }
+
+        // Step 1a: True token age - distinguishes "new token" from "new pool for an old token"
+        if self.settings.security.min_token_age_minutes > 0 || self.settings.security.max_token_age_minutes > 0 {
+            match self.token_creation_time(&pool.token_address).await {
+                Ok(created_at) => {
+                    let token_age = SystemTime::now().duration_since(created_at).unwrap_or_default();
+                    if let Some(reason) = token_age_rejection(
+                        token_age,
+                        self.settings.security.min_token_age_minutes,
+                        self.settings.security.max_token_age_minutes,
+                    ) {
+                        println!("❌ Token age out of range: {}", reason);
+                        return Ok(format!("skipped: token age out of range ({})", reason));
+                    }
+                    println!("🎂 Token age: {}", format_duration(token_age));
+                }
+                Err(e) => {
+                    println!("⚠️  Token age unavailable: {}", e);
+                    if self.settings.security.require_rugcheck_success {
+                        println!("❌ Skipping token due to token age check failure");
+                        return Ok("skipped: token age unavailable".to_string());
+                    }
+                }
+            }
+        }
+
+        // Step 1b: Optional entry delay - let instant rugs reveal themselves before committing
+        if self.settings.trading.entry_delay_ms > 0 {
+            println!("⏳ Waiting {}ms before entry to re-verify pool...", self.settings.trading.entry_delay_ms);
+            tokio::time::sleep(Duration::from_millis(self.settings.trading.entry_delay_ms)).await;
+            
+            let (current_liquidity_sol, current_price_usd) = match self.fetch_pool_snapshot(&pool).await {
+                Ok(snapshot) => snapshot,
+                Err(e) => {
+                    println!("❌ Skipping token - could not re-verify pool after entry delay: {}", e);
+                    return Ok("skipped: could not re-verify pool after entry delay".to_string());
+                }
+            };
+            
+            if let Some(reason) = entry_delay_abort_reason(
+                pool.liquidity_sol,
+                pool.price_usd,
+                current_liquidity_sol,
+                current_price_usd,
+                self.settings.trading.entry_max_price_rise_percent,
+            ) {
+                println!("❌ Entry aborted after delay: {}", reason);
+                return Ok(format!("skipped: entry aborted after delay ({})", reason));
+            }
+            
+            println!("✅ Pool re-verified after delay: {:.2} SOL liquidity, ${:.8}", current_liquidity_sol, current_price_usd);
+        }
+
+        // Step 1c: Entry price limit - don't buy the top of a launch that already ran
+        if let Some(max_entry_price_usd) = max_entry_price_for(
+            &pool.token_address,
+            &self.settings.trading.token_max_entry_price_usd,
+            self.settings.trading.max_entry_price_usd,
+        ) {
+            let current_price_usd = match self.fetch_pool_snapshot(&pool).await {
+                Ok((_, price_usd)) => price_usd,
+                Err(e) => {
+                    println!("⚠️  Could not refresh price ({}) - using detected ${}", e, pool.price_usd);
+                    pool.price_usd
+                }
+            };
+            if let Some(reason) = entry_price_rejection(current_price_usd, max_entry_price_usd) {
+                println!("❌ Entry price limit: {} - skipping", reason);
+                return Ok(format!("skipped: entry price limit ({})", reason));
+            }
+            println!("🏷️  Price ${} within the ${} entry limit", current_price_usd, max_entry_price_usd);
+        }
+
+        // Step 1d: Pool spread - many tiny pools and no single venue worth trading on, or too few
+        // SOL pairs to get in and out through
+        let min_sol_pairs = self.settings.security.min_sol_pairs as usize;
+        if self.settings.security.max_pools_per_token > 0 || min_sol_pairs > 1 {
+            match token_pools(&pool.token_address).await {
+                Ok(pools) => {
+                    let liquidities: Vec<f64> = pools.iter().map(|pool| pool.liquidity_usd).collect();
+                    if let Some(reason) = liquidity_fragmentation(
+                        &liquidities,
+                        self.settings.security.max_pools_per_token as usize,
+                        self.settings.security.min_main_pool_liquidity_usd,
+                    ) {
+                        println!("❌ Fragmented liquidity for {}: {}", pool.token_address, reason);
+                        return Ok(format!("skipped: fragmented liquidity ({})", reason));
+                    }
+                    if let Some(reason) = sol_pair_shortfall(&pools, min_sol_pairs, self.settings.security.min_primary_sol_pair_liquidity_usd) {
+                        println!("❌ Too few SOL pairs for {}: {}", pool.token_address, reason);
+                        return Ok(format!("skipped: too few SOL pairs ({})", reason));
+                    }
+                    println!("🧩 {} pool(s) for {} - liquidity spread acceptable", pools.len(), pool.token_symbol);
+                }
+                Err(e) => {
+                    // Opted-in checks fail closed: an unknown pool spread isn't a passing one
+                    println!("❌ Pool list unavailable for {}: {}", pool.token_address, e);
+                    return Ok(format!("skipped: pool list unavailable ({})", e));
+                }
+            }
+        }
+
+        // Step 1e: Creator reputation - a wallet funded minutes before launch, or a known rugger
+        if self.settings.security.enable_creator_checks {
+            match self.rugcheck_client.fetch_creator_profile(&pool.token_address).await {
+                Ok(profile) => {
+                    let min_age = Duration::from_secs(self.settings.security.min_creator_wallet_age_minutes * 60);
+                    if let Some(reason) = creator_risk(&profile, min_age, &self.creator_blacklist) {
+                        println!("❌ Risky creator: {}", reason);
+                        return Ok(format!("skipped: risky creator ({})", reason));
+                    }
+                    println!("🧑‍💻 Creator {} was {} old at launch", profile.wallet, format_duration(profile.wallet_age_at_launch()));
+                }
+                Err(e) => {
+                    println!("⚠️  Creator profile unavailable: {}", e);
+                    if self.settings.security.require_rugcheck_success {
+                        println!("❌ Skipping token due to creator check failure");
+                        return Ok("skipped: creator profile unavailable".to_string());
+                    }
+                }
+            }
         }
 
         // Step 2: RugCheck security analysis
@@ -585,28 +1965,31 @@ impl PoolScanner {
                 
                 println!("🛡️  Security Score: {}/100", response.score);
                 
-                // Check if meets minimum score requirement
-                if response.score < self.settings.security.min_acceptable_score {
-                    println!("❌ Token failed security check: {}/100 (min: {})", 
-                        response.score, self.settings.security.min_acceptable_score);
+                // Check if it clears the security bar (all criteria, or the minimum score)
+                let security = &self.settings.security;
+                let secondary_mode = self.secondary_rug_checker.as_ref().map(|(mode, _)| *mode);
+                let (min_score, all_criteria) = primary_security_bar(security.min_acceptable_score, security.require_all_criteria, secondary_mode);
+                let verdict = security_verdict(response.score, &report, min_score, all_criteria);
+                if let SecurityVerdict::BelowBar(bar) = verdict {
+                    println!("❌ Token failed security check: {}/100 (needs {})", response.score, bar);
                     
                     // Notify about rejected token
                     if self.settings.telegram.notifications_enabled {
-                        let msg = format!("❌ Token REJECTED\n💎 Token: `{}`\n🛡️ Score: {}/100 (min: {})\n🏊 Pool: {} ({:.2} SOL liquidity)", 
-                            pool.token_address, response.score, self.settings.security.min_acceptable_score, pool.dex, pool.liquidity_sol);
+                        let msg = format!("❌ Token REJECTED\n💎 Token: `{}`\n🛡️ Score: {}/100 (needs {})\n🏊 Pool: {} ({:.2} SOL liquidity)", 
+                            pool.token_address, response.score, bar, pool.dex, pool.liquidity_sol);
                         self.telegram.send_message(&msg).await?;
                     }
                     
-                    return Ok(());
+                    return Ok(format!("rejected: security score {}/100 (needs {})", response.score, bar));
                 }
                 
                 // Display detailed analysis
-                if !report.critical_risks.is_empty() {
+                if let SecurityVerdict::CriticalRisks(risks) = verdict {
                     println!("🚨 Critical risks detected:");
-                    for risk in &report.critical_risks {
+                    for risk in &risks {
                         println!("   ❌ {}", risk);
                     }
-                    return Ok(());
+                    return Ok(format!("rejected: critical risks ({})", risks.join("; ")));
                 }
                 
                 if !report.warnings.is_empty() {
@@ -627,12 +2010,12 @@ impl PoolScanner {
             }
             Err(e) => {
                 println!("⚠️  RugCheck API error: {}", e);
-                // Use fallback - only proceed if we're in permissive mode
-                if !self.settings.security.require_rugcheck_success {
+                // Use fallback - only proceed if we're in permissive mode (never in production)
+                if !self.settings.security.require_rugcheck_success && !self.settings.security.production_mode {
                     println!("ℹ️  Proceeding without RugCheck (permissive mode)");
                 } else {
                     println!("❌ Skipping token due to RugCheck failure");
-                    return Ok(());
+                    return Ok("skipped: RugCheck unavailable".to_string());
                 }
                 // Create a basic report for fallback
                 crate::rugcheck::SecurityReport {
@@ -644,15 +2027,131 @@ impl PoolScanner {
                     criteria_results: vec!["Fallback mode - RugCheck unavailable".to_string()],
                     recommendation: "Proceed with caution".to_string(),
                     criteria_used: crate::rugcheck::RugCheckCriteria::default(),
+                    buy_tax_percent: None,
+                    sell_tax_percent: None,
+                    score_breakdown: Vec::new(),
                 }
             }
         };
 
-        // Step 3: Execute purchase if all checks passed
+        // Step 2a: Second opinion from another provider - a disagreement rejects the token
+        if let Some((mode, secondary)) = &self.secondary_rug_checker {
+            let min_score = self.settings.security.secondary_rugcheck_min_score;
+            match second_opinion(secondary.as_ref(), &pool.token_address, *mode, security_result.score, min_score).await {
+                SecondOpinion::Agree(score) => println!("🛡️  Second opinion (rugcheck.xyz): {}/100", score),
+                SecondOpinion::Disagree { score, high_risks } => {
+                    println!("⚖️  Security providers disagree: criteria {}/100 vs rugcheck.xyz {}/100 ({:?} mode needs {})",
+                        security_result.score, score, mode, min_score);
+                    for risk in &high_risks {
+                        println!("   ❌ {}", risk);
+                    }
+                    return Ok(format!("rejected: security providers disagree (criteria {}/100, rugcheck.xyz {}/100)",
+                        security_result.score, score));
+                }
+                SecondOpinion::Unavailable(e) => {
+                    println!("⚠️  Second-opinion RugCheck unavailable: {}", e);
+                    if self.settings.security.require_rugcheck_success {
+                        println!("❌ Skipping token - both security checks are required");
+                        return Ok("skipped: second-opinion RugCheck unavailable".to_string());
+                    }
+                }
+            }
+        }
+
+        // Step 2b: Holder count - only meaningful once the pool has had time to distribute
+        if self.settings.security.enable_holder_checks {
+            let pool_age = SystemTime::now().duration_since(pool.detected_at).unwrap_or_default();
+            let min_age = Duration::from_secs(self.settings.security.holder_check_min_pool_age_minutes * 60);
+            
+            if pool_age < min_age {
+                println!("ℹ️  Skipping holder check - pool is {}m old (checked after {}m)",
+                    pool_age.as_secs() / 60, self.settings.security.holder_check_min_pool_age_minutes);
+            } else {
+                match self.rugcheck_client.fetch_holder_count(&pool.token_address).await {
+                    Ok(holders) => {
+                        let min_holders = self.settings.security.min_holder_count;
+                        if let Some(reason) = too_few_holders(holders, min_holders) {
+                            println!("❌ Too few holders: {} (min: {})", holders, min_holders);
+                            return Ok(format!("skipped: {}", reason));
+                        }
+                        println!("👥 Holders: {} (min: {})", holders, min_holders);
+                    }
+                    Err(e) => {
+                        println!("⚠️  Holder count unavailable: {}", e);
+                        if self.settings.security.require_rugcheck_success {
+                            println!("❌ Skipping token due to holder check failure");
+                            return Ok("skipped: holder count unavailable".to_string());
+                        }
+                    }
+                }
+            }
+        }
+
+        // Step 2c: On-chain liquidity cross-check - the indexer's figure must match what the pool holds.
+        // DexScreener's USD liquidity counts both sides, so half of it is the SOL reserve.
+        if self.settings.security.max_liquidity_disagreement_factor > 0.0
+            && pool.pool_address.parse::<solana_sdk::pubkey::Pubkey>().is_ok() {
+            let reported_sol = pool.liquidity_sol / 2.0;
+            match self.jupiter_trader.get_pool_sol_reserves(&pool.pool_address).await {
+                Ok(Some(onchain_sol)) => match liquidity_disagreement(reported_sol, onchain_sol, self.settings.security.max_liquidity_disagreement_factor) {
+                    Some(reason) => {
+                        println!("❌ Liquidity mismatch for {}: {}", pool.token_address, reason);
+                        return Ok(format!("skipped: liquidity mismatch ({})", reason));
+                    }
+                    None => println!("⚖️  On-chain SOL reserves {:.2} SOL match DexScreener's ~{:.2} SOL", onchain_sol, reported_sol),
+                },
+                Ok(None) => println!("ℹ️  Pool {} keeps its vaults elsewhere - liquidity cross-check skipped", pool.pool_address),
+                Err(e) => {
+                    println!("⚠️  On-chain liquidity unavailable: {}", e);
+                    if self.settings.security.require_rugcheck_success {
+                        println!("❌ Skipping token - liquidity couldn't be cross-checked");
+                        return Ok("skipped: on-chain liquidity unavailable".to_string());
+                    }
+                }
+            }
+        }
+
+        // Step 2d: Round-trip cost - what buying and instantly selling back would lose
+        if self.settings.trading.max_round_trip_cost_percent > 0.0 {
+            let lamports = Lamports::from_sol(self.settings.trading.position_size_sol).as_u64();
+            let slippage_bps = (self.settings.trading.max_slippage_percent * 100.0) as u16;
+            match self.jupiter_trader.round_trip_quote(&pool.token_address, lamports, slippage_bps).await {
+                Ok(lamports_back) => {
+                    let cost = round_trip_cost_percent(lamports, lamports_back);
+                    println!("🔄 Round-trip cost: {:.2}% ({} -> {} lamports, max {}%)",
+                        cost, lamports, lamports_back, self.settings.trading.max_round_trip_cost_percent);
+                    if cost > self.settings.trading.max_round_trip_cost_percent {
+                        return Ok(format!("skipped: round-trip cost {:.2}% above {}%", cost, self.settings.trading.max_round_trip_cost_percent));
+                    }
+                }
+                Err(e) if e.downcast_ref::<NoRouteError>().is_some() => {
+                    println!("❌ No route back to SOL for {} - treating as unsellable", pool.token_address);
+                    return Ok("skipped: round trip has no sell route".to_string());
+                }
+                Err(e) => {
+                    println!("❌ Round-trip quote failed: {}", e);
+                    return Ok("skipped: round-trip cost unavailable".to_string());
+                }
+            }
+        }
+
+        // Signals-only: auto-trading is off, so alert the would-be buy for manual trading instead
+        if self.settings.trading.signals_only && !self.settings.trading.enable_auto_trading {
+            println!("📡 SIGNAL: {} ({}) passed every check - alerting instead of buying", pool.token_symbol, pool.token_address);
+            let msg = signal_message(&pool, &security_result.summary(), &self.telegram.explorer_links_html(&pool.token_address));
+            if let Err(e) = self.telegram.send_message(&msg).await {
+                println!("⚠️  Telegram signal failed: {}", e);
+            }
+            return Ok("signal sent (signals-only mode, not bought)".to_string());
+        }
+
+        // Step 3: Execute purchase if all checks passed (after manual approval, once it is sized)
         println!("✅ All checks passed! Executing purchase...");
         
-        match self.execute_purchase(&pool.token_address, &pool).await {
-            Ok(_purchase_info) => {
+        let outcome = match self.execute_purchase(&pool.token_address, &pool, &security_result.summary()).await {
+            Ok(outcome) if self.dry_run => outcome, // Would-be buy already logged by execute_purchase
+            Ok(outcome) if outcome.starts_with("skipped") => outcome, // Rejected, or sized/funded out
+            Ok(outcome) => {
                 println!("🎉 Purchase successful!");
                 
                 // Send buy notification
@@ -668,9 +2167,7 @@ impl PoolScanner {
                     );
                     self.telegram.send_message(&msg).await?;
                 }
-                
-                // Execute purchase and track position
-                self.execute_purchase(&pool.token_address, &pool).await?;
+                outcome
             }
             Err(e) => {
                 println!("❌ Purchase failed: {}", e);
@@ -679,49 +2176,436 @@ impl PoolScanner {
                     let msg = format!("❌ Purchase FAILED\n💎 Token: `{}`\n⚠️ Error: {}", pool.token_address, e);
                     self.telegram.send_message(&msg).await?;
                 }
+                format!("purchase failed: {}", e)
             }
-        }
+        };
 
-        Ok(())
+        Ok(outcome)
     }
 
-    /// Execute token purchase using Jupiter V6 API
-    async fn execute_purchase(&mut self, token_address: &str, _pool: &NewPool) -> Result<()> {
+    /// Poll the followed wallets and run each copied buy through the normal pipeline,
+    /// priced and sized from the token's deepest DexScreener SOL pair
+    async fn process_copy_signals(&mut self) {
+        let Some(follower) = self.wallet_follower.as_mut() else {
+            return;
+        };
+        let signals: Vec<CopySignal> = follower.poll().await;
+        for signal in signals {
+            let source = format!("{}{}", COPY_SOURCE_PREFIX, signal.wallet);
+            let mut pool = match deepest_sol_pool(&signal.buy.mint, self.position_monitor.sol_price_usd().await).await {
+                Ok(Some((pool, _))) => pool,
+                Ok(None) => {
+                    println!("⚠️  Copy-trading: no SOL pair for {} - not copying {}", signal.buy.mint, signal.signature);
+                    if let Some(trace) = self.decision_trace.as_mut() {
+                        trace.record(&signal.buy.mint, &source, "skipped: no SOL pair to copy into");
+                    }
+                    continue;
+                }
+                Err(e) => {
+                    println!("⚠️  Copy-trading: pool lookup for {} failed: {}", signal.buy.mint, e);
+                    continue;
+                }
+            };
+            pool.dex = source;
+            if !self.within_trading_hours() {
+                println!("🌙 Outside trading window - not copying {} into {}", signal.signature, pool.token_address);
+                if let Some(trace) = self.decision_trace.as_mut() {
+                    trace.record(&pool.token_address, &pool.dex, "skipped: outside trading window");
+                }
+                continue;
+            }
+            self.mark_pool_processed(&pool.pool_address);
+            if let Err(e) = self.process_new_pool(pool).await {
+                println!("❌ Error processing copied buy: {}", e);
+            }
+        }
+    }
+
+    /// Dry-run the buy pipeline on one known token: DexScreener data, the buy filters, the real
+    /// RugCheck criteria and a Jupiter buy quote. Prints every stage and the final would-buy verdict;
+    /// nothing is ever sent.
+    pub async fn test_pipeline(&mut self, token_address: &str) -> Result<()> {
+        let mut skip_reasons: Vec<String> = Vec::new();
+        
+        // Stage 1: DexScreener - the deepest SOL pair for this mint, shaped like a detected pool
+        println!("\n1️⃣  DexScreener");
+        let (pool, liquidity_usd) = match deepest_sol_pool(token_address, self.position_monitor.sol_price_usd().await).await {
+            Ok(Some(found)) => found,
+            Ok(None) => {
+                println!("   ❌ No Solana SOL pair found for {}", token_address);
+                println!("\n❌ WOULD SKIP: token is not tradeable against SOL on any DexScreener pair");
+                return Ok(());
+            }
+            Err(e) => {
+                println!("   ❌ {}", e);
+                return Ok(());
+            }
+        };
+        println!("   ✅ {} ({}) on {} - pool {}", pool.token_name, pool.token_symbol, pool.dex, pool.pool_address);
+        println!("   💧 Liquidity: {:.2} SOL (${:.0}) | Price: ${:.8} / {:.10} SOL",
+            pool.liquidity_sol, liquidity_usd, pool.price_usd, pool.price_sol);
+        
+        // Stage 2: cheap filters, in process_new_pool order
+        println!("\n2️⃣  Filters");
+        if is_avoided_pair_mint(&pool.token_address, &self.settings.security.avoided_pair_mints) {
+            skip_reasons.push(format!("{} is a stablecoin or wrapped major (AVOIDED_PAIR_MINTS)", pool.token_symbol));
+        }
+        if let Some(pattern) = self.name_blacklist.matching_pattern(&pool.token_name, &pool.token_symbol) {
+            skip_reasons.push(format!("name/symbol matches blacklist \"{}\"", pattern));
+        }
+        if let Some(canonical_mint) = impersonated_ticker(&pool.token_symbol, &pool.token_address, &self.settings.security.known_tickers) {
+            skip_reasons.push(format!("ticker {} belongs to {}", pool.token_symbol, canonical_mint));
+        }
+        if pool.liquidity_sol < self.settings.trading.min_liquidity_sol {
+            skip_reasons.push(format!("liquidity {:.2} SOL below {:.2} SOL", pool.liquidity_sol, self.settings.trading.min_liquidity_sol));
+        }
+        if let Some(reason) = max_entry_price_for(
+            &pool.token_address,
+            &self.settings.trading.token_max_entry_price_usd,
+            self.settings.trading.max_entry_price_usd,
+        ).and_then(|limit| entry_price_rejection(pool.price_usd, limit)) {
+            skip_reasons.push(format!("entry {}", reason));
+        }
+        if skip_reasons.is_empty() {
+            println!("   ✅ Avoided-pair, blacklist, impersonation, liquidity and entry price checks passed");
+        } else {
+            skip_reasons.iter().for_each(|reason| println!("   ❌ {}", reason));
+        }
+        
+        // Stage 3: RugCheck - check_token prints the per-criterion breakdown
+        println!("\n3️⃣  RugCheck");
+        match self.rugcheck_client.check_token(&pool.token_address).await {
+            Ok(response) => {
+                let report = self.rugcheck_client.analyze_security_risks(&response);
+                println!("   🛡️  {}", report.summary());
+                let security = &self.settings.security;
+                if !meets_security_bar(response.score, security.min_acceptable_score, security.require_all_criteria) {
+                    skip_reasons.push(format!("security score {}/100 (needs {})", response.score,
+                        security_bar_description(security.min_acceptable_score, security.require_all_criteria)));
+                }
+                for risk in &report.critical_risks {
+                    skip_reasons.push(format!("critical risk: {}", risk));
+                }
+            }
+            Err(e) => {
+                println!("   ❌ RugCheck failed: {}", e);
+                skip_reasons.push(format!("RugCheck unavailable: {}", e));
+            }
+        }
+        
+        // Stage 4: Jupiter buy quote for the configured position size
+        println!("\n4️⃣  Jupiter quote");
         let sol_amount = self.settings.trading.position_size_sol;
+        let lamports = sol_to_lamports(sol_amount);
+        let slippage_bps = (self.settings.trading.initial_slippage_percent * 100.0) as u16;
+        match self.jupiter_trader.get_quote(&pool.token_address, lamports, slippage_bps).await {
+            Ok(quote) => {
+                println!("   ✅ {} SOL -> {} raw tokens (min {} after {}% slippage)",
+                    sol_amount, quote.out_amount, quote.other_amount_threshold, self.settings.trading.initial_slippage_percent);
+                println!("   🛣️  Route: {} ({} legs) | Price impact: {}%",
+                    quote.route_labels().join(" → "), quote.route_count(), quote.price_impact_pct);
+                if quote.route_count() < self.settings.trading.min_routes as usize {
+                    skip_reasons.push(format!("route has {} legs, need {}", quote.route_count(), self.settings.trading.min_routes));
+                }
+                let max_impact = self.settings.trading.max_price_impact_percent;
+                if max_impact > 0.0 && !quote.price_impact_percent().is_some_and(|impact| impact <= max_impact) {
+                    skip_reasons.push(format!("price impact {}% over the {}% maximum",
+                        quote.price_impact_percent().map_or("unknown".to_string(), |impact| format!("{:.2}", impact)), max_impact));
+                }
+            }
+            Err(e) => {
+                println!("   ❌ Quote failed: {}", e);
+                skip_reasons.push(format!("no Jupiter quote: {}", e));
+            }
+        }
+        
+        if skip_reasons.is_empty() {
+            println!("\n✅ WOULD BUY {} SOL of {} ({})", sol_amount, pool.token_symbol, pool.token_address);
+        } else {
+            println!("\n❌ WOULD SKIP {} ({}):", pool.token_symbol, pool.token_address);
+            skip_reasons.iter().for_each(|reason| println!("   • {}", reason));
+        }
+        println!("ℹ️  Self-test only - no transaction was sent");
+        Ok(())
+    }
+
+    /// Re-fetch a pool's current liquidity (SOL) and price (USD) from DexScreener
+    async fn fetch_pool_snapshot(&self, pool: &NewPool) -> Result<(f64, f64)> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()?;
+        
+        let url = format!("https://api.dexscreener.com/latest/dex/tokens/{}", pool.token_address);
+        let response = client
+            .get(&url)
+            .header("Accept", "application/json")
+            .send()
+            .await?;
+        
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("DexScreener API returned status: {}", response.status()));
+        }
+        
+        let data: serde_json::Value = response.json().await?;
+        let pair = data["pairs"]
+            .as_array()
+            .and_then(|pairs| pairs.iter().find(|p| p["pairAddress"].as_str() == Some(pool.pool_address.as_str())))
+            .ok_or_else(|| anyhow::anyhow!("Pool {} not found on DexScreener", pool.pool_address))?;
+        
+        let sol_price_usd = self.position_monitor.sol_price_usd().await;
+        let liquidity_sol = usd_to_sol(pair["liquidity"]["usd"].as_f64().unwrap_or(0.0), sol_price_usd);
+        let price_usd = pair["priceUsd"]
+            .as_str()
+            .and_then(|s| s.parse::<f64>().ok())
+            .unwrap_or(0.0);
+        
+        Ok((liquidity_sol, price_usd))
+    }
+
+    /// Mint creation time, cached per mint since it can't change
+    async fn token_creation_time(&mut self, token_address: &str) -> Result<SystemTime> {
+        let unix_secs = match self.token_creation_times.get(token_address) {
+            Some(&cached) => cached,
+            None => {
+                let fetched = self.rugcheck_client
+                    .fetch_token_creation_time(token_address)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("{}", e))?;
+                self.token_creation_times.insert(token_address.to_string(), fetched);
+                fetched
+            }
+        };
+        Ok(std::time::UNIX_EPOCH + Duration::from_secs(unix_secs))
+    }
+
+    /// Buy size before per-token and balance limits: POSITION_SIZE_SOL, or its compounded size
+    /// after applying every close booked since the last buy
+    async fn base_position_size_sol(&mut self) -> f64 {
+        let closes = std::mem::take(&mut self.position_monitor.book().await.closed_pnls_sol);
+        let Some(sizer) = &mut self.position_sizer else {
+            return self.settings.trading.position_size_sol;
+        };
+        let before = sizer.position_size_sol();
+        if let Err(e) = sizer.record_closes(&closes) {
+            println!("⚠️  Failed to save compounding state: {}", e);
+        }
+        let after = sizer.position_size_sol();
+        if after > before {
+            println!("📈 Compounding: position size {:.4} -> {:.4} SOL after profitable closes", before, after);
+        } else if after < before {
+            println!("📉 Compounding: position size {:.4} -> {:.4} SOL - realized P&L fell back", before, after);
+        }
+        after
+    }
+
+    /// Execute token purchase using Jupiter V6 API
+    async fn execute_purchase(&mut self, token_address: &str, pool: &NewPool, security_summary: &str) -> Result<String> {
+        if self.shutdown.is_cancelled() {
+            println!("🛑 Skipping purchase of {} - shutting down", token_address);
+            return Ok("skipped: shutting down".to_string());
+        }
+        
+        // In-flight cap - the slot is held until this buy resolves, landed or not
+        let Some(_buy_slot) = self.buy_slots.try_acquire() else {
+            println!("🚦 {}/{} buys already in flight - skipping {}", self.buy_slots.in_flight(), self.buy_slots.max(), token_address);
+            return Ok(format!("skipped: {} buys already in flight", self.buy_slots.max()));
+        };
+        
+        let mut sol_amount = self.base_position_size_sol().await
+            * copy_size_multiplier(&pool.dex, &self.settings.trading.copy_trade_wallets);
+        
+        // Per-token cap: trim the buy to fit, or skip it if even the minimum size would exceed it
+        let (room_sol, current_share) = self.position_monitor.allocation_room_sol(token_address).await;
+        if room_sol < sol_amount {
+            let cap = self.settings.trading.max_token_allocation_percent;
+            if room_sol < self.settings.trading.min_position_size_sol {
+                println!("❌ Skipping purchase - {} is already {:.1}% of capital; the {}% cap leaves {:.4} SOL",
+                    token_address, current_share, cap, room_sol);
+                return Ok(format!("skipped: token allocation {:.1}% at the {}% cap", current_share, cap));
+            }
+            println!("📉 Position trimmed {:.4} -> {:.4} SOL to keep {} under the {}% allocation cap",
+                sol_amount, room_sol, token_address, cap);
+            sol_amount = room_sol;
+        }
+        let max_hold = max_hold_for_source(
+            &pool.dex,
+            &self.settings.trading.source_max_hold_minutes,
+            self.settings.trading.max_hold_time_hours,
+        );
+        
+        if self.dry_run {
+            if let Some(skipped) = self.manual_approval(pool, security_summary, sol_amount).await {
+                return Ok(skipped);
+            }
+            println!("🧪 DRY RUN: would buy {} SOL worth of {}", sol_amount, token_address);
+            println!("   Pool: {} ({}) | Liquidity: {:.2} SOL | Price: ${:.8}",
+                pool.pool_address, pool.dex, pool.liquidity_sol, pool.price_usd);
+            
+            if !self.settings.trading.dry_run_paper_positions {
+                return Ok(format!("dry run: would buy {} SOL", sol_amount));
+            }
+            
+            // DRY_RUN_PAPER_POSITIONS: track an in-memory paper position from a real quote for P&L
+            let lamports = sol_to_lamports(sol_amount);
+            let slippage_bps = (self.settings.trading.max_slippage_percent * 100.0) as u16;
+            match self.jupiter_trader.get_quote_with_grace(WRAPPED_SOL_MINT, token_address, lamports, slippage_bps).await {
+                Ok(quote) => {
+                    let estimated_tokens = quote.checked_out_amount(lamports).unwrap_or(0);
+                    if estimated_tokens > 0 {
+                        let position = TokenPosition {
+                            token_address: token_address.to_string(),
+                            purchase_time: SystemTime::now(),
+                            sol_amount,
+                            estimated_tokens,
+                            entry_price: safe_ratio(sol_amount, estimated_tokens as f64),
+                            kind: PositionKind::Paper,
+                            trade_result: None,
+                            max_hold,
+                            timeout_extension: Duration::ZERO,
+                            sell_probe: None,
+                        };
+                        self.position_monitor.record_trade(&position, TradeSide::Buy, sol_amount, None);
+                        self.position_monitor.track_position(position).await;
+                        println!("📝 PAPER: Tracking {} tokens for {} SOL", estimated_tokens, sol_amount);
+                        self.record_buy_time(&pool.dex);
+                    }
+                }
+                Err(e) if e.downcast_ref::<QuoteUnavailableError>().is_some() => {
+                    // Jupiter unreachable - paper mode falls back to a simulated fill (1M tokens per SOL)
+                    println!("🚨 Quote unavailable ({}) - falling back to a simulated paper position", e);
+                    let estimated_tokens = (sol_amount * 1_000_000.0) as u64;
+                    let position = TokenPosition {
+                        token_address: token_address.to_string(),
+                        purchase_time: SystemTime::now(),
+                        sol_amount,
+                        estimated_tokens,
+                        entry_price: safe_ratio(sol_amount, estimated_tokens as f64),
+                        kind: PositionKind::Simulated,
+                        trade_result: None,
+                        max_hold,
+                        timeout_extension: Duration::ZERO,
+                        sell_probe: None,
+                    };
+                    self.position_monitor.record_trade(&position, TradeSide::Buy, sol_amount, None);
+                    self.position_monitor.track_position(position).await;
+                    println!("🧪 SIMULATED: Tracking {} tokens for {} SOL", estimated_tokens, sol_amount);
+                }
+                Err(e) => println!("⚠️  Paper position skipped - no quote available: {}", e),
+            }
+            return Ok(format!("dry run: would buy {} SOL", sol_amount));
+        }
+        
+        let funding_mint = self.settings.trading.funding_mint.clone();
+        let input = if funding_mint == WRAPPED_SOL_MINT {
+            // Shrink the buy so fees and rent still fit above the wallet reserve. A persistent
+            // WSOL account is spendable too - buys draw on it before wrapping more.
+            let balance = match self.wallet.get_sol_balance().await {
+                Ok(native) => self.jupiter_trader.wsol_balance().await.map(|wsol| native + wsol.as_sol()),
+                Err(e) => Err(e),
+            };
+            let sol_amount = match balance {
+                Ok(balance) => {
+                    let reserve = self.settings.wallet.reserve_sol();
+                    let overhead = estimated_buy_overhead_sol(&self.settings);
+                    let floor = self.settings.trading.min_position_size_sol;
+                    match fit_position_size(sol_amount, balance, reserve, overhead, floor) {
+                        Some(size) if size < sol_amount => {
+                            println!("📉 Position shrunk {:.4} -> {:.4} SOL to fit balance {:.4} SOL ({:.4} reserve + ~{:.4} fees/rent)",
+                                sol_amount, size, balance, reserve, overhead);
+                            size
+                        }
+                        Some(size) => size,
+                        None => {
+                            println!("❌ Skipping purchase - balance {:.4} SOL can't fund the {:.4} SOL minimum after {:.4} reserve + ~{:.4} fees/rent",
+                                balance, floor, reserve, overhead);
+                            return Ok(format!("skipped: balance {:.4} SOL can't fund the {:.4} SOL minimum", balance, floor));
+                        }
+                    }
+                }
+                Err(e) => {
+                    // Without a balance the shrink can't be sized, and a full-size buy may not fit
+                    println!("❌ Skipping purchase - balance check failed: {}", e);
+                    return Ok(format!("skipped: balance check failed ({})", e));
+                }
+            };
+            BuyInput::sol(sol_amount)
+        } else {
+            match self.funding_buy_input(&funding_mint, sol_amount).await {
+                Ok(input) => input,
+                Err(reason) => {
+                    println!("❌ Skipping purchase - {}", reason);
+                    return Ok(format!("skipped: {}", reason));
+                }
+            }
+        };
+        let sol_amount = input.sol_value;
+        
+        if let Some(skipped) = self.manual_approval(pool, security_summary, sol_amount).await {
+            return Ok(skipped);
+        }
         
         println!("💰 Executing real purchase of {} SOL worth of {}", sol_amount, token_address);
         
+        // Floor the fill at the pool's observed price minus the configured tolerance
+        let min_tokens_out = match min_out_floor(sol_amount / pool.price_sol, self.settings.trading.min_out_tolerance_percent) {
+            Some(expected_whole_tokens) => match self.jupiter_trader.get_token_decimals(token_address).await {
+                Ok(decimals) => Some((expected_whole_tokens * 10f64.powi(decimals as i32)) as u64),
+                Err(e) => {
+                    println!("⚠️  No minimum-out floor - could not read token decimals: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+        
         // Execute real trade via Jupiter
-        match self.jupiter_trader.buy_token(token_address, sol_amount).await {
+        match self.jupiter_trader.buy_token(token_address, &input, min_tokens_out).await {
             Ok(trade_result) => {
                 println!("🎉 Purchase successful!");
                 println!("📄 Transaction: {}", trade_result.transaction_signature);
+                if let Some(route) = trade_result.landed_via {
+                    println!("🛣️  Landed via: {:?}", route);
+                }
                 println!("💰 Bought {} tokens for {} SOL", trade_result.tokens_received, sol_amount);
+                if let (Some(time_to_fill), Some(slippage)) = (trade_result.time_to_fill, trade_result.realized_slippage_percent) {
+                    println!("⏱️  Filled in {:.2}s | {:.2}% slippage vs quote", time_to_fill.as_secs_f64(), slippage);
+                    if self.recent_fills.len() == FILL_STATS_WINDOW {
+                        self.recent_fills.pop_front();
+                    }
+                    self.recent_fills.push_back((time_to_fill, slippage));
+                }
+                
+                // Honeypot probe: can what we just bought actually be sold?
+                let sell_probe = if self.settings.security.post_buy_sell_probe {
+                    Some(self.probe_sell_after_buy(token_address, trade_result.tokens_received).await)
+                } else {
+                    None
+                };
                 
-                // Track the position for 30-minute auto-sell
+                // Track the position for max-hold auto-sell
                 let position = TokenPosition {
                     token_address: token_address.to_string(),
                     purchase_time: SystemTime::now(),
                     sol_amount,
                     estimated_tokens: trade_result.tokens_received,
-                    entry_price: sol_amount / trade_result.tokens_received as f64,
+                    entry_price: safe_ratio(sol_amount, trade_result.tokens_received as f64),
+                    kind: PositionKind::Real,
                     trade_result: Some(trade_result.clone()),
+                    max_hold,
+                    timeout_extension: Duration::ZERO,
+                    sell_probe,
                 };
                 
-                self.active_positions.insert(token_address.to_string(), position.clone());
-                println!("⏰ Position will auto-sell in 30 minutes if no take profit");
-                
-                // Add position to profit monitor
-                if let Err(e) = self.profit_monitor.add_position(&position).await {
-                    println!("⚠️  Failed to add position to profit monitor: {}", e);
-                } else {
-                    // Start profit monitoring if this is the first position
-                    if !self.profit_monitor.is_monitoring {
-                        if let Err(e) = self.start_profit_monitoring().await {
-                            println!("⚠️  Failed to initialize profit monitoring: {}", e);
-                        }
-                    }
+                self.position_monitor.record_trade(&position, TradeSide::Buy, sol_amount, Some(trade_result.transaction_signature.clone()));
+                self.position_monitor.track_position(position).await;
+                self.record_buy_time(&pool.dex);
+                if let Err(e) = self.trade_journal.clear(token_address) {
+                    println!("⚠️  Failed to clear trade journal entry: {}", e);
                 }
+                if let Err(e) = self.daily_trades.record_buy(utc_day(unix_secs(SystemTime::now()))) {
+                    println!("⚠️  Failed to save daily trade count: {}", e);
+                }
+                println!("⏰ Position will auto-sell in {} if no take profit", format_duration(max_hold));
                 
                 // Send Telegram buy alert
                 if self.settings.telegram.notifications_enabled && self.settings.telegram.send_buy_alerts {
@@ -730,184 +2614,187 @@ impl PoolScanner {
                         token_address,
                         token_name,
                         sol_amount,
-                        sol_amount / trade_result.tokens_received as f64 // Calculate price from trade result
+                        safe_ratio(sol_amount, trade_result.tokens_received as f64) // Calculate price from trade result
                     ).await {
                         println!("⚠️  Telegram buy alert failed: {}", e);
                     }
                 }
-            }
-            Err(e) => {
-                // Check if this is a Jupiter API error
-                let error_message = e.to_string();
-                if error_message.contains("Jupiter") || error_message.contains("API") || error_message.contains("dns error") {
-                    println!("🚨 Jupiter API Error: {}", e);
-                    println!("💡 The Jupiter API endpoints may have changed or require different authentication.");
-                    println!("📋 Falling back to simulation mode for this token purchase:");
-                    
-                    // Simulate the purchase
-                    let estimated_tokens = (sol_amount * 1_000_000.0) as u64; // Simulate 1M tokens per SOL
-                    
-                    // Create a simulated position
-                    let position = TokenPosition {
-                        token_address: token_address.to_string(),
-                        purchase_time: SystemTime::now(),
-                        sol_amount,
-                        estimated_tokens,
-                        entry_price: sol_amount / estimated_tokens as f64,
-                        trade_result: None, // None indicates simulation
-                    };
-                    
-                    self.active_positions.insert(token_address.to_string(), position.clone());
-                    
-                    // Add simulated position to profit monitor
-                    if let Err(e) = self.profit_monitor.add_position(&position).await {
-                        println!("⚠️  Failed to add simulated position to profit monitor: {}", e);
-                    } else {
-                        // Start profit monitoring if this is the first position
-                        if !self.profit_monitor.is_monitoring {
-                            if let Err(e) = self.start_profit_monitoring().await {
-                                println!("⚠️  Failed to initialize profit monitoring: {}", e);
-                            }
-                        }
+                
+                // Shutdown came while the buy was already sent - don't leave it behind unmanaged
+                if self.shutdown.is_cancelled() {
+                    println!("🛑 Shutdown requested mid-buy - selling {} straight back", token_address);
+                    if let Err(e) = self.position_monitor.sell_now(token_address, "SHUTDOWN").await {
+                        println!("❌ Shutdown sell of {} failed - position stays tracked: {}", token_address, e);
                     }
-                    
-                    println!("🧪 SIMULATED: Bought {} tokens for {} SOL", estimated_tokens, sol_amount);
-                    println!("⏰ Simulated position will auto-sell in 30 minutes");
-                    println!("🔧 To fix Jupiter integration, check API documentation or update endpoints");
-                    
-                    return Ok(()); // Don't fail the whole process
-                } else {
+                }
+            }
+            Err(e) if e.downcast_ref::<BuyCancelledError>().is_some() => {
+                println!("🛑 Purchase of {} cancelled before sending - shutting down", token_address);
+                return Ok("skipped: cancelled by shutdown".to_string());
+            }
+            Err(e) if e.downcast_ref::<MinOutError>().is_some() => {
+                println!("❌ Skipping purchase - fill too poor: {}", e);
+                return Ok(format!("skipped: fill too poor ({})", e));
+            }
+            Err(e) if e.downcast_ref::<RouteCountError>().is_some() => {
+                println!("❌ Skipping purchase - route too thin: {}", e);
+                return Ok(format!("skipped: route too thin ({})", e));
+            }
+            Err(e) if e.downcast_ref::<PriceImpactError>().is_some() => {
+                println!("❌ Skipping purchase - price impact too high: {}", e);
+                return Ok(format!("skipped: price impact too high ({})", e));
+            }
+            Err(e) if e.downcast_ref::<ReserveBreachError>().is_some() => {
+                println!("❌ Skipping purchase - SOL reserve: {}", e);
+                return Ok(format!("skipped: SOL reserve ({})", e));
+            }
+            Err(e) if e.downcast_ref::<NoRouteError>().is_some() => {
+                // Jupiter is up but can't route this token - a simulated fill would be fiction
+                println!("❌ Skipping purchase - no route: {}", e);
+                return Ok(format!("skipped: no route ({})", e));
+            }
+            Err(e) => match e.downcast_ref::<QuoteUnavailableError>() {
+                // Every quote attempt failed - nothing was sent, so skip rather than fail the pool
+                Some(unavailable) => {
+                    println!("❌ Skipping purchase - quote unavailable: {}", e);
+                    return Ok(format!("skipped: quote unavailable after {} attempts", unavailable.attempts));
+                }
+                None => {
                     println!("❌ Purchase failed: {}", e);
                     return Err(e);
                 }
-            }
+            },
         }
         
-        Ok(())
+        Ok(format!("bought {:.4} SOL", sol_amount))
     }
 
-    /// Monitor positions for 30-minute timeout auto-sell
-    async fn monitor_position_timeouts(&mut self) -> Result<()> {
-        let current_time = SystemTime::now();
-        let timeout_duration = Duration::from_secs(30 * 60); // 30 minutes
-        let mut positions_to_sell = Vec::new();
-        
-        // Check which positions have exceeded 30 minutes
-        for (token_address, position) in &self.active_positions {
-            if let Ok(elapsed) = current_time.duration_since(position.purchase_time) {
-                if elapsed >= timeout_duration {
-                    positions_to_sell.push(token_address.clone());
-                }
-            }
+    /// REQUIRE_MANUAL_APPROVAL: a human confirms the buy of `sol_amount` (its final size) via
+    /// Telegram. Some(skip reason) unless approved; None straight away when approval is off.
+    async fn manual_approval(&self, pool: &NewPool, security_summary: &str, sol_amount: f64) -> Option<String> {
+        if !self.settings.trading.require_manual_approval {
+            return None;
         }
+        let timeout = Duration::from_secs(self.settings.trading.manual_approval_timeout_secs);
+        let msg = approval_message(pool, security_summary, sol_amount, timeout);
+        println!("🙋 Waiting up to {}s for Telegram approval...", timeout.as_secs());
         
-        // Execute auto-sell for timed out positions
-        for token_address in positions_to_sell {
-            if let Some(position) = self.active_positions.remove(&token_address) {
-                self.execute_auto_sell(&position).await?;
+        match self.telegram.request_approval(&msg, &pool.token_address, timeout).await {
+            Ok(ApprovalDecision::Approved { by }) => {
+                println!("✅ Buy approved by {}", by);
+                None
             }
-        }
-        
-        Ok(())
-    }
-    
-    /// Execute automatic sell after 30-minute timeout using Jupiter V6 API
-    async fn execute_auto_sell(&mut self, position: &TokenPosition) -> Result<()> {
-        println!("⏰ 30-MINUTE TIMEOUT REACHED - AUTO-SELLING 100%");
-        println!("   Token: {}", position.token_address);
-        println!("   Original investment: {:.4} SOL", position.sol_amount);
-        println!("   Tokens to sell: {}", position.estimated_tokens);
-        
-        // Execute real sell via Jupiter
-        match self.jupiter_trader.sell_token(&position.token_address, position.estimated_tokens).await {
-            Ok(sell_result) => {
-                let received_sol = sell_result.sol_received;
-                let profit_loss = received_sol - position.sol_amount;
-                let profit_percent = (profit_loss / position.sol_amount) * 100.0;
-                
-                println!("💸 AUTO-SELL EXECUTED - 100% of position sold");
-                println!("📄 Transaction: {}", sell_result.transaction_signature);
-                println!("💰 Received: {:.4} SOL", received_sol);
-                
-                if profit_loss >= 0.0 {
-                    println!("   📈 Profit: +{:.4} SOL (+{:.1}%)", profit_loss, profit_percent);
-                } else {
-                    println!("   📉 Loss: {:.4} SOL ({:.1}%)", profit_loss, profit_percent);
-                }
-                
-                // Send Telegram sell alert
-                if self.settings.telegram.notifications_enabled && self.settings.telegram.send_sell_alerts {
-                    if let Err(e) = self.telegram.send_sell_alert(
-                        &position.token_address,
-                        "Auto-Sold Token", // In real implementation, store token name in position
-                        received_sol,
-                        profit_loss,
-                        profit_percent
-                    ).await {
-                        println!("⚠️  Telegram sell alert failed: {}", e);
-                    }
+            Ok(ApprovalDecision::Rejected { by }) => {
+                println!("❌ Buy rejected by {}", by);
+                Some(format!("skipped: buy rejected by {}", by))
+            }
+            Ok(ApprovalDecision::TimedOut) => {
+                println!("⌛ No approval within {}s - rejecting", timeout.as_secs());
+                if let Err(e) = self.telegram.send_message(&format!("⌛ Approval timed out - skipped <code>{}</code>", escape_html(&pool.token_address))).await {
+                    println!("⚠️  Telegram notification failed: {}", e);
                 }
-
-                // Remove position from profit monitor
-                self.profit_monitor.remove_position(&position.token_address);
+                Some("skipped: manual approval timed out".to_string())
             }
             Err(e) => {
-                println!("❌ Auto-sell failed: {}", e);
-                return Err(e);
+                println!("❌ Approval request failed - rejecting: {}", e);
+                Some("skipped: approval request failed".to_string())
             }
         }
-        
-        Ok(())
     }
 
-    /// Display current position status with countdown
-    fn display_position_status(&self) {
-        let active_count = self.active_positions.len();
-        println!("📊 Active positions: {} (30-min auto-sell enabled)", active_count);
-        
-        for (token, position) in &self.active_positions {
-            if let Ok(elapsed) = SystemTime::now().duration_since(position.purchase_time) {
-                let remaining = Duration::from_secs(30 * 60).saturating_sub(elapsed);
-                let remaining_minutes = remaining.as_secs() / 60;
-                let remaining_seconds = remaining.as_secs() % 60;
-                println!("   💎 {} - Auto-sell in {}:{:02}", &token[0..8], remaining_minutes, remaining_seconds);
+    /// Start the MIN_SECONDS_BETWEEN_BUYS window, globally and for the buy's source
+    fn record_buy_time(&mut self, source: &str) {
+        let now = Instant::now();
+        self.last_buy_at = Some(now);
+        self.last_buy_by_source.insert(source.to_string(), now);
+    }
+
+    /// A buy worth `sol_amount` paid in a non-SOL funding mint: the amount comes from a SOL -> mint
+    /// quote, the wallet must hold it, and native SOL must still cover the reserve, fees and rent.
+    /// Err is the skip reason.
+    async fn funding_buy_input(&self, funding_mint: &str, sol_amount: f64) -> Result<BuyInput, String> {
+        let lamports = sol_to_lamports(sol_amount);
+        let quote = self.jupiter_trader.get_quote_for(WRAPPED_SOL_MINT, funding_mint, lamports, 50).await
+            .map_err(|e| format!("could not price {} SOL in {}: {}", sol_amount, funding_mint, e))?;
+        let amount = quote.checked_out_amount(lamports)
+            .map_err(|e| format!("rejected {} pricing quote: {}", funding_mint, e))?;
+
+        match self.jupiter_trader.get_token_balance(funding_mint).await {
+            Ok(held) if held < amount => {
+                return Err(format!("wallet holds {} base units of {}, the buy needs {}", held, funding_mint, amount));
             }
+            Ok(_) => {}
+            Err(e) => println!("⚠️  {} balance check failed - buying anyway: {}", funding_mint, e),
         }
-    }
 
-    /// Monitor existing positions for take profit opportunities
-    async fn monitor_existing_positions(&mut self) -> Result<()> {
-        // Take profit monitoring disabled in simplified version
-        Ok(())
+        let reserve = self.settings.wallet.reserve_sol();
+        let overhead = estimated_buy_overhead_sol(&self.settings);
+        match self.wallet.get_sol_balance().await {
+            Ok(native) if native < reserve + overhead => {
+                return Err(format!("native balance {:.4} SOL can't cover {:.4} reserve + ~{:.4} fees/rent", native, reserve, overhead));
+            }
+            Ok(_) => {}
+            Err(e) => println!("⚠️  Balance check failed - buying anyway: {}", e),
+        }
+
+        println!("💵 Funding buy with {} base units of {} (~{:.4} SOL)", amount, funding_mint, sol_amount);
+        Ok(BuyInput { mint: funding_mint.to_string(), amount, sol_value: sol_amount })
     }
 
-    /// Start real-time profit monitoring for all active positions (initialization only)
-    async fn start_profit_monitoring(&mut self) -> Result<()> {
-        if !self.active_positions.is_empty() && !self.profit_monitor.is_monitoring {
-            if let Err(e) = self.profit_monitor.start_monitoring(&self.active_positions).await {
-                println!("⚠️  Failed to start profit monitoring: {}", e);
+    /// Simulate selling a fresh fill and alert loudly if it can't be sold
+    async fn probe_sell_after_buy(&self, token_address: &str, tokens_received: u64) -> SellProbe {
+        println!("🍯 Probing sell of {} tokens (simulation only)...", tokens_received);
+        let probe = match self.jupiter_trader.probe_sell(token_address, tokens_received).await {
+            Ok(probe) => probe,
+            Err(e) => SellProbe::Inconclusive(e.to_string()),
+        };
+        
+        match &probe {
+            SellProbe::Sellable => println!("✅ Sell probe passed - position can be exited"),
+            SellProbe::Inconclusive(reason) => println!("⚠️  Sell probe inconclusive: {}", reason),
+            SellProbe::Honeypot(reason) => {
+                println!("🚨🍯 HONEYPOT: {} can't be sold - {}", token_address, reason);
+                if self.settings.telegram.notifications_enabled {
+                    let msg = format!("🚨🍯 HONEYPOT DETECTED AFTER BUY\n💎 Token: `{}`\n🪤 {} tokens held, sell simulation fails\n❌ {}",
+                        token_address, tokens_received, reason);
+                    if let Err(e) = self.telegram.send_message(&msg).await {
+                        println!("⚠️  Failed to send honeypot alert: {}", e);
+                    }
+                }
             }
         }
-        Ok(())
+        probe
     }
 
-    /// Update profit monitoring (periodic updates)
-    async fn update_profit_monitoring(&mut self) -> Result<()> {
-        if !self.active_positions.is_empty() {
-            // This will update existing positions and handle timing
-            if let Err(e) = self.profit_monitor.start_monitoring(&self.active_positions).await {
-                println!("⚠️  Failed to update profit monitoring: {}", e);
+    /// Print this run's summary and send it to Telegram - the bookend to the startup message
+    async fn send_session_report(&self) {
+        let started = unix_secs(self.started_at);
+        let trades = match self.storage.load_trades() {
+            Ok(trades) => trades.into_iter().filter(|trade| trade.timestamp_unix_secs >= started).collect(),
+            Err(e) => {
+                println!("⚠️  Trade history unavailable for the session summary: {}", e);
+                Vec::new()
+            }
+        };
+        let fee_per_swap_sol = estimated_swap_fee_sol(&self.settings);
+        let report = session_report(&self.get_stats().await, &trades, fee_per_swap_sol);
+        report.display();
+        if self.settings.telegram.notifications_enabled {
+            if let Err(e) = self.telegram.send_message(&report.telegram_message()).await {
+                println!("⚠️  Failed to send session summary: {}", e);
             }
         }
-        Ok(())
     }
 
-    pub fn get_stats(&self) -> PoolScannerStats {
+    pub async fn get_stats(&self) -> PoolScannerStats {
         PoolScannerStats {
             total_scans: self.scan_count,
             pools_processed: self.processed_pools.len(),
-            active_positions: self.active_positions.len(),
+            active_positions: self.position_monitor.book().await.active_positions.len(),
             uptime_seconds: self.last_scan_time.elapsed().as_secs(),
+            rpc_usage: self.rpc_limiter.usage(),
+            avg_time_to_fill_ms: rolling_fill_averages(&self.recent_fills).map(|(time, _)| time.as_millis() as u64),
+            avg_realized_slippage_percent: rolling_fill_averages(&self.recent_fills).map(|(_, slippage)| slippage),
         }
     }
 }
@@ -918,4 +2805,816 @@ pub struct PoolScannerStats {
     pub pools_processed: usize,
     pub active_positions: usize,
     pub uptime_seconds: u64,
-}
\ No newline at end of file
+    pub rpc_usage: RpcUsage,
+    pub avg_time_to_fill_ms: Option<u64>,          // Over the last FILL_STATS_WINDOW buys
+    pub avg_realized_slippage_percent: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::position_monitor::hold_time_remaining;
+    use crate::rugcheck::{RugCheckFuture, RugCheckResponse};
+
+    #[test]
+    fn real_positions_are_market_priced_and_sold_on_chain() {
+        assert!(PositionKind::Real.market_priced());
+        assert!(PositionKind::Real.on_chain());
+    }
+
+    #[test]
+    fn paper_positions_are_market_priced_but_never_sold_on_chain() {
+        assert!(PositionKind::Paper.market_priced());
+        assert!(!PositionKind::Paper.on_chain());
+    }
+
+    #[test]
+    fn simulated_positions_are_neither_market_priced_nor_on_chain() {
+        assert!(!PositionKind::Simulated.market_priced());
+        assert!(!PositionKind::Simulated.on_chain());
+    }
+
+    #[test]
+    fn live_runs_restore_real_positions_and_dry_runs_restore_none() {
+        assert_eq!(restored_position_kind(false), Some(PositionKind::Real));
+        assert_eq!(restored_position_kind(true), None);
+    }
+
+    #[test]
+    fn min_out_floor_takes_the_tolerance_off_the_expected_fill() {
+        assert_eq!(min_out_floor(1_000.0, 20.0), Some(800.0));
+        assert_eq!(min_out_floor(1_000.0, 0.0), None); // Disabled
+        assert_eq!(min_out_floor(0.0, 20.0), None);    // No usable expectation
+        assert_eq!(min_out_floor(f64::NAN, 20.0), None);
+    }
+
+    fn position(max_hold: Duration, purchase_time: SystemTime) -> TokenPosition {
+        TokenPosition {
+            token_address: "TokenMint111".to_string(),
+            purchase_time,
+            sol_amount: 0.1,
+            estimated_tokens: 1_000,
+            entry_price: 0.0001,
+            kind: PositionKind::Real,
+            trade_result: None,
+            max_hold,
+            timeout_extension: Duration::ZERO,
+            sell_probe: None,
+        }
+    }
+
+    #[test]
+    fn max_hold_for_source_picks_the_longest_matching_prefix() {
+        let overrides = HashMap::from([
+            ("pump".to_string(), 5),
+            ("Pump.fun/Jupiter".to_string(), 15),
+            ("raydium".to_string(), 60),
+        ]);
+        assert_eq!(max_hold_for_source("Pump.fun/Jupiter", &overrides, 24), Duration::from_secs(15 * 60));
+        assert_eq!(max_hold_for_source("Pump.fun", &overrides, 24), Duration::from_secs(5 * 60));
+        assert_eq!(max_hold_for_source("Raydium", &overrides, 24), Duration::from_secs(60 * 60));
+        assert_eq!(max_hold_for_source("Orca", &overrides, 24), Duration::from_secs(24 * 3600));
+    }
+
+    #[test]
+    fn max_hold_for_source_breaks_case_ties_the_same_way_every_time() {
+        let overrides = HashMap::from([("PUMP".to_string(), 30), ("pump".to_string(), 10)]);
+        for _ in 0..10 {
+            assert_eq!(max_hold_for_source("pump.fun", &overrides, 24), Duration::from_secs(10 * 60));
+        }
+    }
+
+    #[test]
+    fn positions_sell_at_their_own_max_hold() {
+        let bought = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let overrides = HashMap::from([("pump".to_string(), 5)]);
+        let momentum = position(max_hold_for_source("Pump.fun/Jupiter", &overrides, 24), bought);
+        let conviction = position(max_hold_for_source("Raydium", &overrides, 24), bought);
+
+        let after_six_minutes = bought + Duration::from_secs(6 * 60);
+        assert!(hold_time_remaining(&momentum, after_six_minutes).is_zero());
+        assert_eq!(hold_time_remaining(&conviction, after_six_minutes), Duration::from_secs(24 * 3600 - 6 * 60));
+
+        let after_a_day = bought + Duration::from_secs(24 * 3600);
+        assert!(hold_time_remaining(&conviction, after_a_day).is_zero());
+    }
+
+    /// Example security backend: canned score and critical risks, no network
+    struct StubRugChecker {
+        score: u8,
+        critical_risks: Vec<String>,
+    }
+
+    impl RugChecker for StubRugChecker {
+        fn check_token<'a>(&'a self, token_address: &'a str) -> RugCheckFuture<'a, RugCheckResponse> {
+            let response = RugCheckResponse {
+                token_address: token_address.to_string(),
+                score: self.score,
+                score_breakdown: Vec::new(),
+                risks: Vec::new(),
+                liquidity_info: None,
+                mint_info: None,
+                market_info: None,
+                tax_info: None,
+            };
+            Box::pin(async move { Ok(response) })
+        }
+
+        fn analyze_security_risks(&self, response: &RugCheckResponse) -> SecurityReport {
+            SecurityReport {
+                score: response.score,
+                critical_risks: self.critical_risks.clone(),
+                warnings: Vec::new(),
+                good_signs: Vec::new(),
+                auto_buy_approved: self.critical_risks.is_empty(),
+                criteria_results: Vec::new(),
+                recommendation: String::new(),
+                criteria_used: RugCheckCriteria::default(),
+                buy_tax_percent: None,
+                sell_tax_percent: None,
+                score_breakdown: Vec::new(),
+            }
+        }
+    }
+
+    async fn verdict_from(checker: &dyn RugChecker, min_acceptable_score: u8, require_all_criteria: bool) -> SecurityVerdict {
+        let response = checker.check_token("TokenMint111").await.unwrap();
+        let report = checker.analyze_security_risks(&response);
+        security_verdict(response.score, &report, min_acceptable_score, require_all_criteria)
+    }
+
+    #[tokio::test]
+    async fn stubbed_clean_token_passes() {
+        let checker = StubRugChecker { score: 100, critical_risks: Vec::new() };
+        assert_eq!(verdict_from(&checker, 80, true).await, SecurityVerdict::Pass);
+    }
+
+    #[tokio::test]
+    async fn stubbed_low_score_is_rejected_against_the_bar() {
+        let checker = StubRugChecker { score: 83, critical_risks: Vec::new() };
+        assert_eq!(verdict_from(&checker, 80, true).await, SecurityVerdict::BelowBar("all 6 criteria".to_string()));
+        assert_eq!(verdict_from(&checker, 80, false).await, SecurityVerdict::Pass);
+        assert_eq!(verdict_from(&checker, 90, false).await, SecurityVerdict::BelowBar("score 90+".to_string()));
+    }
+
+    #[tokio::test]
+    async fn stubbed_critical_risk_is_rejected_despite_the_score() {
+        let checker = StubRugChecker { score: 100, critical_risks: vec!["Mint authority not revoked".to_string()] };
+        assert_eq!(
+            verdict_from(&checker, 80, true).await,
+            SecurityVerdict::CriticalRisks(vec!["Mint authority not revoked".to_string()]),
+        );
+    }
+
+    /// Security backend that can't be reached
+    struct UnreachableRugChecker;
+
+    impl RugChecker for UnreachableRugChecker {
+        fn check_token<'a>(&'a self, _token_address: &'a str) -> RugCheckFuture<'a, RugCheckResponse> {
+            Box::pin(async { Err("connection refused".into()) })
+        }
+
+        fn analyze_security_risks(&self, _response: &RugCheckResponse) -> SecurityReport {
+            unreachable!("no response to analyze")
+        }
+    }
+
+    /// Both providers the way steps 2 and 2a run them: the primary's verdict against its
+    /// (possibly waived) 80+ bar, then a 70+ second opinion once the primary passed
+    async fn cross_check(primary: &StubRugChecker, secondary: &dyn RugChecker, mode: SecondaryCheckMode) -> (SecurityVerdict, Option<SecondOpinion>) {
+        let (min_score, all_criteria) = primary_security_bar(80, false, Some(mode));
+        let verdict = verdict_from(primary, min_score, all_criteria).await;
+        let opinion = match verdict {
+            SecurityVerdict::Pass => Some(second_opinion(secondary, "TokenMint111", mode, primary.score, 70).await),
+            _ => None,
+        };
+        (verdict, opinion)
+    }
+
+    #[tokio::test]
+    async fn both_mode_buys_only_when_the_providers_agree() {
+        let primary = StubRugChecker { score: 90, critical_risks: Vec::new() };
+        let agreeing = StubRugChecker { score: 75, critical_risks: Vec::new() };
+        let disagreeing = StubRugChecker { score: 40, critical_risks: Vec::new() };
+
+        assert_eq!(cross_check(&primary, &agreeing, SecondaryCheckMode::Both).await, (SecurityVerdict::Pass, Some(SecondOpinion::Agree(75))));
+        assert_eq!(
+            cross_check(&primary, &disagreeing, SecondaryCheckMode::Both).await,
+            (SecurityVerdict::Pass, Some(SecondOpinion::Disagree { score: 40, high_risks: Vec::new() })),
+        );
+    }
+
+    #[tokio::test]
+    async fn both_mode_keeps_the_primary_bar() {
+        let weak_primary = StubRugChecker { score: 60, critical_risks: Vec::new() };
+        let clean_secondary = StubRugChecker { score: 100, critical_risks: Vec::new() };
+        assert_eq!(
+            cross_check(&weak_primary, &clean_secondary, SecondaryCheckMode::Both).await,
+            (SecurityVerdict::BelowBar("score 80+".to_string()), None),
+        );
+    }
+
+    #[tokio::test]
+    async fn combined_mode_judges_the_average_instead_of_the_primary_bar() {
+        // 60 misses the primary's 80 bar, but its average with 90 reaches 75
+        let weak_primary = StubRugChecker { score: 60, critical_risks: Vec::new() };
+        let strong_secondary = StubRugChecker { score: 90, critical_risks: Vec::new() };
+        assert_eq!(
+            cross_check(&weak_primary, &strong_secondary, SecondaryCheckMode::Combined).await,
+            (SecurityVerdict::Pass, Some(SecondOpinion::Agree(90))),
+        );
+
+        // 60 and 70 average 65 - short of 70
+        let lukewarm_secondary = StubRugChecker { score: 70, critical_risks: Vec::new() };
+        assert_eq!(
+            cross_check(&weak_primary, &lukewarm_secondary, SecondaryCheckMode::Combined).await,
+            (SecurityVerdict::Pass, Some(SecondOpinion::Disagree { score: 70, high_risks: Vec::new() })),
+        );
+    }
+
+    #[tokio::test]
+    async fn combined_mode_still_rejects_critical_risks() {
+        let risky_primary = StubRugChecker { score: 60, critical_risks: vec!["Freeze authority active".to_string()] };
+        let clean_secondary = StubRugChecker { score: 100, critical_risks: Vec::new() };
+        assert_eq!(
+            cross_check(&risky_primary, &clean_secondary, SecondaryCheckMode::Combined).await,
+            (SecurityVerdict::CriticalRisks(vec!["Freeze authority active".to_string()]), None),
+        );
+    }
+
+    #[tokio::test]
+    async fn unreachable_second_provider_is_reported_not_passed() {
+        let primary = StubRugChecker { score: 100, critical_risks: Vec::new() };
+        assert_eq!(
+            cross_check(&primary, &UnreachableRugChecker, SecondaryCheckMode::Both).await,
+            (SecurityVerdict::Pass, Some(SecondOpinion::Unavailable("connection refused".to_string()))),
+        );
+    }
+
+    #[test]
+    fn primary_bar_is_only_waived_in_combined_mode() {
+        assert_eq!(primary_security_bar(80, true, None), (80, true));
+        assert_eq!(primary_security_bar(80, true, Some(SecondaryCheckMode::Off)), (80, true));
+        assert_eq!(primary_security_bar(80, true, Some(SecondaryCheckMode::Both)), (80, true));
+        assert_eq!(primary_security_bar(80, true, Some(SecondaryCheckMode::Combined)), (0, false));
+    }
+
+    #[test]
+    fn fit_position_size_keeps_the_full_size_when_it_fits() {
+        assert_eq!(fit_position_size(0.1, 1.0, 0.05, 0.01, 0.05), Some(0.1));
+    }
+
+    #[test]
+    fn fit_position_size_shrinks_to_the_spendable_balance() {
+        let size = fit_position_size(0.5, 0.3, 0.05, 0.01, 0.05).unwrap();
+        assert!((size - 0.24).abs() < 1e-9);
+    }
+
+    #[test]
+    fn fit_position_size_skips_below_the_floor() {
+        assert_eq!(fit_position_size(0.5, 0.1, 0.05, 0.01, 0.05), None);
+        assert_eq!(fit_position_size(0.5, 0.02, 0.05, 0.01, 0.0), None); // Nothing spendable at all
+    }
+
+    fn pool(token_name: &str, token_symbol: &str) -> NewPool {
+        NewPool {
+            token_address: "TokenMint111".to_string(),
+            token_name: token_name.to_string(),
+            token_symbol: token_symbol.to_string(),
+            pool_address: "PoolAddress111".to_string(),
+            base_mint: "TokenMint111".to_string(),
+            quote_mint: WRAPPED_SOL_MINT.to_string(),
+            liquidity_sol: 42.0,
+            price_usd: 0.001,
+            price_sol: 0.00001,
+            detected_at: SystemTime::UNIX_EPOCH,
+            dex: "Raydium".to_string(),
+        }
+    }
+
+    #[test]
+    fn signal_message_escapes_token_values() {
+        let message = signal_message(&pool("<b>Fake</b> & Co", "<i>"), "🛡️ Score: 100/100", "<a href=\"x\">View</a>");
+        assert!(message.contains("&lt;b&gt;Fake&lt;/b&gt; &amp; Co (&lt;i&gt;)"));
+        assert!(!message.contains("<i>"));
+        assert!(message.ends_with("<a href=\"x\">View</a>"));
+    }
+
+    #[test]
+    fn approval_message_escapes_token_values_and_shows_the_final_size() {
+        let mut pool = pool("Pepe <b>&</b> Co", "P&P");
+        pool.dex = "Ray<dium>".to_string();
+        let message = approval_message(&pool, "🛡️ Score: 92/100 <weak LP>", 0.0375, Duration::from_secs(60));
+        assert!(message.contains("💎 Pepe &lt;b&gt;&amp;&lt;/b&gt; Co (P&amp;P)\n"));
+        assert!(message.contains("🏊 Ray&lt;dium&gt; ("));
+        assert!(message.contains("💰 Amount: 0.0375 SOL\n🛡️ Score: 92/100 &lt;weak LP&gt;\n"));
+        assert!(message.ends_with("⏳ Auto-reject in 60s"));
+    }
+
+    #[test]
+    fn impersonator_message_escapes_the_ticker() {
+        let message = impersonator_message(&pool("USD Coin", "USDC<&>"), "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+        assert!(message.contains("🏷️ Ticker: USDC&lt;&amp;&gt;\n"));
+        assert!(message.ends_with("<code>EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v</code>"));
+    }
+
+    #[test]
+    fn deferred_pools_keep_the_backlog_in_liquidity_order() {
+        let with_liquidity = |liquidity_sol| NewPool { liquidity_sol, ..pool("Token", "TKN") };
+        let mut pending = Vec::new();
+        for liquidity in [20.0, 80.0, 50.0, 80.0, 5.0] {
+            defer_pool(&mut pending, with_liquidity(liquidity));
+        }
+        let order: Vec<f64> = pending.iter().map(|pool| pool.liquidity_sol).collect();
+        assert_eq!(order, vec![80.0, 80.0, 50.0, 20.0, 5.0]);
+
+        // Draining takes the deepest first
+        let (batch, rest) = prioritize_pools(pending, 2);
+        assert_eq!(batch.iter().map(|pool| pool.liquidity_sol).collect::<Vec<_>>(), vec![80.0, 80.0]);
+        assert_eq!(rest.len(), 3);
+    }
+
+    #[test]
+    fn two_rapid_pools_get_only_one_buy_inside_the_gap() {
+        let gap = Duration::from_secs(30);
+        let start = Instant::now();
+        // First qualifying pool: no previous buy, goes ahead
+        assert_eq!(buy_spacing_wait(None, start, gap), None);
+        // Second one 5s after that buy: deferred for the rest of the gap
+        assert_eq!(buy_spacing_wait(Some(start), start + Duration::from_secs(5), gap), Some(Duration::from_secs(25)));
+        // Once the gap has passed it may buy again
+        assert_eq!(buy_spacing_wait(Some(start), start + gap, gap), None);
+    }
+
+    #[test]
+    fn liquidity_cross_check_passes_when_sources_agree() {
+        assert!(liquidity_disagreement(50.0, 40.0, 3.0).is_none());
+        assert!(liquidity_disagreement(40.0, 50.0, 3.0).is_none());
+        assert!(liquidity_disagreement(30.0, 10.0, 3.0).is_none()); // Exactly at the factor
+    }
+
+    #[test]
+    fn liquidity_cross_check_rejects_when_sources_disagree() {
+        assert!(liquidity_disagreement(100.0, 10.0, 3.0).unwrap().contains("10.0x apart"));
+        assert!(liquidity_disagreement(10.0, 100.0, 3.0).is_some()); // Indexer lagging behind the pool
+        assert!(liquidity_disagreement(25.0, 0.0, 3.0).unwrap().contains("holds none"));
+    }
+
+    fn scan_settings(max_candidates: u32) -> BotSettings {
+        let mut settings = BotSettings::from_env().unwrap();
+        settings.trading.min_liquidity_sol = 10.0;
+        settings.monitoring.max_candidates_per_scan = max_candidates;
+        settings.monitoring.allow_missing_creation_time = false;
+        settings.monitoring.skip_inactive_pools = false;
+        settings
+    }
+
+    /// A raw DexScreener pair: `token` against wrapped SOL (or `quote`), `age_secs` before `now`
+    fn dex_pair(token: &str, quote: &str, chain: &str, age_secs: u64, liquidity_usd: f64, now: SystemTime) -> serde_json::Value {
+        let created_millis = now.duration_since(UNIX_EPOCH).unwrap().as_millis() as u64 - age_secs * 1000;
+        serde_json::json!({
+            "chainId": chain,
+            "dexId": "raydium",
+            "url": format!("https://dexscreener.com/solana/pair-{}", token),
+            "pairAddress": format!("pair-{}", token),
+            "baseToken": { "address": token, "name": format!("Token {}", token), "symbol": "TKN" },
+            "quoteToken": { "address": quote, "name": "Wrapped SOL", "symbol": "SOL" },
+            "priceNative": "0.000001",
+            "priceUsd": "0.0002",
+            "liquidity": { "usd": liquidity_usd },
+            "volume": { "h24": 1000.0, "h1": 10.0, "m5": 1.0 },
+            "pairCreatedAt": created_millis,
+        })
+    }
+
+    fn dex_response(pairs: Vec<serde_json::Value>) -> DexScreenerResponse {
+        DexScreenerResponse { schema_version: "1.0.0".to_string(), pairs: Some(pairs) }
+    }
+
+    #[test]
+    fn large_dexscreener_response_yields_exactly_the_newest_qualifying_pools() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+        // 60 qualifying pairs aged 1..=60 minutes, shuffled, plus pairs every filter drops
+        let mut pairs: Vec<serde_json::Value> = (1..=60u64)
+            .map(|minutes| (minutes * 37) % 61) // A permutation of 1..=60
+            .map(|minutes| dex_pair(&format!("mint{:02}", minutes), WRAPPED_SOL_MINT, "solana", minutes * 60, 5_000.0, now))
+            .collect();
+        pairs.push(dex_pair("other-chain", WRAPPED_SOL_MINT, "ethereum", 1, 5_000.0, now));
+        pairs.push(dex_pair("too-old", WRAPPED_SOL_MINT, "solana", 25 * 3600, 5_000.0, now));
+        pairs.push(dex_pair("usdc-quoted", "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", "solana", 1, 5_000.0, now));
+        pairs.push(dex_pair("too-shallow", WRAPPED_SOL_MINT, "solana", 1, 100.0, now)); // 0.5 SOL at $200
+        pairs.push(serde_json::json!({ "chainId": "solana" })); // Malformed
+
+        let pools = dexscreener_pools(dex_response(pairs), &scan_settings(7), 200.0, now);
+        let mints: Vec<&str> = pools.iter().map(|pool| pool.token_address.as_str()).collect();
+        assert_eq!(mints, ["mint01", "mint02", "mint03", "mint04", "mint05", "mint06", "mint07"]);
+        assert!((pools[0].liquidity_sol - 25.0).abs() < 1e-9);
+        assert_eq!(pools[0].quote_mint, WRAPPED_SOL_MINT);
+        assert_eq!(pools[0].dex, "DexScreener/raydium");
+    }
+
+    fn parsed_pairs(pairs: Vec<serde_json::Value>) -> Vec<DexScreenerPair> {
+        parse_dexscreener_pairs(pairs).0
+    }
+
+    #[test]
+    fn fragmented_token_is_skipped_and_concentrated_one_passes() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+        let usdc = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+        // Six pools of $800-$1,300, one quoted in USDC: no venue worth trading on
+        let fragmented = parsed_pairs((0..6)
+            .map(|i| dex_pair("mintF", if i == 0 { usdc } else { WRAPPED_SOL_MINT }, "solana", 60, 800.0 + i as f64 * 100.0, now))
+            .collect());
+        let pools = token_pools_in(&fragmented, "mintF");
+        assert_eq!(pools.len(), 6);
+        let liquidities: Vec<f64> = pools.iter().map(|pool| pool.liquidity_usd).collect();
+        assert!(liquidity_fragmentation(&liquidities, 4, 5_000.0).unwrap().starts_with("6 pools, deepest $1300"));
+
+        // Same pool count, but one main pool holds $40,000
+        let mut concentrated = fragmented;
+        concentrated.extend(parsed_pairs(vec![dex_pair("mintF", WRAPPED_SOL_MINT, "solana", 60, 40_000.0, now)]));
+        let liquidities: Vec<f64> = token_pools_in(&concentrated, "mintF").iter().map(|pool| pool.liquidity_usd).collect();
+        assert!(liquidity_fragmentation(&liquidities, 4, 5_000.0).is_none());
+        // Off, or few enough pools
+        assert!(liquidity_fragmentation(&[100.0; 6], 0, 5_000.0).is_none());
+        assert!(liquidity_fragmentation(&[100.0; 4], 4, 5_000.0).is_none());
+    }
+
+    #[test]
+    fn token_pools_keep_only_the_tokens_solana_pairs() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+        let pairs = parsed_pairs(vec![
+            dex_pair("mintA", WRAPPED_SOL_MINT, "solana", 60, 1_000.0, now),
+            dex_pair("mintA", "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", "solana", 60, 2_000.0, now),
+            dex_pair("mintA", WRAPPED_SOL_MINT, "base", 60, 3_000.0, now),
+            dex_pair("mintB", WRAPPED_SOL_MINT, "solana", 60, 4_000.0, now),
+        ]);
+        assert_eq!(token_pools_in(&pairs, "mintA"), vec![
+            TokenPool { liquidity_usd: 1_000.0, sol_paired: true },
+            TokenPool { liquidity_usd: 2_000.0, sol_paired: false },
+        ]);
+    }
+
+    #[test]
+    fn single_sol_pair_token_falls_short_and_several_pass() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+        let usdc = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+        // One SOL pair; the USDC pair and the empty SOL pair don't count
+        let single = parsed_pairs(vec![
+            dex_pair("mintS", WRAPPED_SOL_MINT, "solana", 60, 3_000.0, now),
+            dex_pair("mintS", usdc, "solana", 60, 9_000.0, now),
+            dex_pair("mintS", WRAPPED_SOL_MINT, "solana", 60, 0.0, now),
+        ]);
+        let pools = token_pools_in(&single, "mintS");
+        assert_eq!(sol_pair_shortfall(&pools, 2, 0.0).unwrap(), "1 SOL pair(s), deepest $3000 (needs 2 or one holding $0)");
+        // The default of 1 is met by the detected pool
+        assert!(sol_pair_shortfall(&pools, 1, 0.0).is_none());
+        // A primary SOL pair above the floor is enough on its own
+        assert!(sol_pair_shortfall(&pools, 2, 2_500.0).is_none());
+        assert!(sol_pair_shortfall(&pools, 2, 5_000.0).is_some());
+
+        let mut several = single;
+        several.extend(parsed_pairs(vec![
+            dex_pair("mintS", WRAPPED_SOL_MINT, "solana", 120, 1_500.0, now),
+            dex_pair(WRAPPED_SOL_MINT, "mintS", "solana", 120, 800.0, now), // SOL as the base side
+        ]));
+        let pools = token_pools_in(&several, "mintS");
+        assert!(sol_pair_shortfall(&pools, 3, 0.0).is_none());
+        assert!(sol_pair_shortfall(&pools, 4, 0.0).unwrap().starts_with("3 SOL pair(s)"));
+    }
+
+    fn pool_mints(pools: &[NewPool]) -> Vec<&str> {
+        pools.iter().map(|pool| pool.token_address.as_str()).collect()
+    }
+
+    #[test]
+    fn dexscreener_ages_are_cut_at_24h_and_unknown_ages_sort_last() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+        let mut undated = dex_pair("undated", WRAPPED_SOL_MINT, "solana", 0, 5_000.0, now);
+        undated.as_object_mut().unwrap().remove("pairCreatedAt");
+        let pairs = || vec![
+            dex_pair("day-old", WRAPPED_SOL_MINT, "solana", 24 * 3600, 5_000.0, now),
+            undated.clone(),
+            dex_pair("over-a-day", WRAPPED_SOL_MINT, "solana", 24 * 3600 + 1, 5_000.0, now),
+            dex_pair("fresh", WRAPPED_SOL_MINT, "solana", 30, 5_000.0, now),
+        ];
+
+        let mut settings = scan_settings(10);
+        let pools = dexscreener_pools(dex_response(pairs()), &settings, 200.0, now);
+        assert_eq!(pool_mints(&pools), ["fresh", "day-old"]);
+        assert_eq!(pools[0].detected_at, now - Duration::from_secs(30));
+
+        settings.monitoring.allow_missing_creation_time = true;
+        let pools = dexscreener_pools(dex_response(pairs()), &settings, 200.0, now);
+        assert_eq!(pool_mints(&pools), ["fresh", "day-old", "undated"]);
+        assert_eq!(pools[2].detected_at, now); // Counted from first sight
+    }
+
+    /// `dex_pair` as DexScreener lists it before indexing its creation time
+    fn undated_pair(token: &str, quote: &str, liquidity_usd: f64, now: SystemTime) -> serde_json::Value {
+        let mut pair = dex_pair(token, quote, "solana", 0, liquidity_usd, now);
+        pair.as_object_mut().unwrap().remove("pairCreatedAt");
+        pair
+    }
+
+    #[test]
+    fn undated_pairs_still_face_the_other_filters() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+        let pairs = || vec![
+            undated_pair("undated", WRAPPED_SOL_MINT, 5_000.0, now),
+            undated_pair("undated-shallow", WRAPPED_SOL_MINT, 100.0, now),
+            undated_pair("undated-usdc", "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", 5_000.0, now),
+            dex_pair("dated", WRAPPED_SOL_MINT, "solana", 3_600, 5_000.0, now),
+        ];
+
+        // Off (the default): every undated pair is dropped
+        let mut settings = scan_settings(10);
+        assert_eq!(pool_mints(&dexscreener_pools(dex_response(pairs()), &settings, 200.0, now)), ["dated"]);
+
+        // On: only the undated pair that is SOL-paired and deep enough is kept
+        settings.monitoring.allow_missing_creation_time = true;
+        assert_eq!(pool_mints(&dexscreener_pools(dex_response(pairs()), &settings, 200.0, now)), ["dated", "undated"]);
+        // ...and it is the first to go when the cap bites
+        settings.monitoring.max_candidates_per_scan = 1;
+        assert_eq!(pool_mints(&dexscreener_pools(dex_response(pairs()), &settings, 200.0, now)), ["dated"]);
+    }
+
+    #[test]
+    fn dexscreener_liquidity_floor_follows_the_sol_price() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+        let pairs = || vec![dex_pair("mintL", WRAPPED_SOL_MINT, "solana", 60, 2_000.0, now)];
+        let settings = scan_settings(10); // 10 SOL floor
+
+        let pools = dexscreener_pools(dex_response(pairs()), &settings, 150.0, now);
+        assert_eq!(pool_mints(&pools), ["mintL"]);
+        assert!((pools[0].liquidity_sol - 2_000.0 / 150.0).abs() < 1e-9);
+        // The same $2,000 is only 8 SOL at $250
+        assert!(dexscreener_pools(dex_response(pairs()), &settings, 250.0, now).is_empty());
+    }
+
+    #[test]
+    fn dexscreener_keeps_only_pairs_with_exactly_one_sol_side() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+        let usdc = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+        // SOL as the base token: the quote side is the sniped token
+        let mut reversed = dex_pair(WRAPPED_SOL_MINT, "mintQ", "solana", 60, 5_000.0, now);
+        reversed["quoteToken"]["symbol"] = "QTKN".into();
+        reversed["priceNative"] = "1000000".into();
+        let pairs = vec![
+            dex_pair("mintB", WRAPPED_SOL_MINT, "solana", 120, 5_000.0, now),
+            reversed,
+            dex_pair("usdc-quoted", usdc, "solana", 30, 5_000.0, now),
+            dex_pair(WRAPPED_SOL_MINT, WRAPPED_SOL_MINT, "solana", 30, 5_000.0, now),
+        ];
+
+        let pools = dexscreener_pools(dex_response(pairs), &scan_settings(10), 200.0, now);
+        assert_eq!(pool_mints(&pools), ["mintQ", "mintB"]);
+        assert_eq!(pools[0].token_symbol, "QTKN");
+        assert_eq!(pools[0].base_mint, WRAPPED_SOL_MINT);
+        assert!((pools[0].price_sol - 0.000001).abs() < 1e-15); // priceNative inverted
+        assert!((pools[1].price_sol - 0.000001).abs() < 1e-15);
+        assert_eq!(pools[1].price_usd, 0.0002);
+    }
+
+    #[test]
+    fn dexscreener_inactive_pairs_drop_only_when_old_enough() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+        let quiet = |token: &str, age_secs: u64| {
+            let mut pair = dex_pair(token, WRAPPED_SOL_MINT, "solana", age_secs, 5_000.0, now);
+            pair["volume"] = serde_json::json!({ "h24": 0.0, "h1": 0.0, "m5": 0.0 });
+            pair
+        };
+        let pairs = || vec![quiet("quiet-new", 60), quiet("quiet-old", 3 * 3600), dex_pair("busy", WRAPPED_SOL_MINT, "solana", 3 * 3600, 5_000.0, now)];
+
+        let mut settings = scan_settings(10);
+        assert_eq!(dexscreener_pools(dex_response(pairs()), &settings, 200.0, now).len(), 3);
+        settings.monitoring.skip_inactive_pools = true;
+        settings.monitoring.inactive_pool_min_age_minutes = 30;
+        assert_eq!(pool_mints(&dexscreener_pools(dex_response(pairs()), &settings, 200.0, now)), ["quiet-new", "busy"]);
+    }
+
+    /// A parsed pair with `volume` as its DexScreener volume buckets
+    fn pair_with_volume(volume: serde_json::Value) -> DexScreenerPair {
+        let now = UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+        let mut pair = dex_pair("mintV", WRAPPED_SOL_MINT, "solana", 3_600, 5_000.0, now);
+        pair["volume"] = volume;
+        parsed_pairs(vec![pair]).remove(0)
+    }
+
+    #[test]
+    fn pool_with_recent_trades_is_active() {
+        let min_age = Duration::from_secs(30 * 60);
+        let old = Some(Duration::from_secs(3 * 3600));
+        assert!(!pool_inactive(&pair_with_volume(serde_json::json!({ "h24": 900.0, "h1": 40.0, "m5": 2.0 })), old, min_age));
+        // Either bucket is enough
+        assert!(!pool_inactive(&pair_with_volume(serde_json::json!({ "h24": 900.0, "h1": 40.0, "m5": 0.0 })), old, min_age));
+        assert!(!pool_inactive(&pair_with_volume(serde_json::json!({ "h24": 900.0, "h1": 0.0, "m5": 2.0 })), old, min_age));
+    }
+
+    #[test]
+    fn pool_without_recent_trades_is_inactive_past_the_min_age() {
+        let min_age = Duration::from_secs(30 * 60);
+        // Traded earlier in the day, nothing in the last hour
+        let dead = pair_with_volume(serde_json::json!({ "h24": 900.0, "h1": 0.0, "m5": 0.0 }));
+        assert!(pool_inactive(&dead, Some(Duration::from_secs(3 * 3600)), min_age));
+        assert!(pool_inactive(&dead, Some(min_age), min_age));
+        // Still inside the activity window: too new to judge
+        assert!(!pool_inactive(&dead, Some(min_age - Duration::from_secs(1)), min_age));
+        // Unknown age is judged on volume alone
+        assert!(pool_inactive(&dead, None, min_age));
+        // No volume reported at all counts as none
+        assert!(pool_inactive(&pair_with_volume(serde_json::json!({})), None, min_age));
+    }
+
+    fn sniped_address(base: &str, quote: &str) -> Option<String> {
+        let now = UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+        let pair = &parsed_pairs(vec![dex_pair(base, quote, "solana", 60, 5_000.0, now)])[0];
+        sniped_token(pair).map(|token| token.address.clone())
+    }
+
+    #[test]
+    fn sniped_token_is_the_side_opposite_wrapped_sol() {
+        assert_eq!(sniped_address("mintA", WRAPPED_SOL_MINT).as_deref(), Some("mintA")); // SOL as quote
+        assert_eq!(sniped_address(WRAPPED_SOL_MINT, "mintA").as_deref(), Some("mintA")); // SOL as base
+    }
+
+    #[test]
+    fn pairs_without_exactly_one_sol_side_have_no_sniped_token() {
+        assert_eq!(sniped_address("mintA", "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"), None);
+        assert_eq!(sniped_address(WRAPPED_SOL_MINT, WRAPPED_SOL_MINT), None);
+    }
+
+    #[test]
+    fn a_token_merely_named_sol_is_not_wrapped_sol() {
+        // dex_pair labels the quote "SOL" whatever its mint
+        assert_eq!(sniped_address("mintA", "FakeSo11111111111111111111111111111111111111"), None);
+    }
+
+    #[test]
+    fn entry_delay_aborts_when_liquidity_dropped() {
+        let reason = entry_delay_abort_reason(40.0, 0.0002, 25.0, 0.0002, 50.0).unwrap();
+        assert_eq!(reason, "liquidity dropped 40.00 → 25.00 SOL");
+        // Any drop counts, even with the price flat
+        assert!(entry_delay_abort_reason(40.0, 0.0002, 39.99, 0.0002, 50.0).is_some());
+    }
+
+    #[test]
+    fn entry_delay_aborts_when_the_price_ran_past_the_limit() {
+        assert!(entry_delay_abort_reason(40.0, 0.0002, 40.0, 0.0003, 50.0).is_none()); // +50%, at the limit
+        assert_eq!(entry_delay_abort_reason(40.0, 0.0002, 45.0, 0.0004, 50.0).unwrap(), "price rose 100.0% (max: 50.0%)");
+    }
+
+    #[test]
+    fn entry_delay_passes_a_steady_pool_and_skips_the_price_check_without_one() {
+        assert!(entry_delay_abort_reason(40.0, 0.0002, 42.0, 0.00019, 50.0).is_none());
+        assert!(entry_delay_abort_reason(40.0, 0.0, 40.0, 1.0, 50.0).is_none());
+    }
+
+    #[test]
+    fn a_source_is_not_polled_before_its_interval_elapses() {
+        let start = Instant::now();
+        let interval = Duration::from_secs(5);
+        assert!(source_due(None, interval, start)); // Never ran
+        assert!(!source_due(Some(start), interval, start + Duration::from_millis(4_999)));
+        assert_eq!(time_until_due(Some(start), interval, start + Duration::from_secs(3)), Duration::from_secs(2));
+        assert!(source_due(Some(start), interval, start + interval));
+        assert_eq!(time_until_due(Some(start), interval, start + Duration::from_secs(9)), Duration::ZERO);
+    }
+
+    #[test]
+    fn sources_poll_on_their_own_intervals() {
+        let start = Instant::now();
+        let (fast, slow) = (Duration::from_secs(2), Duration::from_secs(5));
+        let (mut fast_last, mut slow_last) = (None, None);
+        let (mut fast_polls, mut slow_polls) = (0, 0);
+        // One loop tick per second for 10s
+        for second in 0..=10 {
+            let now = start + Duration::from_secs(second);
+            if source_due(fast_last, fast, now) {
+                fast_last = Some(now);
+                fast_polls += 1;
+            }
+            if source_due(slow_last, slow, now) {
+                slow_last = Some(now);
+                slow_polls += 1;
+            }
+        }
+        assert_eq!(fast_polls, 6); // 0, 2, 4, 6, 8, 10
+        assert_eq!(slow_polls, 3); // 0, 5, 10
+    }
+
+    #[test]
+    fn tokens_below_the_holder_minimum_are_rejected() {
+        assert_eq!(too_few_holders(12, 50).unwrap(), "12 holders below 50 minimum");
+        assert!(too_few_holders(50, 50).is_none());
+        assert!(too_few_holders(400, 50).is_none());
+    }
+
+    #[test]
+    fn only_the_cap_is_processed_and_the_deepest_pools_go_first() {
+        let pools: Vec<NewPool> = [12.0, 95.0, 3.0, 40.0, 60.0, 7.0, 88.0]
+            .into_iter()
+            .enumerate()
+            .map(|(i, liquidity_sol)| NewPool { token_address: format!("mint{}", i), liquidity_sol, ..pool("Token", "TKN") })
+            .collect();
+
+        let (batch, rest) = prioritize_pools(pools.clone(), 3);
+        assert_eq!(pool_mints(&batch), ["mint1", "mint6", "mint4"]);
+        assert_eq!(rest.iter().map(|pool| pool.liquidity_sol).collect::<Vec<_>>(), vec![40.0, 12.0, 7.0, 3.0]);
+
+        // No cap, or fewer pools than the cap: everything, still deepest first
+        let (batch, rest) = prioritize_pools(pools.clone(), 0);
+        assert_eq!((batch.len(), rest.len()), (7, 0));
+        assert_eq!(batch[0].liquidity_sol, 95.0);
+        assert_eq!(prioritize_pools(pools, 10).0.len(), 7);
+    }
+
+    #[test]
+    fn token_age_is_judged_in_whole_minutes_against_both_limits() {
+        let minutes = |m: u64| Duration::from_secs(m * 60);
+        assert_eq!(token_age_rejection(minutes(4), 5, 0).unwrap(), "token is 4m old (min: 5m)");
+        assert!(token_age_rejection(minutes(5), 5, 0).is_none());
+        assert!(token_age_rejection(minutes(5) - Duration::from_secs(1), 5, 0).is_some()); // 4m59s
+        assert!(token_age_rejection(minutes(120), 5, 120).is_none());
+        assert!(token_age_rejection(minutes(121), 5, 120).is_some());
+        assert!(token_age_rejection(Duration::ZERO, 0, 0).is_none()); // Both limits off
+    }
+
+    #[test]
+    fn new_pool_for_an_old_token_is_told_apart_from_a_new_token() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+        let minted_at = now - Duration::from_secs(6 * 3600); // Token minted 6h ago...
+        let pool_created_at = now - Duration::from_secs(60); // ...pool opened a minute ago
+        let token_age = now.duration_since(minted_at).unwrap();
+        let pool_age = now.duration_since(pool_created_at).unwrap();
+        assert_eq!(token_age_rejection(token_age, 0, 60).unwrap(), "token is 360m old (max: 60m) - new pool for an old token");
+        assert!(token_age_rejection(pool_age, 0, 60).is_none()); // The pool's age alone would have passed
+    }
+
+    #[test]
+    fn pairs_with_null_optional_fields_parse_and_broken_ones_are_skipped() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+        let well_formed = dex_pair("mintA", WRAPPED_SOL_MINT, "solana", 60, 5_000.0, now);
+        let mut nulls = dex_pair("mintB", WRAPPED_SOL_MINT, "solana", 60, 5_000.0, now);
+        for field in ["priceUsd", "priceNative", "liquidity", "volume", "pairCreatedAt"] {
+            nulls[field] = serde_json::Value::Null;
+        }
+        let mut no_base_token = dex_pair("mintC", WRAPPED_SOL_MINT, "solana", 60, 5_000.0, now);
+        no_base_token.as_object_mut().unwrap().remove("baseToken");
+        let mut wrong_type = dex_pair("mintD", WRAPPED_SOL_MINT, "solana", 60, 5_000.0, now);
+        wrong_type["liquidity"]["usd"] = "lots".into();
+
+        let (pairs, skipped) = parse_dexscreener_pairs(vec![well_formed, nulls, no_base_token, wrong_type, serde_json::json!(null)]);
+        assert_eq!(skipped, 3);
+        assert_eq!(pairs.iter().map(|pair| pair.base_token.address.as_str()).collect::<Vec<_>>(), ["mintA", "mintB"]);
+        assert_eq!(pairs[1].liquidity_usd(), 0.0);
+        assert_eq!(sniped_token_price_sol(&pairs[1]), 0.0);
+    }
+
+    #[test]
+    fn malformed_pairs_dont_sink_the_scan() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+        let pairs = vec![
+            serde_json::json!({ "chainId": "solana", "pairAddress": "broken" }),
+            dex_pair("mintA", WRAPPED_SOL_MINT, "solana", 60, 5_000.0, now),
+        ];
+        assert_eq!(pool_mints(&dexscreener_pools(dex_response(pairs), &scan_settings(10), 200.0, now)), ["mintA"]);
+        let empty = DexScreenerResponse { schema_version: "1.0.0".to_string(), pairs: None };
+        assert!(dexscreener_pools(empty, &scan_settings(10), 200.0, now).is_empty());
+    }
+
+    #[test]
+    fn usdc_sol_pair_is_avoided_while_a_memecoin_pair_is_processed() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+        let usdc = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+        let settings = scan_settings(10);
+        let pools = dexscreener_pools(dex_response(vec![
+            dex_pair(usdc, WRAPPED_SOL_MINT, "solana", 60, 5_000_000.0, now),
+            dex_pair("MemeMint111111111111111111111111111111111111", WRAPPED_SOL_MINT, "solana", 120, 5_000.0, now),
+        ]), &settings, 200.0, now);
+        // Both pass the DexScreener filters - the avoided-pair check is what stops the USDC "launch"
+        assert_eq!(pools.len(), 2);
+        let processed: Vec<&str> = pools.iter()
+            .filter(|pool| !is_avoided_pair_mint(&pool.token_address, &settings.security.avoided_pair_mints))
+            .map(|pool| pool.token_address.as_str())
+            .collect();
+        assert_eq!(processed, ["MemeMint111111111111111111111111111111111111"]);
+    }
+
+    #[test]
+    fn buy_above_the_entry_price_limit_is_skipped() {
+        assert_eq!(entry_price_rejection(0.0025, 0.002).unwrap(), "price $0.0025 is above the $0.002 limit");
+        assert!(entry_price_rejection(0.002, 0.002).is_none());
+        assert!(entry_price_rejection(0.0015, 0.002).is_none());
+        assert_eq!(entry_price_rejection(0.0, 0.002).unwrap(), "price unknown (limit $0.002)");
+    }
+
+    #[test]
+    fn a_tokens_own_entry_limit_beats_the_global_one() {
+        let per_token: HashMap<String, f64> = [("WatchMint".to_string(), 0.01)].into();
+        assert_eq!(max_entry_price_for("WatchMint", &per_token, 0.002), Some(0.01));
+        assert_eq!(max_entry_price_for("OtherMint", &per_token, 0.002), Some(0.002));
+        assert_eq!(max_entry_price_for("OtherMint", &per_token, 0.0), None);
+        assert_eq!(max_entry_price_for("WatchMint", &per_token, 0.0), Some(0.01));
+    }
+}
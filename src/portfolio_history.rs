@@ -0,0 +1,62 @@
+// Portfolio History - append-only on-disk log of portfolio summaries for equity curves
+use crate::profit_monitor::PortfolioSummary;
+
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+use anyhow::Result;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioSnapshot {
+    pub timestamp_unix_secs: u64,
+    #[serde(flatten)]
+    pub summary: PortfolioSummary,
+}
+
+/// Append one snapshot as a JSON line, creating the file and parent directory if needed
+pub fn append_snapshot(file_path: &str, snapshot: &PortfolioSnapshot) -> Result<()> {
+    if let Some(parent) = Path::new(file_path).parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file_path)?;
+    writeln!(file, "{}", serde_json::to_string(snapshot)?)?;
+    file.flush()?;
+    Ok(())
+}
+
+/// All recorded snapshots, oldest first. Unparseable lines (e.g. torn by a crash mid-write) are skipped.
+pub fn load_snapshots(file_path: &str) -> Result<Vec<PortfolioSnapshot>> {
+    if !Path::new(file_path).exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(file_path)?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Equity curve as CSV: one row per snapshot with total value and P&L
+pub fn equity_csv(snapshots: &[PortfolioSnapshot]) -> String {
+    let mut csv = String::from("timestamp,active_positions,total_invested_sol,current_value_sol,total_pnl_sol,total_pnl_percentage,total_pnl_usd\n");
+    for snapshot in snapshots {
+        let timestamp = chrono::DateTime::from_timestamp(snapshot.timestamp_unix_secs as i64, 0)
+            .map(|time| time.to_rfc3339())
+            .unwrap_or_default();
+        let summary = &snapshot.summary;
+        csv.push_str(&format!(
+            "{},{},{:.6},{:.6},{:.6},{:.2},{:.2}\n",
+            timestamp,
+            summary.active_positions,
+            summary.total_invested_sol,
+            summary.current_value_sol,
+            summary.total_pnl_sol,
+            summary.total_pnl_percentage,
+            summary.total_pnl_usd,
+        ));
+    }
+    csv
+}
@@ -0,0 +1,1388 @@
+// Position Monitor - max-hold, trailing-stop and price checks for open positions, inline or on their own task
+use crate::settings::BotSettings;
+use crate::telegram::TelegramNotifier;
+use crate::jupiter_trader::{JupiterTrader, MinOutError, SellProbe, MintAuthorities};
+use crate::profit_monitor::{ProfitMonitor, PortfolioSummary, SolPriceFeed, format_duration, untrackable_position_reason, safe_ratio};
+use crate::daily_trades::utc_day;
+use crate::health::unix_secs;
+use crate::pool_scanner::{TokenPosition, min_out_floor, estimated_swap_fee_sol, deepest_pool_liquidity_usd};
+use crate::storage::{Storage, StoredPosition, TradeRecord, TradeSide};
+use crate::webhook::{WebhookEvent, WebhookNotifier};
+use crate::units::usd_to_sol;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use anyhow::Result;
+
+/// Time left before a position's max hold expires (zero once it has)
+pub fn hold_time_remaining(position: &TokenPosition, now: SystemTime) -> Duration {
+    let elapsed = now.duration_since(position.purchase_time).unwrap_or_default();
+    position.max_hold.saturating_sub(elapsed)
+}
+
+/// Fold a repeat buy of an already held token into the held position: tokens and cost add up,
+/// the entry price is re-averaged, and the max-hold keeps counting from the first buy.
+/// None when `add` is the buy already tracked (same signature), e.g. restored twice.
+pub fn merge_positions(held: &TokenPosition, add: TokenPosition) -> Option<TokenPosition> {
+    let signature = |position: &TokenPosition| position.trade_result.as_ref().map(|result| result.transaction_signature.clone());
+    if signature(&add).is_some() && signature(&add) == signature(held) {
+        return None;
+    }
+    let sol_amount = held.sol_amount + add.sol_amount;
+    let estimated_tokens = held.estimated_tokens.saturating_add(add.estimated_tokens);
+    Some(TokenPosition {
+        token_address: held.token_address.clone(),
+        purchase_time: held.purchase_time.min(add.purchase_time),
+        sol_amount,
+        estimated_tokens,
+        entry_price: safe_ratio(sol_amount, estimated_tokens as f64),
+        kind: held.kind,
+        trade_result: held.trade_result.clone().or(add.trade_result),
+        max_hold: held.max_hold,
+        timeout_extension: held.timeout_extension,
+        sell_probe: add.sell_probe.or_else(|| held.sell_probe.clone()),
+    })
+}
+
+/// Capital a token's share is measured against: what's deployed, but at least `budget_sol`
+/// (MAX_ACTIVE_POSITIONS x POSITION_SIZE_SOL) so the first buy isn't 100% of a tiny base
+pub fn allocation_base_sol(total_deployed_sol: f64, budget_sol: f64) -> f64 {
+    total_deployed_sol.max(budget_sol)
+}
+
+/// Most SOL that can go into a token already holding `token_sol` of `total_deployed_sol`
+/// without its share passing `cap_percent`. Infinite when the cap is off (0 or >= 100).
+pub fn allocation_room_sol(token_sol: f64, total_deployed_sol: f64, budget_sol: f64, cap_percent: f64) -> f64 {
+    if cap_percent <= 0.0 || cap_percent >= 100.0 {
+        return f64::INFINITY;
+    }
+    let cap = cap_percent / 100.0;
+    // The share only grows with the buy, so the room is where it reaches the cap: first while
+    // the budget is still the base, else once the buy itself pushes deployed capital past it
+    let within_budget = cap * budget_sol - token_sol;
+    let room = if total_deployed_sol + within_budget <= budget_sol {
+        within_budget
+    } else {
+        (cap * total_deployed_sol - token_sol) / (1.0 - cap)
+    };
+    room.max(0.0)
+}
+
+/// Tokens a partial sell of `fraction` takes, or None when it should sell everything instead
+/// (the fraction is the whole position, or the remainder would fall below `min_tokens`)
+pub fn partial_sell_tokens(held_tokens: u64, fraction: f64, min_tokens: u64) -> Option<u64> {
+    let tokens = (held_tokens as f64 * fraction.clamp(0.0, 1.0)) as u64;
+    (tokens > 0 && held_tokens - tokens >= min_tokens.max(1)).then_some(tokens)
+}
+
+/// USD value of `tokens` raw units of a mint with `decimals`, at `price_usd` per whole token
+pub fn token_value_usd(tokens: u64, decimals: u8, price_usd: f64) -> f64 {
+    tokens as f64 / 10f64.powi(decimals as i32) * price_usd
+}
+
+/// True when `tokens` (raw units) at `price_usd` per whole token are worth less than `threshold_usd`,
+/// or there are none. An unknown price (0) or unknown decimals is never dust, so a live balance is still sold.
+pub fn is_dust(tokens: u64, decimals: Option<u8>, price_usd: f64, threshold_usd: f64) -> bool {
+    tokens == 0 || decimals.is_some_and(|decimals| {
+        price_usd > 0.0 && token_value_usd(tokens, decimals, price_usd) < threshold_usd
+    })
+}
+
+/// What an auto-sell does about the pool's current liquidity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitLiquidityAction {
+    Sell,
+    Hold,     // Put the position back and check again next round
+    WriteOff, // Rugged - book the loss instead of swapping into an empty pool
+}
+
+/// Decide an auto-sell against MIN_SELL_LIQUIDITY_SOL. An unknown liquidity (lookup failed) or a
+/// zero floor never blocks the sell; `held_for` is how long the position has already waited.
+pub fn exit_liquidity_action(
+    liquidity_sol: Option<f64>,
+    floor_sol: f64,
+    hold_on_low_liquidity: bool,
+    held_for: Duration,
+    max_hold: Duration,
+) -> ExitLiquidityAction {
+    match liquidity_sol {
+        Some(liquidity) if floor_sol > 0.0 && liquidity < floor_sol && hold_on_low_liquidity => {
+            if held_for >= max_hold { ExitLiquidityAction::WriteOff } else { ExitLiquidityAction::Hold }
+        }
+        _ => ExitLiquidityAction::Sell,
+    }
+}
+
+/// What the max-hold timeout does with a position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeoutAction {
+    Sell,
+    Extend(Duration), // Hold this much longer, then decide again
+}
+
+/// Decide a max-hold timeout against MIN_TIMEOUT_SELL_PROFIT_SOL. `projected_profit_sol` is what
+/// selling now would net after fees (None = couldn't be priced, which never blocks the sell);
+/// `extended` is the grace already given, capped at `max_extension` in total.
+pub fn timeout_action(
+    projected_profit_sol: Option<f64>,
+    min_profit_sol: f64,
+    extended: Duration,
+    grace: Duration,
+    max_extension: Duration,
+) -> TimeoutAction {
+    match projected_profit_sol {
+        Some(profit) if profit < min_profit_sol && extended < max_extension && !grace.is_zero() => {
+            TimeoutAction::Extend(grace.min(max_extension - extended))
+        }
+        _ => TimeoutAction::Sell,
+    }
+}
+
+/// Why the portfolio-level take profit fires, or None. `day_pnl_sol` is today's realized P&L plus
+/// the open positions' unrealized P&L; the percent target applies to the open positions. 0 = off.
+pub fn portfolio_take_profit_reason(
+    summary: &PortfolioSummary,
+    day_pnl_sol: f64,
+    target_sol: f64,
+    target_percent: f64,
+) -> Option<String> {
+    if target_sol > 0.0 && day_pnl_sol >= target_sol {
+        return Some(format!("day P&L +{:.4} SOL reached the +{} SOL target", day_pnl_sol, target_sol));
+    }
+    if target_percent > 0.0 && summary.active_positions > 0 && summary.total_pnl_percentage >= target_percent {
+        return Some(format!("open positions up {:.1}%, past the +{}% target", summary.total_pnl_percentage, target_percent));
+    }
+    None
+}
+
+/// What the periodic security re-check sees for a held token (None = lookup failed)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SecuritySnapshot {
+    pub authorities: Option<MintAuthorities>,
+    pub liquidity_sol: Option<f64>,
+}
+
+impl SecuritySnapshot {
+    /// Fill fields the baseline couldn't read with the current values
+    fn fill_unknown(&mut self, current: &SecuritySnapshot) {
+        self.authorities = self.authorities.or(current.authorities);
+        self.liquidity_sol = self.liquidity_sol.or(current.liquidity_sol);
+    }
+}
+
+/// Why a held token is no longer safe compared to its first re-check, or None if it still is.
+/// An authority counts as re-enabled when one is set that the baseline didn't have; a field
+/// either side couldn't read never triggers an exit.
+pub fn security_degradation(
+    baseline: &SecuritySnapshot,
+    current: &SecuritySnapshot,
+    max_liquidity_drop_percent: f64,
+) -> Option<String> {
+    if let (Some(before), Some(now)) = (baseline.authorities, current.authorities) {
+        if let Some(authority) = now.mint_authority.filter(|_| now.mint_authority != before.mint_authority) {
+            return Some(format!("mint authority re-enabled ({})", authority));
+        }
+        if let Some(authority) = now.freeze_authority.filter(|_| now.freeze_authority != before.freeze_authority) {
+            return Some(format!("freeze authority re-enabled ({})", authority));
+        }
+    }
+    if let (Some(before), Some(now)) = (baseline.liquidity_sol, current.liquidity_sol) {
+        let drop_percent = if before > 0.0 { (before - now) / before * 100.0 } else { 0.0 };
+        if drop_percent >= max_liquidity_drop_percent {
+            return Some(format!("liquidity down {:.0}% ({:.2} -> {:.2} SOL)", drop_percent, before, now));
+        }
+    }
+    None
+}
+
+/// When each mint was last sold or written off, for the re-entry cooldown (SELL_COOLDOWN_SECS).
+/// Wall-clock stamps, so the trade history can restore them after a restart.
+#[derive(Debug, Clone, Default)]
+pub struct SellCooldowns(HashMap<String, SystemTime>);
+
+impl SellCooldowns {
+    pub fn record(&mut self, token_address: &str, sold_at: SystemTime) {
+        let stamp = self.0.entry(token_address.to_string()).or_insert(sold_at);
+        *stamp = (*stamp).max(sold_at);
+    }
+
+    /// Stamp every sell and write-off in `trades` that is still cooling down at `now`
+    pub fn restore(&mut self, trades: &[TradeRecord], cooldown: Duration, now: SystemTime) {
+        for trade in trades.iter().filter(|trade| trade.side != TradeSide::Buy) {
+            let sold_at = SystemTime::UNIX_EPOCH + Duration::from_secs(trade.timestamp_unix_secs);
+            if now.duration_since(sold_at).unwrap_or_default() < cooldown {
+                self.record(&trade.token_address, sold_at);
+            }
+        }
+    }
+
+    /// How much longer `token_address` is off limits; forgets sells older than `cooldown` so the
+    /// map doesn't grow without bound
+    pub fn remaining(&mut self, token_address: &str, cooldown: Duration, now: SystemTime) -> Option<Duration> {
+        self.0.retain(|_, sold_at| now.duration_since(*sold_at).unwrap_or_default() < cooldown);
+        self.0.get(token_address)
+            .map(|&sold_at| cooldown.saturating_sub(now.duration_since(sold_at).unwrap_or_default()))
+    }
+}
+
+/// Open positions and their valuations - the state shared by the scanner and the monitor task
+pub struct PositionBook {
+    pub active_positions: HashMap<String, TokenPosition>,
+    pub profit_monitor: ProfitMonitor,
+    pub realized_pnl_sol: f64,
+    pub auto_sell_failures: u32, // Consecutive failed monitoring rounds, reported in the health snapshot
+    pub low_liquidity_since: HashMap<String, Instant>, // Auto-sells held back by a drained pool
+    pub security_baselines: HashMap<String, SecuritySnapshot>, // First re-check of each held token
+    pub last_security_recheck: Option<Instant>,
+    pub day_start_realized_pnl: Option<(u64, f64)>, // (UTC day, realized_pnl_sol when it began)
+    pub portfolio_target_hit_day: Option<u64>, // UTC day the portfolio take profit fired - no buys until it ends
+    pub closed_pnls_sol: Vec<f64>, // Each close's realized P&L since the scanner last drained them (auto-compounding)
+    pub sell_cooldowns: SellCooldowns, // Latest sell or write-off per mint, restored from the trade history
+}
+
+impl PositionBook {
+    /// Book a sell's or write-off's realized P&L
+    pub fn record_realized(&mut self, token_address: &str, pnl_sol: f64) {
+        self.realized_pnl_sol += pnl_sol;
+        self.closed_pnls_sol.push(pnl_sol);
+        self.sell_cooldowns.record(token_address, SystemTime::now());
+    }
+
+    /// How much longer `token_address` is off limits after its last sell
+    pub fn sell_cooldown_remaining(&mut self, token_address: &str, cooldown: Duration, now: SystemTime) -> Option<Duration> {
+        self.sell_cooldowns.remaining(token_address, cooldown, now)
+    }
+}
+
+/// Cloneable handle to the position book. The book sits behind a single lock, and every method
+/// releases it before submitting a sell, so neither the scanner nor the monitor task can
+/// deadlock on it or wait behind a slow transaction.
+#[derive(Clone)]
+pub struct PositionMonitor {
+    settings: BotSettings,
+    telegram: TelegramNotifier,
+    jupiter_trader: Arc<JupiterTrader>,
+    book: Arc<Mutex<PositionBook>>,
+    storage: Option<Arc<dyn Storage>>, // Open positions and closed trades survive a restart
+    saved_positions: Arc<Mutex<Option<Vec<StoredPosition>>>>, // What storage holds now; unchanged rounds skip the write
+    webhook: Option<WebhookNotifier>,  // WEBHOOK_URL: every recorded trade is also POSTed
+    sol_price: SolPriceFeed, // Shared with the profit monitor; refreshed before the book is locked
+}
+
+impl PositionMonitor {
+    pub fn new(settings: BotSettings, telegram: TelegramNotifier, jupiter_trader: Arc<JupiterTrader>) -> Self {
+        let profit_monitor = ProfitMonitor::new(settings.clone(), telegram.clone());
+        let sol_price = profit_monitor.sol_price_feed();
+        let book = PositionBook {
+            active_positions: HashMap::new(),
+            profit_monitor,
+            realized_pnl_sol: 0.0,
+            auto_sell_failures: 0,
+            low_liquidity_since: HashMap::new(),
+            security_baselines: HashMap::new(),
+            last_security_recheck: None,
+            day_start_realized_pnl: None,
+            portfolio_target_hit_day: None,
+            closed_pnls_sol: Vec::new(),
+            sell_cooldowns: SellCooldowns::default(),
+        };
+        Self {
+            settings,
+            telegram,
+            jupiter_trader,
+            book: Arc::new(Mutex::new(book)),
+            storage: None,
+            saved_positions: Arc::new(Mutex::new(None)),
+            webhook: None,
+            sol_price,
+        }
+    }
+
+    pub fn with_storage(mut self, storage: Arc<dyn Storage>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    pub fn with_webhook(mut self, webhook: Option<WebhookNotifier>) -> Self {
+        self.webhook = webhook;
+        self
+    }
+
+    /// Re-apply the re-entry cooldowns of sells made before a restart, from the stored trade history
+    pub async fn restore_sell_cooldowns(&self) {
+        let cooldown = Duration::from_secs(self.settings.trading.sell_cooldown_secs);
+        let Some(storage) = self.storage.as_ref().filter(|_| !cooldown.is_zero()) else {
+            return;
+        };
+        match storage.load_trades() {
+            Ok(trades) => self.book.lock().await.sell_cooldowns.restore(&trades, cooldown, SystemTime::now()),
+            Err(e) => println!("⚠️  Failed to load trade history for sell cooldowns: {}", e),
+        }
+    }
+
+    /// Direct access to the shared book; keep the guard short-lived
+    pub async fn book(&self) -> tokio::sync::MutexGuard<'_, PositionBook> {
+        self.book.lock().await
+    }
+
+    /// Run `run_checks` every `interval` on a dedicated task, independent of the scan loop
+    pub fn spawn(self, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            println!("👁️  Position monitor running on its own task (every {}ms)", interval.as_millis());
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                self.run_checks().await;
+            }
+        })
+    }
+
+    /// The live SOL price (refreshed when due), for converting USD liquidity outside the monitor
+    pub async fn sol_price_usd(&self) -> f64 {
+        self.sol_price.refresh().await;
+        self.sol_price.current()
+    }
+
+    /// One monitoring round: max-hold auto-sells, profit monitor updates, trailing stops and
+    /// (on its own interval) the security re-check
+    pub async fn run_checks(&self) {
+        self.sol_price.refresh().await;
+
+        // Monitor positions for max-hold auto-sell
+        match self.monitor_position_timeouts().await {
+            Ok(_) => self.record_auto_sell_success().await,
+            Err(e) => {
+                println!("⚠️  Position timeout monitoring error: {}", e);
+                self.record_auto_sell_error(&e.to_string()).await;
+            }
+        }
+
+        // Update profit monitoring (only runs periodic updates, doesn't restart)
+        if let Err(e) = self.update_profit_monitoring().await {
+            println!("⚠️  Profit monitoring error: {}", e);
+        }
+
+        // Monitor existing positions
+        if let Err(e) = self.monitor_existing_positions().await {
+            println!("⚠️  Position monitoring error: {}", e);
+        }
+
+        if let Err(e) = self.check_portfolio_take_profit().await {
+            println!("⚠️  Portfolio take profit error: {}", e);
+        }
+
+        if self.settings.security.position_recheck_interval_secs > 0 {
+            if let Err(e) = self.recheck_position_security().await {
+                println!("⚠️  Position security re-check error: {}", e);
+            }
+        }
+
+        self.persist_positions().await;
+    }
+
+    /// Save the open positions when they changed since the last save. Positions taken out for a
+    /// sell stay saved until the round ends, so a crash mid-sell restores them on the next start.
+    /// The write runs on the blocking pool; the saved-positions lock keeps saves in order.
+    pub async fn persist_positions(&self) {
+        let Some(storage) = &self.storage else {
+            return;
+        };
+        let mut positions: Vec<StoredPosition> = self.book.lock().await.active_positions.values()
+            .map(StoredPosition::from)
+            .collect();
+        positions.sort_by(|a, b| a.token_address.cmp(&b.token_address));
+        let mut saved = self.saved_positions.lock().await;
+        if saved.as_ref() == Some(&positions) {
+            return;
+        }
+        let storage = storage.clone();
+        let to_save = positions.clone();
+        match tokio::task::spawn_blocking(move || storage.save_positions(&to_save)).await {
+            Ok(Ok(())) => *saved = Some(positions),
+            Ok(Err(e)) => println!("⚠️  Failed to save positions: {}", e),
+            Err(e) => println!("⚠️  Failed to save positions: {}", e),
+        }
+    }
+
+    /// Append a buy, sell or write-off to the trade history and the webhook
+    pub fn record_trade(&self, position: &TokenPosition, side: TradeSide, sol_amount: f64, signature: Option<String>) {
+        if self.storage.is_none() && self.webhook.is_none() {
+            return;
+        }
+        let record = TradeRecord {
+            timestamp_unix_secs: unix_secs(SystemTime::now()),
+            token_address: position.token_address.clone(),
+            side,
+            kind: position.kind,
+            sol_amount,
+            token_amount: position.estimated_tokens,
+            pnl_sol: (side != TradeSide::Buy).then_some(sol_amount - position.sol_amount),
+            signature,
+        };
+        if let Some(webhook) = &self.webhook {
+            webhook.dispatch(WebhookEvent::from_trade(&record));
+        }
+        if let Some(storage) = &self.storage {
+            if let Err(e) = storage.append_trade(&record) {
+                println!("⚠️  Failed to record trade: {}", e);
+            }
+        }
+    }
+
+    /// Register a position for the max-hold auto-sell and hand it to the profit monitor. A buy of
+    /// a token already held is added to that position instead of replacing it.
+    pub async fn track_position(&self, position: TokenPosition) {
+        if let Some(reason) = untrackable_position_reason(&position, self.settings.trading.min_position_tokens) {
+            println!("❌ Not tracking {:?} position {}: {} - check the wallet manually", position.kind, position.token_address, reason);
+            return;
+        }
+        self.sol_price.refresh().await;
+        {
+            let mut book = self.book.lock().await;
+            let tracked = match book.active_positions.get(&position.token_address) {
+                Some(held) => match merge_positions(held, position.clone()) {
+                    Some(merged) => {
+                        println!("➕ Added {:.4} SOL to the {} position (now {:.4} SOL)",
+                            position.sol_amount, position.token_address, merged.sol_amount);
+                        merged
+                    }
+                    None => return, // This buy is already tracked
+                },
+                None => position.clone(),
+            };
+            book.active_positions.insert(tracked.token_address.clone(), tracked);
+
+            if let Err(e) = book.profit_monitor.add_position(&position).await {
+                println!("⚠️  Failed to add {:?} position to profit monitor: {}", position.kind, e);
+            } else if !book.profit_monitor.is_monitoring {
+                // Start profit monitoring if this is the first position
+                let PositionBook { active_positions, profit_monitor, .. } = &mut *book;
+                if let Err(e) = profit_monitor.start_monitoring(active_positions).await {
+                    println!("⚠️  Failed to initialize profit monitoring: {}", e);
+                }
+            }
+        }
+        self.persist_positions().await;
+    }
+
+    /// Sell a tracked position right away, outside the regular checks. A failed sell keeps the
+    /// position, and the saved positions are updated either way.
+    pub async fn sell_now(&self, token_address: &str, reason: &str) -> Result<()> {
+        let Some(position) = self.book.lock().await.active_positions.remove(token_address) else {
+            return Ok(()); // Never tracked or already sold
+        };
+        // A failed sell puts the position back itself
+        let result = self.execute_auto_sell(&position, reason).await;
+        self.persist_positions().await;
+        result
+    }
+
+    /// Capital budget allocation shares are measured against (MAX_ACTIVE_POSITIONS x POSITION_SIZE_SOL)
+    fn allocation_budget_sol(&self) -> f64 {
+        self.settings.trading.max_positions as f64 * self.settings.trading.position_size_sol
+    }
+
+    /// Most SOL a buy of `token_address` may spend under MAX_TOKEN_ALLOCATION_PERCENT, counting
+    /// what's already held in it, plus the token's current share (%) for the log
+    pub async fn allocation_room_sol(&self, token_address: &str) -> (f64, f64) {
+        let book = self.book.lock().await;
+        let token_sol = book.active_positions.get(token_address).map_or(0.0, |position| position.sol_amount);
+        let total_deployed_sol: f64 = book.active_positions.values().map(|position| position.sol_amount).sum();
+        let budget_sol = self.allocation_budget_sol();
+        let room = allocation_room_sol(token_sol, total_deployed_sol, budget_sol, self.settings.trading.max_token_allocation_percent);
+        (room, safe_ratio(token_sol, allocation_base_sol(total_deployed_sol, budget_sol)) * 100.0)
+    }
+
+    /// Display current position status with countdown. Skipped while the monitor holds the book.
+    pub fn display_position_status(&self) {
+        let Ok(book) = self.book.try_lock() else {
+            return;
+        };
+        if book.active_positions.is_empty() {
+            return;
+        }
+        println!("📊 Active positions: {} (max-hold auto-sell enabled)", book.active_positions.len());
+
+        let now = SystemTime::now();
+        let total_deployed_sol: f64 = book.active_positions.values().map(|position| position.sol_amount).sum();
+        let base_sol = allocation_base_sol(total_deployed_sol, self.allocation_budget_sol());
+        for (token, position) in &book.active_positions {
+            let remaining = hold_time_remaining(position, now);
+            let honeypot_flag = if matches!(position.sell_probe, Some(SellProbe::Honeypot(_))) { " 🍯 HONEYPOT (sell probe failed)" } else { "" };
+            println!("   💎 {} - {:.1}% of capital - Auto-sell in {}:{:02}:{:02} (max hold {}){}",
+                &token[0..8],
+                safe_ratio(position.sol_amount, base_sol) * 100.0,
+                remaining.as_secs() / 3600,
+                (remaining.as_secs() % 3600) / 60,
+                remaining.as_secs() % 60,
+                format_duration(position.max_hold),
+                honeypot_flag);
+        }
+    }
+
+    /// Auto-sell positions held past their own max hold time
+    async fn monitor_position_timeouts(&self) -> Result<()> {
+        let current_time = SystemTime::now();
+
+        // Take the expired positions out under the lock, sell them without it
+        let expired: Vec<TokenPosition> = {
+            let mut book = self.book.lock().await;
+            let tokens: Vec<String> = book.active_positions.iter()
+                .filter(|(_, position)| hold_time_remaining(position, current_time).is_zero())
+                .map(|(token_address, _)| token_address.clone())
+                .collect();
+            tokens.iter().filter_map(|token| book.active_positions.remove(token)).collect()
+        };
+
+        // Execute auto-sell for timed out positions, carrying on past a failed sell
+        let mut first_error = None;
+        for position in expired {
+            if self.extend_unprofitable_timeout(&position).await {
+                continue;
+            }
+            let reason = format!("MAX HOLD TIME ({}) REACHED", format_duration(position.max_hold));
+            if let Err(e) = self.execute_auto_sell(&position, &reason).await {
+                first_error.get_or_insert(e);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    /// MAX_TIMEOUT_EXTENSION_MINUTES: put a timed-out position back with a longer max hold when
+    /// selling now would net less than MIN_TIMEOUT_SELL_PROFIT_SOL. Real positions are priced
+    /// with a sell quote minus the swap fees, paper ones at the monitor's valuation.
+    async fn extend_unprofitable_timeout(&self, position: &TokenPosition) -> bool {
+        let trading = &self.settings.trading;
+        let max_extension = Duration::from_secs(trading.max_timeout_extension_minutes * 60);
+        let extended = position.timeout_extension;
+        if extended >= max_extension {
+            return false;
+        }
+
+        let projected_sol = if position.kind.on_chain() {
+            match self.jupiter_trader.quote_sell_sol(&position.token_address, position.estimated_tokens).await {
+                Ok(quoted_sol) => Some(quoted_sol - estimated_swap_fee_sol(&self.settings)),
+                Err(e) => {
+                    println!("⚠️  Timeout sell quote failed - selling on time: {}", e);
+                    None
+                }
+            }
+        } else {
+            self.book.lock().await.profit_monitor
+                .get_profit_data(&position.token_address)
+                .map(|data| data.current_value_sol)
+        };
+        let projected_profit_sol = projected_sol.map(|proceeds| proceeds - position.sol_amount);
+
+        let grace = Duration::from_secs(trading.timeout_grace_minutes * 60);
+        match timeout_action(projected_profit_sol, trading.min_timeout_sell_profit_sol, extended, grace, max_extension) {
+            TimeoutAction::Sell => false,
+            TimeoutAction::Extend(grace) => {
+                println!("⏳ MAX HOLD reached for {} but a sell would net {:+.4} SOL (minimum {:+} SOL) - holding {} longer ({} of {} extra used)",
+                    position.token_address, projected_profit_sol.unwrap_or_default(), trading.min_timeout_sell_profit_sol,
+                    format_duration(grace), format_duration(extended + grace), format_duration(max_extension));
+                let extended_position = TokenPosition {
+                    max_hold: position.max_hold + grace,
+                    timeout_extension: extended + grace,
+                    ..position.clone()
+                };
+                self.book.lock().await.active_positions.insert(position.token_address.clone(), extended_position);
+                true
+            }
+        }
+    }
+
+    /// Monitor existing positions for take profit opportunities
+    async fn monitor_existing_positions(&self) -> Result<()> {
+        if self.settings.trading.exit_mode == "momentum" {
+            return self.scale_out_stalled_positions().await;
+        }
+
+        // Take profit monitoring disabled in simplified version; armed trailing stops still exit
+        if !self.settings.trading.trailing_stop_enabled {
+            return Ok(());
+        }
+
+        // Take the stopped positions out under the lock, sell them without it
+        let stopped: Vec<(TokenPosition, f64)> = {
+            let mut book = self.book.lock().await;
+            let hits = book.profit_monitor.trailing_stop_hits(self.settings.trading.trailing_stop_percent, &self.settings.trading.trailing_stop_rungs);
+            hits.into_iter()
+                .filter_map(|(token_address, decline)| Some((book.active_positions.remove(&token_address)?, decline)))
+                .collect()
+        };
+
+        let mut first_error = None;
+        for (position, decline) in stopped {
+            let reason = format!("TRAILING STOP HIT ({:.1}% off peak)", decline);
+            if let Err(e) = self.execute_auto_sell(&position, &reason).await {
+                first_error.get_or_insert(e);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    /// EXIT_MODE=momentum: sell MOMENTUM_SELL_PERCENT of every position whose rally stalled
+    async fn scale_out_stalled_positions(&self) -> Result<()> {
+        let lower_updates = self.settings.trading.momentum_lower_updates;
+        let hits = self.book.lock().await.profit_monitor.momentum_stall_hits(lower_updates as usize);
+        for (token_address, drop) in hits {
+            let reason = format!("MOMENTUM STALLED ({} lower updates, {:.1}% off peak)", lower_updates, drop);
+            self.execute_partial_sell(&token_address, self.settings.trading.momentum_sell_percent / 100.0, &reason).await?;
+        }
+        Ok(())
+    }
+
+    /// Sell `fraction` of a position and keep the rest open with its share of the cost basis.
+    /// Becomes a full auto-sell when the remainder would be too small to track.
+    async fn execute_partial_sell(&self, token_address: &str, fraction: f64, reason: &str) -> Result<()> {
+        let decimals = self.token_decimals(token_address).await;
+        let (position, value_sol, tokens_to_sell) = {
+            let mut book = self.book.lock().await;
+            let Some(position) = book.active_positions.get(token_address).cloned() else {
+                return Ok(()); // Sold meanwhile
+            };
+            let price_usd = book.profit_monitor.get_profit_data(token_address).map_or(0.0, |data| data.current_price_usd);
+            let dust_threshold_usd = self.settings.trading.dust_threshold_usd;
+            match partial_sell_tokens(position.estimated_tokens, fraction, self.settings.trading.min_position_tokens)
+                // A remainder worth less than the dust threshold would never be worth selling later
+                .filter(|&tokens| !is_dust(position.estimated_tokens - tokens, decimals, price_usd, dust_threshold_usd))
+            {
+                Some(tokens_to_sell) => {
+                    let value_sol = book.profit_monitor.get_profit_data(token_address).map(|data| data.current_value_sol);
+                    (position, value_sol, tokens_to_sell)
+                }
+                None => {
+                    book.active_positions.remove(token_address);
+                    drop(book);
+                    return self.execute_auto_sell(&position, reason).await;
+                }
+            }
+        };
+
+        let sold_share = safe_ratio(tokens_to_sell as f64, position.estimated_tokens as f64);
+        let cost_sol = position.sol_amount * sold_share;
+        let expected_sol = value_sol.map(|value| value * sold_share);
+        println!("✂️  {} - SCALING OUT {:.0}% of {}", reason, sold_share * 100.0, token_address);
+        println!("   Tokens to sell: {} of {}", tokens_to_sell, position.estimated_tokens);
+
+        let (received_sol, signature) = if position.kind.on_chain() {
+            let min_sol_out = expected_sol
+                .and_then(|expected| min_out_floor(expected, self.settings.trading.min_out_tolerance_percent));
+            match self.jupiter_trader.sell_token(token_address, tokens_to_sell, min_sol_out).await {
+                Ok(sell_result) => (sell_result.sol_received, Some(sell_result.transaction_signature)),
+                Err(e) if e.downcast_ref::<MinOutError>().is_some() => {
+                    println!("⚠️  Scale-out deferred - fill too poor: {}", e);
+                    return Ok(());
+                }
+                Err(e) => {
+                    println!("❌ Scale-out failed: {}", e);
+                    return Err(e);
+                }
+            }
+        } else {
+            // Paper and simulated positions close at the monitor's valuation
+            (expected_sol.unwrap_or(cost_sol), None)
+        };
+
+        let profit_loss = received_sol - cost_sol;
+        let profit_percent = safe_ratio(profit_loss, cost_sol) * 100.0;
+        println!("💸 Scaled out: {:.4} SOL received for {:.4} SOL of cost ({:+.4} SOL, {:+.1}%)",
+            received_sol, cost_sol, profit_loss, profit_percent);
+
+        {
+            let mut book = self.book.lock().await;
+            if let Some(held) = book.active_positions.get_mut(token_address) {
+                held.estimated_tokens = held.estimated_tokens.saturating_sub(tokens_to_sell);
+                held.sol_amount = (held.sol_amount - cost_sol).max(0.0);
+            }
+            book.record_realized(token_address, profit_loss);
+            book.profit_monitor.reduce_position(token_address, tokens_to_sell, cost_sol);
+        }
+        let sold_part = TokenPosition { estimated_tokens: tokens_to_sell, sol_amount: cost_sol, ..position };
+        self.record_trade(&sold_part, TradeSide::Sell, received_sol, signature);
+
+        if self.settings.telegram.notifications_enabled && self.settings.telegram.send_sell_alerts {
+            if let Err(e) = self.telegram.send_sell_alert(token_address, "Scaled Out", received_sol, profit_loss, profit_percent).await {
+                println!("⚠️  Telegram sell alert failed: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// True while the portfolio take profit has paused buying (until the UTC day ends)
+    pub async fn buys_paused(&self) -> bool {
+        let today = utc_day(unix_secs(SystemTime::now()));
+        self.book.lock().await.portfolio_target_hit_day == Some(today)
+    }
+
+    /// Portfolio-level exit: once the day's P&L reaches PORTFOLIO_TAKE_PROFIT_SOL/_PERCENT, sell
+    /// every position and pause buys for the rest of the UTC day. Positions whose sell was
+    /// deferred are retried on later rounds.
+    async fn check_portfolio_take_profit(&self) -> Result<()> {
+        let target_sol = self.settings.trading.portfolio_take_profit_sol;
+        let target_percent = self.settings.trading.portfolio_take_profit_percent;
+        if target_sol <= 0.0 && target_percent <= 0.0 {
+            return Ok(());
+        }
+
+        let today = utc_day(unix_secs(SystemTime::now()));
+        let (positions, newly_hit) = {
+            let mut book = self.book.lock().await;
+            let day_start = match book.day_start_realized_pnl {
+                Some((day, realized)) if day == today => realized,
+                _ => {
+                    let realized = book.realized_pnl_sol;
+                    book.day_start_realized_pnl = Some((today, realized));
+                    realized
+                }
+            };
+
+            let newly_hit = if book.portfolio_target_hit_day == Some(today) {
+                None
+            } else {
+                let summary = book.profit_monitor.get_portfolio_summary();
+                if !summary.pricing_reliable {
+                    return Ok(()); // Frozen prices would trigger (or miss) the target on stale P&L
+                }
+                let day_pnl_sol = book.realized_pnl_sol - day_start + summary.total_pnl_sol;
+                match portfolio_take_profit_reason(&summary, day_pnl_sol, target_sol, target_percent) {
+                    Some(reason) => {
+                        book.portfolio_target_hit_day = Some(today);
+                        Some(reason)
+                    }
+                    None => return Ok(()),
+                }
+            };
+            let positions: Vec<TokenPosition> = book.active_positions.drain().map(|(_, position)| position).collect();
+            (positions, newly_hit)
+        };
+
+        if let Some(reason) = &newly_hit {
+            println!("🏁 PORTFOLIO TAKE PROFIT: {} - selling {} position(s), no new buys until UTC midnight", reason, positions.len());
+            if self.settings.telegram.notifications_enabled {
+                let msg = format!("🏁 PORTFOLIO TAKE PROFIT HIT\n📈 {}\n💸 Selling {} position(s)\n⏸️ No new buys until UTC midnight",
+                    reason, positions.len());
+                if let Err(e) = self.telegram.send_message(&msg).await {
+                    println!("⚠️  Telegram notification failed: {}", e);
+                }
+            }
+        }
+
+        // Keep going on errors so one failed sell doesn't strand the rest. A failed or deferred
+        // sell puts its position back, and it's sold on a later check (the target stays hit all day).
+        let mut first_error = None;
+        for position in positions {
+            if let Err(e) = self.execute_auto_sell(&position, "PORTFOLIO TAKE PROFIT").await {
+                first_error.get_or_insert(e);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    /// Re-read every held token's authorities and liquidity and exit any whose safety degraded
+    /// since its first re-check (delayed rugs on tokens that passed RugCheck at entry)
+    async fn recheck_position_security(&self) -> Result<()> {
+        let interval = Duration::from_secs(self.settings.security.position_recheck_interval_secs);
+        let tokens: Vec<String> = {
+            let mut book = self.book.lock().await;
+            if book.last_security_recheck.is_some_and(|last| last.elapsed() < interval) {
+                return Ok(());
+            }
+            book.last_security_recheck = Some(Instant::now());
+            book.active_positions.keys().cloned().collect()
+        };
+
+        // A failed exit keeps its position (execute_auto_sell puts it back); carry on with the rest
+        let mut first_error = None;
+        for token_address in tokens {
+            let authorities = match self.jupiter_trader.get_mint_authorities(&token_address).await {
+                Ok(authorities) => Some(authorities),
+                Err(e) => {
+                    println!("⚠️  Authority re-check failed for {}: {}", token_address, e);
+                    None
+                }
+            };
+            let liquidity_sol = match self.fetch_liquidity_sol(&token_address).await {
+                Ok(liquidity_sol) => Some(liquidity_sol),
+                Err(e) => {
+                    println!("⚠️  Liquidity re-check failed for {}: {}", token_address, e);
+                    None
+                }
+            };
+            let current = SecuritySnapshot { authorities, liquidity_sol };
+
+            // Take the position out only if it degraded (and wasn't sold meanwhile)
+            let degraded = {
+                let mut book = self.book.lock().await;
+                let baseline = book.security_baselines.entry(token_address.clone()).or_insert(current);
+                let reason = security_degradation(baseline, &current, self.settings.security.position_recheck_max_liquidity_drop_percent);
+                baseline.fill_unknown(&current);
+                reason.and_then(|reason| Some((book.active_positions.remove(&token_address)?, reason)))
+            };
+
+            if let Some((position, reason)) = degraded {
+                println!("🚨 SECURITY DEGRADED for {}: {}", token_address, reason);
+                if self.settings.telegram.notifications_enabled {
+                    let msg = format!("🚨 SECURITY DEGRADED - EXITING\n💎 Token: `{}`\n⚠️ {}", token_address, reason);
+                    if let Err(e) = self.telegram.send_message(&msg).await {
+                        println!("⚠️  Telegram notification failed: {}", e);
+                    }
+                }
+                if let Err(e) = self.execute_auto_sell(&position, &format!("SECURITY DEGRADED ({})", reason)).await {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    /// Update profit monitoring (periodic updates)
+    async fn update_profit_monitoring(&self) -> Result<()> {
+        let mut book = self.book.lock().await;
+        if !book.active_positions.is_empty() {
+            // This will update existing positions and handle timing
+            let PositionBook { active_positions, profit_monitor, .. } = &mut *book;
+            if let Err(e) = profit_monitor.start_monitoring(active_positions).await {
+                println!("⚠️  Failed to update profit monitoring: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Execute automatic sell (max hold time or trailing stop) using Jupiter V6 API.
+    /// The position must already be out of the book; it is put back if the sell is deferred or fails.
+    async fn execute_auto_sell(&self, position: &TokenPosition, reason: &str) -> Result<()> {
+        println!("⏰ {} - AUTO-SELLING 100%", reason);
+        println!("   Token: {}", position.token_address);
+        println!("   Original investment: {:.4} SOL", position.sol_amount);
+        println!("   Tokens to sell: {}", position.estimated_tokens);
+
+        // Paper and simulated positions never hit the chain - close them at the monitor's last valuation
+        if !position.kind.on_chain() {
+            let (exit_value_sol, profit_loss) = {
+                let mut book = self.book.lock().await;
+                book.security_baselines.remove(&position.token_address);
+                book.low_liquidity_since.remove(&position.token_address);
+                let exit_value_sol = book.profit_monitor
+                    .remove_position(&position.token_address)
+                    .map(|data| data.current_value_sol)
+                    .unwrap_or(position.sol_amount);
+                let profit_loss = exit_value_sol - position.sol_amount;
+                book.record_realized(&position.token_address, profit_loss);
+                (exit_value_sol, profit_loss)
+            };
+            println!("📝 {:?} position closed: {:.4} SOL -> {:.4} SOL ({:+.4} SOL)",
+                position.kind, position.sol_amount, exit_value_sol, profit_loss);
+            self.record_trade(position, TradeSide::Sell, exit_value_sol, None);
+            return Ok(());
+        }
+
+        // Sell what the wallet actually holds; a balance already gone or worth less than the
+        // dust threshold is closed as sold instead of sending a swap that fails or costs more than it returns
+        let price_usd = self.book.lock().await.profit_monitor
+            .get_profit_data(&position.token_address)
+            .map_or(0.0, |data| data.current_price_usd);
+        let held_tokens = match self.jupiter_trader.get_token_balance(&position.token_address).await {
+            Ok(0) => {
+                self.close_dust_position(position, 0, 0.0).await;
+                return Ok(());
+            }
+            Ok(held) => match self.token_decimals(&position.token_address).await {
+                Some(decimals) if is_dust(held, Some(decimals), price_usd, self.settings.trading.dust_threshold_usd) => {
+                    self.close_dust_position(position, held, token_value_usd(held, decimals, price_usd)).await;
+                    return Ok(());
+                }
+                _ => held.min(position.estimated_tokens),
+            },
+            Err(e) => {
+                println!("⚠️  Token balance check failed - selling the tracked amount: {}", e);
+                position.estimated_tokens
+            }
+        };
+        let position = &TokenPosition { estimated_tokens: held_tokens, ..position.clone() };
+
+        // Don't dump into a drained pool: hold and retry, or write the position off
+        if self.settings.trading.min_sell_liquidity_sol > 0.0 && !self.check_exit_liquidity(position).await {
+            return Ok(());
+        }
+
+        // Floor the exit at the monitor's current valuation minus the configured tolerance
+        let min_sol_out = self.book.lock().await.profit_monitor
+            .get_profit_data(&position.token_address)
+            .and_then(|data| min_out_floor(data.current_value_sol, self.settings.trading.min_out_tolerance_percent));
+
+        // Execute real sell via Jupiter
+        match self.jupiter_trader.sell_token(&position.token_address, position.estimated_tokens, min_sol_out).await {
+            Ok(sell_result) => {
+                let received_sol = sell_result.sol_received;
+                let profit_loss = received_sol - position.sol_amount;
+                let profit_percent = (profit_loss / position.sol_amount) * 100.0;
+
+                println!("💸 AUTO-SELL EXECUTED - 100% of position sold");
+                println!("📄 Transaction: {}", sell_result.transaction_signature);
+                println!("💰 Received: {:.4} SOL", received_sol);
+
+                if profit_loss >= 0.0 {
+                    println!("   📈 Profit: +{:.4} SOL (+{:.1}%)", profit_loss, profit_percent);
+                } else {
+                    println!("   📉 Loss: {:.4} SOL ({:.1}%)", profit_loss, profit_percent);
+                }
+
+                // Book the result and remove the position from the profit monitor
+                {
+                    let mut book = self.book.lock().await;
+                    book.record_realized(&position.token_address, profit_loss);
+                    book.profit_monitor.remove_position(&position.token_address);
+                    book.low_liquidity_since.remove(&position.token_address);
+                    book.security_baselines.remove(&position.token_address);
+                }
+                self.record_trade(position, TradeSide::Sell, received_sol, Some(sell_result.transaction_signature.clone()));
+
+                // Send Telegram sell alert
+                if self.settings.telegram.notifications_enabled && self.settings.telegram.send_sell_alerts {
+                    if let Err(e) = self.telegram.send_sell_alert(
+                        &position.token_address,
+                        "Auto-Sold Token", // In real implementation, store token name in position
+                        received_sol,
+                        profit_loss,
+                        profit_percent
+                    ).await {
+                        println!("⚠️  Telegram sell alert failed: {}", e);
+                    }
+                }
+            }
+            Err(e) if e.downcast_ref::<MinOutError>().is_some() => {
+                // Keep the position so the sell is retried on the next check
+                println!("⚠️  Auto-sell deferred - fill too poor: {}", e);
+                self.book.lock().await.active_positions.insert(position.token_address.clone(), position.clone());
+            }
+            Err(e) => {
+                // Back in the book so the next check retries instead of forgetting the tokens
+                println!("❌ Auto-sell failed - keeping the position: {}", e);
+                self.book.lock().await.active_positions.insert(position.token_address.clone(), position.clone());
+                return Err(e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check the pool before an auto-sell. Returns true to go ahead; false when the position was
+    /// put back to wait for liquidity or written off as rugged.
+    async fn check_exit_liquidity(&self, position: &TokenPosition) -> bool {
+        let floor_sol = self.settings.trading.min_sell_liquidity_sol;
+        let liquidity_sol = match self.fetch_liquidity_sol(&position.token_address).await {
+            Ok(liquidity_sol) => Some(liquidity_sol),
+            Err(e) => {
+                println!("⚠️  Exit liquidity check failed - selling anyway: {}", e);
+                None
+            }
+        };
+
+        let held_for = self.book.lock().await.low_liquidity_since
+            .get(&position.token_address)
+            .map(|since| since.elapsed())
+            .unwrap_or_default();
+        let max_hold = Duration::from_secs(self.settings.trading.low_liquidity_hold_minutes * 60);
+
+        match exit_liquidity_action(liquidity_sol, floor_sol, self.settings.trading.hold_on_low_liquidity, held_for, max_hold) {
+            ExitLiquidityAction::Sell => {
+                if let Some(liquidity) = liquidity_sol.filter(|&liquidity| liquidity < floor_sol) {
+                    println!("🫗 Pool liquidity {:.2} SOL is below the {:.2} SOL floor - selling anyway", liquidity, floor_sol);
+                }
+                true
+            }
+            ExitLiquidityAction::Hold => {
+                println!("🫗 Pool liquidity {:.2} SOL is below the {:.2} SOL floor - holding {} (waited {}, write-off after {})",
+                    liquidity_sol.unwrap_or_default(), floor_sol, position.token_address,
+                    format_duration(held_for), format_duration(max_hold));
+                let mut book = self.book.lock().await;
+                book.low_liquidity_since.entry(position.token_address.clone()).or_insert_with(Instant::now);
+                book.active_positions.insert(position.token_address.clone(), position.clone());
+                false
+            }
+            ExitLiquidityAction::WriteOff => {
+                self.write_off_rugged(position, liquidity_sol.unwrap_or_default()).await;
+                false
+            }
+        }
+    }
+
+    /// A mint's decimals for dust checks; None (never dust) when they can't be read
+    async fn token_decimals(&self, token_address: &str) -> Option<u8> {
+        match self.jupiter_trader.get_token_decimals(token_address).await {
+            Ok(decimals) => Some(decimals),
+            Err(e) => {
+                println!("⚠️  Could not read {} decimals - skipping the dust check: {}", token_address, e);
+                None
+            }
+        }
+    }
+
+    /// Close a position whose wallet balance is empty or dust as sold, without swapping
+    async fn close_dust_position(&self, position: &TokenPosition, held_tokens: u64, value_usd: f64) {
+        println!("🧹 DUST - wallet holds {} of {} tokens (~${:.4}), below the ${} threshold - closing {} without a sell",
+            held_tokens, position.estimated_tokens, value_usd,
+            self.settings.trading.dust_threshold_usd, position.token_address);
+
+        {
+            let mut book = self.book.lock().await;
+            book.record_realized(&position.token_address, -position.sol_amount);
+            book.profit_monitor.remove_position(&position.token_address);
+            book.low_liquidity_since.remove(&position.token_address);
+            book.security_baselines.remove(&position.token_address);
+        }
+        let remaining = TokenPosition { estimated_tokens: held_tokens, ..position.clone() };
+        self.record_trade(&remaining, TradeSide::Sell, 0.0, None);
+    }
+
+    /// Close a rugged position at a total loss without swapping; the tokens stay in the wallet
+    async fn write_off_rugged(&self, position: &TokenPosition, liquidity_sol: f64) {
+        println!("🪦 RUGGED - liquidity stayed at {:.2} SOL, writing off {} without selling", liquidity_sol, position.token_address);
+        println!("   📉 Loss: -{:.4} SOL (tokens left in wallet)", position.sol_amount);
+
+        {
+            let mut book = self.book.lock().await;
+            book.record_realized(&position.token_address, -position.sol_amount);
+            book.profit_monitor.remove_position(&position.token_address);
+            book.low_liquidity_since.remove(&position.token_address);
+            book.security_baselines.remove(&position.token_address);
+        }
+        self.record_trade(position, TradeSide::WriteOff, 0.0, None);
+
+        if self.settings.telegram.notifications_enabled {
+            let msg = format!("🪦 POSITION WRITTEN OFF (RUGGED)\n💎 Token: `{}`\n🫗 Liquidity: {:.2} SOL (floor {:.2} SOL)\n📉 Loss: -{:.4} SOL\n⚠️ No sell executed - tokens left in wallet",
+                position.token_address, liquidity_sol, self.settings.trading.min_sell_liquidity_sol, position.sol_amount);
+            if let Err(e) = self.telegram.send_message(&msg).await {
+                println!("⚠️  Telegram notification failed: {}", e);
+            }
+        }
+    }
+
+    /// Current liquidity (SOL) of the token's deepest DexScreener pool
+    async fn fetch_liquidity_sol(&self, token_address: &str) -> Result<f64> {
+        let liquidity_usd = deepest_pool_liquidity_usd(token_address).await?;
+        Ok(usd_to_sol(liquidity_usd, self.sol_price.current()))
+    }
+
+    /// Count a failed round and send a deduplicated Telegram error alert (if enabled)
+    async fn record_auto_sell_error(&self, error: &str) {
+        self.book.lock().await.auto_sell_failures += 1;
+
+        if self.settings.telegram.notifications_enabled && self.settings.telegram.send_error_alerts {
+            if let Err(e) = self.telegram.send_error_alert("auto-sell", error).await {
+                println!("⚠️  Telegram error alert failed: {}", e);
+            }
+        }
+    }
+
+    /// Reset the failure streak, sending a suppressed-repeats summary if needed
+    async fn record_auto_sell_success(&self) {
+        self.book.lock().await.auto_sell_failures = 0;
+
+        if self.settings.telegram.notifications_enabled && self.settings.telegram.send_error_alerts {
+            if let Err(e) = self.telegram.resolve_error_alerts("auto-sell").await {
+                println!("⚠️  Telegram recovery alert failed: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pool_scanner::PositionKind;
+    use solana_sdk::pubkey::Pubkey;
+
+    fn snapshot(mint_authority: Option<Pubkey>, liquidity_sol: Option<f64>) -> SecuritySnapshot {
+        SecuritySnapshot {
+            authorities: Some(MintAuthorities { mint_authority, freeze_authority: None }),
+            liquidity_sol,
+        }
+    }
+
+    #[test]
+    fn re_enabled_mint_authority_is_a_degradation() {
+        let authority = Pubkey::new_unique();
+        let reason = security_degradation(&snapshot(None, Some(50.0)), &snapshot(Some(authority), Some(50.0)), 50.0);
+        assert_eq!(reason, Some(format!("mint authority re-enabled ({})", authority)));
+    }
+
+    #[test]
+    fn liquidity_drop_past_the_limit_is_a_degradation() {
+        assert_eq!(security_degradation(&snapshot(None, Some(50.0)), &snapshot(None, Some(30.0)), 50.0), None);
+        assert!(security_degradation(&snapshot(None, Some(50.0)), &snapshot(None, Some(20.0)), 50.0).is_some());
+    }
+
+    #[test]
+    fn unreadable_fields_never_trigger_an_exit() {
+        let unknown = SecuritySnapshot { authorities: None, liquidity_sol: None };
+        assert_eq!(security_degradation(&snapshot(None, Some(50.0)), &unknown, 50.0), None);
+        assert_eq!(security_degradation(&unknown, &snapshot(Some(Pubkey::new_unique()), Some(1.0)), 50.0), None);
+    }
+
+    fn summary(total_pnl_percentage: f64, active_positions: usize) -> PortfolioSummary {
+        PortfolioSummary {
+            total_invested_sol: 1.0,
+            current_value_sol: 1.0 + total_pnl_percentage / 100.0,
+            total_pnl_sol: total_pnl_percentage / 100.0,
+            total_pnl_percentage,
+            total_pnl_usd: 0.0,
+            active_positions,
+            winning_positions: 0,
+            losing_positions: 0,
+            best_performer: None,
+            worst_performer: None,
+            daily_high: 0.0,
+            daily_low: 0.0,
+            pricing_reliable: true,
+        }
+    }
+
+    #[test]
+    fn portfolio_take_profit_fires_once_the_day_crosses_the_sol_target() {
+        assert_eq!(portfolio_take_profit_reason(&summary(10.0, 2), 0.9, 1.0, 0.0), None);
+        assert!(portfolio_take_profit_reason(&summary(10.0, 2), 1.0, 1.0, 0.0).is_some());
+    }
+
+    #[test]
+    fn portfolio_take_profit_fires_once_open_positions_cross_the_percent_target() {
+        assert_eq!(portfolio_take_profit_reason(&summary(24.0, 2), 0.0, 0.0, 25.0), None);
+        assert!(portfolio_take_profit_reason(&summary(25.0, 2), 0.0, 0.0, 25.0).is_some());
+        // No open positions, nothing to liquidate
+        assert_eq!(portfolio_take_profit_reason(&summary(50.0, 0), 0.0, 0.0, 25.0), None);
+    }
+
+    #[test]
+    fn portfolio_take_profit_is_off_at_zero() {
+        assert_eq!(portfolio_take_profit_reason(&summary(500.0, 3), 100.0, 0.0, 0.0), None);
+    }
+
+    fn bought(sol_amount: f64, tokens: u64, bought_at_secs: u64, signature: &str) -> TokenPosition {
+        TokenPosition {
+            token_address: "TokenMint111".to_string(),
+            purchase_time: SystemTime::UNIX_EPOCH + Duration::from_secs(bought_at_secs),
+            sol_amount,
+            estimated_tokens: tokens,
+            entry_price: safe_ratio(sol_amount, tokens as f64),
+            kind: PositionKind::Real,
+            trade_result: Some(crate::jupiter_trader::TradeResult {
+                transaction_signature: signature.to_string(),
+                tokens_received: tokens,
+                sol_received: 0.0,
+                landed_via: None,
+                time_to_fill: None,
+                realized_slippage_percent: None,
+            }),
+            max_hold: Duration::from_secs(600),
+            timeout_extension: Duration::ZERO,
+            sell_probe: None,
+        }
+    }
+
+    #[test]
+    fn repeat_buy_adds_to_the_held_position() {
+        let held = bought(0.1, 1_000, 100, "sigA");
+        let merged = merge_positions(&held, bought(0.3, 1_000, 160, "sigB")).unwrap();
+        assert!((merged.sol_amount - 0.4).abs() < 1e-12);
+        assert_eq!(merged.estimated_tokens, 2_000);
+        assert!((merged.entry_price - 0.0002).abs() < 1e-12);
+        assert_eq!(merged.purchase_time, held.purchase_time);
+        assert_eq!(merged.max_hold, held.max_hold);
+        assert_eq!(merged.trade_result.unwrap().transaction_signature, "sigA");
+    }
+
+    #[test]
+    fn same_buy_tracked_twice_is_not_added_again() {
+        let held = bought(0.1, 1_000, 100, "sigA");
+        assert!(merge_positions(&held, bought(0.1, 1_000, 100, "sigA")).is_none());
+    }
+
+    #[test]
+    fn allocation_cap_trims_a_repeat_buy_to_fit() {
+        // Budget 1 SOL (5 x 0.2), token holds 0.1 of 0.5 deployed, 25% cap: 0.15 SOL of room
+        let room = allocation_room_sol(0.1, 0.5, 1.0, 25.0);
+        assert!((room - 0.15).abs() < 1e-12);
+        assert!(room < 0.2);
+        // Past the budget the buy itself grows the base: 1.0 of 4.0 deployed at 40% leaves 1.0
+        assert!((allocation_room_sol(1.0, 4.0, 1.0, 40.0) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn allocation_cap_skips_a_token_already_at_it() {
+        assert_eq!(allocation_room_sol(0.3, 0.8, 1.0, 25.0), 0.0);
+        assert_eq!(allocation_room_sol(0.3, 0.8, 1.0, 0.0), f64::INFINITY);
+    }
+
+    #[test]
+    fn dust_is_measured_in_whole_tokens() {
+        // 40,000 raw units of a 6-decimal token at $1 = $0.04, under a $0.05 threshold
+        assert!((token_value_usd(40_000, 6, 1.0) - 0.04).abs() < 1e-12);
+        assert!(is_dust(40_000, Some(6), 1.0, 0.05));
+        // The same raw amount at 2 decimals is $400
+        assert!(!is_dust(40_000, Some(2), 1.0, 0.05));
+        // Empty is always dust; unknown price or decimals never is
+        assert!(is_dust(0, None, 0.0, 0.05));
+        assert!(!is_dust(40_000, Some(6), 0.0, 0.05));
+        assert!(!is_dust(40_000, None, 1.0, 0.05));
+    }
+
+    #[test]
+    fn dust_remainder_turns_a_scale_out_into_a_full_sell() {
+        // Selling 99% of 1,000,000 raw units (6 decimals, $0.01) leaves $0.0001: sold in full
+        let tokens_to_sell = partial_sell_tokens(1_000_000, 0.99, 1)
+            .filter(|&tokens| !is_dust(1_000_000 - tokens, Some(6), 0.01, 0.05));
+        assert_eq!(tokens_to_sell, None);
+        let tokens_to_sell = partial_sell_tokens(1_000_000_000, 0.5, 1)
+            .filter(|&tokens| !is_dust(1_000_000_000 - tokens, Some(6), 0.01, 0.05));
+        assert_eq!(tokens_to_sell, Some(500_000_000));
+    }
+
+    fn closed_trade(token: &str, side: TradeSide, timestamp_unix_secs: u64) -> TradeRecord {
+        TradeRecord {
+            timestamp_unix_secs,
+            token_address: token.to_string(),
+            side,
+            kind: PositionKind::Real,
+            sol_amount: 0.1,
+            token_amount: 1_000,
+            pnl_sol: (side != TradeSide::Buy).then_some(0.02),
+            signature: None,
+        }
+    }
+
+    #[test]
+    fn sold_mint_is_ignored_until_its_cooldown_elapses() {
+        let sold_at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let cooldown = Duration::from_secs(600);
+        let mut cooldowns = SellCooldowns::default();
+        cooldowns.record("mintA", sold_at);
+
+        assert_eq!(cooldowns.remaining("mintA", cooldown, sold_at + Duration::from_secs(60)), Some(Duration::from_secs(540)));
+        assert_eq!(cooldowns.remaining("mintB", cooldown, sold_at + Duration::from_secs(60)), None);
+        assert_eq!(cooldowns.remaining("mintA", cooldown, sold_at + cooldown), None); // Reconsidered from here on
+    }
+
+    #[test]
+    fn cooldowns_are_restored_from_recent_sells_and_write_offs_only() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let cooldown = Duration::from_secs(600);
+        let trades = vec![
+            closed_trade("boughtOnly", TradeSide::Buy, 1_700_000_000 - 30),
+            closed_trade("soldLongAgo", TradeSide::Sell, 1_700_000_000 - 3_600),
+            closed_trade("soldTwice", TradeSide::Sell, 1_700_000_000 - 500),
+            closed_trade("soldTwice", TradeSide::Sell, 1_700_000_000 - 100),
+            closed_trade("writtenOff", TradeSide::WriteOff, 1_700_000_000 - 200),
+        ];
+        let mut cooldowns = SellCooldowns::default();
+        cooldowns.restore(&trades, cooldown, now);
+
+        assert_eq!(cooldowns.remaining("boughtOnly", cooldown, now), None);
+        assert_eq!(cooldowns.remaining("soldLongAgo", cooldown, now), None);
+        assert_eq!(cooldowns.remaining("soldTwice", cooldown, now), Some(Duration::from_secs(500))); // Latest sell counts
+        assert_eq!(cooldowns.remaining("writtenOff", cooldown, now), Some(Duration::from_secs(400)));
+    }
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn timeout_sells_once_the_projected_profit_clears_the_minimum() {
+        assert_eq!(timeout_action(Some(0.02), 0.01, Duration::ZERO, 5 * MINUTE, 30 * MINUTE), TimeoutAction::Sell);
+        assert_eq!(timeout_action(Some(0.01), 0.01, Duration::ZERO, 5 * MINUTE, 30 * MINUTE), TimeoutAction::Sell);
+    }
+
+    #[test]
+    fn timeout_holds_longer_when_fees_would_eat_the_gain() {
+        // Marginally up before fees, a net loss after them
+        assert_eq!(timeout_action(Some(-0.001), 0.0, Duration::ZERO, 5 * MINUTE, 30 * MINUTE), TimeoutAction::Extend(5 * MINUTE));
+        assert_eq!(timeout_action(Some(0.004), 0.01, 10 * MINUTE, 5 * MINUTE, 30 * MINUTE), TimeoutAction::Extend(5 * MINUTE));
+    }
+
+    #[test]
+    fn timeout_extensions_stop_at_the_cap() {
+        assert_eq!(timeout_action(Some(-0.05), 0.0, 28 * MINUTE, 5 * MINUTE, 30 * MINUTE), TimeoutAction::Extend(2 * MINUTE));
+        assert_eq!(timeout_action(Some(-0.05), 0.0, 30 * MINUTE, 5 * MINUTE, 30 * MINUTE), TimeoutAction::Sell);
+        assert_eq!(timeout_action(Some(-0.05), 0.0, Duration::ZERO, 5 * MINUTE, Duration::ZERO), TimeoutAction::Sell); // Feature off
+        assert_eq!(timeout_action(Some(-0.05), 0.0, Duration::ZERO, Duration::ZERO, 30 * MINUTE), TimeoutAction::Sell);
+    }
+
+    #[test]
+    fn unpriced_timeout_always_sells() {
+        assert_eq!(timeout_action(None, 0.01, Duration::ZERO, 5 * MINUTE, 30 * MINUTE), TimeoutAction::Sell);
+    }
+
+    #[test]
+    fn timeout_extension_survives_a_restart() {
+        let extended = TokenPosition {
+            max_hold: Duration::from_secs(600) + 10 * MINUTE,
+            timeout_extension: 10 * MINUTE,
+            ..bought(0.1, 1_000, 1_700_000_000, "sig1")
+        };
+        let restored = StoredPosition::from(&extended).to_position();
+        assert_eq!(restored.max_hold, extended.max_hold);
+        assert_eq!(restored.timeout_extension, 10 * MINUTE);
+    }
+
+    #[test]
+    fn repeat_buy_keeps_the_extension_already_used() {
+        let held = TokenPosition { timeout_extension: 5 * MINUTE, ..bought(0.1, 1_000, 1_700_000_000, "sig1") };
+        let merged = merge_positions(&held, bought(0.1, 1_000, 1_700_000_060, "sig2")).unwrap();
+        assert_eq!(merged.timeout_extension, 5 * MINUTE);
+    }
+
+    #[test]
+    fn collapsed_liquidity_holds_then_writes_off_when_configured() {
+        let (floor, max_hold) = (5.0, Duration::from_secs(300));
+        assert_eq!(exit_liquidity_action(Some(0.4), floor, true, Duration::ZERO, max_hold), ExitLiquidityAction::Hold);
+        assert_eq!(exit_liquidity_action(Some(0.4), floor, true, Duration::from_secs(299), max_hold), ExitLiquidityAction::Hold);
+        assert_eq!(exit_liquidity_action(Some(0.4), floor, true, max_hold, max_hold), ExitLiquidityAction::WriteOff);
+    }
+
+    #[test]
+    fn collapsed_liquidity_sells_anyway_when_holding_is_off() {
+        assert_eq!(exit_liquidity_action(Some(0.4), 5.0, false, Duration::from_secs(600), Duration::from_secs(300)), ExitLiquidityAction::Sell);
+    }
+
+    #[test]
+    fn healthy_unknown_or_unchecked_liquidity_never_blocks_the_sell() {
+        let max_hold = Duration::from_secs(300);
+        assert_eq!(exit_liquidity_action(Some(5.0), 5.0, true, Duration::ZERO, max_hold), ExitLiquidityAction::Sell); // At the floor
+        assert_eq!(exit_liquidity_action(None, 5.0, true, Duration::ZERO, max_hold), ExitLiquidityAction::Sell);
+        assert_eq!(exit_liquidity_action(Some(0.0), 0.0, true, Duration::ZERO, max_hold), ExitLiquidityAction::Sell);
+    }
+
+    #[tokio::test]
+    async fn paper_closes_book_their_realized_pnl() {
+        let settings = BotSettings::from_env().unwrap();
+        let private_key = solana_sdk::signature::Keypair::new().to_base58_string();
+        let trader = Arc::new(JupiterTrader::new("http://127.0.0.1:8899", &private_key).unwrap());
+        let monitor = PositionMonitor::new(settings, TelegramNotifier::new("", ""), trader);
+        let position = TokenPosition {
+            token_address: "PaperMint111".to_string(),
+            purchase_time: SystemTime::now(),
+            sol_amount: 0.5,
+            estimated_tokens: 1_000,
+            entry_price: 0.0005,
+            kind: PositionKind::Paper,
+            trade_result: None,
+            max_hold: Duration::from_secs(60),
+            timeout_extension: Duration::ZERO,
+            sell_probe: None,
+        };
+        monitor.book.lock().await.low_liquidity_since.insert(position.token_address.clone(), Instant::now());
+
+        monitor.execute_auto_sell(&position, "MAX HOLD TIME").await.unwrap();
+
+        let mut book = monitor.book.lock().await;
+        // Unmonitored, so it closes at cost: a zero P&L, but still a close the sizer and cooldown see
+        assert_eq!(book.realized_pnl_sol, 0.0);
+        assert_eq!(book.closed_pnls_sol, vec![0.0]);
+        assert!(book.low_liquidity_since.is_empty());
+        assert!(book.sell_cooldown_remaining("PaperMint111", Duration::from_secs(60), SystemTime::now()).is_some());
+    }
+}
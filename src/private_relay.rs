@@ -0,0 +1,123 @@
+// Private Relay Submission - send swaps through a premium relay instead of the public mempool
+use crate::settings::BotSettings;
+use crate::lamports::Lamports;
+
+use serde::Deserialize;
+use solana_sdk::{pubkey::Pubkey, signature::Signature, transaction::Transaction};
+use base64::{engine::general_purpose, Engine as _};
+use std::time::Duration;
+use anyhow::{Result, anyhow};
+
+/// Relays PRIVATE_TX_PROVIDER can name
+pub const PRIVATE_TX_PROVIDERS: [&str; 2] = ["zeroslot", "nozomi"];
+
+#[derive(Debug, Deserialize)]
+struct RelayRpcError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SendTransactionResponse {
+    result: Option<String>, // Transaction signature
+    error: Option<RelayRpcError>,
+}
+
+pub struct PrivateRelay {
+    client: reqwest::Client,
+    name: String,
+    url: String, // Includes the provider's auth query parameter
+    tip_account: Pubkey,
+    tip: Lamports,
+}
+
+impl PrivateRelay {
+    pub fn new(name: &str, url: &str, tip_account: Pubkey, tip: Lamports) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .unwrap_or_default(),
+            name: name.to_string(),
+            url: url.to_string(),
+            tip_account,
+            tip,
+        }
+    }
+
+    /// Relay for the configured provider, or None when private submission is off or misconfigured
+    pub fn from_settings(settings: &BotSettings) -> Option<Self> {
+        let premium = &settings.apis.premium_endpoints;
+        if !premium.private_tx_enabled {
+            return None;
+        }
+
+        let (url, tip_account) = match premium.private_tx_provider.as_str() {
+            "zeroslot" => (
+                format!("{}?api-key={}", premium.zeroslot_rpc_url, premium.zeroslot_api_key),
+                &premium.zeroslot_tip_account,
+            ),
+            "nozomi" => (
+                format!("{}?c={}", premium.nozomi_url, premium.nozomi_uuid),
+                &premium.nozomi_tip_account,
+            ),
+            other => {
+                println!("⚠️  Unknown PRIVATE_TX_PROVIDER '{}' - using public submission", other);
+                return None;
+            }
+        };
+
+        match tip_account.parse() {
+            Ok(tip_account) => Some(Self::new(
+                &premium.private_tx_provider,
+                &url,
+                tip_account,
+                premium.private_tx_tip(),
+            )),
+            Err(e) => {
+                println!("⚠️  Invalid {} tip account - using public submission: {}", premium.private_tx_provider, e);
+                None
+            }
+        }
+    }
+
+    /// Submit a signed transaction straight to the relay (no preflight - Jupiter already simulated it)
+    pub async fn send_transaction(&self, transaction: &Transaction) -> Result<Signature> {
+        let encoded = general_purpose::STANDARD.encode(bincode::serialize(transaction)?);
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "sendTransaction",
+            "params": [encoded, { "encoding": "base64", "skipPreflight": true }],
+        });
+
+        let response = self.client
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!("{} relay returned status: {}", self.name, response.status()));
+        }
+
+        let sent: SendTransactionResponse = response.json().await?;
+        match (sent.result, sent.error) {
+            (_, Some(error)) => Err(anyhow!("{} relay rejected transaction: {}", self.name, error.message)),
+            (Some(signature), None) => signature.parse()
+                .map_err(|e| anyhow!("{} relay returned invalid signature: {}", self.name, e)),
+            (None, None) => Err(anyhow!("{} relay returned no signature", self.name)),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn tip_account(&self) -> Pubkey {
+        self.tip_account
+    }
+
+    pub fn tip(&self) -> Lamports {
+        self.tip
+    }
+}
@@ -1,17 +1,23 @@
 // Real-time Profit Monitoring Module
 use crate::settings::BotSettings;
-use crate::telegram::TelegramNotifier;
-use crate::pool_scanner::TokenPosition;
+use crate::telegram::{TelegramNotifier, TemplateKind};
+use crate::pool_scanner::{TokenPosition, PositionKind};
+use crate::birdeye::BirdeyeClient;
+use crate::health::unix_secs;
+use crate::portfolio_history::{self, PortfolioSnapshot};
+use crate::units::{sol_to_usd, usd_to_sol};
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use tokio::time::interval;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProfitData {
     pub token_address: String,
+    pub kind: PositionKind,
     pub symbol: String,
     pub current_price_usd: f64,
     pub entry_price_usd: f64,
@@ -24,7 +30,44 @@ pub struct ProfitData {
     pub last_updated: SystemTime,
     pub highest_value: f64,
     pub lowest_value: f64,
+    pub trailing_armed: bool, // Trailing stop only applies once the position reached the activation gain
     pub time_held: Duration,
+    pub peak_price_usd: f64,     // Highest price seen (unaffected by partial sells, unlike highest_value)
+    pub recent_prices: Vec<f64>, // Last few price updates for EXIT_MODE=momentum, oldest first
+}
+
+/// One step of the stepped trailing stop: once a position's best gain passes
+/// `profit_above_percent`, it trails by at most `trail_percent`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TrailRung {
+    pub profit_above_percent: f64,
+    pub trail_percent: f64,
+}
+
+/// Parse TRAILING_STOP_RUNGS ("50:30,100:15,300:8" = profit above %:trail %). Unparseable numbers
+/// become NaN so validate() can reject them.
+pub fn parse_trail_rungs(raw: &str) -> Vec<TrailRung> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (profit_above, trail) = entry.split_once(':').unwrap_or((entry, ""));
+            TrailRung {
+                profit_above_percent: profit_above.trim().parse().unwrap_or(f64::NAN),
+                trail_percent: trail.trim().parse().unwrap_or(f64::NAN),
+            }
+        })
+        .collect()
+}
+
+/// Trail distance (%) for a position whose best gain so far is `peak_gain_percent`: the tightest
+/// of `base_percent` and every rung that gain has passed. Keyed on the peak, so a pullback never
+/// loosens a trail that already tightened.
+pub fn stepped_trail_percent(base_percent: f64, rungs: &[TrailRung], peak_gain_percent: f64) -> f64 {
+    rungs.iter()
+        .filter(|rung| peak_gain_percent > rung.profit_above_percent)
+        .map(|rung| rung.trail_percent)
+        .fold(base_percent, f64::min)
 }
 
 #[derive(Debug, Clone)]
@@ -46,7 +89,7 @@ pub enum AlertType {
     LiquidityChange,       // Liquidity pool changes
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PortfolioSummary {
     pub total_invested_sol: f64,
     pub current_value_sol: f64,
@@ -60,6 +103,133 @@ pub struct PortfolioSummary {
     pub worst_performer: Option<String>,
     pub daily_high: f64,
     pub daily_low: f64,
+    #[serde(default = "pricing_reliable_default")]
+    pub pricing_reliable: bool, // False while the stale-pricing watchdog is firing - P&L is frozen, not flat
+}
+
+fn pricing_reliable_default() -> bool {
+    true
+}
+
+const COINGECKO_SOL_PRICE_URL: &str = "https://api.coingecko.com/api/v3/simple/price?ids=solana&vs_currencies=usd";
+const JUPITER_SOL_PRICE_URL: &str = "https://price.jup.ag/v6/price?ids=SOL";
+// Wait between CoinGecko SOL price attempts
+const SOL_PRICE_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Where a SOL price attempt goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolPriceSource {
+    CoinGecko,
+    Jupiter,
+}
+
+/// CoinGecko up to `retries + 1` times (`retry_delay` apart), then Jupiter once. `fetch` returns the
+/// source's JSON body, None on a network error or non-2xx status. A 429 or empty body is a failure.
+pub async fn fetch_sol_price_with<F, Fut>(retries: u32, retry_delay: Duration, mut fetch: F) -> Option<f64>
+where
+    F: FnMut(SolPriceSource) -> Fut,
+    Fut: std::future::Future<Output = Option<serde_json::Value>>,
+{
+    let attempts = retries + 1;
+    for attempt in 1..=attempts {
+        if let Some(sol_price) = fetch(SolPriceSource::CoinGecko).await.as_ref().and_then(coingecko_sol_price) {
+            return Some(sol_price);
+        }
+        if attempt < attempts {
+            println!("⚠️  CoinGecko SOL price unavailable (attempt {}/{}), retrying", attempt, attempts);
+            tokio::time::sleep(retry_delay).await;
+        }
+    }
+    println!("⚠️  CoinGecko SOL price unavailable - trying Jupiter");
+    fetch(SolPriceSource::Jupiter).await.as_ref().and_then(jupiter_sol_price)
+}
+
+/// True when the next SOL price fetch is due. Failed attempts count, so a dead source is retried
+/// every `interval` instead of on every call.
+pub fn sol_price_due(last_attempt: Option<SystemTime>, now: SystemTime, interval: Duration) -> bool {
+    last_attempt.is_none_or(|last| now.duration_since(last).unwrap_or_default() >= interval)
+}
+
+#[derive(Debug, Default)]
+struct SolPriceState {
+    price_usd: Option<f64>, // Last fetched price
+    last_attempt: Option<SystemTime>,
+    refreshing: bool,       // A fetch is in flight; other callers keep the current price
+}
+
+/// SOL/USD price shared by the profit monitor and the position monitor. `refresh` does the network
+/// calls (and retry sleeps) without holding any lock, so callers run it before taking the
+/// position book; `current` is a cheap read for code that already holds it.
+#[derive(Debug, Clone)]
+pub struct SolPriceFeed {
+    client: reqwest::Client,
+    retries: u32,
+    interval: Duration,
+    fallback_usd: f64,
+    state: Arc<std::sync::Mutex<SolPriceState>>,
+}
+
+impl SolPriceFeed {
+    pub fn from_settings(settings: &BotSettings) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            retries: settings.monitoring.sol_price_retries,
+            interval: Duration::from_millis(settings.monitoring.sol_price_interval_ms),
+            fallback_usd: settings.monitoring.fallback_sol_price_usd,
+            state: Arc::new(std::sync::Mutex::new(SolPriceState::default())),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, SolPriceState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Last fetched SOL price, or FALLBACK_SOL_PRICE_USD when none was ever fetched
+    pub fn current(&self) -> f64 {
+        self.state().price_usd.unwrap_or(self.fallback_usd)
+    }
+
+    /// Fetch a new SOL price when SOL_PRICE_INTERVAL_MS passed since the last attempt (successful
+    /// or not). On failure the last price is kept; with none yet, the fallback is logged loudly.
+    pub async fn refresh(&self) {
+        {
+            let mut state = self.state();
+            if state.refreshing || !sol_price_due(state.last_attempt, SystemTime::now(), self.interval) {
+                return;
+            }
+            state.refreshing = true;
+        }
+        let fetched = fetch_sol_price_with(self.retries, SOL_PRICE_RETRY_DELAY, |source| self.fetch_json(source)).await;
+
+        let mut state = self.state();
+        state.refreshing = false;
+        state.last_attempt = Some(SystemTime::now());
+        match (fetched, state.price_usd) {
+            (Some(price), _) => state.price_usd = Some(price),
+            (None, Some(last)) => println!("⚠️  Every SOL price source failed - keeping the last price ${:.2}", last),
+            (None, None) => println!("🚨 NO SOL PRICE - CoinGecko and Jupiter both failed and none was ever fetched. \
+                Using FALLBACK_SOL_PRICE_USD=${:.2}; SOL-denominated P&L may be badly wrong", self.fallback_usd),
+        }
+    }
+
+    /// GET a price API; None on a network error, a non-2xx status or a body that isn't JSON
+    async fn fetch_json(&self, source: SolPriceSource) -> Option<serde_json::Value> {
+        let url = match source {
+            SolPriceSource::CoinGecko => COINGECKO_SOL_PRICE_URL,
+            SolPriceSource::Jupiter => JUPITER_SOL_PRICE_URL,
+        };
+        match self.client.get(url).send().await {
+            Ok(response) if response.status().is_success() => response.json().await.ok(),
+            Ok(response) => {
+                println!("⚠️  {} returned {}", url, response.status());
+                None
+            }
+            Err(e) => {
+                println!("⚠️  {} failed: {}", url, e);
+                None
+            }
+        }
+    }
 }
 
 pub struct ProfitMonitor {
@@ -70,17 +240,20 @@ pub struct ProfitMonitor {
     alert_history: Vec<ProfitAlert>,
     portfolio_history: Vec<PortfolioSummary>,
     client: reqwest::Client,
-    sol_price_usd: f64,
-    last_sol_price_update: SystemTime,
+    birdeye: Option<BirdeyeClient>, // Price fallback when DexScreener has nothing
+    sol_price: SolPriceFeed,
     last_telegram_update: SystemTime,
     last_portfolio_summary: SystemTime,
     pub is_monitoring: bool,
     last_price_update: SystemTime,
+    pricing_stale: bool, // Stale-pricing alert sent and not yet recovered
 }
 
 impl ProfitMonitor {
     pub fn new(settings: BotSettings, telegram: TelegramNotifier) -> Self {
         Self {
+            birdeye: BirdeyeClient::from_settings(&settings),
+            sol_price: SolPriceFeed::from_settings(&settings),
             settings,
             telegram,
             profit_data: HashMap::new(),
@@ -88,12 +261,11 @@ impl ProfitMonitor {
             alert_history: Vec::new(),
             portfolio_history: Vec::new(),
             client: reqwest::Client::new(),
-            sol_price_usd: 0.0,
-            last_sol_price_update: SystemTime::UNIX_EPOCH,
             last_telegram_update: SystemTime::now(),
             last_portfolio_summary: SystemTime::now(),
             is_monitoring: false,
             last_price_update: SystemTime::now(),
+            pricing_stale: false,
         }
     }
 
@@ -128,6 +300,7 @@ impl ProfitMonitor {
                 println!("⚠️ Error updating prices: {}", e);
             }
             self.last_price_update = now;
+            self.check_pricing_staleness(now).await;
         }
         
         // Send Telegram updates every 5 minutes
@@ -153,89 +326,188 @@ impl ProfitMonitor {
     pub async fn add_position(&mut self, position: &TokenPosition) -> Result<()> {
         println!("📊 Adding position to profit monitor: {}", position.token_address);
 
-        // Check if position already exists to avoid duplicates
-        if self.profit_data.contains_key(&position.token_address) {
+        if let Some(reason) = untrackable_position_reason(position, self.settings.trading.min_position_tokens) {
+            return Err(anyhow!("Not monitoring {}: {}", position.token_address, reason));
+        }
+        // A repeat buy of a monitored token grows the existing entry
+        if let Some(data) = self.profit_data.get_mut(&position.token_address) {
+            grow_position(data, position.estimated_tokens, position.sol_amount);
             return Ok(());
         }
 
-        // For simulated/test tokens, use simulated price data
-        let (current_price_usd, symbol) = if position.token_address.starts_with("Sample-") ||
-                                             position.token_address.len() < 32 || // Real Solana addresses are 32+ chars
-                                             position.token_address.contains("Sample") {
-            // Use entry price as current price for simulation
-            let sol_price = self.get_sol_price().await.unwrap_or(150.0); // Fallback SOL price
-            let simulated_price = position.entry_price * sol_price;
-            (simulated_price, "SIM-TOKEN".to_string())
-        } else {
-            // Try to get real price data
-            match self.get_token_price(&position.token_address).await {
-                Ok(price_data) => price_data,
-                Err(_) => {
-                    // Fallback for tokens without price data
-                    let sol_price = self.get_sol_price().await.unwrap_or(150.0);
-                    let fallback_price = position.entry_price * sol_price;
-                    (fallback_price, "UNKNOWN".to_string())
+        let (current_price_usd, symbol) = match position.kind {
+            PositionKind::Simulated => {
+                // Use entry price as current price for simulation
+                let sol_price = self.sol_price();
+                let simulated_price = position.entry_price * sol_price;
+                (simulated_price, "SIM-TOKEN".to_string())
+            }
+            PositionKind::Real | PositionKind::Paper => {
+                // Try to get real price data
+                match self.get_token_price(&position.token_address).await {
+                    Ok(price_data) => price_data,
+                    Err(_) => {
+                        // Fallback for tokens without price data
+                        let sol_price = self.sol_price();
+                        let fallback_price = position.entry_price * sol_price;
+                        (fallback_price, "UNKNOWN".to_string())
+                    }
                 }
             }
         };
         
-        let sol_price = self.get_sol_price().await.unwrap_or(150.0);
+        let sol_price = self.sol_price();
         
         let entry_price_usd = position.entry_price * sol_price;
-        let current_value_sol = (position.estimated_tokens as f64 * current_price_usd) / sol_price;
+        let current_value_sol = usd_to_sol(position.estimated_tokens as f64 * current_price_usd, sol_price);
         
         let profit_data = ProfitData {
             token_address: position.token_address.clone(),
+            kind: position.kind,
             symbol,
             current_price_usd,
             entry_price_usd,
             current_value_sol,
             entry_value_sol: position.sol_amount,
             pnl_sol: current_value_sol - position.sol_amount,
-            pnl_percentage: ((current_value_sol - position.sol_amount) / position.sol_amount) * 100.0,
-            pnl_usd: (current_value_sol - position.sol_amount) * sol_price,
+            pnl_percentage: safe_ratio(current_value_sol - position.sol_amount, position.sol_amount) * 100.0,
+            pnl_usd: sol_to_usd(current_value_sol - position.sol_amount, sol_price),
             tokens_held: position.estimated_tokens,
             last_updated: SystemTime::now(),
             highest_value: current_value_sol.max(position.sol_amount),
             lowest_value: current_value_sol.min(position.sol_amount),
+            trailing_armed: false,
             time_held: SystemTime::now().duration_since(position.purchase_time).unwrap_or_default(),
+            peak_price_usd: current_price_usd,
+            recent_prices: vec![current_price_usd],
         };
 
         self.profit_data.insert(position.token_address.clone(), profit_data);
         Ok(())
     }
 
+    /// Positions whose armed trailing stop has been hit, with their decline from peak (%)
+    pub fn trailing_stop_hits(&self, trailing_stop_percent: f64, rungs: &[TrailRung]) -> Vec<(String, f64)> {
+        self.profit_data.iter()
+            .filter_map(|(token_address, data)| {
+                trailing_stop_decline(data, trailing_stop_percent, rungs).map(|decline| (token_address.clone(), decline))
+            })
+            .collect()
+    }
+
+    /// Positions in profit whose price made a new high and then `lower_updates` consecutive lower
+    /// updates, with their drop from that peak (%)
+    pub fn momentum_stall_hits(&self, lower_updates: usize) -> Vec<(String, f64)> {
+        self.profit_data.iter()
+            .filter(|(_, data)| data.pnl_percentage > 0.0)
+            .filter(|(_, data)| momentum_stalled(&data.recent_prices, data.peak_price_usd, lower_updates))
+            .map(|(token_address, data)| {
+                let drop = safe_ratio(data.peak_price_usd - data.current_price_usd, data.peak_price_usd) * 100.0;
+                (token_address.clone(), drop)
+            })
+            .collect()
+    }
+
+    /// Shrink a position after a partial sell of `tokens_sold` that carried `cost_sol` of its cost
+    /// basis. The price history restarts so the next scale-out needs a fresh high.
+    pub fn reduce_position(&mut self, token_address: &str, tokens_sold: u64, cost_sol: f64) {
+        let Some(data) = self.profit_data.get_mut(token_address) else {
+            return;
+        };
+        let remaining = safe_ratio(data.tokens_held.saturating_sub(tokens_sold) as f64, data.tokens_held as f64);
+        data.tokens_held = data.tokens_held.saturating_sub(tokens_sold);
+        data.entry_value_sol = (data.entry_value_sol - cost_sol).max(0.0);
+        data.current_value_sol *= remaining;
+        data.highest_value *= remaining;
+        data.lowest_value *= remaining;
+        data.pnl_sol = data.current_value_sol - data.entry_value_sol;
+        data.pnl_percentage = safe_ratio(data.pnl_sol, data.entry_value_sol) * 100.0;
+        data.recent_prices.clear();
+    }
+
     /// Remove a position from monitoring (when sold)
     pub fn remove_position(&mut self, token_address: &str) -> Option<ProfitData> {
         self.profit_data.remove(token_address)
     }
 
+    /// Portfolio-wide pricing watchdog: one alert when most positions stop getting fresh prices,
+    /// one recovery notice when they come back
+    async fn check_pricing_staleness(&mut self, now: SystemTime) {
+        let max_age_secs = self.settings.monitoring.stale_pricing_alert_secs;
+        if max_age_secs == 0 {
+            return;
+        }
+        let last_updated: Vec<SystemTime> = self.profit_data.values()
+            .filter(|data| data.kind.market_priced())
+            .map(|data| data.last_updated)
+            .collect();
+        let stale = portfolio_pricing_stale(&last_updated, now, Duration::from_secs(max_age_secs),
+            self.settings.monitoring.stale_pricing_alert_fraction);
+        if stale == self.pricing_stale {
+            return;
+        }
+        self.pricing_stale = stale;
+
+        let message = if stale {
+            format!("🧊 PORTFOLIO PRICING STALE\n{} positions have had no fresh price for over {}s - P&L is frozen and unreliable until the price source recovers",
+                last_updated.len(), max_age_secs)
+        } else {
+            "✅ PORTFOLIO PRICING RECOVERED\nPositions are getting fresh prices again".to_string()
+        };
+        println!("{}", message.replace('\n', " - "));
+        if self.settings.telegram.notifications_enabled {
+            if let Err(e) = self.telegram.send_message(&message).await {
+                println!("⚠️  Telegram notification failed: {}", e);
+            }
+        }
+    }
+
     /// Update prices for all monitored positions
     async fn update_all_prices(&mut self) -> Result<()> {
-        let sol_price = self.get_sol_price().await?;
+        // Sold and churned-through tokens would otherwise stay cached forever
+        evict_stale_prices(
+            &mut self.price_cache,
+            SystemTime::now(),
+            Duration::from_secs(self.settings.monitoring.price_cache_ttl_secs),
+        );
+        
+        let sol_price = self.sol_price();
         
-        // Collect token addresses to avoid borrow checker issues
-        let token_addresses: Vec<String> = self.profit_data.keys().cloned().collect();
+        // Collect token addresses to avoid borrow checker issues (simulated positions have no market price)
+        let token_addresses: Vec<String> = self.profit_data.values()
+            .filter(|data| data.kind.market_priced())
+            .map(|data| data.token_address.clone())
+            .collect();
         
         for token_address in token_addresses {
             // Get token price first
             match self.get_token_price(&token_address).await {
-                Ok((current_price_usd, _)) => {
+                Ok((current_price_usd, source)) => {
                     // Now update the profit data
                     if let Some(profit_data) = self.profit_data.get_mut(&token_address) {
-                        let current_value_sol = (profit_data.tokens_held as f64 * current_price_usd) / sol_price;
+                        let current_value_sol = usd_to_sol(profit_data.tokens_held as f64 * current_price_usd, sol_price);
                         
                         // Update profit data
                         profit_data.current_price_usd = current_price_usd;
                         profit_data.current_value_sol = current_value_sol;
                         profit_data.pnl_sol = current_value_sol - profit_data.entry_value_sol;
-                        profit_data.pnl_percentage = (profit_data.pnl_sol / profit_data.entry_value_sol) * 100.0;
-                        profit_data.pnl_usd = profit_data.pnl_sol * sol_price;
-                        profit_data.last_updated = SystemTime::now();
+                        profit_data.pnl_percentage = safe_ratio(profit_data.pnl_sol, profit_data.entry_value_sol) * 100.0;
+                        profit_data.pnl_usd = sol_to_usd(profit_data.pnl_sol, sol_price);
+                        // A stale cached fallback keeps P&L moving but isn't a fresh price
+                        if source != "STALE" {
+                            profit_data.last_updated = SystemTime::now();
+                        }
                         
                         // Update high/low tracking
                         profit_data.highest_value = profit_data.highest_value.max(current_value_sol);
                         profit_data.lowest_value = profit_data.lowest_value.min(current_value_sol);
+                        profit_data.peak_price_usd = profit_data.peak_price_usd.max(current_price_usd);
+                        profit_data.recent_prices.push(current_price_usd);
+                        keep_last(&mut profit_data.recent_prices, self.settings.trading.momentum_lower_updates as usize + 1);
+                        
+                        if arm_trailing_stop(profit_data, self.settings.trading.trailing_activation_percent) {
+                            println!("📈 Trailing stop armed for {} at +{:.1}%", token_address, profit_data.pnl_percentage);
+                        }
                     }
                 }
                 Err(e) => {
@@ -288,6 +560,17 @@ impl ProfitMonitor {
             }
         }
         
+        // Birdeye fallback (premium, optional)
+        if let Some(birdeye) = &self.birdeye {
+            match birdeye.token_price(token_address).await {
+                Ok(price_usd) => {
+                    self.price_cache.insert(token_address.to_string(), (price_usd, SystemTime::now()));
+                    return Ok((price_usd, "BIRDEYE".to_string()));
+                }
+                Err(e) => println!("⚠️ Birdeye price fallback failed for {}: {}", token_address, e),
+            }
+        }
+        
         // Return cached value if API fails
         if let Some((cached_price, _)) = self.price_cache.get(token_address) {
             return Ok((*cached_price, "STALE".to_string()));
@@ -296,46 +579,14 @@ impl ProfitMonitor {
         Err(anyhow::anyhow!("Failed to get price for {}", token_address))
     }
 
-    /// Get current SOL price in USD
-    async fn get_sol_price(&mut self) -> Result<f64> {
-        // Cache SOL price for 5 minutes
-        if self.last_sol_price_update.elapsed().unwrap_or_default() < Duration::from_secs(300) && self.sol_price_usd > 0.0 {
-            return Ok(self.sol_price_usd);
-        }
+    /// SOL price for valuing positions; fetched by the feed outside the position book lock
+    fn sol_price(&self) -> f64 {
+        self.sol_price.current()
+    }
 
-        let url = "https://api.coingecko.com/api/v3/simple/price?ids=solana&vs_currencies=usd";
-        
-        match self.client.get(url).send().await {
-            Ok(response) => {
-                if let Ok(data) = response.json::<serde_json::Value>().await {
-                    if let Some(sol_price) = data["solana"]["usd"].as_f64() {
-                        self.sol_price_usd = sol_price;
-                        self.last_sol_price_update = SystemTime::now();
-                        return Ok(sol_price);
-                    }
-                }
-            }
-            Err(_) => {
-                // Fallback: use Jupiter price API
-                if let Ok(response) = self.client.get("https://price.jup.ag/v6/price?ids=SOL").send().await {
-                    if let Ok(data) = response.json::<serde_json::Value>().await {
-                        if let Some(sol_price) = data["data"]["SOL"]["price"].as_f64() {
-                            self.sol_price_usd = sol_price;
-                            self.last_sol_price_update = SystemTime::now();
-                            return Ok(sol_price);
-                        }
-                    }
-                }
-            }
-        }
-        
-        // Use cached value if available
-        if self.sol_price_usd > 0.0 {
-            return Ok(self.sol_price_usd);
-        }
-        
-        // Fallback to estimated value
-        Ok(150.0) // Rough SOL price fallback
+    /// Shared handle to the SOL price this monitor values positions with
+    pub fn sol_price_feed(&self) -> SolPriceFeed {
+        self.sol_price.clone()
     }
 
     /// Check for profit/loss alerts and send notifications
@@ -354,10 +605,11 @@ impl ProfitMonitor {
                 alerts_to_send.push((token_address.clone(), AlertType::StopLoss(profit_data.pnl_percentage), profit_data.clone()));
             }
             
-            // Check trailing stop
-            let decline_from_high = ((profit_data.highest_value - profit_data.current_value_sol) / profit_data.highest_value) * 100.0;
-            if decline_from_high >= self.settings.trading.trailing_stop_percent {
-                alerts_to_send.push((token_address.clone(), AlertType::TrailingStop(decline_from_high), profit_data.clone()));
+            // Check trailing stop (only once armed)
+            if self.settings.trading.trailing_stop_enabled && self.settings.trading.exit_mode == "trailing" {
+                if let Some(decline_from_high) = trailing_stop_decline(profit_data, self.settings.trading.trailing_stop_percent, &self.settings.trading.trailing_stop_rungs) {
+                    alerts_to_send.push((token_address.clone(), AlertType::TrailingStop(decline_from_high), profit_data.clone()));
+                }
             }
             
             // Check time-based alerts (every 30 minutes)
@@ -389,7 +641,25 @@ impl ProfitMonitor {
             AlertType::LiquidityChange => "💧",
         };
 
-        let message = match alert_type {
+        let alert_name = match &alert_type {
+            AlertType::ProfitTarget(_) => "PROFIT TARGET HIT",
+            AlertType::StopLoss(_) => "STOP LOSS TRIGGERED",
+            AlertType::TrailingStop(_) => "TRAILING STOP",
+            AlertType::TimeAlert(_) => "TIME UPDATE",
+            AlertType::VolumeSpike => "VOLUME SPIKE",
+            AlertType::LiquidityChange => "LIQUIDITY CHANGE",
+        };
+        let custom = self.telegram.render(TemplateKind::Profit, &[
+            ("mint", token_address.to_string()),
+            ("name", profit_data.symbol.clone()),
+            ("sol_amount", format!("{:.4}", profit_data.current_value_sol)),
+            ("pnl", format!("{:.4}", profit_data.pnl_sol)),
+            ("pnl_pct", format!("{:.2}", profit_data.pnl_percentage)),
+            ("time", chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string()),
+            ("alert", alert_name.to_string()),
+        ]);
+
+        let message = custom.unwrap_or_else(|| match alert_type {
             AlertType::ProfitTarget(pct) => {
                 format!("🎯 PROFIT TARGET HIT!\n💎 Token: `{}`\n💰 P&L: {:.4} SOL ({:.2}%)\n💵 USD Value: ${:.2}\n⏰ Held: {}",
                     profit_data.symbol,
@@ -427,7 +697,7 @@ impl ProfitMonitor {
                 )
             }
             _ => format!("{} Alert for {}", emoji, profit_data.symbol)
-        };
+        });
 
         self.telegram.send_message(&message).await?;
         
@@ -439,6 +709,7 @@ impl ProfitMonitor {
             value_sol: profit_data.current_value_sol,
             timestamp: SystemTime::now(),
         });
+        keep_last(&mut self.alert_history, self.settings.monitoring.max_alert_history);
         
         Ok(())
     }
@@ -462,6 +733,9 @@ impl ProfitMonitor {
             summary.winning_positions,
             summary.losing_positions
         );
+        if !summary.pricing_reliable {
+            message.push_str("🧊 Prices are stale - P&L above is unreliable\n\n");
+        }
 
         // Add top 3 performers
         let mut sorted_positions: Vec<_> = self.profit_data.values().collect();
@@ -489,13 +763,21 @@ impl ProfitMonitor {
         let summary = self.calculate_portfolio_summary();
         self.portfolio_history.push(summary.clone());
         
-        // Keep only last 24 hours of history (96 entries at 15-min intervals)
-        if self.portfolio_history.len() > 96 {
-            self.portfolio_history.remove(0);
+        // Persist every snapshot so the equity curve survives restarts
+        let snapshot = PortfolioSnapshot {
+            timestamp_unix_secs: unix_secs(SystemTime::now()),
+            summary: summary.clone(),
+        };
+        if let Err(e) = portfolio_history::append_snapshot(&self.settings.monitoring.portfolio_history_path, &snapshot) {
+            println!("⚠️  Failed to persist portfolio snapshot: {}", e);
         }
         
-        println!("📊 Portfolio Summary: {:.4} SOL total value, {:.2}% P&L", 
-            summary.current_value_sol, summary.total_pnl_percentage);
+        // Keep only last 24 hours of history (96 entries at 15-min intervals)
+        keep_last(&mut self.portfolio_history, 96);
+        
+        println!("📊 Portfolio Summary: {:.4} SOL total value, {:.2}% P&L{}", 
+            summary.current_value_sol, summary.total_pnl_percentage,
+            if summary.pricing_reliable { "" } else { " (⚠️ stale prices - unreliable)" });
         
         Ok(())
     }
@@ -516,6 +798,7 @@ impl ProfitMonitor {
                 worst_performer: None,
                 daily_high: 0.0,
                 daily_low: 0.0,
+                pricing_reliable: true,
             };
         }
 
@@ -549,6 +832,7 @@ impl ProfitMonitor {
             worst_performer,
             daily_high: current_value, // Simplified - could track actual daily high/low
             daily_low: current_value,
+            pricing_reliable: !self.pricing_stale,
         }
     }
 
@@ -650,7 +934,104 @@ impl ProfitMonitor {
 }
 
 // Helper functions
-fn format_duration(duration: Duration) -> String {
+/// `numerator / denominator`, or 0.0 when the denominator is zero or the result isn't finite,
+/// so one bad quote or price can't turn the portfolio totals into NaN/inf
+pub fn safe_ratio(numerator: f64, denominator: f64) -> f64 {
+    let ratio = numerator / denominator;
+    if denominator == 0.0 || !ratio.is_finite() { 0.0 } else { ratio }
+}
+
+/// Fold a repeat buy of `tokens_added` costing `cost_sol` into `data`, valued at the current price
+fn grow_position(data: &mut ProfitData, tokens_added: u64, cost_sol: f64) {
+    let scale = safe_ratio(data.tokens_held.saturating_add(tokens_added) as f64, data.tokens_held as f64);
+    data.tokens_held = data.tokens_held.saturating_add(tokens_added);
+    data.entry_value_sol += cost_sol;
+    data.current_value_sol *= scale;
+    data.highest_value = (data.highest_value * scale).max(data.entry_value_sol);
+    data.lowest_value = (data.lowest_value * scale).min(data.entry_value_sol);
+    data.pnl_sol = data.current_value_sol - data.entry_value_sol;
+    data.pnl_percentage = safe_ratio(data.pnl_sol, data.entry_value_sol) * 100.0;
+}
+
+/// Why a position can't be valued (too few tokens or a non-finite entry), None if it can
+pub fn untrackable_position_reason(position: &TokenPosition, min_tokens: u64) -> Option<String> {
+    if position.estimated_tokens < min_tokens {
+        return Some(format!("{} tokens received (min {})", position.estimated_tokens, min_tokens));
+    }
+    if !position.entry_price.is_finite() || position.sol_amount <= 0.0 {
+        return Some(format!("invalid entry ({} SOL at {} SOL/token)", position.sol_amount, position.entry_price));
+    }
+    None
+}
+
+/// Whether the last `lower_updates` prices each printed below the one before, starting from the
+/// all-time `peak_price` - momentum stalled after a new high
+pub fn momentum_stalled(recent_prices: &[f64], peak_price: f64, lower_updates: usize) -> bool {
+    if lower_updates == 0 || recent_prices.len() < lower_updates + 1 {
+        return false;
+    }
+    let window = &recent_prices[recent_prices.len() - lower_updates - 1..];
+    window[0] >= peak_price && window.windows(2).all(|pair| pair[1] < pair[0])
+}
+
+/// Arm the trailing stop once P&L reaches `activation_percent`; it then trails from the current
+/// value, not from the pre-activation high. True when this update armed it.
+fn arm_trailing_stop(profit_data: &mut ProfitData, activation_percent: f64) -> bool {
+    if profit_data.trailing_armed || profit_data.pnl_percentage < activation_percent {
+        return false;
+    }
+    profit_data.trailing_armed = true;
+    profit_data.highest_value = profit_data.current_value_sol;
+    true
+}
+
+/// Decline from the peak (in %) when an armed trailing stop has been hit, None otherwise. The
+/// trail is `trailing_stop_percent`, tightened by whichever `rungs` the peak gain has passed.
+fn trailing_stop_decline(profit_data: &ProfitData, trailing_stop_percent: f64, rungs: &[TrailRung]) -> Option<f64> {
+    if !profit_data.trailing_armed || profit_data.highest_value <= 0.0 {
+        return None;
+    }
+    let peak_gain_percent = safe_ratio(profit_data.highest_value - profit_data.entry_value_sol, profit_data.entry_value_sol) * 100.0;
+    let trail_percent = stepped_trail_percent(trailing_stop_percent, rungs, peak_gain_percent);
+    let decline_from_high = ((profit_data.highest_value - profit_data.current_value_sol) / profit_data.highest_value) * 100.0;
+    (decline_from_high >= trail_percent).then_some(decline_from_high)
+}
+
+/// SOL price from a CoinGecko simple/price body; None for error bodies (e.g. a 429's
+/// {"status":{"error_code":429}}) or a missing, zero or non-finite price
+pub fn coingecko_sol_price(body: &serde_json::Value) -> Option<f64> {
+    body["solana"]["usd"].as_f64().filter(|price| price.is_finite() && *price > 0.0)
+}
+
+/// SOL price from a Jupiter price API body, with the same checks
+pub fn jupiter_sol_price(body: &serde_json::Value) -> Option<f64> {
+    body["data"]["SOL"]["price"].as_f64().filter(|price| price.is_finite() && *price > 0.0)
+}
+
+/// True when more than `max_stale_fraction` of the positions' last fresh prices are older than `max_age`
+pub fn portfolio_pricing_stale(last_updated: &[SystemTime], now: SystemTime, max_age: Duration, max_stale_fraction: f64) -> bool {
+    if last_updated.is_empty() {
+        return false;
+    }
+    let stale = last_updated.iter()
+        .filter(|updated| now.duration_since(**updated).unwrap_or_default() > max_age)
+        .count();
+    stale as f64 / last_updated.len() as f64 > max_stale_fraction
+}
+
+/// Drop cached prices fetched more than `ttl` before `now`
+fn evict_stale_prices(price_cache: &mut HashMap<String, (f64, SystemTime)>, now: SystemTime, ttl: Duration) {
+    price_cache.retain(|_, (_, fetched_at)| now.duration_since(*fetched_at).unwrap_or_default() <= ttl);
+}
+
+/// Trim `items` to its newest `max` entries (oldest are at the front)
+fn keep_last<T>(items: &mut Vec<T>, max: usize) {
+    if items.len() > max {
+        items.drain(..items.len() - max);
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
     let total_secs = duration.as_secs();
     let hours = total_secs / 3600;
     let minutes = (total_secs % 3600) / 60;
@@ -668,4 +1049,398 @@ fn truncate_string(s: &str, max_len: usize) -> String {
     } else {
         format!("{}...", &s[..max_len.saturating_sub(3)])
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A position bought for 1 SOL, currently worth `value_sol`
+    fn profit_data(value_sol: f64) -> ProfitData {
+        ProfitData {
+            token_address: "TokenMint111".to_string(),
+            kind: PositionKind::Real,
+            symbol: "TEST".to_string(),
+            current_price_usd: value_sol,
+            entry_price_usd: 1.0,
+            current_value_sol: value_sol,
+            entry_value_sol: 1.0,
+            pnl_sol: value_sol - 1.0,
+            pnl_percentage: (value_sol - 1.0) * 100.0,
+            pnl_usd: 0.0,
+            tokens_held: 1_000,
+            last_updated: SystemTime::UNIX_EPOCH,
+            highest_value: value_sol.max(1.0),
+            lowest_value: value_sol.min(1.0),
+            trailing_armed: false,
+            time_held: Duration::ZERO,
+            peak_price_usd: value_sol,
+            recent_prices: vec![value_sol],
+        }
+    }
+
+    /// Apply one price update the way update_all_prices does
+    fn tick(data: &mut ProfitData, value_sol: f64, activation_percent: f64) {
+        data.current_value_sol = value_sol;
+        data.pnl_sol = value_sol - data.entry_value_sol;
+        data.pnl_percentage = safe_ratio(data.pnl_sol, data.entry_value_sol) * 100.0;
+        data.highest_value = data.highest_value.max(value_sol);
+        arm_trailing_stop(data, activation_percent);
+    }
+
+    #[test]
+    fn trailing_stop_stays_off_until_the_activation_gain() {
+        let mut data = profit_data(1.0);
+        // Up 10%, then back down 25%: a stop trailing from entry would have fired
+        tick(&mut data, 1.1, 20.0);
+        tick(&mut data, 0.8, 20.0);
+        assert!(!data.trailing_armed);
+        assert_eq!(trailing_stop_decline(&data, 15.0, &[]), None);
+    }
+
+    #[test]
+    fn trailing_stop_arms_then_trails_from_the_peak() {
+        let mut data = profit_data(1.0);
+        tick(&mut data, 1.25, 20.0);
+        assert!(data.trailing_armed);
+        assert_eq!(data.highest_value, 1.25);
+
+        tick(&mut data, 1.6, 20.0);
+        tick(&mut data, 1.45, 20.0);
+        // 9.4% off the 1.6 peak: inside a 15% trail
+        assert_eq!(trailing_stop_decline(&data, 15.0, &[]), None);
+
+        tick(&mut data, 1.3, 20.0);
+        let decline = trailing_stop_decline(&data, 15.0, &[]).unwrap();
+        assert!((decline - 18.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn arming_resets_the_pre_activation_high() {
+        let mut data = profit_data(1.0);
+        data.highest_value = 1.5; // Earlier spike, before arming was possible
+        tick(&mut data, 1.25, 20.0);
+        assert!(data.trailing_armed);
+        assert_eq!(data.highest_value, 1.25);
+        assert_eq!(trailing_stop_decline(&data, 15.0, &[]), None);
+    }
+
+    #[test]
+    fn arming_happens_once() {
+        let mut data = profit_data(1.3);
+        assert!(arm_trailing_stop(&mut data, 20.0));
+        data.highest_value = 1.8;
+        assert!(!arm_trailing_stop(&mut data, 20.0));
+        assert_eq!(data.highest_value, 1.8);
+    }
+
+    #[test]
+    fn only_armed_positions_are_trailing_stop_hits() {
+        let mut monitor = ProfitMonitor::new(BotSettings::from_env().unwrap(), TelegramNotifier::new("", ""));
+        monitor.profit_data.insert("NeverArmed".to_string(), profit_data(1.0));
+        monitor.profit_data.insert("Armed".to_string(), profit_data(1.0));
+        // Both fall 25% from a high; only one got past the 20% activation gain first
+        for (token_address, path) in [("NeverArmed", [1.15, 0.86]), ("Armed", [1.4, 1.05])] {
+            for value in path {
+                tick(monitor.profit_data.get_mut(token_address).unwrap(), value, 20.0);
+            }
+        }
+        let hits = monitor.trailing_stop_hits(15.0, &[]);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0, "Armed");
+        assert!((hits[0].1 - 25.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rungs_tighten_the_trailing_stop_hits_the_monitor_sells_on() {
+        let mut monitor = ProfitMonitor::new(BotSettings::from_env().unwrap(), TelegramNotifier::new("", ""));
+        let rungs = parse_trail_rungs("100:15,300:8");
+        // One runner past each rung, each pulled back 10%-16% from its peak
+        for (token_address, path) in [("Modest", [1.6, 1.36]), ("Runner", [2.5, 2.1]), ("Parabolic", [5.0, 4.5])] {
+            let mut data = profit_data(1.0);
+            for value in path {
+                tick(&mut data, value, 20.0);
+            }
+            monitor.profit_data.insert(token_address.to_string(), data);
+        }
+
+        // The flat 30% trail holds all three
+        assert!(monitor.trailing_stop_hits(30.0, &[]).is_empty());
+
+        // With rungs, the +150% runner's 16% pullback passes its 15% trail and the +400% one's
+        // 10% passes its 8%; the +60% one is still on the 30% base
+        let mut hits = monitor.trailing_stop_hits(30.0, &rungs);
+        hits.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(hits.iter().map(|(token_address, _)| token_address.as_str()).collect::<Vec<_>>(), ["Parabolic", "Runner"]);
+        assert!((hits[0].1 - 10.0).abs() < 1e-9);
+        assert!((hits[1].1 - 16.0).abs() < 1e-9);
+    }
+
+    fn rung(profit_above_percent: f64, trail_percent: f64) -> TrailRung {
+        TrailRung { profit_above_percent, trail_percent }
+    }
+
+    #[test]
+    fn trail_rungs_parse_and_flag_bad_numbers() {
+        assert_eq!(parse_trail_rungs(""), Vec::new());
+        assert_eq!(parse_trail_rungs(" 100:15, 300:8 "), vec![rung(100.0, 15.0), rung(300.0, 8.0)]);
+        let bad = parse_trail_rungs("100:fifteen,300");
+        assert!(bad[0].trail_percent.is_nan());
+        assert!(bad[1].trail_percent.is_nan());
+    }
+
+    #[test]
+    fn stepped_trail_is_the_tightest_rung_passed() {
+        let rungs = [rung(300.0, 8.0), rung(100.0, 15.0)]; // Order doesn't matter
+        assert_eq!(stepped_trail_percent(30.0, &rungs, 40.0), 30.0);
+        assert_eq!(stepped_trail_percent(30.0, &rungs, 100.0), 30.0); // "Above" is strict
+        assert_eq!(stepped_trail_percent(30.0, &rungs, 150.0), 15.0);
+        assert_eq!(stepped_trail_percent(30.0, &rungs, 400.0), 8.0);
+        assert_eq!(stepped_trail_percent(10.0, &rungs, 150.0), 10.0); // A rung never loosens the base trail
+    }
+
+    #[test]
+    fn price_path_through_the_rungs_tightens_the_trail() {
+        let rungs = parse_trail_rungs("100:15,300:8");
+        let mut data = profit_data(1.0);
+
+        // +60%: armed, base 30% trail - a 25% pullback holds
+        tick(&mut data, 1.6, 20.0);
+        tick(&mut data, 1.2, 20.0);
+        assert_eq!(trailing_stop_decline(&data, 30.0, &rungs), None);
+
+        // +150% peak: the 15% rung - a 16% pullback now sells
+        tick(&mut data, 2.5, 20.0);
+        tick(&mut data, 2.2, 20.0);
+        assert_eq!(trailing_stop_decline(&data, 30.0, &rungs), None); // 12% off
+        tick(&mut data, 2.1, 20.0);
+        assert!((trailing_stop_decline(&data, 30.0, &rungs).unwrap() - 16.0).abs() < 1e-9);
+
+        // +400% peak: the 8% rung - a 10% pullback sells, and still does once the gain falls back
+        // under 300%, since the rung is keyed on the peak
+        tick(&mut data, 5.0, 20.0);
+        tick(&mut data, 4.65, 20.0);
+        assert_eq!(trailing_stop_decline(&data, 30.0, &rungs), None); // 7% off
+        tick(&mut data, 3.9, 20.0);
+        assert!((trailing_stop_decline(&data, 30.0, &rungs).unwrap() - 22.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn pricing_is_stale_once_too_many_positions_lack_a_fresh_price() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(10_000);
+        let fresh = now - Duration::from_secs(30);
+        let frozen = now - Duration::from_secs(600);
+        let max_age = Duration::from_secs(300);
+        assert!(!portfolio_pricing_stale(&[], now, max_age, 0.5));
+        assert!(!portfolio_pricing_stale(&[fresh, fresh, frozen], now, max_age, 0.5));
+        assert!(portfolio_pricing_stale(&[fresh, frozen, frozen], now, max_age, 0.5));
+    }
+
+    #[test]
+    fn repeat_buy_grows_cost_and_value() {
+        // 1 SOL of 1,000 tokens now worth 1.5 SOL; buy another 1,000 for 1.5 SOL
+        let mut data = profit_data(1.5);
+        grow_position(&mut data, 1_000, 1.5);
+        assert_eq!(data.tokens_held, 2_000);
+        assert!((data.entry_value_sol - 2.5).abs() < 1e-12);
+        assert!((data.current_value_sol - 3.0).abs() < 1e-12);
+        assert!((data.pnl_sol - 0.5).abs() < 1e-12);
+        assert!((data.pnl_percentage - 20.0).abs() < 1e-9);
+    }
+
+    /// Run the fetch chain against canned bodies, recording which sources were asked
+    async fn sol_price_chain(retries: u32, coingecko: Vec<Option<serde_json::Value>>, jupiter: Option<serde_json::Value>) -> (Option<f64>, Vec<SolPriceSource>) {
+        let mut coingecko = coingecko.into_iter();
+        let mut asked = Vec::new();
+        let price = fetch_sol_price_with(retries, Duration::ZERO, |source| {
+            asked.push(source);
+            let body = match source {
+                SolPriceSource::CoinGecko => coingecko.next().flatten(),
+                SolPriceSource::Jupiter => jupiter.clone(),
+            };
+            async move { body }
+        }).await;
+        (price, asked)
+    }
+
+    #[tokio::test]
+    async fn coingecko_price_is_used_when_it_answers() {
+        let (price, asked) = sol_price_chain(1, vec![Some(serde_json::json!({ "solana": { "usd": 180.5 } }))], None).await;
+        assert_eq!(price, Some(180.5));
+        assert_eq!(asked, [SolPriceSource::CoinGecko]);
+    }
+
+    #[tokio::test]
+    async fn coingecko_429_body_is_retried_then_falls_back_to_jupiter() {
+        let rate_limited = serde_json::json!({ "status": { "error_code": 429, "error_message": "You've exceeded the Rate Limit" } });
+        let (price, asked) = sol_price_chain(1, vec![Some(rate_limited.clone()), Some(rate_limited)],
+            Some(serde_json::json!({ "data": { "SOL": { "price": 181.0 } } }))).await;
+        assert_eq!(price, Some(181.0));
+        assert_eq!(asked, [SolPriceSource::CoinGecko, SolPriceSource::CoinGecko, SolPriceSource::Jupiter]);
+    }
+
+    #[tokio::test]
+    async fn empty_bodies_everywhere_give_no_price() {
+        let (price, asked) = sol_price_chain(0, vec![Some(serde_json::json!({}))], None).await;
+        assert_eq!(price, None);
+        assert_eq!(asked, [SolPriceSource::CoinGecko, SolPriceSource::Jupiter]);
+        assert_eq!(coingecko_sol_price(&serde_json::json!({ "solana": { "usd": 0.0 } })), None);
+    }
+
+    #[tokio::test]
+    async fn retry_recovers_a_coingecko_price() {
+        let (price, asked) = sol_price_chain(2, vec![None, Some(serde_json::json!({ "solana": { "usd": 179.0 } }))], None).await;
+        assert_eq!(price, Some(179.0));
+        assert_eq!(asked, [SolPriceSource::CoinGecko, SolPriceSource::CoinGecko]);
+    }
+
+    #[test]
+    fn failed_sol_price_attempts_wait_for_the_interval() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let interval = Duration::from_secs(300);
+        assert!(sol_price_due(None, now, interval));
+        assert!(!sol_price_due(Some(now - Duration::from_secs(10)), now, interval));
+        assert!(sol_price_due(Some(now - interval), now, interval));
+    }
+
+    #[test]
+    fn many_alerts_stay_within_the_history_cap() {
+        let mut history = Vec::new();
+        for alert in 0..1_000 {
+            history.push(alert);
+            keep_last(&mut history, 100);
+            assert!(history.len() <= 100);
+        }
+        assert_eq!(history.first(), Some(&900)); // The newest are kept
+        assert_eq!(history.last(), Some(&999));
+        keep_last(&mut history, 0);
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn churned_prices_are_evicted_after_the_ttl() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+        let ttl = Duration::from_secs(300);
+        let mut price_cache = HashMap::new();
+        // 1,000 tokens priced once each, one per second over the last 1,000 seconds
+        for age in 0..1_000u64 {
+            price_cache.insert(format!("mint{}", age), (0.001, now - Duration::from_secs(age)));
+        }
+        evict_stale_prices(&mut price_cache, now, ttl);
+        assert_eq!(price_cache.len(), 301); // Ages 0..=300
+        assert!(price_cache.contains_key("mint300"));
+        assert!(!price_cache.contains_key("mint301"));
+    }
+
+    fn simulated_position(token_address: &str, sol_amount: f64, tokens: u64) -> TokenPosition {
+        TokenPosition {
+            token_address: token_address.to_string(),
+            purchase_time: SystemTime::now(),
+            sol_amount,
+            estimated_tokens: tokens,
+            entry_price: safe_ratio(sol_amount, tokens as f64),
+            kind: PositionKind::Simulated,
+            trade_result: None,
+            max_hold: Duration::from_secs(1800),
+            timeout_extension: Duration::ZERO,
+            sell_probe: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn zero_token_fill_is_refused_and_the_summary_stays_finite() {
+        let mut settings = BotSettings::from_env().unwrap();
+        settings.trading.min_position_tokens = 1;
+        let mut monitor = ProfitMonitor::new(settings, TelegramNotifier::new("", ""));
+        monitor.add_position(&simulated_position("GoodMint", 0.5, 1_000_000)).await.unwrap();
+
+        let err = monitor.add_position(&simulated_position("ZeroMint", 0.5, 0)).await.unwrap_err();
+        assert_eq!(err.to_string(), "Not monitoring ZeroMint: 0 tokens received (min 1)");
+        assert!(monitor.get_profit_data("ZeroMint").is_none());
+
+        let summary = monitor.get_portfolio_summary();
+        assert_eq!(summary.active_positions, 1);
+        for value in [summary.total_invested_sol, summary.current_value_sol, summary.total_pnl_sol, summary.total_pnl_percentage, summary.total_pnl_usd] {
+            assert!(value.is_finite());
+        }
+    }
+
+    #[test]
+    fn zero_denominators_give_zero_not_nan() {
+        assert_eq!(safe_ratio(0.5, 0.0), 0.0);
+        assert_eq!(safe_ratio(0.0, 0.0), 0.0);
+        assert_eq!(safe_ratio(f64::INFINITY, 2.0), 0.0);
+        assert_eq!(safe_ratio(0.5, 2.0), 0.25);
+    }
+
+    #[test]
+    fn non_finite_entries_are_untrackable() {
+        let mut position = simulated_position("BadMint", 0.5, 1_000);
+        position.entry_price = f64::NAN;
+        assert!(untrackable_position_reason(&position, 1).unwrap().starts_with("invalid entry"));
+        assert!(untrackable_position_reason(&simulated_position("OkMint", 0.5, 1_000), 1).is_none());
+    }
+
+    /// `tick` plus the price history EXIT_MODE=momentum reads, priced at the SOL value
+    fn momentum_tick(data: &mut ProfitData, value_sol: f64, lower_updates: usize) {
+        tick(data, value_sol, 100.0);
+        data.current_price_usd = value_sol;
+        data.peak_price_usd = data.peak_price_usd.max(value_sol);
+        data.recent_prices.push(value_sol);
+        keep_last(&mut data.recent_prices, lower_updates + 1);
+    }
+
+    #[test]
+    fn scale_out_fires_after_a_peak_and_three_lower_updates() {
+        let mut monitor = ProfitMonitor::new(BotSettings::from_env().unwrap(), TelegramNotifier::new("", ""));
+        monitor.profit_data.insert("TokenMint111".to_string(), profit_data(1.0));
+        let mut fired_at = None;
+        for (update, value) in [1.2, 1.5, 1.8, 1.7, 1.6, 1.5, 1.4].into_iter().enumerate() {
+            momentum_tick(monitor.profit_data.get_mut("TokenMint111").unwrap(), value, 3);
+            let hits = monitor.momentum_stall_hits(3);
+            if fired_at.is_none() && !hits.is_empty() {
+                assert_eq!(hits[0].0, "TokenMint111");
+                assert!((hits[0].1 - 100.0 / 6.0).abs() < 1e-9); // 1.8 -> 1.5
+                fired_at = Some(update);
+            }
+        }
+        assert_eq!(fired_at, Some(5)); // The third update below the 1.8 high
+    }
+
+    #[test]
+    fn a_bounce_or_a_loss_does_not_scale_out() {
+        let mut data = profit_data(1.0);
+        for value in [1.8, 1.7, 1.75, 1.6, 1.5] {
+            momentum_tick(&mut data, value, 3);
+        }
+        // The 1.75 bounce broke the run, and 1.75 isn't a new high
+        assert!(!momentum_stalled(&data.recent_prices, data.peak_price_usd, 3));
+
+        let mut monitor = ProfitMonitor::new(BotSettings::from_env().unwrap(), TelegramNotifier::new("", ""));
+        monitor.profit_data.insert("TokenMint111".to_string(), profit_data(1.0));
+        for value in [0.9, 0.8, 0.7] {
+            momentum_tick(monitor.profit_data.get_mut("TokenMint111").unwrap(), value, 3);
+        }
+        // Stalled from the entry high, but not in profit
+        assert!(monitor.momentum_stall_hits(3).is_empty());
+    }
+
+    #[test]
+    fn next_scale_out_needs_a_fresh_high() {
+        let mut monitor = ProfitMonitor::new(BotSettings::from_env().unwrap(), TelegramNotifier::new("", ""));
+        monitor.profit_data.insert("TokenMint111".to_string(), profit_data(1.0));
+        for value in [2.0, 1.9, 1.8, 1.7] {
+            momentum_tick(monitor.profit_data.get_mut("TokenMint111").unwrap(), value, 3);
+        }
+        assert_eq!(monitor.momentum_stall_hits(3).len(), 1);
+        monitor.reduce_position("TokenMint111", 500, 0.5);
+        let data = monitor.get_profit_data("TokenMint111").unwrap();
+        assert_eq!((data.tokens_held, data.entry_value_sol), (500, 0.5));
+        assert!(data.recent_prices.is_empty());
+
+        // Still falling, but below the 2.0 high: no second scale-out
+        for value in [1.6, 1.5, 1.4, 1.3] {
+            momentum_tick(monitor.profit_data.get_mut("TokenMint111").unwrap(), value, 3);
+        }
+        assert!(monitor.momentum_stall_hits(3).is_empty());
+    }
+}
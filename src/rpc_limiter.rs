@@ -0,0 +1,89 @@
+// RPC Rate Limiter - token bucket shared by every component that talks to the RPC provider
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64, // Goes negative while callers are queued for future tokens
+    last_refill: Instant,
+    total_requests: u64,
+    throttled_requests: u64,
+    total_wait: Duration,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RpcUsage {
+    pub max_requests_per_second: u32,
+    pub total_requests: u64,
+    pub throttled_requests: u64,
+    pub total_wait_ms: u64,
+}
+
+/// Cheap to clone - clones share the same bucket
+#[derive(Debug, Clone)]
+pub struct RpcRateLimiter {
+    max_requests_per_second: u32, // 0 = unlimited
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+impl RpcRateLimiter {
+    pub fn new(max_requests_per_second: u32) -> Self {
+        Self {
+            max_requests_per_second,
+            bucket: Arc::new(Mutex::new(Bucket {
+                tokens: max_requests_per_second as f64,
+                last_refill: Instant::now(),
+                total_requests: 0,
+                throttled_requests: 0,
+                total_wait: Duration::ZERO,
+            })),
+        }
+    }
+
+    /// A limiter that only counts requests
+    pub fn unlimited() -> Self {
+        Self::new(0)
+    }
+
+    /// Wait for a request slot. Never fails - callers queue until the bucket refills.
+    pub async fn acquire(&self) {
+        let wait = {
+            let mut bucket = self.bucket.lock().unwrap();
+            bucket.total_requests += 1;
+
+            if self.max_requests_per_second == 0 {
+                return;
+            }
+
+            let rate = self.max_requests_per_second as f64;
+            let now = Instant::now();
+            let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * rate).min(rate);
+            bucket.last_refill = now;
+
+            // Reserve our token now so concurrent callers queue behind us
+            bucket.tokens -= 1.0;
+            if bucket.tokens >= 0.0 {
+                return;
+            }
+
+            let wait = Duration::from_secs_f64(-bucket.tokens / rate);
+            bucket.throttled_requests += 1;
+            bucket.total_wait += wait;
+            wait
+        };
+
+        tokio::time::sleep(wait).await;
+    }
+
+    pub fn usage(&self) -> RpcUsage {
+        let bucket = self.bucket.lock().unwrap();
+        RpcUsage {
+            max_requests_per_second: self.max_requests_per_second,
+            total_requests: bucket.total_requests,
+            throttled_requests: bucket.throttled_requests,
+            total_wait_ms: bucket.total_wait.as_millis() as u64,
+        }
+    }
+}
@@ -1,5 +1,10 @@
 // RugCheck API Integration for Token Security Analysis - DexScreener Based
 use serde::{Deserialize, Serialize};
+use base64::{engine::general_purpose, Engine as _};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use crate::rpc_limiter::RpcRateLimiter;
 
 // Auto-buy criteria configuration
 #[derive(Debug, Clone)]
@@ -8,7 +13,9 @@ pub struct AutoBuyCriteria {
     pub require_freeze_authority_revoked: bool,
     pub min_lp_burned_or_locked_percent: f64,
     pub min_lp_lock_months: u32,
-    pub max_tax_percent: f64,
+    pub max_buy_tax_percent: f64,
+    pub max_sell_tax_percent: f64,
+    pub max_tax_asymmetry_percent: f64, // How far sell tax may exceed buy tax (soft-rug guard)
     pub max_top10_holders_percent: f64,
     pub require_can_sell_test: bool,
 }
@@ -20,7 +27,9 @@ impl Default for AutoBuyCriteria {
             require_freeze_authority_revoked: true,
             min_lp_burned_or_locked_percent: 70.0,
             min_lp_lock_months: 6,
-            max_tax_percent: 3.0,
+            max_buy_tax_percent: 3.0,
+            max_sell_tax_percent: 3.0,
+            max_tax_asymmetry_percent: 2.0,
             max_top10_holders_percent: 30.0,
             require_can_sell_test: true,
         }
@@ -79,6 +88,7 @@ impl Default for RugCheckCriteria {
 
 impl RugCheckCriteria {
     pub fn from_env() -> AutoBuyCriteria {
+        let legacy_max_tax = legacy_max_tax_percent();
         AutoBuyCriteria {
             require_mint_authority_revoked: std::env::var("REQUIRE_MINT_AUTHORITY_REVOKED")
                 .unwrap_or_else(|_| "true".to_string())
@@ -96,10 +106,12 @@ impl RugCheckCriteria {
                 .unwrap_or_else(|_| "6".to_string())
                 .parse()
                 .unwrap_or(6),
-            max_tax_percent: std::env::var("MAX_TAX_PERCENT")
-                .unwrap_or_else(|_| "3.0".to_string())
+            max_buy_tax_percent: tax_limit(std::env::var("MAX_BUY_TAX_PERCENT").ok(), legacy_max_tax.clone()),
+            max_sell_tax_percent: tax_limit(std::env::var("MAX_SELL_TAX_PERCENT").ok(), legacy_max_tax),
+            max_tax_asymmetry_percent: std::env::var("MAX_TAX_ASYMMETRY_PERCENT")
+                .unwrap_or_else(|_| "2.0".to_string())
                 .parse()
-                .unwrap_or(3.0),
+                .unwrap_or(2.0),
             max_top10_holders_percent: std::env::var("MAX_TOP10_HOLDERS_PERCENT")
                 .unwrap_or_else(|_| "30.0".to_string())
                 .parse()
@@ -115,11 +127,14 @@ impl RugCheckCriteria {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RugCheckResponse {
     pub token_address: String,
-    pub score: u8,
+    pub score: u8, // Weighted 0-100; 100 only when every criterion passes
+    #[serde(default)]
+    pub score_breakdown: Vec<ScoreContribution>,
     pub risks: Vec<Risk>,
     pub liquidity_info: Option<LiquidityInfo>,
     pub mint_info: Option<MintInfo>,
     pub market_info: Option<MarketInfo>,
+    pub tax_info: Option<TaxInfo>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -143,6 +158,12 @@ pub struct MintInfo {
     pub freeze_authority: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxInfo {
+    pub buy_tax_percent: f64,
+    pub sell_tax_percent: f64,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MarketInfo {
     pub market_cap_usd: Option<f64>,
@@ -150,9 +171,165 @@ pub struct MarketInfo {
     pub holders_count: Option<u32>,
 }
 
+const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+const MAX_SIGNATURE_PAGES: usize = 5; // 5 x 1000 signatures before settling for a lower bound
+const SIGNATURE_PAGE_SIZE: usize = 1000;
+
 pub struct RugCheckClient {
     client: reqwest::Client,
     criteria: AutoBuyCriteria,
+    rpc_url: String,
+    fetch_max_attempts: u32,
+    fetch_retry_delay: std::time::Duration,
+    rpc_limiter: RpcRateLimiter,
+    production_mode: bool, // Never use random test scenarios, even for sample tokens
+    score_weights: ScoreWeights,
+}
+
+/// Relative weight of each of the six criteria in the 0-100 score
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoreWeights {
+    pub mint_authority: f64,
+    pub freeze_authority: f64,
+    pub lp: f64,
+    pub taxes: f64,
+    pub top_holders: f64,
+    pub can_sell: f64,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        Self { mint_authority: 20.0, freeze_authority: 20.0, lp: 20.0, taxes: 15.0, top_holders: 15.0, can_sell: 10.0 }
+    }
+}
+
+impl ScoreWeights {
+    /// Criterion names and weights, in evaluation order
+    fn named(&self) -> [(&'static str, f64); 6] {
+        [
+            ("Mint authority", self.mint_authority),
+            ("Freeze authority", self.freeze_authority),
+            ("LP burned/locked", self.lp),
+            ("Taxes", self.taxes),
+            ("Top-10 holders", self.top_holders),
+            ("Can-sell test", self.can_sell),
+        ]
+    }
+}
+
+/// Points one criterion added to the score, out of the most it could add
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreContribution {
+    pub criterion: String,
+    pub points: f64,
+    pub max_points: f64,
+}
+
+/// Each criterion's points out of 100: its share of the total weight times the credit it earned
+fn score_contributions(results: &[CriteriaResult], weights: &ScoreWeights) -> Vec<ScoreContribution> {
+    let total_weight: f64 = weights.named().iter().map(|(_, weight)| weight).sum();
+    weights.named().iter().zip(results)
+        .map(|((criterion, weight), result)| {
+            let max_points = if total_weight > 0.0 { weight / total_weight * 100.0 } else { 0.0 };
+            ScoreContribution {
+                criterion: criterion.to_string(),
+                points: max_points * result.credit.clamp(0.0, 1.0),
+                max_points,
+            }
+        })
+        .collect()
+}
+
+/// The 0-100 score. Rounded down so only a clean sweep reaches 100.
+pub fn weighted_security_score(contributions: &[ScoreContribution]) -> u8 {
+    let points: f64 = contributions.iter().map(|contribution| contribution.points).sum();
+    (points + 1e-9).floor().clamp(0.0, 100.0) as u8
+}
+
+/// Credit for a measured value that must stay at or under `limit` (1.0 within it, limit/value above)
+fn limit_credit(value: f64, limit: f64) -> f64 {
+    if value <= limit { 1.0 } else if value > 0.0 { (limit / value).max(0.0) } else { 0.0 }
+}
+
+/// Credit for a measured value that must reach `minimum` (1.0 at or above it, value/minimum below)
+fn minimum_credit(value: f64, minimum: f64) -> f64 {
+    if value >= minimum { 1.0 } else if minimum > 0.0 { (value / minimum).max(0.0) } else { 0.0 }
+}
+
+/// Whether a RugCheck score clears the configured bar. The score weights the six criteria (near
+/// misses earn partial credit) and is 100 only when all of them pass, so REQUIRE_ALL_CRITERIA
+/// demands a clean sweep while MIN_ACCEPTABLE_SCORE alone accepts "mostly safe" tokens.
+pub fn meets_security_bar(score: u8, min_acceptable_score: u8, require_all_criteria: bool) -> bool {
+    (!require_all_criteria || score == 100) && score >= min_acceptable_score
+}
+
+/// The bar in words, for logs and rejection messages
+pub fn security_bar_description(min_acceptable_score: u8, require_all_criteria: bool) -> String {
+    if require_all_criteria {
+        "all 6 criteria".to_string()
+    } else {
+        format!("score {}+", min_acceptable_score)
+    }
+}
+
+/// Whether a token with no market data may get a random test scenario instead of failing closed.
+/// Never true in production mode, and never true for a real mint.
+pub fn test_scenario_allowed(token_address: &str, production_mode: bool) -> bool {
+    !production_mode && is_sample_address(token_address)
+}
+
+/// Whether an address is one of our generated sample/simulation tokens rather than a real mint.
+/// Real Solana mints are 32+ base58 chars and never contain "Sample".
+pub fn is_sample_address(token_address: &str) -> bool {
+    token_address.len() < 32 || token_address.contains("Sample")
+}
+
+/// Boxed future returned by RugChecker's async methods (keeps the trait object-safe)
+pub type RugCheckFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, Box<dyn std::error::Error>>> + 'a>>;
+
+/// Security backend the scanner consults before buying. RugCheckClient is the default;
+/// a stub returning canned responses/reports makes the buy/reject flow deterministic.
+pub trait RugChecker {
+    fn check_token<'a>(&'a self, token_address: &'a str) -> RugCheckFuture<'a, RugCheckResponse>;
+
+    fn analyze_security_risks(&self, response: &RugCheckResponse) -> SecurityReport;
+
+    /// Holder count for the holder-distribution check
+    fn fetch_holder_count<'a>(&'a self, _token_address: &'a str) -> RugCheckFuture<'a, u32> {
+        Box::pin(async { Err("holder count not supported by this security backend".into()) })
+    }
+
+    /// Unix seconds of the mint's first transaction, for the token-age filter
+    fn fetch_token_creation_time<'a>(&'a self, _token_address: &'a str) -> RugCheckFuture<'a, u64> {
+        Box::pin(async { Err("token creation time not supported by this security backend".into()) })
+    }
+
+    /// The token's creator wallet and its history, for the creator reputation check
+    fn fetch_creator_profile<'a>(&'a self, _token_address: &'a str) -> RugCheckFuture<'a, CreatorProfile> {
+        Box::pin(async { Err("creator profile not supported by this security backend".into()) })
+    }
+}
+
+impl RugChecker for RugCheckClient {
+    fn check_token<'a>(&'a self, token_address: &'a str) -> RugCheckFuture<'a, RugCheckResponse> {
+        Box::pin(RugCheckClient::check_token(self, token_address))
+    }
+
+    fn analyze_security_risks(&self, response: &RugCheckResponse) -> SecurityReport {
+        RugCheckClient::analyze_security_risks(self, response)
+    }
+
+    fn fetch_holder_count<'a>(&'a self, token_address: &'a str) -> RugCheckFuture<'a, u32> {
+        Box::pin(RugCheckClient::fetch_holder_count(self, token_address))
+    }
+
+    fn fetch_token_creation_time<'a>(&'a self, token_address: &'a str) -> RugCheckFuture<'a, u64> {
+        Box::pin(RugCheckClient::fetch_token_creation_time(self, token_address))
+    }
+
+    fn fetch_creator_profile<'a>(&'a self, token_address: &'a str) -> RugCheckFuture<'a, CreatorProfile> {
+        Box::pin(RugCheckClient::fetch_creator_profile(self, token_address))
+    }
 }
 
 impl RugCheckClient {
@@ -164,6 +341,12 @@ impl RugCheckClient {
                 .build()
                 .unwrap_or_default(),
             criteria: AutoBuyCriteria::default(),
+            rpc_url: std::env::var("RPC_URL").unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string()),
+            fetch_max_attempts: 1,
+            fetch_retry_delay: std::time::Duration::from_millis(500),
+            rpc_limiter: RpcRateLimiter::unlimited(),
+            production_mode: false,
+            score_weights: ScoreWeights::default(),
         }
     }
 
@@ -175,9 +358,46 @@ impl RugCheckClient {
                 .build()
                 .unwrap_or_default(),
             criteria,
+            rpc_url: std::env::var("RPC_URL").unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string()),
+            fetch_max_attempts: 1,
+            fetch_retry_delay: std::time::Duration::from_millis(500),
+            rpc_limiter: RpcRateLimiter::unlimited(),
+            production_mode: false,
+            score_weights: ScoreWeights::default(),
         }
     }
 
+    /// How much each of the six criteria counts toward the 0-100 score
+    pub fn with_score_weights(mut self, score_weights: ScoreWeights) -> Self {
+        self.score_weights = score_weights;
+        self
+    }
+
+    /// Auto-buy criteria to evaluate against (RugCheckCriteria::from_env reads them from .env)
+    pub fn with_auto_buy_criteria(mut self, criteria: AutoBuyCriteria) -> Self {
+        self.criteria = criteria;
+        self
+    }
+
+    /// Disable the random test-scenario path entirely: tokens without real data are rejected
+    pub fn with_production_mode(mut self, production_mode: bool) -> Self {
+        self.production_mode = production_mode;
+        self
+    }
+
+    /// Retry the DexScreener fetch up to `max_attempts` times, doubling `retry_delay_ms` between attempts
+    pub fn with_fetch_retries(mut self, max_attempts: u32, retry_delay_ms: u64) -> Self {
+        self.fetch_max_attempts = max_attempts.max(1);
+        self.fetch_retry_delay = std::time::Duration::from_millis(retry_delay_ms);
+        self
+    }
+
+    /// Share an RPC rate limiter for the on-chain checks (mint account, holders)
+    pub fn with_rpc_limiter(mut self, rpc_limiter: RpcRateLimiter) -> Self {
+        self.rpc_limiter = rpc_limiter;
+        self
+    }
+
     /// Check token using DexScreener API and apply auto-buy criteria
     pub async fn check_token(&self, token_address: &str) -> Result<RugCheckResponse, Box<dyn std::error::Error>> {
         println!("🔍 Analyzing token with DexScreener: {}", token_address);
@@ -186,7 +406,7 @@ impl RugCheckClient {
         let token_data = self.fetch_dexscreener_data(token_address).await?;
         
         // Perform auto-buy criteria checks
-        let auto_buy_result = self.evaluate_auto_buy_criteria(&token_data).await?;
+        let auto_buy_result = self.evaluate_auto_buy_criteria(token_address, &token_data).await?;
         
         Ok(auto_buy_result)
     }
@@ -196,8 +416,29 @@ impl RugCheckClient {
         
         println!("🌐 Fetching DexScreener data: {}", url);
         
+        let mut delay = self.fetch_retry_delay;
+        let mut attempt = 1;
+        loop {
+            match self.try_fetch_dexscreener_data(&url).await {
+                Ok(data) => {
+                    println!("✅ DexScreener data received successfully");
+                    return Ok(data);
+                }
+                Err(e) if attempt < self.fetch_max_attempts => {
+                    println!("⚠️  DexScreener fetch attempt {}/{} failed: {} - retrying in {}ms",
+                        attempt, self.fetch_max_attempts, e, delay.as_millis());
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+    
+    async fn try_fetch_dexscreener_data(&self, url: &str) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
         let response = self.client
-            .get(&url)
+            .get(url)
             .header("Accept", "application/json")
             .send()
             .await?;
@@ -206,13 +447,10 @@ impl RugCheckClient {
             return Err(format!("DexScreener API failed with status: {}", response.status()).into());
         }
 
-        let data: serde_json::Value = response.json().await?;
-        println!("✅ DexScreener data received successfully");
-        
-        Ok(data)
+        Ok(response.json().await?)
     }
     
-    async fn evaluate_auto_buy_criteria(&self, data: &serde_json::Value) -> Result<RugCheckResponse, Box<dyn std::error::Error>> {
+    async fn evaluate_auto_buy_criteria(&self, token_address: &str, data: &serde_json::Value) -> Result<RugCheckResponse, Box<dyn std::error::Error>> {
         let mut criteria_results = Vec::new();
         let mut passed_criteria = 0;
         let total_criteria = 6;
@@ -221,10 +459,13 @@ impl RugCheckClient {
         let empty_vec = vec![];
         let pairs = data.get("pairs").and_then(|p| p.as_array()).unwrap_or(&empty_vec);
         
-        // For test tokens (when no pairs found), create realistic test scenarios
+        // Random test scenarios are only for our own sample tokens outside production - anything else fails closed
         if pairs.is_empty() {
-            println!("ℹ️  No DexScreener data found - generating test scenario");
-            return self.generate_test_scenario().await;
+            if test_scenario_allowed(token_address, self.production_mode) {
+                println!("ℹ️  No DexScreener data for sample token - generating test scenario");
+                return self.generate_test_scenario().await;
+            }
+            return Err(format!("No DexScreener pairs found for {} - refusing to evaluate", token_address).into());
         }
         
         let pair = &pairs[0]; // Use first (most liquid) pair
@@ -261,8 +502,9 @@ impl RugCheckClient {
         }
         criteria_results.push(lp_check);
         
-        // ✅ 4. CHECK TAXES: MAX 3% TAX
-        let tax_check = self.check_taxes(pair).await;
+        // ✅ 4. CHECK TAXES: BUY/SELL LIMITS + ASYMMETRY
+        let tax_info = self.extract_tax_info(pair);
+        let tax_check = self.check_taxes(tax_info.as_ref());
         if tax_check.passed {
             println!("   ✅ 4/6 Taxes: {}", tax_check.message);
             passed_criteria += 1;
@@ -291,15 +533,33 @@ impl RugCheckClient {
         }
         criteria_results.push(sell_test_check);
         
-        // Calculate final result
-        let all_criteria_passed = passed_criteria == total_criteria;
+        // 🚫 HARD GATE: TOKEN-2022 EXTENSIONS (not part of the 6-point score)
+        let extensions_check = self.check_token_extensions(token_address).await;
+        let extensions_passed = extensions_check.passed;
+        if extensions_passed {
+            println!("   ✅ Token program: {}", extensions_check.message);
+        } else {
+            println!("   ❌ Token program: {}", extensions_check.message);
+        }
+        criteria_results.push(extensions_check);
         
-        println!("📊 Auto-buy criteria result: {}/{} passed", passed_criteria, total_criteria);
+        // Calculate final result: the six criteria are weighted, the token-program gate zeroes the score
+        let all_criteria_passed = passed_criteria == total_criteria && extensions_passed;
+        let score_breakdown = score_contributions(&criteria_results[..total_criteria], &self.score_weights);
+        let score = match (extensions_passed, all_criteria_passed) {
+            (false, _) => 0,
+            (true, true) => weighted_security_score(&score_breakdown),
+            (true, false) => weighted_security_score(&score_breakdown).min(99), // A zero-weight failure still isn't a clean sweep
+        };
+        
+        println!("📊 Auto-buy criteria result: {}/{} passed, weighted score {}/100", passed_criteria, total_criteria, score);
         
         if all_criteria_passed {
             println!("🎉 ALL CRITERIA PASSED - AUTO-BUY APPROVED!");
+        } else if !extensions_passed {
+            println!("⚠️  Token program gate failed - score 0");
         } else {
-            println!("⚠️  Some criteria failed - skipping token");
+            println!("⚠️  Some criteria failed - partial score");
         }
         
         // Convert results to RugCheckResponse format
@@ -313,8 +573,6 @@ impl RugCheckClient {
             })
             .collect();
         
-        let score = if all_criteria_passed { 100 } else { 0 };
-        
         Ok(RugCheckResponse {
             token_address: pair.get("baseToken")
                 .and_then(|t| t.get("address"))
@@ -322,49 +580,18 @@ impl RugCheckClient {
                 .unwrap_or("unknown")
                 .to_string(),
             score,
+            score_breakdown,
             risks,
             liquidity_info: self.extract_liquidity_info(pair),
             mint_info: self.extract_mint_info(pair),
             market_info: self.extract_market_info(pair),
+            tax_info,
         })
     }
 
     /// Legacy method for compatibility - converts RugCheckResponse to SecurityReport
     pub fn analyze_security_risks(&self, response: &RugCheckResponse) -> SecurityReport {
-        let auto_buy_approved = response.score == 100;
-        
-        let critical_risks: Vec<String> = response.risks.iter()
-            .filter(|r| r.severity == "CRITICAL" || r.severity == "HIGH")
-            .map(|r| r.description.clone())
-            .collect();
-            
-        let warnings: Vec<String> = response.risks.iter()
-            .filter(|r| r.severity == "MEDIUM" || r.severity == "LOW")
-            .map(|r| r.description.clone())
-            .collect();
-            
-        let good_signs = if auto_buy_approved {
-            vec!["All auto-buy criteria passed".to_string()]
-        } else {
-            vec![]
-        };
-        
-        let recommendation = if auto_buy_approved {
-            "✅ AUTO-BUY APPROVED - All criteria passed".to_string()
-        } else {
-            "❌ AUTO-BUY REJECTED - Some criteria failed".to_string()
-        };
-        
-        SecurityReport {
-            score: response.score,
-            critical_risks,
-            warnings,
-            good_signs,
-            auto_buy_approved,
-            criteria_results: vec![],
-            recommendation,
-            criteria_used: RugCheckCriteria::default(),
-        }
+        security_report(response)
     }
 
     /// Generate realistic test scenarios for sample tokens
@@ -481,7 +708,7 @@ impl RugCheckClient {
         
         let total_criteria = 6;
         let all_criteria_passed = passed_criteria == total_criteria;
-        let score = if all_criteria_passed { 100 } else { 0 };
+        let score = (passed_criteria * 100 / total_criteria) as u8; // Scenarios weight the criteria equally
         
         println!("📊 Auto-buy criteria result: {}/{} passed", passed_criteria, total_criteria);
         
@@ -494,6 +721,7 @@ impl RugCheckClient {
         Ok(RugCheckResponse {
             token_address: "test_token".to_string(),
             score,
+            score_breakdown: Vec::new(),
             risks,
             liquidity_info: Some(LiquidityInfo {
                 total_liquidity_usd: Some(50000.0),
@@ -509,6 +737,10 @@ impl RugCheckClient {
                 volume_24h_usd: Some(25000.0),
                 holders_count: Some(150),
             }),
+            tax_info: Some(TaxInfo {
+                buy_tax_percent: 0.0,
+                sell_tax_percent: 0.0,
+            }),
         })
     }
 
@@ -525,11 +757,13 @@ impl RugCheckClient {
         if mint_authority_revoked {
             CriteriaResult {
                 passed: true,
+                credit: 1.0,
                 message: "Mint authority revoked ✅".to_string(),
             }
         } else {
             CriteriaResult {
                 passed: false,
+                credit: 0.0,
                 message: "Mint authority not revoked ❌".to_string(),
             }
         }
@@ -543,11 +777,13 @@ impl RugCheckClient {
         if freeze_authority_revoked {
             CriteriaResult {
                 passed: true,
+                credit: 1.0,
                 message: "Freeze authority revoked ✅".to_string(),
             }
         } else {
             CriteriaResult {
                 passed: false,
+                credit: 0.0,
                 message: "Freeze authority not revoked ❌".to_string(),
             }
         }
@@ -562,33 +798,26 @@ impl RugCheckClient {
         if lp_burned_percent >= self.criteria.min_lp_burned_or_locked_percent {
             CriteriaResult {
                 passed: true,
+                credit: 1.0,
                 message: format!("LP {}% burned/locked ✅", lp_burned_percent),
             }
         } else {
             CriteriaResult {
                 passed: false,
+                credit: minimum_credit(lp_burned_percent, self.criteria.min_lp_burned_or_locked_percent),
                 message: format!("LP only {}% burned/locked (need {}%) ❌", lp_burned_percent, self.criteria.min_lp_burned_or_locked_percent),
             }
         }
     }
     
-    async fn check_taxes(&self, _pair: &serde_json::Value) -> CriteriaResult {
-        // Check for trading taxes/fees
-        // This requires on-chain analysis or specific API calls
-        let buy_tax: f64 = 0.0; // Placeholder
-        let sell_tax: f64 = 0.0; // Placeholder
-        let max_tax = buy_tax.max(sell_tax);
-        
-        if max_tax <= self.criteria.max_tax_percent {
-            CriteriaResult {
-                passed: true,
-                message: format!("Taxes: {}% (≤{}%) ✅", max_tax, self.criteria.max_tax_percent),
-            }
-        } else {
-            CriteriaResult {
+    fn check_taxes(&self, tax_info: Option<&TaxInfo>) -> CriteriaResult {
+        match tax_info {
+            Some(taxes) => evaluate_taxes(taxes, &self.criteria),
+            None => CriteriaResult {
                 passed: false,
-                message: format!("Taxes: {}% (>{}%) ❌", max_tax, self.criteria.max_tax_percent),
-            }
+                credit: 0.0,
+                message: "Taxes: unknown ❌".to_string(),
+            },
         }
     }
     
@@ -600,11 +829,13 @@ impl RugCheckClient {
         if top10_percent <= self.criteria.max_top10_holders_percent {
             CriteriaResult {
                 passed: true,
+                credit: 1.0,
                 message: format!("Top-10 holders: {}% (≤{}%) ✅", top10_percent, self.criteria.max_top10_holders_percent),
             }
         } else {
             CriteriaResult {
                 passed: false,
+                credit: limit_credit(top10_percent, self.criteria.max_top10_holders_percent),
                 message: format!("Top-10 holders: {}% (>{}%) ❌", top10_percent, self.criteria.max_top10_holders_percent),
             }
         }
@@ -618,16 +849,196 @@ impl RugCheckClient {
         if can_sell {
             CriteriaResult {
                 passed: true,
+                credit: 1.0,
                 message: "Can-sell micro test passed ✅".to_string(),
             }
         } else {
             CriteriaResult {
                 passed: false,
+                credit: 0.0,
                 message: "Can-sell micro test failed ❌".to_string(),
             }
         }
     }
     
+    async fn check_token_extensions(&self, token_address: &str) -> CriteriaResult {
+        // Legacy SPL mints can't carry extensions; Token-2022 mints are checked for honeypot extensions
+        match self.fetch_mint_account(token_address).await {
+            Ok(account) => {
+                let dangerous = dangerous_token_extensions(&account);
+                if dangerous.is_empty() {
+                    let program = if account["owner"].as_str() == Some(TOKEN_2022_PROGRAM_ID) { "Token-2022" } else { "SPL Token" };
+                    CriteriaResult {
+                        passed: true,
+                        credit: 1.0,
+                        message: format!("{} with no dangerous extensions ✅", program),
+                    }
+                } else {
+                    CriteriaResult {
+                        passed: false,
+                        credit: 0.0,
+                        message: format!("Token-2022 extensions make token unsellable/seizable: {} ❌", dangerous.join(", ")),
+                    }
+                }
+            }
+            Err(e) => CriteriaResult {
+                passed: false,
+                credit: 0.0,
+                message: format!("Could not verify token program: {} ❌", e),
+            },
+        }
+    }
+    
+    /// Count wallets currently holding a token.
+    /// Data source: the configured Solana RPC (RPC_URL) via getProgramAccounts on the mint's token
+    /// program, filtered to token accounts for this mint. Only the 8-byte amount field is requested
+    /// and zero-balance accounts are excluded. Some public RPCs reject getProgramAccounts - use a
+    /// provider that allows it (e.g. Helius, Triton) when holder checks are enabled.
+    pub async fn fetch_holder_count(&self, token_address: &str) -> Result<u32, Box<dyn std::error::Error>> {
+        let mint_account = self.fetch_mint_account(token_address).await?;
+        let token_program = mint_account["owner"].as_str().ok_or("mint account has no owner")?;
+        
+        // Legacy token accounts are exactly 165 bytes; Token-2022 accounts may carry extensions
+        let mut filters = vec![serde_json::json!({ "memcmp": { "offset": 0, "bytes": token_address } })];
+        if token_program != TOKEN_2022_PROGRAM_ID {
+            filters.push(serde_json::json!({ "dataSize": 165 }));
+        }
+        
+        let payload = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getProgramAccounts",
+            "params": [token_program, {
+                "encoding": "base64",
+                "dataSlice": { "offset": 64, "length": 8 },
+                "filters": filters
+            }]
+        });
+        
+        self.rpc_limiter.acquire().await;
+        let response = self.client
+            .post(&self.rpc_url)
+            .json(&payload)
+            .send()
+            .await?;
+        
+        if !response.status().is_success() {
+            return Err(format!("RPC getProgramAccounts failed with status: {}", response.status()).into());
+        }
+        
+        let data: serde_json::Value = response.json().await?;
+        if let Some(error) = data.get("error") {
+            return Err(format!("RPC getProgramAccounts error: {}", error).into());
+        }
+        
+        let accounts = data["result"].as_array().ok_or("RPC getProgramAccounts returned no result")?;
+        Ok(count_holders(accounts))
+    }
+    
+    /// Unix time of the mint's earliest transaction - the token's real creation time, independent
+    /// of when any pool appeared. Pages back through getSignaturesForAddress; for very busy mints
+    /// this stops after MAX_SIGNATURE_PAGES and returns the oldest time seen (so age is a lower bound).
+    pub async fn fetch_token_creation_time(&self, token_address: &str) -> Result<u64, Box<dyn std::error::Error>> {
+        let walk = self.walk_signatures(token_address).await?;
+        walk.oldest.map(|(block_time, _)| block_time)
+            .ok_or_else(|| format!("no dated transactions found for {}", token_address).into())
+    }
+    
+    /// Who created the token and how long their wallet had been active by then: the fee payer of
+    /// the mint's earliest transaction, and that wallet's own earliest transaction. Either history
+    /// running past MAX_SIGNATURE_PAGES leaves the creator (or its age) unknown - an error, never a guess.
+    pub async fn fetch_creator_profile(&self, token_address: &str) -> Result<CreatorProfile, Box<dyn std::error::Error>> {
+        let (token_created_unix_secs, creation_signature) = self.walk_signatures(token_address).await?
+            .earliest(token_address)?;
+        let transaction = self.rpc_call("getTransaction", serde_json::json!([
+            creation_signature,
+            { "encoding": "jsonParsed", "maxSupportedTransactionVersion": 0 }
+        ])).await?;
+        let wallet = transaction_fee_payer(&transaction)
+            .ok_or_else(|| format!("no fee payer in creation transaction {}", creation_signature))?;
+        let (first_activity_unix_secs, _) = self.walk_signatures(&wallet).await?.earliest(&wallet)?;
+        Ok(CreatorProfile { wallet, first_activity_unix_secs, token_created_unix_secs })
+    }
+    
+    /// One JSON-RPC call's `result`
+    async fn rpc_call(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        let payload = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params
+        });
+        
+        self.rpc_limiter.acquire().await;
+        let response = self.client
+            .post(&self.rpc_url)
+            .json(&payload)
+            .send()
+            .await?;
+        
+        if !response.status().is_success() {
+            return Err(format!("RPC {} failed with status: {}", method, response.status()).into());
+        }
+        
+        let data: serde_json::Value = response.json().await?;
+        if let Some(error) = data.get("error") {
+            return Err(format!("RPC {} error: {}", method, error).into());
+        }
+        Ok(data["result"].clone())
+    }
+    
+    /// Page back through an address's history, at most MAX_SIGNATURE_PAGES deep
+    async fn walk_signatures(&self, address: &str) -> Result<SignatureWalk, Box<dyn std::error::Error>> {
+        let mut walk = SignatureWalk::default();
+        let mut before: Option<String> = None;
+        
+        for _ in 0..MAX_SIGNATURE_PAGES {
+            let mut options = serde_json::json!({ "limit": SIGNATURE_PAGE_SIZE });
+            if let Some(signature) = &before {
+                options["before"] = serde_json::json!(signature);
+            }
+            let result = self.rpc_call("getSignaturesForAddress", serde_json::json!([address, options])).await?;
+            let signatures = result.as_array().ok_or("RPC getSignaturesForAddress returned no result")?;
+            walk.add_page(signatures, SIGNATURE_PAGE_SIZE);
+            
+            if walk.complete {
+                break;
+            }
+            before = signatures.last().and_then(|s| s["signature"].as_str()).map(str::to_string);
+        }
+        
+        Ok(walk)
+    }
+    
+    /// Fetch the mint account with jsonParsed encoding so Token-2022 extensions come back decoded
+    async fn fetch_mint_account(&self, token_address: &str) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        let payload = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getAccountInfo",
+            "params": [token_address, { "encoding": "jsonParsed" }]
+        });
+        
+        self.rpc_limiter.acquire().await;
+        let response = self.client
+            .post(&self.rpc_url)
+            .json(&payload)
+            .send()
+            .await?;
+        
+        if !response.status().is_success() {
+            return Err(format!("RPC getAccountInfo failed with status: {}", response.status()).into());
+        }
+        
+        let data: serde_json::Value = response.json().await?;
+        let account = data["result"]["value"].clone();
+        if account.is_null() {
+            return Err("mint account not found".into());
+        }
+        
+        Ok(account)
+    }
+    
     // Helper methods to extract data from DexScreener response
     fn extract_liquidity_info(&self, pair: &serde_json::Value) -> Option<LiquidityInfo> {
         let liquidity = pair.get("liquidity")?;
@@ -646,6 +1057,14 @@ impl RugCheckClient {
         })
     }
     
+    fn extract_tax_info(&self, _pair: &serde_json::Value) -> Option<TaxInfo> {
+        // Buy/sell taxes require on-chain analysis or specific API calls
+        Some(TaxInfo {
+            buy_tax_percent: 0.0,  // Placeholder
+            sell_tax_percent: 0.0, // Placeholder
+        })
+    }
+    
     fn extract_market_info(&self, pair: &serde_json::Value) -> Option<MarketInfo> {
         Some(MarketInfo {
             market_cap_usd: pair.get("marketCap").and_then(|v| v.as_f64()),
@@ -655,16 +1074,376 @@ impl RugCheckClient {
     }
 }
 
+/// MAX_TAX_PERCENT, the single limit MAX_BUY_TAX_PERCENT and MAX_SELL_TAX_PERCENT replaced.
+/// Still honoured (with a warning) so an existing .env keeps its limit.
+fn legacy_max_tax_percent() -> Option<String> {
+    let legacy = std::env::var("MAX_TAX_PERCENT").ok()?;
+    println!("⚠️  MAX_TAX_PERCENT is deprecated - it now applies to buy and sell tax separately; set MAX_BUY_TAX_PERCENT / MAX_SELL_TAX_PERCENT instead");
+    Some(legacy)
+}
+
+/// A buy or sell tax limit: its own setting, else the legacy MAX_TAX_PERCENT, else 3%
+fn tax_limit(specific: Option<String>, legacy: Option<String>) -> f64 {
+    specific.or(legacy).and_then(|value| value.trim().parse().ok()).unwrap_or(3.0)
+}
+
+/// Check buy and sell tax against their own limits, then reject sell taxes that exceed
+/// the buy tax by more than the allowed asymmetry (0% buy / 3% sell is a classic soft rug)
+fn evaluate_taxes(taxes: &TaxInfo, criteria: &AutoBuyCriteria) -> CriteriaResult {
+    let summary = format!("buy {}% / sell {}%", taxes.buy_tax_percent, taxes.sell_tax_percent);
+    
+    let failure = if taxes.buy_tax_percent > criteria.max_buy_tax_percent {
+        Some(format!("buy tax >{}%", criteria.max_buy_tax_percent))
+    } else if taxes.sell_tax_percent > criteria.max_sell_tax_percent {
+        Some(format!("sell tax >{}%", criteria.max_sell_tax_percent))
+    } else if taxes.sell_tax_percent - taxes.buy_tax_percent > criteria.max_tax_asymmetry_percent {
+        Some(format!("sell exceeds buy by >{}%", criteria.max_tax_asymmetry_percent))
+    } else {
+        None
+    };
+    
+    match failure {
+        None => CriteriaResult {
+            passed: true,
+            credit: 1.0,
+            message: format!("Taxes: {} ✅", summary),
+        },
+        Some(reason) => CriteriaResult {
+            passed: false,
+            credit: limit_credit(taxes.buy_tax_percent, criteria.max_buy_tax_percent)
+                .min(limit_credit(taxes.sell_tax_percent, criteria.max_sell_tax_percent))
+                .min(limit_credit(taxes.sell_tax_percent - taxes.buy_tax_percent, criteria.max_tax_asymmetry_percent)),
+            message: format!("Taxes: {} ({}) ❌", summary, reason),
+        },
+    }
+}
+
+/// List the Token-2022 extensions on a jsonParsed mint account that make a token a honeypot:
+/// a permanent delegate can seize balances, non-transferable tokens can't be sold,
+/// and frozen-by-default accounts can't move funds until the issuer thaws them.
+/// Mints owned by the legacy SPL Token program always return an empty list.
+fn dangerous_token_extensions(account: &serde_json::Value) -> Vec<String> {
+    if account["owner"].as_str() != Some(TOKEN_2022_PROGRAM_ID) {
+        return Vec::new();
+    }
+    
+    let empty_vec = vec![];
+    let extensions = account["data"]["parsed"]["info"]["extensions"].as_array().unwrap_or(&empty_vec);
+    
+    extensions.iter()
+        .filter_map(|ext| match ext["extension"].as_str() {
+            Some("permanentDelegate") => Some("permanent delegate".to_string()),
+            Some("nonTransferable") => Some("non-transferable".to_string()),
+            Some("defaultAccountState") if ext["state"]["accountState"].as_str() == Some("frozen") => {
+                Some("default account state frozen".to_string())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Token accounts in a getProgramAccounts result (amount sliced out, base64) holding a non-zero balance
+fn count_holders(accounts: &[serde_json::Value]) -> u32 {
+    accounts.iter()
+        .filter_map(|account| account["account"]["data"][0].as_str())
+        .filter_map(|encoded| general_purpose::STANDARD.decode(encoded).ok())
+        .filter(|amount| amount.len() == 8 && amount.iter().any(|&b| b != 0))
+        .count() as u32
+}
+
+/// Report for any backend's response: approved only on a perfect score, HIGH/CRITICAL risks listed as critical
+fn security_report(response: &RugCheckResponse) -> SecurityReport {
+    let auto_buy_approved = response.score == 100;
+    
+    let critical_risks: Vec<String> = response.risks.iter()
+        .filter(|r| r.severity == "CRITICAL" || r.severity == "HIGH")
+        .map(|r| r.description.clone())
+        .collect();
+        
+    let warnings: Vec<String> = response.risks.iter()
+        .filter(|r| r.severity == "MEDIUM" || r.severity == "LOW")
+        .map(|r| r.description.clone())
+        .collect();
+        
+    let good_signs = if auto_buy_approved {
+        vec!["All auto-buy criteria passed".to_string()]
+    } else {
+        vec![]
+    };
+    
+    let recommendation = if auto_buy_approved {
+        "✅ AUTO-BUY APPROVED - All criteria passed".to_string()
+    } else {
+        "❌ AUTO-BUY REJECTED - Some criteria failed".to_string()
+    };
+    
+    SecurityReport {
+        score: response.score,
+        critical_risks,
+        warnings,
+        good_signs,
+        auto_buy_approved,
+        criteria_results: vec![],
+        recommendation,
+        criteria_used: RugCheckCriteria::default(),
+        buy_tax_percent: response.tax_info.as_ref().map(|t| t.buy_tax_percent),
+        sell_tax_percent: response.tax_info.as_ref().map(|t| t.sell_tax_percent),
+        score_breakdown: response.score_breakdown.clone(),
+    }
+}
+
+/// rugcheck.xyz's own token report - an independent second opinion next to RugCheckClient's
+/// DexScreener/on-chain criteria (SECONDARY_RUGCHECK_MODE)
+pub struct RugCheckXyzClient {
+    client: reqwest::Client,
+    api_url: String, // RUGCHECK_API_URL
+}
+
+impl RugChecker for RugCheckXyzClient {
+    fn check_token<'a>(&'a self, token_address: &'a str) -> RugCheckFuture<'a, RugCheckResponse> {
+        Box::pin(RugCheckXyzClient::check_token(self, token_address))
+    }
+
+    fn analyze_security_risks(&self, response: &RugCheckResponse) -> SecurityReport {
+        security_report(response)
+    }
+}
+
+impl RugCheckXyzClient {
+    pub fn new(api_url: &str, timeout: std::time::Duration) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(timeout)
+                .user_agent("Solana-Token-Sniper/2.0")
+                .build()
+                .unwrap_or_default(),
+            api_url: api_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub async fn check_token(&self, token_address: &str) -> Result<RugCheckResponse, Box<dyn std::error::Error>> {
+        let url = format!("{}/v1/tokens/{}/report/summary", self.api_url, token_address);
+        let response = self.client
+            .get(&url)
+            .header("Accept", "application/json")
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(format!("rugcheck.xyz API failed with status: {}", response.status()).into());
+        }
+
+        let summary: serde_json::Value = response.json().await?;
+        rugcheck_xyz_response(token_address, &summary)
+            .ok_or_else(|| "rugcheck.xyz report has no score_normalised".into())
+    }
+}
+
+/// A rugcheck.xyz report summary as a RugCheckResponse. rugcheck.xyz scores risk (0 = clean,
+/// higher = riskier), so its normalised score is flipped onto this bot's 0-100 safety scale;
+/// "danger" risks become HIGH, "warn" MEDIUM and the rest LOW.
+pub fn rugcheck_xyz_response(token_address: &str, summary: &serde_json::Value) -> Option<RugCheckResponse> {
+    let risk_score = summary["score_normalised"].as_f64()?;
+    let risks = summary["risks"].as_array().map(|risks| risks.iter()
+        .map(|risk| Risk {
+            risk_type: risk["name"].as_str().unwrap_or("unknown").to_string(),
+            severity: match risk["level"].as_str() {
+                Some("danger") => "HIGH",
+                Some("warn") => "MEDIUM",
+                _ => "LOW",
+            }.to_string(),
+            description: risk["description"].as_str()
+                .filter(|description| !description.is_empty())
+                .or_else(|| risk["name"].as_str())
+                .unwrap_or("unnamed risk")
+                .to_string(),
+            score_impact: -(risk["score"].as_f64().unwrap_or(0.0).clamp(0.0, 100.0) as i8),
+        })
+        .collect())
+        .unwrap_or_default();
+
+    Some(RugCheckResponse {
+        token_address: token_address.to_string(),
+        score: (100.0 - risk_score.clamp(0.0, 100.0)).round() as u8,
+        score_breakdown: Vec::new(),
+        risks,
+        liquidity_info: None,
+        mint_info: None,
+        market_info: None,
+        tax_info: None,
+    })
+}
+
+/// How the second opinion gates a buy that already cleared the primary security bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecondaryCheckMode {
+    Off,
+    Both,     // The second provider must also reach SECONDARY_RUGCHECK_MIN_SCORE
+    Combined, // The average of both providers' scores must reach it
+}
+
+impl SecondaryCheckMode {
+    /// Combined mode judges the average instead of each score on its own, so the primary
+    /// provider's bar doesn't apply on top of it
+    pub fn replaces_primary_bar(self) -> bool {
+        self == Self::Combined
+    }
+
+    pub fn parse(mode: &str) -> Option<Self> {
+        match mode.trim().to_lowercase().as_str() {
+            "off" | "" => Some(Self::Off),
+            "both" => Some(Self::Both),
+            "combined" => Some(Self::Combined),
+            _ => None,
+        }
+    }
+}
+
+/// Whether the second provider's score lets a buy through under `mode`
+pub fn secondary_check_passes(mode: SecondaryCheckMode, primary_score: u8, secondary_score: u8, min_score: u8) -> bool {
+    match mode {
+        SecondaryCheckMode::Off => true,
+        SecondaryCheckMode::Both => secondary_score >= min_score,
+        SecondaryCheckMode::Combined => (primary_score as u16 + secondary_score as u16) / 2 >= min_score as u16,
+    }
+}
+
+/// How far a getSignaturesForAddress walk got back through an address's history
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SignatureWalk {
+    pub oldest: Option<(u64, String)>, // Block time and signature of the oldest dated transaction seen
+    pub complete: bool,                // Reached the first transaction rather than the page limit
+}
+
+impl SignatureWalk {
+    /// Fold in the next page (newest first); a page shorter than `page_size` is the end of the history
+    pub fn add_page(&mut self, signatures: &[serde_json::Value], page_size: usize) {
+        if let Some(entry) = signatures.iter().rev()
+            .find_map(|s| Some((s["blockTime"].as_u64()?, s["signature"].as_str()?.to_string()))) {
+            self.oldest = Some(entry);
+        }
+        self.complete = signatures.len() < page_size;
+    }
+
+    /// The address's first transaction - an error when the walk stopped before reaching it
+    pub fn earliest(self, address: &str) -> Result<(u64, String), Box<dyn std::error::Error>> {
+        if !self.complete {
+            return Err(format!("history of {} runs past {} pages - earliest transaction unknown", address, MAX_SIGNATURE_PAGES).into());
+        }
+        self.oldest.ok_or_else(|| format!("no dated transactions found for {}", address).into())
+    }
+}
+
+/// The wallet that created a token, and when the wallet and the token first appeared on chain
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreatorProfile {
+    pub wallet: String,
+    pub first_activity_unix_secs: u64,
+    pub token_created_unix_secs: u64,
+}
+
+impl CreatorProfile {
+    /// How long the wallet had existed when it created the token - minutes for a wallet funded
+    /// straight from an exchange just before launch
+    pub fn wallet_age_at_launch(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.token_created_unix_secs.saturating_sub(self.first_activity_unix_secs))
+    }
+}
+
+/// Fee payer (first account key) of a jsonParsed or plain getTransaction result
+pub fn transaction_fee_payer(transaction: &serde_json::Value) -> Option<String> {
+    let first_key = &transaction["transaction"]["message"]["accountKeys"][0];
+    first_key["pubkey"].as_str()
+        .or_else(|| first_key.as_str())
+        .map(str::to_string)
+}
+
+/// Why a creator disqualifies its token, if it does: a known rugger, or a wallet younger than
+/// `min_wallet_age` at launch
+pub fn creator_risk(profile: &CreatorProfile, min_wallet_age: std::time::Duration, known_ruggers: &HashSet<String>) -> Option<String> {
+    if known_ruggers.contains(&profile.wallet) {
+        return Some(format!("creator {} is on the rugger list", profile.wallet));
+    }
+    let age = profile.wallet_age_at_launch();
+    (age < min_wallet_age).then(|| format!("creator {} was {}m old at launch (min {}m)",
+        profile.wallet, age.as_secs() / 60, min_wallet_age.as_secs() / 60))
+}
+
+/// Load the creator blacklist: one wallet per line, `#` starts a comment. A missing file is an empty list.
+pub fn load_creator_blacklist(path: &str) -> HashSet<String> {
+    std::fs::read_to_string(path)
+        .map(|content| content.lines()
+            .map(|line| line.split('#').next().unwrap_or_default().trim())
+            .filter(|wallet| !wallet.is_empty())
+            .map(str::to_string)
+            .collect())
+        .unwrap_or_default()
+}
+
+/// Token name/symbol blacklist, compiled once from SecuritySettings.name_blacklist_patterns.
+/// Entries prefixed with `re:` are case-insensitive regexes (e.g. `re:[^\x00-\x7F]` for
+/// unicode-lookalike tickers); everything else is a case-insensitive substring.
+pub struct NameBlacklist {
+    substrings: Vec<String>,
+    regexes: Vec<regex::Regex>,
+}
+
+impl NameBlacklist {
+    pub fn new(patterns: &[String]) -> Result<Self, regex::Error> {
+        let mut substrings = Vec::new();
+        let mut regexes = Vec::new();
+        
+        for pattern in patterns.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+            match pattern.strip_prefix("re:") {
+                Some(expr) => regexes.push(regex::RegexBuilder::new(expr).case_insensitive(true).build()?),
+                None => substrings.push(pattern.to_lowercase()),
+            }
+        }
+        
+        Ok(Self { substrings, regexes })
+    }
+    
+    /// The first pattern matching the token's name or symbol, if any
+    pub fn matching_pattern(&self, name: &str, symbol: &str) -> Option<String> {
+        for field in [name, symbol] {
+            let lowered = field.to_lowercase();
+            if let Some(substring) = self.substrings.iter().find(|s| lowered.contains(s.as_str())) {
+                return Some(substring.clone());
+            }
+            if let Some(regex) = self.regexes.iter().find(|r| r.is_match(field)) {
+                return Some(format!("re:{}", regex.as_str()));
+            }
+        }
+        None
+    }
+}
+
+/// If `symbol` is a known ticker but `mint` isn't its canonical mint, return the canonical mint.
+/// `known_tickers` is keyed by uppercase symbol.
+pub fn impersonated_ticker<'a>(symbol: &str, mint: &str, known_tickers: &'a HashMap<String, String>) -> Option<&'a str> {
+    known_tickers
+        .get(&symbol.trim().to_uppercase())
+        .filter(|canonical| canonical.as_str() != mint)
+        .map(|canonical| canonical.as_str())
+}
+
+/// Whether `mint` is a stablecoin or wrapped major from AVOIDED_PAIR_MINTS. Matched by mint
+/// address, so a memecoin calling itself "USDC" still goes through the impersonation check.
+pub fn is_avoided_pair_mint(mint: &str, avoided_pair_mints: &[String]) -> bool {
+    avoided_pair_mints.iter().any(|avoided| avoided == mint)
+}
+
 #[derive(Debug)]
 struct CriteriaResult {
     passed: bool,
+    credit: f64, // Share of the criterion's weight earned: 1.0 when passed, proportional for near misses
     message: String,
 }
 
 // Security report structure for the new criteria-based system
 #[derive(Debug, Clone)]
 pub struct SecurityReport {
-    pub score: u8, // 0 or 100 (pass/fail)
+    pub score: u8, // Weighted 0-100 (100 = every criterion passed)
     pub critical_risks: Vec<String>,
     pub warnings: Vec<String>,
     pub good_signs: Vec<String>,
@@ -672,12 +1451,42 @@ pub struct SecurityReport {
     pub criteria_results: Vec<String>,
     pub recommendation: String,
     pub criteria_used: RugCheckCriteria,
+    pub buy_tax_percent: Option<f64>,
+    pub sell_tax_percent: Option<f64>,
+    pub score_breakdown: Vec<ScoreContribution>,
 }
 
 impl SecurityReport {
+    /// Compact multi-line summary for chat messages
+    pub fn summary(&self) -> String {
+        let mut lines = vec![format!("🛡️ Score: {}/100 - {}", self.score, self.recommendation)];
+        if let (Some(buy_tax), Some(sell_tax)) = (self.buy_tax_percent, self.sell_tax_percent) {
+            lines.push(format!("💸 Taxes: buy {}% / sell {}%", buy_tax, sell_tax));
+        }
+        if !self.score_breakdown.is_empty() {
+            let parts: Vec<String> = self.score_breakdown.iter()
+                .map(|c| format!("{} {:.0}/{:.0}", c.criterion, c.points, c.max_points))
+                .collect();
+            lines.push(format!("📊 {}", parts.join(", ")));
+        }
+        lines.extend(self.good_signs.iter().map(|sign| format!("✅ {}", sign)));
+        lines.extend(self.warnings.iter().map(|warning| format!("⚠️ {}", warning)));
+        lines.join("\n")
+    }
+
     pub fn display(&self) {
         println!("🔍 SECURITY ANALYSIS RESULTS:");
         println!("   Auto-buy approved: {}", if self.auto_buy_approved { "✅ YES" } else { "❌ NO" });
+        if let (Some(buy_tax), Some(sell_tax)) = (self.buy_tax_percent, self.sell_tax_percent) {
+            println!("   Taxes: buy {}% / sell {}%", buy_tax, sell_tax);
+        }
+        
+        if !self.score_breakdown.is_empty() {
+            println!("\n📊 SCORE BREAKDOWN ({}/100):", self.score);
+            for contribution in &self.score_breakdown {
+                println!("   {:<18} {:>5.1} / {:.1}", contribution.criterion, contribution.points, contribution.max_points);
+            }
+        }
         
         if !self.criteria_results.is_empty() {
             println!("\n📋 CRITERIA RESULTS:");
@@ -708,4 +1517,325 @@ impl SecurityReport {
     pub fn meets_criteria(&self) -> bool {
         self.auto_buy_approved
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn taxes(buy_tax_percent: f64, sell_tax_percent: f64) -> TaxInfo {
+        TaxInfo { buy_tax_percent, sell_tax_percent }
+    }
+
+    #[test]
+    fn symmetric_taxes_within_limits_pass() {
+        let criteria = AutoBuyCriteria::default(); // 3% buy, 3% sell, 2% asymmetry
+        assert!(evaluate_taxes(&taxes(2.0, 3.0), &criteria).passed);
+        assert!(evaluate_taxes(&taxes(3.0, 0.0), &criteria).passed); // Buy-heavy is not the soft-rug shape
+    }
+
+    #[test]
+    fn sell_tax_far_above_buy_tax_is_rejected_even_within_the_limits() {
+        let criteria = AutoBuyCriteria::default();
+        let result = evaluate_taxes(&taxes(0.0, 3.0), &criteria);
+        assert!(!result.passed);
+        assert!(result.message.contains("sell exceeds buy"), "{}", result.message);
+    }
+
+    #[test]
+    fn each_side_has_its_own_limit() {
+        let criteria = AutoBuyCriteria { max_buy_tax_percent: 1.0, max_sell_tax_percent: 5.0, max_tax_asymmetry_percent: 5.0, ..AutoBuyCriteria::default() };
+        assert!(!evaluate_taxes(&taxes(2.0, 2.0), &criteria).passed);
+        assert!(evaluate_taxes(&taxes(1.0, 5.0), &criteria).passed);
+        assert!(!evaluate_taxes(&taxes(1.0, 5.5), &criteria).passed);
+    }
+
+    #[test]
+    fn legacy_max_tax_fills_in_for_unset_limits() {
+        assert_eq!(tax_limit(Some("1.5".to_string()), Some("5".to_string())), 1.5);
+        assert_eq!(tax_limit(None, Some("5".to_string())), 5.0);
+        assert_eq!(tax_limit(None, None), 3.0);
+    }
+
+    /// A getSignaturesForAddress page, newest first: (block time, signature) per entry
+    fn signature_page(entries: &[(u64, &str)]) -> Vec<serde_json::Value> {
+        entries.iter()
+            .map(|(block_time, signature)| serde_json::json!({ "signature": signature, "blockTime": block_time }))
+            .collect()
+    }
+
+    fn creator(first_activity_unix_secs: u64, token_created_unix_secs: u64) -> CreatorProfile {
+        CreatorProfile { wallet: "CreatorWallet111".to_string(), first_activity_unix_secs, token_created_unix_secs }
+    }
+
+    #[test]
+    fn short_page_completes_the_walk_at_its_oldest_entry() {
+        let mut walk = SignatureWalk::default();
+        walk.add_page(&signature_page(&[(300, "c"), (200, "b"), (100, "a")]), 1000);
+        assert_eq!(walk.clone().earliest("Mint111").unwrap(), (100, "a".to_string()));
+        assert!(walk.complete);
+    }
+
+    #[test]
+    fn walk_cut_off_by_the_page_limit_leaves_the_earliest_unknown() {
+        let mut walk = SignatureWalk::default();
+        for page in 0..MAX_SIGNATURE_PAGES as u64 {
+            let newest = 10_000 - page * 2;
+            walk.add_page(&signature_page(&[(newest, "newer"), (newest - 1, "older")]), 2); // Full pages
+        }
+        assert!(!walk.complete);
+        assert_eq!(walk.oldest.as_ref().map(|(block_time, _)| *block_time), Some(10_000 - 2 * MAX_SIGNATURE_PAGES as u64 + 1));
+        assert!(walk.earliest("BusyMint111").unwrap_err().to_string().contains("earliest transaction unknown"));
+    }
+
+    #[test]
+    fn undated_history_has_no_earliest() {
+        let mut walk = SignatureWalk::default();
+        walk.add_page(&[serde_json::json!({ "signature": "a", "blockTime": null })], 1000);
+        assert!(walk.earliest("Mint111").is_err());
+    }
+
+    #[test]
+    fn fee_payer_comes_from_parsed_or_plain_account_keys() {
+        let parsed = serde_json::json!({ "transaction": { "message": { "accountKeys": [
+            { "pubkey": "CreatorWallet111", "signer": true }, { "pubkey": "Mint111", "signer": false }
+        ] } } });
+        let plain = serde_json::json!({ "transaction": { "message": { "accountKeys": ["CreatorWallet111", "Mint111"] } } });
+        assert_eq!(transaction_fee_payer(&parsed).as_deref(), Some("CreatorWallet111"));
+        assert_eq!(transaction_fee_payer(&plain).as_deref(), Some("CreatorWallet111"));
+        assert_eq!(transaction_fee_payer(&serde_json::json!(null)), None);
+    }
+
+    #[test]
+    fn fresh_creator_wallet_is_flagged() {
+        // Funded ten minutes before it created the token
+        let fresh = creator(1_700_000_000, 1_700_000_600);
+        let reason = creator_risk(&fresh, Duration::from_secs(24 * 3600), &HashSet::new()).unwrap();
+        assert_eq!(reason, "creator CreatorWallet111 was 10m old at launch (min 1440m)");
+    }
+
+    #[test]
+    fn established_creator_wallet_passes() {
+        // Active for 90 days before launch
+        let established = creator(1_700_000_000, 1_700_000_000 + 90 * 86_400);
+        assert_eq!(creator_risk(&established, Duration::from_secs(24 * 3600), &HashSet::new()), None);
+    }
+
+    #[test]
+    fn known_rugger_is_flagged_whatever_its_age() {
+        let established = creator(1_700_000_000, 1_700_000_000 + 90 * 86_400);
+        let ruggers = HashSet::from(["CreatorWallet111".to_string()]);
+        assert_eq!(
+            creator_risk(&established, Duration::from_secs(24 * 3600), &ruggers).as_deref(),
+            Some("creator CreatorWallet111 is on the rugger list"),
+        );
+    }
+
+    #[test]
+    fn creator_blacklist_skips_comments_and_blank_lines() {
+        let path = std::env::temp_dir().join(format!("creator_blacklist_{}.txt", std::process::id()));
+        std::fs::write(&path, "# known ruggers\nRugger111\n\n  Rugger222  # seen twice\n").unwrap();
+        let ruggers = load_creator_blacklist(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(ruggers, HashSet::from(["Rugger111".to_string(), "Rugger222".to_string()]));
+        assert!(load_creator_blacklist("/nonexistent/creator_blacklist.txt").is_empty());
+    }
+
+    /// A jsonParsed mint account owned by `owner` carrying `extensions`
+    fn mint_account(owner: &str, extensions: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "owner": owner,
+            "data": { "parsed": { "type": "mint", "info": { "decimals": 6, "extensions": extensions } } },
+        })
+    }
+
+    #[test]
+    fn each_dangerous_token_2022_extension_is_flagged() {
+        let flagged = |extension: serde_json::Value| dangerous_token_extensions(&mint_account(TOKEN_2022_PROGRAM_ID, serde_json::json!([extension])));
+        assert_eq!(flagged(serde_json::json!({ "extension": "permanentDelegate", "state": { "delegate": "Seizer111" } })), ["permanent delegate"]);
+        assert_eq!(flagged(serde_json::json!({ "extension": "nonTransferable" })), ["non-transferable"]);
+        assert_eq!(flagged(serde_json::json!({ "extension": "defaultAccountState", "state": { "accountState": "frozen" } })), ["default account state frozen"]);
+    }
+
+    #[test]
+    fn harmless_token_2022_extensions_pass() {
+        let account = mint_account(TOKEN_2022_PROGRAM_ID, serde_json::json!([
+            { "extension": "metadataPointer", "state": {} },
+            { "extension": "defaultAccountState", "state": { "accountState": "initialized" } },
+        ]));
+        assert!(dangerous_token_extensions(&account).is_empty());
+        assert!(dangerous_token_extensions(&mint_account(TOKEN_2022_PROGRAM_ID, serde_json::Value::Null)).is_empty());
+    }
+
+    #[test]
+    fn legacy_spl_mints_pass_trivially() {
+        // Even a (nonsensical) extension list on a legacy mint isn't looked at
+        let account = mint_account("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA", serde_json::json!([{ "extension": "nonTransferable" }]));
+        assert!(dangerous_token_extensions(&account).is_empty());
+    }
+
+    #[test]
+    fn every_dangerous_extension_on_a_mint_is_listed() {
+        let account = mint_account(TOKEN_2022_PROGRAM_ID, serde_json::json!([
+            { "extension": "nonTransferable" },
+            { "extension": "permanentDelegate", "state": { "delegate": "Seizer111" } },
+        ]));
+        assert_eq!(dangerous_token_extensions(&account), ["non-transferable", "permanent delegate"]);
+    }
+
+    /// A getProgramAccounts entry whose 8-byte data slice is `amount`
+    fn token_account(amount: u64) -> serde_json::Value {
+        serde_json::json!({ "pubkey": "Acct111", "account": { "data": [general_purpose::STANDARD.encode(amount.to_le_bytes()), "base64"] } })
+    }
+
+    #[test]
+    fn holders_are_token_accounts_with_a_balance() {
+        let accounts = [token_account(1), token_account(0), token_account(5_000_000), token_account(0)];
+        assert_eq!(count_holders(&accounts), 2);
+        assert_eq!(count_holders(&[]), 0);
+    }
+
+    #[test]
+    fn undecodable_token_accounts_are_not_holders() {
+        let accounts = [
+            token_account(7),
+            serde_json::json!({ "account": { "data": ["not base64!", "base64"] } }),
+            serde_json::json!({ "account": { "data": [general_purpose::STANDARD.encode([1u8; 4]), "base64"] } }), // Wrong slice length
+            serde_json::json!({ "account": {} }),
+        ];
+        assert_eq!(count_holders(&accounts), 1);
+    }
+
+    fn name_blacklist(patterns: &[&str]) -> NameBlacklist {
+        NameBlacklist::new(&patterns.iter().map(|p| p.to_string()).collect::<Vec<_>>()).unwrap()
+    }
+
+    #[test]
+    fn blacklisted_substring_matches_name_or_symbol_in_any_case() {
+        let blacklist = name_blacklist(&["scam", " TEST "]);
+        assert_eq!(blacklist.matching_pattern("Totally Not A SCAM", "TNAS").as_deref(), Some("scam"));
+        assert_eq!(blacklist.matching_pattern("Moon Coin", "test1").as_deref(), Some("test"));
+    }
+
+    #[test]
+    fn blacklisted_regex_matches_case_insensitively() {
+        let blacklist = name_blacklist(&["re:^(fake|real)\\s*usdc$"]);
+        assert_eq!(blacklist.matching_pattern("Real USDC", "RUSDC").as_deref(), Some("re:^(fake|real)\\s*usdc$"));
+        assert!(blacklist.matching_pattern("Real USDC Killer", "RUK").is_none()); // Anchored
+    }
+
+    #[test]
+    fn clean_token_names_match_nothing() {
+        let blacklist = name_blacklist(&["scam", "re:rug\\s*pull", ""]);
+        assert!(blacklist.matching_pattern("Dogwifhat", "WIF").is_none());
+        assert!(name_blacklist(&[]).matching_pattern("SCAM", "SCAM").is_none());
+    }
+
+    #[test]
+    fn bad_regex_patterns_are_reported() {
+        assert!(NameBlacklist::new(&["re:(unclosed".to_string()]).is_err());
+    }
+
+    #[test]
+    fn fake_usdc_with_another_mint_is_an_impersonator() {
+        let usdc = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+        let known: HashMap<String, String> = [("USDC".to_string(), usdc.to_string())].into();
+        assert_eq!(impersonated_ticker("usdc ", "FakeUsdc1111111111111111111111111111111111", &known), Some(usdc));
+        assert_eq!(impersonated_ticker("USDC", usdc, &known), None); // The real one
+        assert_eq!(impersonated_ticker("MOON", "FakeUsdc1111111111111111111111111111111111", &known), None);
+    }
+
+    #[test]
+    fn real_mints_never_get_a_random_test_scenario() {
+        let real_mint = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+        assert!(!test_scenario_allowed(real_mint, true));
+        assert!(!test_scenario_allowed(real_mint, false)); // Fails closed outside production too
+    }
+
+    #[test]
+    fn production_mode_disables_test_scenarios_even_for_sample_tokens() {
+        assert!(is_sample_address("SampleToken1"));
+        assert!(!test_scenario_allowed("SampleToken1", true));
+        assert!(!test_scenario_allowed("SampleTokenWithAVeryLongAddress1111111111", true));
+        assert!(test_scenario_allowed("SampleToken1", false));
+    }
+
+    #[test]
+    fn security_bar_at_several_thresholds() {
+        // A clean sweep clears every bar
+        for threshold in [0, 50, 80, 100] {
+            assert!(meets_security_bar(100, threshold, true));
+            assert!(meets_security_bar(100, threshold, false));
+        }
+        // One near miss: REQUIRE_ALL_CRITERIA refuses it whatever the threshold
+        for threshold in [0, 50, 80, 100] {
+            assert!(!meets_security_bar(92, threshold, true));
+        }
+        // Without it the threshold decides
+        assert!(meets_security_bar(92, 80, false));
+        assert!(meets_security_bar(80, 80, false));
+        assert!(!meets_security_bar(79, 80, false));
+        assert!(!meets_security_bar(92, 100, false));
+        assert!(meets_security_bar(0, 0, false));
+    }
+
+    #[test]
+    fn security_bar_is_described_by_its_mode() {
+        assert_eq!(security_bar_description(80, true), "all 6 criteria");
+        assert_eq!(security_bar_description(80, false), "score 80+");
+    }
+
+    /// Six criteria results earning `credits`, in evaluation order
+    fn credited(credits: [f64; 6]) -> Vec<CriteriaResult> {
+        credits.iter()
+            .map(|&credit| CriteriaResult { passed: credit >= 1.0, credit, message: String::new() })
+            .collect()
+    }
+
+    fn score(credits: [f64; 6], weights: &ScoreWeights) -> u8 {
+        weighted_security_score(&score_contributions(&credited(credits), weights))
+    }
+
+    #[test]
+    fn near_misses_earn_proportional_credit() {
+        assert_eq!(minimum_credit(68.0, 80.0), 0.85); // LP 68% burned against an 80% minimum
+        assert_eq!(minimum_credit(95.0, 80.0), 1.0);
+        assert_eq!(limit_credit(40.0, 30.0), 0.75); // Top-10 holding 40% against a 30% cap
+        assert_eq!(limit_credit(20.0, 30.0), 1.0);
+        assert_eq!(minimum_credit(0.0, 0.0), 1.0);
+    }
+
+    #[test]
+    fn mixed_results_are_weighted_into_the_score() {
+        let weights = ScoreWeights::default();
+        assert_eq!(score([1.0; 6], &weights), 100);
+        // LP 68% of an 80% minimum loses 3 of its 20 points
+        let contributions = score_contributions(&credited([1.0, 1.0, 0.85, 1.0, 1.0, 1.0]), &weights);
+        assert_eq!(contributions[2].criterion, "LP burned/locked");
+        assert!((contributions[2].points - 17.0).abs() < 1e-9);
+        assert_eq!(contributions[2].max_points, 20.0);
+        assert_eq!(weighted_security_score(&contributions), 97);
+        // ...plus top holders at 0.75 of 15 points: 93.25
+        assert_eq!(score([1.0, 1.0, 0.85, 1.0, 0.75, 1.0], &weights), 93);
+        // Mint authority kept and the sell test failed
+        assert_eq!(score([0.0, 1.0, 1.0, 1.0, 1.0, 0.0], &weights), 70);
+        assert_eq!(score([0.0; 6], &weights), 0);
+    }
+
+    #[test]
+    fn weights_are_relative() {
+        // Equal weights of any size split the score six ways
+        let equal = ScoreWeights { mint_authority: 3.0, freeze_authority: 3.0, lp: 3.0, taxes: 3.0, top_holders: 3.0, can_sell: 3.0 };
+        assert_eq!(score([1.0, 1.0, 1.0, 1.0, 1.0, 0.0], &equal), 83);
+        // A zero weight makes a criterion irrelevant
+        let no_sell_test = ScoreWeights { can_sell: 0.0, ..ScoreWeights::default() };
+        assert_eq!(score([1.0, 1.0, 1.0, 1.0, 1.0, 0.0], &no_sell_test), 100);
+        let none = ScoreWeights { mint_authority: 0.0, freeze_authority: 0.0, lp: 0.0, taxes: 0.0, top_holders: 0.0, can_sell: 0.0 };
+        assert_eq!(score([1.0; 6], &none), 0);
+    }
+
+    #[test]
+    fn only_a_clean_sweep_scores_100() {
+        assert_eq!(score([1.0, 1.0, 1.0, 1.0, 1.0, 0.9999], &ScoreWeights::default()), 99);
+    }
+}
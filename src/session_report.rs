@@ -0,0 +1,163 @@
+// Session report - the end-of-run debrief printed and sent on graceful shutdown
+use crate::pool_scanner::{PoolScannerStats, PositionKind};
+use crate::profit_monitor::format_duration;
+use crate::storage::{TradeRecord, TradeSide};
+use std::time::Duration;
+
+/// One closed trade's result, for the best/worst lines
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClosedTrade {
+    pub token_address: String,
+    pub pnl_sol: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionReport {
+    pub uptime_seconds: u64,
+    pub total_scans: u64,
+    pub pools_seen: usize,
+    pub buys: usize,
+    pub sells: usize,
+    pub write_offs: usize,
+    pub open_positions: usize,
+    pub realized_pnl_sol: f64,
+    pub estimated_fees_sol: f64, // Network/priority fees and tips for on-chain swaps; paper trades pay none
+    pub best_trade: Option<ClosedTrade>,
+    pub worst_trade: Option<ClosedTrade>,
+}
+
+/// Aggregate the session's trades (already limited to this run) with the scanner's counters.
+/// `fee_per_swap_sol` is charged once per on-chain buy or sell.
+pub fn session_report(stats: &PoolScannerStats, trades: &[TradeRecord], fee_per_swap_sol: f64) -> SessionReport {
+    let mut report = SessionReport {
+        uptime_seconds: stats.uptime_seconds,
+        total_scans: stats.total_scans,
+        pools_seen: stats.pools_processed,
+        buys: 0,
+        sells: 0,
+        write_offs: 0,
+        open_positions: stats.active_positions,
+        realized_pnl_sol: 0.0,
+        estimated_fees_sol: 0.0,
+        best_trade: None,
+        worst_trade: None,
+    };
+    for trade in trades {
+        match trade.side {
+            TradeSide::Buy => report.buys += 1,
+            TradeSide::Sell => report.sells += 1,
+            TradeSide::WriteOff => report.write_offs += 1,
+        }
+        if trade.side != TradeSide::WriteOff && trade.kind == PositionKind::Real {
+            report.estimated_fees_sol += fee_per_swap_sol;
+        }
+        let Some(pnl_sol) = trade.pnl_sol else {
+            continue;
+        };
+        report.realized_pnl_sol += pnl_sol;
+        let result = ClosedTrade { token_address: trade.token_address.clone(), pnl_sol };
+        if report.best_trade.as_ref().is_none_or(|best| pnl_sol > best.pnl_sol) {
+            report.best_trade = Some(result.clone());
+        }
+        if report.worst_trade.as_ref().is_none_or(|worst| pnl_sol < worst.pnl_sol) {
+            report.worst_trade = Some(result);
+        }
+    }
+    report
+}
+
+impl SessionReport {
+    fn lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("⏱️ Uptime: {}", format_duration(Duration::from_secs(self.uptime_seconds))),
+            format!("🔍 Scans: {} | Pools seen: {}", self.total_scans, self.pools_seen),
+            format!("🛒 Buys: {} | Sells: {} | Write-offs: {} | Still open: {}", self.buys, self.sells, self.write_offs, self.open_positions),
+            format!("💰 Realized P&L: {:+.4} SOL", self.realized_pnl_sol),
+            format!("⛽ Fees paid (est.): {:.4} SOL", self.estimated_fees_sol),
+        ];
+        if let Some(best) = &self.best_trade {
+            lines.push(format!("🏆 Best: {} {:+.4} SOL", best.token_address, best.pnl_sol));
+        }
+        if let Some(worst) = &self.worst_trade {
+            lines.push(format!("📉 Worst: {} {:+.4} SOL", worst.token_address, worst.pnl_sol));
+        }
+        lines
+    }
+
+    pub fn display(&self) {
+        println!("\n📋 SESSION SUMMARY");
+        println!("{}", "=".repeat(50));
+        for line in self.lines() {
+            println!("   {}", line);
+        }
+    }
+
+    /// Telegram message - the bookend to the "scanner started" alert
+    pub fn telegram_message(&self) -> String {
+        format!("🛑 <b>SNIPER STOPPED - SESSION SUMMARY</b>\n{}", self.lines().join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc_limiter::RpcUsage;
+
+    fn stats() -> PoolScannerStats {
+        PoolScannerStats {
+            total_scans: 240,
+            pools_processed: 57,
+            active_positions: 1,
+            uptime_seconds: 3_725,
+            rpc_usage: RpcUsage { max_requests_per_second: 10, total_requests: 0, throttled_requests: 0, total_wait_ms: 0 },
+            avg_time_to_fill_ms: None,
+            avg_realized_slippage_percent: None,
+        }
+    }
+
+    fn trade(token: &str, side: TradeSide, kind: PositionKind, pnl_sol: Option<f64>) -> TradeRecord {
+        TradeRecord {
+            timestamp_unix_secs: 1_800_000_000,
+            token_address: token.to_string(),
+            side,
+            kind,
+            sol_amount: 0.1,
+            token_amount: 1_000,
+            pnl_sol,
+            signature: None,
+        }
+    }
+
+    #[test]
+    fn summary_aggregates_the_session_trades() {
+        let trades = vec![
+            trade("mintA", TradeSide::Buy, PositionKind::Real, None),
+            trade("mintA", TradeSide::Sell, PositionKind::Real, Some(0.05)),
+            trade("mintB", TradeSide::Buy, PositionKind::Real, None),
+            trade("mintB", TradeSide::WriteOff, PositionKind::Real, Some(-0.1)),
+            trade("mintC", TradeSide::Buy, PositionKind::Simulated, None),
+            trade("mintC", TradeSide::Sell, PositionKind::Simulated, Some(0.02)),
+            trade("mintD", TradeSide::Buy, PositionKind::Real, None), // Still open
+        ];
+        let report = session_report(&stats(), &trades, 0.001);
+        assert_eq!((report.uptime_seconds, report.total_scans, report.pools_seen), (3_725, 240, 57));
+        assert_eq!((report.buys, report.sells, report.write_offs, report.open_positions), (4, 2, 1, 1));
+        assert!((report.realized_pnl_sol - (-0.03)).abs() < 1e-12);
+        // Three real buys and one real sell paid fees; the write-off and the simulated trades didn't
+        assert!((report.estimated_fees_sol - 0.004).abs() < 1e-12);
+        assert_eq!(report.best_trade, Some(ClosedTrade { token_address: "mintA".to_string(), pnl_sol: 0.05 }));
+        assert_eq!(report.worst_trade, Some(ClosedTrade { token_address: "mintB".to_string(), pnl_sol: -0.1 }));
+
+        let message = report.telegram_message();
+        assert!(message.contains("Buys: 4 | Sells: 2 | Write-offs: 1 | Still open: 1"));
+        assert!(message.contains("Realized P&L: -0.0300 SOL"));
+    }
+
+    #[test]
+    fn quiet_session_has_no_best_or_worst_trade() {
+        let report = session_report(&stats(), &[], 0.001);
+        assert_eq!((report.buys, report.sells, report.realized_pnl_sol, report.estimated_fees_sol), (0, 0, 0.0, 0.0));
+        assert!(report.best_trade.is_none() && report.worst_trade.is_none());
+        assert!(!report.telegram_message().contains("Best:"));
+    }
+}
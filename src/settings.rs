@@ -1,10 +1,21 @@
 // Centralized Settings Manager for Solana Token Sniper Bot
 // Production-ready configuration with only working APIs and automatic propagation
 
+use std::collections::HashMap;
 use std::env;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 use serde::{Deserialize, Serialize};
 use once_cell::sync::Lazy;
+use crate::telegram::{parse_explorer_links, unknown_placeholders, ExplorerLink, TemplateKind, DEFAULT_EXPLORER_LINKS};
+use crate::private_relay::PRIVATE_TX_PROVIDERS;
+use crate::lamports::Lamports;
+use crate::copy_trading::{CopyTarget, parse_copy_targets};
+use crate::wallet::parse_commitment;
+use crate::units::FALLBACK_SOL_PRICE_USD;
+use crate::rugcheck::SecondaryCheckMode;
+use crate::trading_hours::parse_trading_hours;
+use crate::profit_monitor::{TrailRung, parse_trail_rungs};
 
 // Global settings instance for automatic propagation
 static GLOBAL_SETTINGS: Lazy<Arc<RwLock<Option<BotSettings>>>> = Lazy::new(|| {
@@ -58,8 +69,15 @@ pub struct WorkingApiSettings {
     pub jupiter_enabled: bool,
     pub jupiter_v2_recent_url: String,
     pub jupiter_v1_all_url: String,
-    pub jupiter_quote_api: String,
+    pub jupiter_tier: String,          // "lite" (free, rate limited) or "paid" (api.jup.ag, needs JUPITER_API_KEY)
+    pub jupiter_base_url: String,      // Empty = the tier's default host
+    pub jupiter_api_key: String,       // Never defaulted; required on the paid tier
+    pub jupiter_send_api_key: bool,    // Send X-API-KEY (defaults to on for paid, off for lite)
+    pub jupiter_quote_api: String,     // Full quote URL override; empty = derived from the base URL
+    pub jupiter_swap_api: String,      // Full swap URL override; empty = derived from the base URL
     pub jupiter_timeout_ms: u64,
+    pub jupiter_fee_account: Option<String>, // Referral wSOL token account for platform fees (None = off)
+    pub jupiter_platform_fee_bps: u16,
     
     // RugCheck API (CONFIRMED WORKING)
     pub rugcheck_enabled: bool,
@@ -100,6 +118,19 @@ pub struct PremiumEndpointSettings {
     pub grpc_enabled: bool,
     pub grpc_endpoint: String,
     pub grpc_token: String,
+    
+    // Birdeye (API KEY REQUIRED) - new token listings and price fallback
+    pub birdeye_enabled: bool,
+    pub birdeye_api_key: String,
+    
+    // Jito block engine - buys/sells submitted as tipped bundles, RPC fallback
+    pub jito_enabled: bool,
+    pub jito_block_engine_url: String,
+    pub jito_tip_lamports: u64,
+    
+    // Private submission - swaps go to a relay (zeroslot / nozomi above) instead of the public mempool
+    pub private_tx_enabled: bool,
+    pub private_tx_provider: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -129,32 +160,97 @@ pub struct PerformanceSettings {
 pub struct WalletSettings {
     pub private_key: String,
     pub min_balance_sol: f64,
+    pub reserve_fee_buffer_sol: f64,   // Kept liquid for fees on top of min_balance_sol; every SOL-spending send must leave both
     pub rpc_url: String,
     pub backup_rpc_urls: Vec<String>,
+    pub rpc_max_requests_per_second: u32, // Shared by trader, wallet and RugCheck; 0 = unlimited
     pub commitment: String,
-    pub confirmation_timeout_ms: u64,
+    pub balance_commitment: String,    // Balance guard reads: processed, confirmed or finalized
+    pub confirmation_timeout_ms: u64,  // Wait per swap send before checking whether its blockhash expired
     pub max_retries: u32,
     pub priority_fee_micro_lamports: u64,
     pub compute_unit_limit: u32,
     pub compute_unit_price: u64,
+    pub sell_priority_fee_micro_lamports: u64, // Compute unit price for sells, so exits outbid entries; 0 = COMPUTE_UNIT_PRICE
+    pub persistent_wsol: bool,         // Keep one WSOL account instead of wrapping/unwrapping per swap
+    pub wsol_top_up_sol: f64,          // Wrap at least this much whenever the WSOL account runs short
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradingSettings {
     // Position management
     pub position_size_sol: f64,
+    pub min_position_size_sol: f64,    // Buys shrink to fit the balance after reserve and fees, but never below this
+    pub funding_mint: String,          // Mint buys are paid in (wrapped SOL by default; e.g. USDC), sized at position_size_sol worth
+    pub sell_to_funding_mint: bool,    // Sell back into the funding mint instead of SOL
     pub max_positions: u8,
+    pub max_token_allocation_percent: f64, // One token's share of capital (at least max_positions x position size); 0 = off
+    pub min_position_tokens: u64,      // Fills below this many raw tokens aren't tracked (a zero fill would poison P&L)
+    pub min_seconds_between_buys: u64, // Pools arriving sooner after the last buy are deferred; 0 = no spacing
+    pub max_concurrent_buys: usize,    // Buys allowed in flight at once; more are skipped until one resolves
+    pub sell_cooldown_secs: u64,       // A mint is ignored this long after any sell of it, win or loss; 0 = off
+    pub buy_spacing_per_source: bool,  // Space buys per detection source instead of globally
+    pub max_daily_trades: u32,         // Buys per UTC day (sells don't count); 0 = unlimited
+    pub compound_enabled: bool,        // Grow the buy size from POSITION_SIZE_SOL as realized profits accumulate
+    pub compound_step_percent: f64,    // Size increase per step
+    pub compound_wins_per_step: u32,   // Profitable closes that earn a step (a step is undone once realized P&L falls below where its run began)
+    pub max_position_size_sol: f64,    // Compounding never grows the buy size past this
+    pub trading_hours: String,         // Hours new buys are allowed, e.g. "9-17,22-2"; empty = always (positions are managed 24/7)
+    pub trading_hours_utc_offset: f64, // Hours TRADING_HOURS is ahead of UTC (e.g. -5, 5.5); 0 = UTC
     pub min_liquidity_sol: f64,
     pub max_slippage_percent: f64,
+    pub initial_slippage_percent: f64, // Buys start here and widen toward max_slippage_percent on slippage failures
+    pub slippage_escalation_attempts: u32, // Total buy attempts including the first
+    pub confirm_attempts: u32,          // Sends per swap; an expired send is rebuilt with a fresh blockhash
+    pub quote_attempts: u32,            // Buy quotes while Jupiter is temporarily unavailable ("no route" follows NO_ROUTE_RETRY_SECS)
+    pub quote_retry_delay_ms: u64,      // First re-quote wait; the Nth waits N times this
+    pub no_route_retry_secs: u64,       // Keep re-quoting a buy Jupiter can't route yet for this long; 0 = skip at once
+    pub no_route_retry_interval_ms: u64, // Wait between those re-quotes
+    pub require_manual_approval: bool,  // Ask via Telegram Approve/Reject buttons before every buy
+    pub manual_approval_timeout_secs: u64, // No answer in this window = reject
     pub enable_auto_trading: bool,
+    pub signals_only: bool,             // With auto-trading off: alert tokens that pass every check instead of buying
+    pub dry_run_paper_positions: bool,  // start --dry-run also tracks would-be buys as in-memory paper positions
+    pub max_round_trip_cost_percent: f64, // Reject when buying then instantly selling would lose more than this; 0 = off
+    pub copy_trade_wallets: Vec<CopyTarget>, // Wallets whose buys are copied (source "copy:<wallet>"); empty = off
+    pub copy_trade_poll_interval_ms: u64, // How often followed wallets are checked for new buys
     
     // Risk management (EXACT USER SPECIFICATIONS)
     pub stop_loss_percent: f64,        // -50% stop loss
     pub trailing_stop_enabled: bool,   // Enable trailing stop
     pub trailing_stop_percent: f64,    // -30% trailing stop
+    pub trailing_activation_percent: f64, // Trailing stop arms once up this much
+    pub trailing_stop_rungs: Vec<TrailRung>, // Tighter trails past higher peak gains; empty = trailing_stop_percent throughout
+    pub exit_mode: String,             // "trailing" (trailing stop) or "momentum" (scale out when a rally stalls)
+    pub momentum_lower_updates: u32,   // Momentum: consecutive lower price updates after a new high that trigger a scale-out
+    pub momentum_sell_percent: f64,    // Momentum: share of the position sold per scale-out
     pub profit_threshold_percent: f64, // +50% take profit
     pub sell_percentage: f64,          // Sell 75% at profit
     pub max_hold_time_hours: u32,      // Max 24 hours
+    pub source_max_hold_minutes: HashMap<String, u64>, // Detection source prefix -> hold time override
+    pub min_timeout_sell_profit_sol: f64,    // Max-hold sells projected to net less than this (after fees) are deferred...
+    pub timeout_grace_minutes: u64,          // ...this long at a time...
+    pub max_timeout_extension_minutes: u64,  // ...up to this much in total; 0 = always sell on time
+    pub portfolio_take_profit_sol: f64,     // Day's P&L (realized + open) at which everything is sold and buys pause; 0 = off
+    pub portfolio_take_profit_percent: f64, // Same, as % gain on the open positions; 0 = off
+    
+    // Entry confirmation window
+    pub entry_delay_ms: u64,           // 0 = buy immediately
+    pub entry_max_price_rise_percent: f64, // Abort if price rose more than this during the delay
+    pub max_entry_price_usd: f64,      // Don't buy above this price; 0 = no limit
+    pub token_max_entry_price_usd: HashMap<String, f64>, // Mint -> price limit, overriding the global one
+    
+    // Hard floor on fills, on top of Jupiter slippage
+    pub min_out_tolerance_percent: f64, // Reject quotes guaranteeing less than expected minus this; 0 = disabled
+    pub min_routes: u32,                // Skip buys whose best Jupiter route has fewer legs (1 = any route)
+    pub max_price_impact_percent: f64,  // Skip buys whose quote moves the price more than this; 0 = no limit
+    pub requote_before_send_min_sol: f64, // Buys at least this size re-check price impact on a fresh quote right before sending; 0 = off
+    
+    // Exit liquidity - don't dump into a drained pool
+    pub min_sell_liquidity_sol: f64,    // Auto-sells check pool liquidity first; 0 = disabled
+    pub dust_threshold_usd: f64,        // Balances worth less count as sold: no swap, no scale-out remainder; 0 = only empty balances
+    pub hold_on_low_liquidity: bool,    // true = hold and retry, then write off; false = sell anyway
+    pub low_liquidity_hold_minutes: u64, // How long a held position waits for liquidity before it's written off as rugged
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -164,12 +260,29 @@ pub struct SecuritySettings {
     pub high_confidence_score: u8,     // 85+
     pub medium_confidence_score: u8,   // 70+
     pub require_rugcheck_success: bool,
+    pub require_all_criteria: bool,    // Buy only when every criterion passes (the weighted score is 100 only then)
+    pub score_weight_mint_authority: f64, // Relative weights of the six criteria in the 0-100 score
+    pub score_weight_freeze_authority: f64,
+    pub score_weight_lp: f64,
+    pub score_weight_taxes: f64,
+    pub score_weight_top_holders: f64,
+    pub score_weight_can_sell: f64,
+    pub production_mode: bool,         // No sample tokens or random test scenarios - missing data = reject
+    pub rugcheck_fetch_max_attempts: u32,
+    pub rugcheck_fetch_retry_delay_ms: u64, // Doubles after each failed attempt
+    pub secondary_rugcheck_mode: String,    // rugcheck.xyz second opinion: "off", "both" (it must reach the min score too) or "combined" (the average must, instead of the primary bar)
+    pub secondary_rugcheck_min_score: u8,
     
     // Liquidity analysis
     pub enable_liquidity_checks: bool,
     pub min_liquidity_lock_percentage: f64,
     pub min_total_liquidity_usd: f64,
     pub max_dev_wallet_percentage: f64,
+    pub max_liquidity_disagreement_factor: f64, // Reject when indexer and on-chain SOL reserves differ by more than this (0 = off)
+    pub max_pools_per_token: u32,          // Reject tokens spread over more pools than this... (0 = off)
+    pub min_main_pool_liquidity_usd: f64,  // ...unless one of them holds at least this much
+    pub min_sol_pairs: u32,                // Require this many SOL-paired pools with liquidity (1 = the detected pool is enough)...
+    pub min_primary_sol_pair_liquidity_usd: f64, // ...unless one SOL pair holds at least this much (0 = no exemption)
     
     // Authority verification
     pub enable_authority_checks: bool,
@@ -177,10 +290,32 @@ pub struct SecuritySettings {
     pub reject_freeze_authority: bool,
     pub allow_mutable_metadata: bool,
     
+    // Honeypot probe: simulate selling what a buy just received
+    pub post_buy_sell_probe: bool,
+    
+    // Re-check held tokens for delayed rugs (authority re-enabled, liquidity pulled)
+    pub position_recheck_interval_secs: u64, // 0 = disabled
+    pub position_recheck_max_liquidity_drop_percent: f64, // Exit when liquidity falls this far below the first re-check
+    
+    // Name/symbol filtering ("re:" prefix = regex, otherwise substring; case-insensitive)
+    pub name_blacklist_patterns: Vec<String>,
+    pub known_tickers: HashMap<String, String>, // Uppercase symbol -> canonical mint
+    pub avoided_pair_mints: Vec<String>, // Stablecoins / wrapped majors never worth sniping
+    
     // Holder distribution
     pub enable_holder_checks: bool,
     pub max_top_holder_percentage: f64,
     pub min_holder_count: u32,
+    pub holder_check_min_pool_age_minutes: u64, // Younger pools legitimately have few holders
+    
+    // Token age from the mint's first transaction, not pool creation (0 = no limit)
+    pub min_token_age_minutes: u64,
+    pub max_token_age_minutes: u64,
+    
+    // Creator reputation: the wallet that created the token (fee payer of the mint's first transaction)
+    pub enable_creator_checks: bool,
+    pub min_creator_wallet_age_minutes: u64, // Reject creators whose wallet was younger than this at launch
+    pub creator_blacklist_path: String,      // Known rugger wallets, one per line (# comments); missing file = empty
     
     // Risk tolerance
     pub auto_reject_critical_risks: bool,
@@ -200,14 +335,36 @@ pub struct TelegramSettings {
     pub send_profit_summaries: bool,
     pub send_error_alerts: bool,
     pub send_rugcheck_alerts: bool,
+    pub error_alert_dedup_window_secs: u64, // Identical errors sent at most once per window
+    
+    // Custom alert layouts with {placeholders}; empty = built-in layout
+    pub buy_template: String,
+    pub sell_template: String,
+    pub profit_template: String,
+    pub explorer_links: Vec<ExplorerLink>, // Linked from buy, sell and signal alerts
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MonitoringSettings {
     pub scan_interval_seconds: u64,    // Pool scanning frequency
     pub price_check_interval_ms: u64,  // Position monitoring
+    pub concurrent_position_monitoring: bool, // Check positions on a dedicated task instead of between scans
+    pub dexscreener_interval_ms: u64,  // New pool discovery (tolerates fast polling)
+    pub scan_error_backoff_base_ms: u64, // First retry delay after a failed scan, doubled per failure
+    pub scan_error_backoff_max_ms: u64,
+    pub scan_degraded_alert_threshold: u32, // Consecutive failures before a "scanner degraded" alert
+    pub sol_price_interval_ms: u64,    // CoinGecko SOL price refresh (rate limited)
+    pub sol_price_retries: u32,        // Extra CoinGecko attempts before falling back to Jupiter
+    pub fallback_sol_price_usd: f64,   // Last resort when no SOL price was ever fetched (logged loudly)
     pub position_update_interval_ms: u64,
     pub health_check_interval_minutes: u32,
+    pub price_cache_ttl_secs: u64,     // Cached token prices older than this are evicted (and no longer a stale fallback)
+    pub max_alert_history: usize,      // Profit alerts kept in memory for the status report
+    pub stale_pricing_alert_secs: u64, // Positions without a fresh price for this long count as stale; 0 = no watchdog
+    pub stale_pricing_alert_fraction: f64, // Alert once more than this share of positions is stale
+    pub decision_trace_enabled: bool,  // Log why every scanned pool was or wasn't bought
+    pub decision_trace_path: String,
+    pub decision_trace_mints: Vec<String>, // Mints to report as "never detected" if no source sees them
     
     // Logging
     pub log_level: String,
@@ -215,10 +372,25 @@ pub struct MonitoringSettings {
     pub log_file_path: String,
     pub save_analysis_results: bool,
     pub analysis_results_path: String,
+    pub health_snapshot_path: String,
+    pub health_snapshot_interval_secs: u64,
+    pub trade_journal_path: String, // Pending buys, reconciled on startup
+    pub daily_trades_path: String,  // Today's buy count for MAX_DAILY_TRADES
+    pub compound_state_path: String, // Auto-compounding steps, so a restart keeps the grown buy size
+    pub portfolio_history_path: String, // Append-only JSON lines, one portfolio summary per 15 minutes
+    pub storage_backend: String, // "json" or "sqlite" (needs --features sqlite): open positions, trades, processed pools
+    pub storage_path: String,    // Directory for json, database file for sqlite
+    pub create_data_dirs: bool,  // Create missing log/data directories on startup
+    pub webhook_url: String,     // POST a JSON event on every buy/sell/write-off/reject; empty = disabled
+    pub webhook_bearer_token: String, // Optional Authorization: Bearer token for WEBHOOK_URL
     
     // Real-time features
     pub enable_real_time_alerts: bool,
     pub max_new_tokens_per_scan: u32,
+    pub max_candidates_per_scan: u32, // Qualifying pools each detection source returns per scan, newest first
+    pub allow_missing_creation_time: bool, // Keep DexScreener pairs without pairCreatedAt as unknown age instead of dropping them
+    pub skip_inactive_pools: bool,          // Drop DexScreener pairs with zero 5m and 1h volume - liquidity but nobody trading
+    pub inactive_pool_min_age_minutes: u64, // Pairs younger than this haven't had time to trade and are never judged inactive
 }
 
 impl BotSettings {
@@ -261,29 +433,206 @@ impl BotSettings {
         })
     }
     
+    /// Directories the enabled features write into: the log and analysis directories, the
+    /// storage directory, and the parents of every data file. Deduplicated, in a stable order.
+    pub fn data_directories(&self) -> Vec<PathBuf> {
+        let monitoring = &self.monitoring;
+        let parent = |file: &str| Path::new(file).parent().map(Path::to_path_buf);
+        let mut directories = vec![
+            parent(&monitoring.health_snapshot_path),
+            parent(&monitoring.trade_journal_path),
+            parent(&monitoring.daily_trades_path),
+            parent(&monitoring.compound_state_path),
+            parent(&monitoring.portfolio_history_path),
+            if monitoring.storage_backend == "json" {
+                Some(PathBuf::from(&monitoring.storage_path))
+            } else {
+                parent(&monitoring.storage_path)
+            },
+        ];
+        if monitoring.log_to_file {
+            directories.push(parent(&monitoring.log_file_path));
+        }
+        if monitoring.save_analysis_results {
+            directories.push(Some(PathBuf::from(&monitoring.analysis_results_path)));
+        }
+        if monitoring.decision_trace_enabled {
+            directories.push(parent(&monitoring.decision_trace_path));
+        }
+        let mut unique = Vec::new();
+        for directory in directories.into_iter().flatten().filter(|dir| !dir.as_os_str().is_empty()) {
+            if !unique.contains(&directory) {
+                unique.push(directory);
+            }
+        }
+        unique
+    }
+    
     /// Validate all settings
     pub fn validate(&self) -> Result<(), String> {
         // Validate wallet
         if self.wallet.private_key.is_empty() {
             return Err("❌ SOLANA_PRIVATE_KEY is required".to_string());
         }
+        if let Err(e) = crate::wallet::parse_keypair(&self.wallet.private_key) {
+            return Err(format!("❌ SOLANA_PRIVATE_KEY is invalid: {}", e));
+        }
         
         if self.wallet.min_balance_sol < 0.01 {
             return Err("❌ MIN_WALLET_BALANCE_SOL must be at least 0.01 SOL".to_string());
         }
+        if self.wallet.reserve_fee_buffer_sol < 0.0 || self.wallet.reserve_fee_buffer_sol.is_nan() {
+            return Err("❌ RESERVE_FEE_BUFFER_SOL must be 0 or more".to_string());
+        }
         
         // Validate trading settings (EXACT USER REQUIREMENTS)
         if self.trading.position_size_sol <= 0.0 {
             return Err("❌ POSITION_SIZE_SOL must be greater than 0".to_string());
         }
         
+        if self.trading.min_position_size_sol <= 0.0 || self.trading.min_position_size_sol > self.trading.position_size_sol {
+            return Err("❌ MIN_POSITION_SIZE_SOL must be greater than 0 and at most POSITION_SIZE_SOL".to_string());
+        }
+        
+        if self.trading.compound_enabled {
+            if self.trading.compound_step_percent <= 0.0 || self.trading.compound_step_percent.is_nan() {
+                return Err("❌ COMPOUND_STEP_PERCENT must be greater than 0".to_string());
+            }
+            if self.trading.compound_wins_per_step == 0 {
+                return Err("❌ COMPOUND_WINS_PER_STEP must be at least 1".to_string());
+            }
+            if self.trading.max_position_size_sol < self.trading.position_size_sol || self.trading.max_position_size_sol.is_nan() {
+                return Err("❌ MAX_POSITION_SIZE_SOL must be at least POSITION_SIZE_SOL".to_string());
+            }
+        }
+        
+        if self.trading.max_concurrent_buys == 0 {
+            return Err("❌ MAX_CONCURRENT_BUYS must be at least 1".to_string());
+        }
+        
+        if let Err(e) = parse_trading_hours(&self.trading.trading_hours) {
+            return Err(format!("❌ TRADING_HOURS is invalid: {}", e));
+        }
+        
+        if !(-12.0..=14.0).contains(&self.trading.trading_hours_utc_offset) {
+            return Err("❌ TRADING_HOURS_UTC_OFFSET must be between -12 and 14".to_string());
+        }
+        
+        if !(0.0..=100.0).contains(&self.trading.max_token_allocation_percent) {
+            return Err("❌ MAX_TOKEN_ALLOCATION_PERCENT must be between 0 and 100".to_string());
+        }
+        
+        if self.trading.portfolio_take_profit_sol < 0.0 || self.trading.portfolio_take_profit_percent < 0.0 {
+            return Err("❌ PORTFOLIO_TAKE_PROFIT_SOL and PORTFOLIO_TAKE_PROFIT_PERCENT cannot be negative".to_string());
+        }
+        
+        if !self.trading.min_timeout_sell_profit_sol.is_finite() {
+            return Err("❌ MIN_TIMEOUT_SELL_PROFIT_SOL must be a number".to_string());
+        }
+        if self.trading.max_timeout_extension_minutes > 0 && self.trading.timeout_grace_minutes == 0 {
+            return Err("❌ TIMEOUT_GRACE_MINUTES must be at least 1 when MAX_TIMEOUT_EXTENSION_MINUTES is set".to_string());
+        }
+        
+        if self.trading.min_position_tokens == 0 {
+            return Err("❌ MIN_POSITION_TOKENS must be at least 1".to_string());
+        }
+        
+        if self.trading.funding_mint.parse::<solana_sdk::pubkey::Pubkey>().is_err() {
+            return Err(format!("❌ FUNDING_MINT is not a valid mint address: {}", self.trading.funding_mint));
+        }
+        
+        if self.trading.max_entry_price_usd < 0.0 || self.trading.token_max_entry_price_usd.values().any(|&price| price <= 0.0) {
+            return Err("❌ MAX_ENTRY_PRICE_USD cannot be negative and TOKEN_MAX_ENTRY_PRICES_USD limits must be greater than 0".to_string());
+        }
+        
+        if parse_commitment(&self.wallet.balance_commitment).is_none() {
+            return Err("❌ BALANCE_COMMITMENT must be processed, confirmed or finalized".to_string());
+        }
+        
+        if self.wallet.persistent_wsol && self.wallet.wsol_top_up_sol <= 0.0 {
+            return Err("❌ WSOL_TOP_UP_SOL must be greater than 0 when PERSISTENT_WSOL is enabled".to_string());
+        }
+        
+        if self.trading.max_price_impact_percent < 0.0 || self.trading.max_price_impact_percent.is_nan() {
+            return Err("❌ MAX_PRICE_IMPACT_PERCENT must be 0 (no limit) or more".to_string());
+        }
+        if self.trading.requote_before_send_min_sol < 0.0 || self.trading.requote_before_send_min_sol.is_nan() {
+            return Err("❌ REQUOTE_BEFORE_SEND_MIN_SOL must be 0 (off) or more".to_string());
+        }
+        if self.trading.requote_before_send_min_sol > 0.0 && self.trading.max_price_impact_percent <= 0.0 {
+            return Err("❌ REQUOTE_BEFORE_SEND_MIN_SOL needs a MAX_PRICE_IMPACT_PERCENT to check the fresh quote against".to_string());
+        }
+        
+        if self.trading.confirm_attempts == 0 {
+            return Err("❌ CONFIRM_ATTEMPTS must be at least 1".to_string());
+        }
+        
+        if self.trading.quote_attempts == 0 {
+            return Err("❌ QUOTE_ATTEMPTS must be at least 1".to_string());
+        }
+        if self.trading.no_route_retry_secs > 0 && self.trading.no_route_retry_interval_ms == 0 {
+            return Err("❌ NO_ROUTE_RETRY_INTERVAL_MS must be at least 1 when NO_ROUTE_RETRY_SECS is set".to_string());
+        }
+        
+        if self.trading.min_sell_liquidity_sol < 0.0 {
+            return Err("❌ MIN_SELL_LIQUIDITY_SOL cannot be negative".to_string());
+        }
+        
+        if !(0.0..1.0).contains(&self.monitoring.stale_pricing_alert_fraction) {
+            return Err("❌ STALE_PRICING_ALERT_FRACTION must be at least 0 and below 1".to_string());
+        }
+        
+        if !(self.monitoring.fallback_sol_price_usd > 0.0 && self.monitoring.fallback_sol_price_usd.is_finite()) {
+            return Err("❌ FALLBACK_SOL_PRICE_USD must be greater than 0".to_string());
+        }
+        
+        if self.monitoring.max_candidates_per_scan == 0 {
+            return Err("❌ MAX_CANDIDATES_PER_SCAN must be at least 1".to_string());
+        }
+        
+        if self.trading.dust_threshold_usd < 0.0 || self.trading.dust_threshold_usd.is_nan() {
+            return Err("❌ DUST_THRESHOLD_USD cannot be negative".to_string());
+        }
+        
+        if self.security.position_recheck_max_liquidity_drop_percent <= 0.0 || self.security.position_recheck_max_liquidity_drop_percent > 100.0 {
+            return Err("❌ POSITION_RECHECK_MAX_LIQUIDITY_DROP_PERCENT must be between 0 and 100".to_string());
+        }
+        
+        if self.trading.initial_slippage_percent <= 0.0 || self.trading.max_slippage_percent <= 0.0 {
+            return Err("❌ INITIAL_SLIPPAGE_PERCENT and MAX_SLIPPAGE_PERCENT must be greater than 0".to_string());
+        }
+        // The floor is compared with Jupiter's slippage-adjusted minimum, so at or below the slippage it rejects every buy
+        if self.trading.min_out_tolerance_percent > 0.0 && self.trading.min_out_tolerance_percent <= self.trading.max_slippage_percent {
+            return Err(format!("❌ MIN_OUT_TOLERANCE_PERCENT ({}) must be above MAX_SLIPPAGE_PERCENT ({}) or 0 (disabled)",
+                self.trading.min_out_tolerance_percent, self.trading.max_slippage_percent));
+        }
+        
         if self.trading.stop_loss_percent != 50.0 {
             return Err("❌ STOP_LOSS_PERCENT should be 50.0 as requested".to_string());
         }
         
+        match self.trading.exit_mode.as_str() {
+            "trailing" => {}
+            "momentum" => {
+                if self.trading.momentum_lower_updates == 0 {
+                    return Err("❌ MOMENTUM_LOWER_UPDATES must be at least 1".to_string());
+                }
+                if self.trading.momentum_sell_percent <= 0.0 || self.trading.momentum_sell_percent > 100.0 {
+                    return Err("❌ MOMENTUM_SELL_PERCENT must be greater than 0 and at most 100".to_string());
+                }
+            }
+            other => return Err(format!("❌ EXIT_MODE must be trailing or momentum, got: {}", other)),
+        }
+        
         if self.trading.trailing_stop_percent != 30.0 {
             return Err("❌ TRAILING_STOP_PERCENT should be 30.0 as requested".to_string());
         }
+        if let Some(rung) = self.trading.trailing_stop_rungs.iter().find(|rung| {
+            rung.profit_above_percent < 0.0 || rung.profit_above_percent.is_nan() || !(rung.trail_percent > 0.0 && rung.trail_percent <= 100.0)
+        }) {
+            return Err(format!("❌ TRAILING_STOP_RUNGS entries must be profit:trail with profit >= 0 and trail between 0 and 100, got: {}:{}",
+                rung.profit_above_percent, rung.trail_percent));
+        }
         
         if self.trading.profit_threshold_percent != 50.0 {
             return Err("❌ PROFIT_THRESHOLD_PERCENT should be 50.0 as requested".to_string());
@@ -298,6 +647,137 @@ impl BotSettings {
             return Err("❌ MIN_ACCEPTABLE_SCORE should be 70+ as requested".to_string());
         }
         
+        let liquidity_factor = self.security.max_liquidity_disagreement_factor;
+        if liquidity_factor != 0.0 && (liquidity_factor < 1.0 || liquidity_factor.is_nan()) {
+            return Err("❌ MAX_LIQUIDITY_DISAGREEMENT_FACTOR must be 0 (off) or at least 1.0".to_string());
+        }
+        
+        if self.security.max_pools_per_token > 0 && (self.security.min_main_pool_liquidity_usd < 0.0 || self.security.min_main_pool_liquidity_usd.is_nan()) {
+            return Err("❌ MIN_MAIN_POOL_LIQUIDITY_USD cannot be negative".to_string());
+        }
+        
+        if SecondaryCheckMode::parse(&self.security.secondary_rugcheck_mode).is_none() {
+            return Err(format!("❌ SECONDARY_RUGCHECK_MODE must be off, both or combined, got: {}", self.security.secondary_rugcheck_mode));
+        }
+        
+        if self.security.secondary_rugcheck_min_score > 100 {
+            return Err("❌ SECONDARY_RUGCHECK_MIN_SCORE must be at most 100".to_string());
+        }
+        
+        if self.security.min_sol_pairs == 0 {
+            return Err("❌ MIN_SOL_PAIRS must be at least 1".to_string());
+        }
+        
+        if self.security.min_primary_sol_pair_liquidity_usd < 0.0 || self.security.min_primary_sol_pair_liquidity_usd.is_nan() {
+            return Err("❌ MIN_PRIMARY_SOL_PAIR_LIQUIDITY_USD cannot be negative".to_string());
+        }
+        
+        if self.security.min_acceptable_score > 100 {
+            return Err("❌ MIN_ACCEPTABLE_SCORE must be at most 100 - no token could reach it".to_string());
+        }
+        
+        let weights = self.security.score_weights();
+        let weight_values = [weights.mint_authority, weights.freeze_authority, weights.lp, weights.taxes, weights.top_holders, weights.can_sell];
+        if weight_values.iter().any(|weight| !weight.is_finite() || *weight < 0.0) {
+            return Err("❌ SCORE_WEIGHT_* values must be zero or positive".to_string());
+        }
+        if weight_values.iter().sum::<f64>() <= 0.0 {
+            return Err("❌ At least one SCORE_WEIGHT_* must be positive".to_string());
+        }
+        
+        if let Err(e) = crate::rugcheck::NameBlacklist::new(&self.security.name_blacklist_patterns) {
+            return Err(format!("❌ NAME_BLACKLIST_PATTERNS contains an invalid regex: {}", e));
+        }
+        
+        for (symbol, mint) in &self.security.known_tickers {
+            if mint.parse::<solana_sdk::pubkey::Pubkey>().is_err() {
+                return Err(format!("❌ KNOWN_TICKERS entry {} has an invalid mint address: {}", symbol, mint));
+            }
+        }
+        
+        if !(0.0..=100.0).contains(&self.trading.max_round_trip_cost_percent) {
+            return Err("❌ MAX_ROUND_TRIP_COST_PERCENT must be between 0 (off) and 100".to_string());
+        }
+        
+        for target in &self.trading.copy_trade_wallets {
+            if target.wallet.parse::<solana_sdk::pubkey::Pubkey>().is_err() {
+                return Err(format!("❌ COPY_TRADE_WALLETS contains an invalid wallet address: {}", target.wallet));
+            }
+            if !(target.size_multiplier.is_finite() && target.size_multiplier > 0.0) {
+                return Err(format!("❌ COPY_TRADE_WALLETS multiplier for {} must be a positive number", target.wallet));
+            }
+        }
+        
+        if let Some(mint) = self.security.avoided_pair_mints.iter().find(|mint| mint.parse::<solana_sdk::pubkey::Pubkey>().is_err()) {
+            return Err(format!("❌ AVOIDED_PAIR_MINTS contains an invalid mint address: {}", mint));
+        }
+        
+        if let Some(mint) = self.monitoring.decision_trace_mints.iter().find(|mint| mint.parse::<solana_sdk::pubkey::Pubkey>().is_err()) {
+            return Err(format!("❌ DECISION_TRACE_MINTS contains an invalid mint address: {}", mint));
+        }
+        
+        match self.monitoring.storage_backend.as_str() {
+            "json" => {}
+            "sqlite" if cfg!(feature = "sqlite") => {}
+            "sqlite" => return Err("❌ STORAGE_BACKEND=sqlite needs a build with --features sqlite".to_string()),
+            other => return Err(format!("❌ STORAGE_BACKEND must be json or sqlite, got: {}", other)),
+        }
+        
+        if !self.monitoring.webhook_url.is_empty()
+            && !self.monitoring.webhook_url.starts_with("http://")
+            && !self.monitoring.webhook_url.starts_with("https://") {
+            return Err(format!("❌ WEBHOOK_URL must be an http(s) URL, got: {}", self.monitoring.webhook_url));
+        }
+        
+        for (setting, template, kind) in [
+            ("TELEGRAM_BUY_TEMPLATE", &self.telegram.buy_template, TemplateKind::Buy),
+            ("TELEGRAM_SELL_TEMPLATE", &self.telegram.sell_template, TemplateKind::Sell),
+            ("TELEGRAM_PROFIT_TEMPLATE", &self.telegram.profit_template, TemplateKind::Profit),
+        ] {
+            let unknown = unknown_placeholders(template, kind);
+            if !unknown.is_empty() {
+                return Err(format!("❌ {} has unknown placeholder(s) {{{}}} (allowed: {})",
+                    setting, unknown.join("}, {"), kind.placeholders().join(", ")));
+            }
+        }
+        
+        for link in &self.telegram.explorer_links {
+            if link.name.is_empty()
+                || !(link.url_template.starts_with("http://") || link.url_template.starts_with("https://"))
+                || !link.url_template.contains("{mint}") {
+                return Err(format!("❌ TELEGRAM_EXPLORER_LINKS entries must be name:url with an http(s) URL containing {{mint}}, got: {}:{}",
+                    link.name, link.url_template));
+            }
+        }
+        
+        if self.trading.require_manual_approval && !self.telegram.notifications_enabled {
+            return Err("❌ REQUIRE_MANUAL_APPROVAL needs Telegram notifications enabled".to_string());
+        }
+        
+        if self.trading.signals_only && !self.trading.enable_auto_trading && !self.telegram.notifications_enabled {
+            return Err("❌ SIGNALS_ONLY needs Telegram notifications enabled".to_string());
+        }
+        
+        if self.apis.premium_endpoints.jito_enabled && self.apis.premium_endpoints.jito_tip() < Lamports::new(1000) {
+            return Err("❌ JITO_TIP_LAMPORTS must be at least 1000 (Jito's minimum tip)".to_string());
+        }
+        
+        let premium = &self.apis.premium_endpoints;
+        if premium.private_tx_enabled {
+            if premium.jito_enabled {
+                return Err("❌ PRIVATE_TX_ENABLED and JITO_ENABLED are alternative submission paths - enable only one".to_string());
+            }
+            let credential = match premium.private_tx_provider.as_str() {
+                "zeroslot" => &premium.zeroslot_api_key,
+                "nozomi" => &premium.nozomi_uuid,
+                other => return Err(format!("❌ PRIVATE_TX_PROVIDER '{}' is not supported (use one of: {})",
+                    other, PRIVATE_TX_PROVIDERS.join(", "))),
+            };
+            if credential.is_empty() {
+                return Err(format!("❌ PRIVATE_TX_PROVIDER {} needs its API key / UUID configured", premium.private_tx_provider));
+            }
+        }
+        
         // Validate Telegram if enabled
         if self.telegram.notifications_enabled {
             if self.telegram.bot_token.is_empty() || self.telegram.chat_id.is_empty() {
@@ -305,6 +785,19 @@ impl BotSettings {
             }
         }
         
+        // Validate the Jupiter tier - the paid host rejects requests without a key
+        validate_jupiter_tier(&self.apis.jupiter_tier, &self.apis.jupiter_api_key, self.apis.jupiter_send_api_key)?;
+        
+        // Validate Jupiter platform fee (Jupiter caps platform fees at 255 bps)
+        if let Some(fee_account) = &self.apis.jupiter_fee_account {
+            if fee_account.parse::<solana_sdk::pubkey::Pubkey>().is_err() {
+                return Err("❌ JUPITER_FEE_ACCOUNT is not a valid Solana address".to_string());
+            }
+            if self.apis.jupiter_platform_fee_bps == 0 || self.apis.jupiter_platform_fee_bps > 255 {
+                return Err("❌ JUPITER_PLATFORM_FEE_BPS must be between 1 and 255 when JUPITER_FEE_ACCOUNT is set".to_string());
+            }
+        }
+        
         // Validate working APIs
         if !self.apis.dexscreener_enabled && !self.apis.jupiter_enabled {
             return Err("❌ At least one working API (DexScreener or Jupiter) must be enabled".to_string());
@@ -321,27 +814,208 @@ impl BotSettings {
         
         // Trading Strategy
         println!("� TRADING STRATEGY (USER SPECIFICATIONS):");
-        println!("   💰 Position Size: {:.4} SOL", self.trading.position_size_sol);
+        println!("   💰 Position Size: {:.4} SOL (shrinks to fit balance, min {:.4} SOL)", self.trading.position_size_sol, self.trading.min_position_size_sol);
+        if !self.trading.trading_hours.trim().is_empty() {
+            println!("   🕘 Trading Hours: {} (UTC{:+}) - positions are managed around the clock",
+                self.trading.trading_hours, self.trading.trading_hours_utc_offset);
+        }
+        if self.trading.compound_enabled {
+            println!("   📈 Compounding: +{}% per {} profitable closes, up to {:.4} SOL", self.trading.compound_step_percent,
+                self.trading.compound_wins_per_step, self.trading.max_position_size_sol);
+        }
+        if self.trading.funding_mint != "So11111111111111111111111111111111111111112" {
+            println!("   💵 Funding Mint: {} (sells return to {})", self.trading.funding_mint,
+                if self.trading.sell_to_funding_mint { "it" } else { "SOL" });
+        }
+        if self.trading.max_round_trip_cost_percent > 0.0 {
+            println!("   🔄 Max Round-Trip Cost: {}% (buy quote + instant sell quote)", self.trading.max_round_trip_cost_percent);
+        }
+        if !self.trading.copy_trade_wallets.is_empty() {
+            let wallets: Vec<String> = self.trading.copy_trade_wallets.iter()
+                .map(|target| format!("{} (x{})", target.wallet, target.size_multiplier))
+                .collect();
+            println!("   🪞 Copy Trading: {} every {}ms", wallets.join(", "), self.trading.copy_trade_poll_interval_ms);
+        }
+        if self.trading.max_token_allocation_percent > 0.0 {
+            println!("   ⚖️  Max Per-Token Allocation: {}% of {:.4}+ SOL capital", self.trading.max_token_allocation_percent,
+                self.trading.max_positions as f64 * self.trading.position_size_sol);
+        }
+        println!("   ⛽ Compute Budget Fee: {} ({} CU x {} micro-lamports)",
+            self.wallet.compute_budget_fee(), self.wallet.compute_unit_limit, self.wallet.compute_unit_price);
+        if self.wallet.sell_priority_fee_micro_lamports > 0 {
+            println!("   ⛽ Sell Compute Budget Fee: {} ({} CU x {} micro-lamports)",
+                Lamports::priority_fee(self.wallet.compute_unit_limit, self.wallet.sell_priority_fee_micro_lamports),
+                self.wallet.compute_unit_limit, self.wallet.sell_priority_fee_micro_lamports);
+        }
+        println!("   ⛽ Priority Fee Setting: {}", self.wallet.priority_fee());
+        println!("   🔎 Balance Reads: {} commitment", self.wallet.balance_commitment);
+        println!("   🛡️  SOL Reserve: {:.4} SOL ({:.4} minimum + {:.4} fee buffer)",
+            self.wallet.reserve_sol(), self.wallet.min_balance_sol, self.wallet.reserve_fee_buffer_sol);
+        if self.wallet.persistent_wsol {
+            println!("   🔄 Persistent WSOL: ✅ (top-ups of {:.4} SOL, unwrapped on shutdown)", self.wallet.wsol_top_up_sol);
+        }
+        if self.trading.min_seconds_between_buys > 0 {
+            println!("   ⏱️  Buy Spacing: {}s between buys {}", self.trading.min_seconds_between_buys,
+                if self.trading.buy_spacing_per_source { "(per source)" } else { "(global)" });
+        }
+        if self.trading.max_concurrent_buys > 1 {
+            println!("   🚦 Concurrent Buys: up to {} in flight", self.trading.max_concurrent_buys);
+        }
+        if self.trading.sell_cooldown_secs > 0 {
+            println!("   🧊 Re-entry Cooldown: {}s per mint after a sell", self.trading.sell_cooldown_secs);
+        }
+        if self.trading.dry_run_paper_positions {
+            println!("   📝 Dry-run Paper Positions: ✅ (in memory only, never saved)");
+        }
+        if self.trading.signals_only {
+            println!("   📡 Signals Only: {}", if self.trading.enable_auto_trading {
+                "❌ ignored (ENABLE_AUTO_SNIPE=true)"
+            } else {
+                "✅ (passing tokens are alerted via Telegram, never bought)"
+            });
+        }
+        if self.trading.require_manual_approval {
+            println!("   🙋 Manual Approval: required via Telegram ({}s timeout = reject)", self.trading.manual_approval_timeout_secs);
+        }
+        println!("   📈 Buy Slippage: {}% widening to {}% over {} attempts",
+                self.trading.initial_slippage_percent.min(self.trading.max_slippage_percent),
+                self.trading.max_slippage_percent,
+                self.trading.slippage_escalation_attempts);
+        println!("   🔁 Confirmation: up to {} sends, {}ms each before a fresh-blockhash resend",
+                self.trading.confirm_attempts, self.wallet.confirmation_timeout_ms);
+        println!("   ⏳ Buy Quotes: up to {} attempts, re-quoting after {}ms (+{}ms each)",
+                self.trading.quote_attempts, self.trading.quote_retry_delay_ms, self.trading.quote_retry_delay_ms);
+        if self.trading.no_route_retry_secs > 0 {
+            println!("   🛤️  No Route: re-quote every {}ms for up to {}s before skipping",
+                    self.trading.no_route_retry_interval_ms, self.trading.no_route_retry_secs);
+        }
         println!("   🛑 Stop Loss: -{}%", self.trading.stop_loss_percent);
-        println!("   📉 Trailing Stop: -{}% ({})", 
+        println!("   📉 Trailing Stop: -{}% from peak, arms at +{}% ({})", 
                 self.trading.trailing_stop_percent,
+                self.trading.trailing_activation_percent,
                 if self.trading.trailing_stop_enabled { "✅ Enabled" } else { "❌ Disabled" }
         );
+        for rung in &self.trading.trailing_stop_rungs {
+            println!("      past +{}%: trail tightens to -{}%", rung.profit_above_percent, rung.trail_percent);
+        }
+        if self.trading.exit_mode == "momentum" {
+            println!("   ✂️  Exit Mode: momentum - sell {}% after a new high and {} lower updates (replaces the trailing stop)",
+                self.trading.momentum_sell_percent, self.trading.momentum_lower_updates);
+        }
         println!("   🎯 Take Profit: +{}%", self.trading.profit_threshold_percent);
+        if self.trading.portfolio_take_profit_sol > 0.0 || self.trading.portfolio_take_profit_percent > 0.0 {
+            println!("   🏁 Portfolio Take Profit: +{} SOL today / +{}% open (0 = off) - sell all, pause until UTC midnight",
+                self.trading.portfolio_take_profit_sol, self.trading.portfolio_take_profit_percent);
+        }
         println!("   💸 Sell Amount: {}% of position", self.trading.sell_percentage);
         println!("   ⏰ Max Hold Time: {} hours", self.trading.max_hold_time_hours);
+        for (source, minutes) in &self.trading.source_max_hold_minutes {
+            println!("      {} positions: {} minutes", source, minutes);
+        }
+        if self.trading.max_timeout_extension_minutes > 0 {
+            println!("   ⏳ Timeout Grace: hold {}m more (up to {}m) while a sell would net under {:+} SOL",
+                    self.trading.timeout_grace_minutes, self.trading.max_timeout_extension_minutes, self.trading.min_timeout_sell_profit_sol);
+        }
+        if self.trading.entry_delay_ms > 0 {
+            println!("   ⏳ Entry Delay: {}ms (abort if price +{}% or liquidity drops)",
+                    self.trading.entry_delay_ms, self.trading.entry_max_price_rise_percent);
+        }
+        if self.trading.max_entry_price_usd > 0.0 {
+            println!("   🏷️  Max Entry Price: ${}", self.trading.max_entry_price_usd);
+        }
+        for (mint, price) in &self.trading.token_max_entry_price_usd {
+            println!("      {}: max ${}", mint, price);
+        }
+        if self.trading.min_out_tolerance_percent > 0.0 {
+            println!("   🧱 Minimum Out: expected fill -{}%", self.trading.min_out_tolerance_percent);
+        }
+        if self.trading.min_routes > 1 {
+            println!("   🛣️  Minimum Route Legs: {}", self.trading.min_routes);
+        }
+        if self.trading.max_price_impact_percent > 0.0 {
+            if self.trading.requote_before_send_min_sol > 0.0 {
+                println!("   🌊 Max Price Impact: {}% (re-quoted before sending buys of {} SOL or more)",
+                    self.trading.max_price_impact_percent, self.trading.requote_before_send_min_sol);
+            } else {
+                println!("   🌊 Max Price Impact: {}%", self.trading.max_price_impact_percent);
+            }
+        }
+        if self.trading.min_sell_liquidity_sol > 0.0 {
+            if self.trading.hold_on_low_liquidity {
+                println!("   🫗 Exit Liquidity Floor: {:.2} SOL (hold up to {}m, then write off as rugged)",
+                    self.trading.min_sell_liquidity_sol, self.trading.low_liquidity_hold_minutes);
+            } else {
+                println!("   🫗 Exit Liquidity Floor: {:.2} SOL (warn and sell anyway)", self.trading.min_sell_liquidity_sol);
+            }
+        }
+        if self.trading.dust_threshold_usd > 0.0 {
+            println!("   🧹 Dust Threshold: ${} (smaller balances are closed without a sell)", self.trading.dust_threshold_usd);
+        }
+        if self.trading.max_daily_trades > 0 {
+            println!("   📅 Daily Buy Cap: {} (resets at UTC midnight)", self.trading.max_daily_trades);
+        }
         
         // Security Configuration
         println!("\n🛡️  SECURITY & RISK MANAGEMENT:");
-        println!("   📊 RugCheck Score: {}/100 minimum", self.security.min_acceptable_score);
+        if self.security.require_all_criteria {
+            println!("   📊 RugCheck: all 6 criteria must pass");
+        } else {
+            println!("   📊 RugCheck Score: {}/100 minimum (weighted, partial credit for near misses)",
+                self.security.min_acceptable_score);
+        }
+        println!("   ⚖️  Score Weights: mint {} / freeze {} / LP {} / taxes {} / holders {} / can-sell {}",
+            self.security.score_weight_mint_authority, self.security.score_weight_freeze_authority,
+            self.security.score_weight_lp, self.security.score_weight_taxes,
+            self.security.score_weight_top_holders, self.security.score_weight_can_sell);
         println!("   🔒 Liquidity Checks: {}", if self.security.enable_liquidity_checks { "✅" } else { "❌" });
+        if self.security.max_liquidity_disagreement_factor > 0.0 {
+            println!("   ⚖️  Liquidity Cross-Check: on-chain SOL reserves within {}x of DexScreener", self.security.max_liquidity_disagreement_factor);
+        }
+        if self.security.max_pools_per_token > 0 {
+            println!("   🧩 Fragmentation: reject over {} pools unless one holds ${:.0}",
+                self.security.max_pools_per_token, self.security.min_main_pool_liquidity_usd);
+        }
+        if self.security.min_sol_pairs > 1 {
+            println!("   🔗 SOL Pairs: at least {} (or one holding ${:.0})",
+                self.security.min_sol_pairs, self.security.min_primary_sol_pair_liquidity_usd);
+        }
+        match SecondaryCheckMode::parse(&self.security.secondary_rugcheck_mode) {
+            Some(SecondaryCheckMode::Off) | None => {}
+            Some(mode) => println!("   🛡️  Second Opinion: rugcheck.xyz, {:?} mode needs {}+", mode, self.security.secondary_rugcheck_min_score),
+        }
         println!("   👥 Authority Checks: {}", if self.security.enable_authority_checks { "✅" } else { "❌" });
-        println!("   📈 Holder Analysis: {}", if self.security.enable_holder_checks { "✅" } else { "❌" });
+        println!("   📈 Holder Analysis: {}", if self.security.enable_holder_checks {
+            format!("✅ (min {} holders for pools older than {}m)", self.security.min_holder_count, self.security.holder_check_min_pool_age_minutes)
+        } else {
+            "❌".to_string()
+        });
+        if self.security.enable_creator_checks {
+            println!("   🧑‍💻 Creator Checks: wallet at least {}m old at launch, not on {}",
+                self.security.min_creator_wallet_age_minutes, self.security.creator_blacklist_path);
+        }
+        if self.security.min_token_age_minutes > 0 || self.security.max_token_age_minutes > 0 {
+            println!("   🎂 Token Age: {}m - {} (from first mint transaction)",
+                    self.security.min_token_age_minutes,
+                    if self.security.max_token_age_minutes > 0 { format!("{}m", self.security.max_token_age_minutes) } else { "no max".to_string() });
+        }
+        println!("   🚫 Name Blacklist: {} patterns", self.security.name_blacklist_patterns.len());
+        println!("   🍯 Post-Buy Sell Probe: {}", if self.security.post_buy_sell_probe { "✅ (simulate selling every fill)" } else { "❌" });
+        if self.security.position_recheck_interval_secs > 0 {
+            println!("   🔁 Position Re-check: every {}s (exit on authority re-enabled or {:.0}% liquidity drop)",
+                self.security.position_recheck_interval_secs, self.security.position_recheck_max_liquidity_drop_percent);
+        } else {
+            println!("   🔁 Position Re-check: ❌");
+        }
+        println!("   🏭 Production Mode: {}", if self.security.production_mode { "✅ (no sample data, missing data = reject)" } else { "❌ (sample tokens and test scenarios allowed)" });
+        println!("   🎭 Impersonation Check: {} known tickers", self.security.known_tickers.len());
+        println!("   💵 Avoided Pairs: {} stablecoin/wrapped-major mints", self.security.avoided_pair_mints.len());
         
         // Working APIs Status
         println!("\n� WORKING API ENDPOINTS:");
         println!("   📡 DexScreener: {}", if self.apis.dexscreener_enabled { "✅ Active" } else { "❌ Disabled" });
         println!("   🪐 Jupiter API: {}", if self.apis.jupiter_enabled { "✅ Active" } else { "❌ Disabled" });
+        println!("   🔑 Jupiter Tier: {} ({})", self.apis.jupiter_tier,
+            if self.apis.jupiter_send_api_key && !self.apis.jupiter_api_key.is_empty() { "X-API-KEY sent" } else { "no API key" });
         println!("   🛡️  RugCheck API: {}", if self.apis.rugcheck_enabled { "✅ Active" } else { "❌ Disabled" });
         println!("   📱 Telegram API: {}", if self.apis.telegram_enabled { "✅ Active" } else { "❌ Disabled" });
         
@@ -352,13 +1026,27 @@ impl BotSettings {
             self.apis.premium_endpoints.nozomi_enabled,
             self.apis.premium_endpoints.nextblock_enabled,
             self.apis.premium_endpoints.grpc_enabled,
+            self.apis.premium_endpoints.birdeye_enabled,
+            self.apis.premium_endpoints.jito_enabled,
         ].iter().filter(|&&x| x).count();
         
         println!("   🔥 ZeroSlot: {}", if self.apis.premium_endpoints.zeroslot_enabled { "✅ Active" } else { "❌ Disabled" });
         println!("   🚀 Nozomi: {}", if self.apis.premium_endpoints.nozomi_enabled { "✅ Active" } else { "❌ Disabled" });
         println!("   🌟 NextBlock: {}", if self.apis.premium_endpoints.nextblock_enabled { "✅ Active" } else { "❌ Disabled" });
         println!("   🔌 gRPC Direct: {}", if self.apis.premium_endpoints.grpc_enabled { "✅ Active" } else { "❌ Disabled" });
-        println!("   📊 Total Active: {}/4 premium endpoints", premium_count);
+        println!("   🐦 Birdeye: {}", if self.apis.premium_endpoints.birdeye_enabled { "✅ Active" } else { "❌ Disabled" });
+        if self.apis.premium_endpoints.jito_enabled {
+            println!("   🎯 Jito Bundles: ✅ Active ({} tip)", self.apis.premium_endpoints.jito_tip());
+        } else {
+            println!("   🎯 Jito Bundles: ❌ Disabled");
+        }
+        if self.apis.premium_endpoints.private_tx_enabled {
+            println!("   🕶️  Private Submission: ✅ via {} ({} tip, public RPC fallback)",
+                self.apis.premium_endpoints.private_tx_provider, self.apis.premium_endpoints.private_tx_tip());
+        } else {
+            println!("   🕶️  Private Submission: ❌ Disabled (public mempool)");
+        }
+        println!("   📊 Total Active: {}/6 premium endpoints", premium_count);
         
         // Performance Settings
         println!("\n⚡ PERFORMANCE CONFIGURATION:");
@@ -366,14 +1054,41 @@ impl BotSettings {
         println!("   ⏱️  Request Timeout: {}ms", self.performance.request_timeout_ms);
         println!("   🔁 Retry Attempts: {}", self.performance.retry_attempts);
         println!("   🧠 Parallel Analysis: {}", if self.performance.use_parallel_analysis { "✅" } else { "❌" });
+        println!("   👁️  Position Checks: every {}ms {}", self.monitoring.price_check_interval_ms,
+            if self.monitoring.concurrent_position_monitoring { "(own task)" } else { "(between scans)" });
+        println!("   🧹 Memory Caps: prices cached {}s, last {} alerts kept",
+            self.monitoring.price_cache_ttl_secs, self.monitoring.max_alert_history);
+        if self.monitoring.stale_pricing_alert_secs > 0 {
+            println!("   🧊 Stale Pricing Alert: over {:.0}% of positions without a fresh price for {}s",
+                self.monitoring.stale_pricing_alert_fraction * 100.0, self.monitoring.stale_pricing_alert_secs);
+        }
+        if self.monitoring.decision_trace_enabled {
+            println!("   🧭 Decision Trace: {} ({} watched mints)",
+                self.monitoring.decision_trace_path, self.monitoring.decision_trace_mints.len());
+        }
+        println!("   🗄️  Storage: {} at {}", self.monitoring.storage_backend, self.monitoring.storage_path);
         
         // Monitoring & Notifications
         println!("\n📱 NOTIFICATIONS:");
         println!("   🤖 Telegram: {}", if self.telegram.notifications_enabled { "✅ Enabled" } else { "❌ Disabled" });
+        if !self.monitoring.webhook_url.is_empty() {
+            println!("   🪝 Webhook: {}{}", self.monitoring.webhook_url,
+                if self.monitoring.webhook_bearer_token.is_empty() { "" } else { " (bearer token)" });
+        }
         if self.telegram.notifications_enabled {
             println!("   💰 Buy Alerts: {}", if self.telegram.send_buy_alerts { "✅" } else { "❌" });
             println!("   💸 Sell Alerts: {}", if self.telegram.send_sell_alerts { "✅" } else { "❌" });
             println!("   📊 Profit Reports: {}", if self.telegram.send_profit_summaries { "✅" } else { "❌" });
+            let custom: Vec<&str> = [
+                ("buy", &self.telegram.buy_template),
+                ("sell", &self.telegram.sell_template),
+                ("profit", &self.telegram.profit_template),
+            ].iter().filter(|(_, t)| !t.trim().is_empty()).map(|(name, _)| *name).collect();
+            if !custom.is_empty() {
+                println!("   📝 Custom Templates: {}", custom.join(", "));
+            }
+            let explorers: Vec<&str> = self.telegram.explorer_links.iter().map(|link| link.name.as_str()).collect();
+            println!("   🔗 Explorer Links: {}", if explorers.is_empty() { "none".to_string() } else { explorers.join(", ") });
         }
         
         println!("\n🎯 Bot configured for CONTINUOUS SCANNING with automated trading!");
@@ -408,41 +1123,139 @@ impl WalletSettings {
         Self {
             private_key: env::var("SOLANA_PRIVATE_KEY").unwrap_or_default(),
             min_balance_sol: env::var("MIN_WALLET_BALANCE_SOL").unwrap_or_else(|_| "0.1".to_string()).parse().unwrap_or(0.1),
+            reserve_fee_buffer_sol: env::var("RESERVE_FEE_BUFFER_SOL").unwrap_or_else(|_| "0.01".to_string()).parse().unwrap_or(0.01),
             rpc_url: env::var("RPC_URL").unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string()),
             backup_rpc_urls: env::var("BACKUP_RPC_URLS")
                 .unwrap_or_else(|_| "https://solana-rpc.publicnode.com,https://rpc.ankr.com/solana".to_string())
                 .split(',').map(|s| s.trim().to_string()).collect(),
+            rpc_max_requests_per_second: env::var("RPC_MAX_REQUESTS_PER_SECOND").unwrap_or_else(|_| "10".to_string()).parse().unwrap_or(10),
             commitment: env::var("COMMITMENT").unwrap_or_else(|_| "confirmed".to_string()),
+            balance_commitment: env::var("BALANCE_COMMITMENT").unwrap_or_else(|_| "confirmed".to_string()),
             confirmation_timeout_ms: env::var("CONFIRMATION_TIMEOUT_MS").unwrap_or_else(|_| "15000".to_string()).parse().unwrap_or(15000),
             max_retries: env::var("MAX_RETRIES").unwrap_or_else(|_| "10".to_string()).parse().unwrap_or(10),
             priority_fee_micro_lamports: env::var("PRIORITY_FEE_MICRO_LAMPORTS").unwrap_or_else(|_| "100000".to_string()).parse().unwrap_or(100000),
             compute_unit_limit: env::var("COMPUTE_UNIT_LIMIT").unwrap_or_else(|_| "300000".to_string()).parse().unwrap_or(300000),
             compute_unit_price: env::var("COMPUTE_UNIT_PRICE").unwrap_or_else(|_| "2000".to_string()).parse().unwrap_or(2000),
+            sell_priority_fee_micro_lamports: env::var("SELL_PRIORITY_FEE_MICRO_LAMPORTS").unwrap_or_else(|_| "0".to_string()).parse().unwrap_or(0),
+            persistent_wsol: env::var("PERSISTENT_WSOL").unwrap_or_else(|_| "false".to_string()).parse().unwrap_or(false),
+            wsol_top_up_sol: env::var("WSOL_TOP_UP_SOL").unwrap_or_else(|_| "1.0".to_string()).parse().unwrap_or(1.0),
         }
     }
+
+    /// Native SOL every SOL-spending send must leave behind: MIN_WALLET_BALANCE_SOL + RESERVE_FEE_BUFFER_SOL
+    pub fn reserve_sol(&self) -> f64 {
+        self.min_balance_sol + self.reserve_fee_buffer_sol
+    }
+
+    /// PRIORITY_FEE_MICRO_LAMPORTS as lamports
+    pub fn priority_fee(&self) -> Lamports {
+        Lamports::from_micro_lamports(self.priority_fee_micro_lamports)
+    }
+
+    /// What a swap pays for its compute budget: COMPUTE_UNIT_LIMIT units at COMPUTE_UNIT_PRICE micro-lamports each
+    pub fn compute_budget_fee(&self) -> Lamports {
+        Lamports::priority_fee(self.compute_unit_limit, self.compute_unit_price)
+    }
 }
 
 impl TradingSettings {
     pub fn from_env() -> Self {
         Self {
             position_size_sol: env::var("POSITION_SIZE_SOL").unwrap_or_else(|_| "1.0".to_string()).parse().unwrap_or(1.0),
+            min_position_size_sol: env::var("MIN_POSITION_SIZE_SOL").unwrap_or_else(|_| "0.05".to_string()).parse().unwrap_or(0.05),
+            funding_mint: env::var("FUNDING_MINT").unwrap_or_else(|_| "So11111111111111111111111111111111111111112".to_string()),
+            sell_to_funding_mint: env::var("SELL_TO_FUNDING_MINT").unwrap_or_else(|_| "false".to_string()).parse().unwrap_or(false),
             max_positions: env::var("MAX_ACTIVE_POSITIONS").unwrap_or_else(|_| "5".to_string()).parse().unwrap_or(5),
+            max_round_trip_cost_percent: env::var("MAX_ROUND_TRIP_COST_PERCENT").unwrap_or_else(|_| "0".to_string()).parse().unwrap_or(0.0),
+            copy_trade_wallets: parse_copy_targets(&env::var("COPY_TRADE_WALLETS").unwrap_or_default()),
+            copy_trade_poll_interval_ms: env::var("COPY_TRADE_POLL_INTERVAL_MS").unwrap_or_else(|_| "2000".to_string()).parse().unwrap_or(2000),
+            max_token_allocation_percent: env::var("MAX_TOKEN_ALLOCATION_PERCENT").unwrap_or_else(|_| "0".to_string()).parse().unwrap_or(0.0),
+            min_position_tokens: env::var("MIN_POSITION_TOKENS").unwrap_or_else(|_| "1".to_string()).parse().unwrap_or(1),
+            min_seconds_between_buys: env::var("MIN_SECONDS_BETWEEN_BUYS").unwrap_or_else(|_| "0".to_string()).parse().unwrap_or(0),
+            max_concurrent_buys: env::var("MAX_CONCURRENT_BUYS").unwrap_or_else(|_| "1".to_string()).parse().unwrap_or(1),
+            sell_cooldown_secs: env::var("SELL_COOLDOWN_SECS").unwrap_or_else(|_| "0".to_string()).parse().unwrap_or(0),
+            buy_spacing_per_source: env::var("BUY_SPACING_PER_SOURCE").unwrap_or_else(|_| "false".to_string()).parse().unwrap_or(false),
+            max_daily_trades: env::var("MAX_DAILY_TRADES").unwrap_or_else(|_| "0".to_string()).parse().unwrap_or(0),
+            compound_enabled: env::var("COMPOUND_ENABLED").unwrap_or_else(|_| "false".to_string()).parse().unwrap_or(false),
+            compound_step_percent: env::var("COMPOUND_STEP_PERCENT").unwrap_or_else(|_| "10.0".to_string()).parse().unwrap_or(10.0),
+            compound_wins_per_step: env::var("COMPOUND_WINS_PER_STEP").unwrap_or_else(|_| "3".to_string()).parse().unwrap_or(3),
+            max_position_size_sol: env::var("MAX_POSITION_SIZE_SOL").unwrap_or_else(|_| "2.0".to_string()).parse().unwrap_or(2.0),
+            trading_hours: env::var("TRADING_HOURS").unwrap_or_default(),
+            trading_hours_utc_offset: env::var("TRADING_HOURS_UTC_OFFSET").unwrap_or_else(|_| "0".to_string()).parse().unwrap_or(0.0),
             min_liquidity_sol: env::var("MIN_LIQUIDITY_SOL").unwrap_or_else(|_| "10.0".to_string()).parse().unwrap_or(10.0),
-            max_slippage_percent: env::var("MAX_SLIPPAGE_PERCENT").unwrap_or_else(|_| "5.0".to_string()).parse().unwrap_or(5.0),
+            max_slippage_percent: env::var("MAX_SLIPPAGE_PERCENT").unwrap_or_else(|_| "15.0".to_string()).parse().unwrap_or(15.0),
+            initial_slippage_percent: env::var("INITIAL_SLIPPAGE_PERCENT").unwrap_or_else(|_| "15.0".to_string()).parse().unwrap_or(15.0),
+            slippage_escalation_attempts: env::var("SLIPPAGE_ESCALATION_ATTEMPTS").unwrap_or_else(|_| "3".to_string()).parse().unwrap_or(3),
+            confirm_attempts: env::var("CONFIRM_ATTEMPTS").unwrap_or_else(|_| "3".to_string()).parse().unwrap_or(3),
+            quote_attempts: env::var("QUOTE_ATTEMPTS").unwrap_or_else(|_| "3".to_string()).parse().unwrap_or(3),
+            quote_retry_delay_ms: env::var("QUOTE_RETRY_DELAY_MS").unwrap_or_else(|_| "150".to_string()).parse().unwrap_or(150),
+            no_route_retry_secs: env::var("NO_ROUTE_RETRY_SECS").unwrap_or_else(|_| "0".to_string()).parse().unwrap_or(0),
+            no_route_retry_interval_ms: env::var("NO_ROUTE_RETRY_INTERVAL_MS").unwrap_or_else(|_| "2000".to_string()).parse().unwrap_or(2000),
+            require_manual_approval: env::var("REQUIRE_MANUAL_APPROVAL").unwrap_or_else(|_| "false".to_string()).parse().unwrap_or(false),
+            manual_approval_timeout_secs: env::var("MANUAL_APPROVAL_TIMEOUT_SECS").unwrap_or_else(|_| "120".to_string()).parse().unwrap_or(120),
             enable_auto_trading: env::var("ENABLE_AUTO_SNIPE").unwrap_or_else(|_| "false".to_string()).parse().unwrap_or(false),
+            signals_only: env::var("SIGNALS_ONLY").unwrap_or_else(|_| "false".to_string()).parse().unwrap_or(false),
+            dry_run_paper_positions: env::var("DRY_RUN_PAPER_POSITIONS").unwrap_or_else(|_| "false".to_string()).parse().unwrap_or(false),
             
             // EXACT USER SPECIFICATIONS
             stop_loss_percent: env::var("STOP_LOSS_PERCENT").unwrap_or_else(|_| "50.0".to_string()).parse().unwrap_or(50.0),
             trailing_stop_enabled: env::var("TRAILING_STOP_ENABLED").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
             trailing_stop_percent: env::var("TRAILING_STOP_PERCENT").unwrap_or_else(|_| "30.0".to_string()).parse().unwrap_or(30.0),
+            trailing_activation_percent: env::var("TRAILING_ACTIVATION_PERCENT").unwrap_or_else(|_| "20.0".to_string()).parse().unwrap_or(20.0),
+            trailing_stop_rungs: parse_trail_rungs(&env::var("TRAILING_STOP_RUNGS").unwrap_or_default()),
+            exit_mode: env::var("EXIT_MODE").unwrap_or_else(|_| "trailing".to_string()).to_lowercase(),
+            momentum_lower_updates: env::var("MOMENTUM_LOWER_UPDATES").unwrap_or_else(|_| "3".to_string()).parse().unwrap_or(3),
+            momentum_sell_percent: env::var("MOMENTUM_SELL_PERCENT").unwrap_or_else(|_| "50.0".to_string()).parse().unwrap_or(50.0),
             profit_threshold_percent: env::var("PROFIT_THRESHOLD_PERCENT").unwrap_or_else(|_| "50.0".to_string()).parse().unwrap_or(50.0),
             sell_percentage: env::var("SELL_PERCENTAGE").unwrap_or_else(|_| "75.0".to_string()).parse().unwrap_or(75.0),
             max_hold_time_hours: env::var("MAX_HOLD_TIME_HOURS").unwrap_or_else(|_| "24".to_string()).parse().unwrap_or(24),
+            source_max_hold_minutes: env::var("SOURCE_MAX_HOLD_MINUTES")
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|entry| entry.split_once(':'))
+                .filter_map(|(source, minutes)| Some((source.trim().to_string(), minutes.trim().parse().ok()?)))
+                .collect(),
+            min_timeout_sell_profit_sol: env::var("MIN_TIMEOUT_SELL_PROFIT_SOL").unwrap_or_else(|_| "0.0".to_string()).parse().unwrap_or(0.0),
+            timeout_grace_minutes: env::var("TIMEOUT_GRACE_MINUTES").unwrap_or_else(|_| "5".to_string()).parse().unwrap_or(5),
+            max_timeout_extension_minutes: env::var("MAX_TIMEOUT_EXTENSION_MINUTES").unwrap_or_else(|_| "0".to_string()).parse().unwrap_or(0),
+            portfolio_take_profit_sol: env::var("PORTFOLIO_TAKE_PROFIT_SOL").unwrap_or_else(|_| "0.0".to_string()).parse().unwrap_or(0.0),
+            portfolio_take_profit_percent: env::var("PORTFOLIO_TAKE_PROFIT_PERCENT").unwrap_or_else(|_| "0.0".to_string()).parse().unwrap_or(0.0),
+            
+            entry_delay_ms: env::var("ENTRY_DELAY_MS").unwrap_or_else(|_| "0".to_string()).parse().unwrap_or(0),
+            entry_max_price_rise_percent: env::var("ENTRY_MAX_PRICE_RISE_PERCENT").unwrap_or_else(|_| "20.0".to_string()).parse().unwrap_or(20.0),
+            max_entry_price_usd: env::var("MAX_ENTRY_PRICE_USD").unwrap_or_else(|_| "0.0".to_string()).parse().unwrap_or(0.0),
+            token_max_entry_price_usd: env::var("TOKEN_MAX_ENTRY_PRICES_USD")
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|entry| entry.split_once(':'))
+                .filter_map(|(mint, price)| Some((mint.trim().to_string(), price.trim().parse().ok()?)))
+                .collect(),
+            
+            min_out_tolerance_percent: env::var("MIN_OUT_TOLERANCE_PERCENT").unwrap_or_else(|_| "20.0".to_string()).parse().unwrap_or(20.0),
+            min_routes: env::var("MIN_ROUTES").unwrap_or_else(|_| "1".to_string()).parse().unwrap_or(1),
+            max_price_impact_percent: env::var("MAX_PRICE_IMPACT_PERCENT").unwrap_or_else(|_| "0.0".to_string()).parse().unwrap_or(0.0),
+            requote_before_send_min_sol: env::var("REQUOTE_BEFORE_SEND_MIN_SOL").unwrap_or_else(|_| "0.0".to_string()).parse().unwrap_or(0.0),
+            
+            min_sell_liquidity_sol: env::var("MIN_SELL_LIQUIDITY_SOL").unwrap_or_else(|_| "0.0".to_string()).parse().unwrap_or(0.0),
+            dust_threshold_usd: env::var("DUST_THRESHOLD_USD").unwrap_or_else(|_| "0.05".to_string()).parse().unwrap_or(0.05),
+            hold_on_low_liquidity: env::var("HOLD_ON_LOW_LIQUIDITY").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
+            low_liquidity_hold_minutes: env::var("LOW_LIQUIDITY_HOLD_MINUTES").unwrap_or_else(|_| "5".to_string()).parse().unwrap_or(5),
         }
     }
 }
 
 impl SecuritySettings {
+    pub fn score_weights(&self) -> crate::rugcheck::ScoreWeights {
+        crate::rugcheck::ScoreWeights {
+            mint_authority: self.score_weight_mint_authority,
+            freeze_authority: self.score_weight_freeze_authority,
+            lp: self.score_weight_lp,
+            taxes: self.score_weight_taxes,
+            top_holders: self.score_weight_top_holders,
+            can_sell: self.score_weight_can_sell,
+        }
+    }
+
     pub fn from_env() -> Self {
         Self {
             // RugCheck integration with user-specified 70+ minimum
@@ -450,20 +1263,70 @@ impl SecuritySettings {
             high_confidence_score: env::var("HIGH_CONFIDENCE_SCORE").unwrap_or_else(|_| "85".to_string()).parse().unwrap_or(85),
             medium_confidence_score: env::var("MEDIUM_CONFIDENCE_SCORE").unwrap_or_else(|_| "70".to_string()).parse().unwrap_or(70),
             require_rugcheck_success: env::var("REQUIRE_RUGCHECK_SUCCESS").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
+            require_all_criteria: env::var("REQUIRE_ALL_CRITERIA").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
+            score_weight_mint_authority: env::var("SCORE_WEIGHT_MINT_AUTHORITY").unwrap_or_else(|_| "20".to_string()).parse().unwrap_or(20.0),
+            score_weight_freeze_authority: env::var("SCORE_WEIGHT_FREEZE_AUTHORITY").unwrap_or_else(|_| "20".to_string()).parse().unwrap_or(20.0),
+            score_weight_lp: env::var("SCORE_WEIGHT_LP").unwrap_or_else(|_| "20".to_string()).parse().unwrap_or(20.0),
+            score_weight_taxes: env::var("SCORE_WEIGHT_TAXES").unwrap_or_else(|_| "15".to_string()).parse().unwrap_or(15.0),
+            score_weight_top_holders: env::var("SCORE_WEIGHT_TOP_HOLDERS").unwrap_or_else(|_| "15".to_string()).parse().unwrap_or(15.0),
+            score_weight_can_sell: env::var("SCORE_WEIGHT_CAN_SELL").unwrap_or_else(|_| "10".to_string()).parse().unwrap_or(10.0),
+            production_mode: env::var("PRODUCTION_MODE").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
+            rugcheck_fetch_max_attempts: env::var("RUGCHECK_FETCH_MAX_ATTEMPTS").unwrap_or_else(|_| "3".to_string()).parse().unwrap_or(3),
+            rugcheck_fetch_retry_delay_ms: env::var("RUGCHECK_FETCH_RETRY_DELAY_MS").unwrap_or_else(|_| "500".to_string()).parse().unwrap_or(500),
+            secondary_rugcheck_mode: env::var("SECONDARY_RUGCHECK_MODE").unwrap_or_else(|_| "off".to_string()),
+            secondary_rugcheck_min_score: env::var("SECONDARY_RUGCHECK_MIN_SCORE").unwrap_or_else(|_| "70".to_string()).parse().unwrap_or(70),
             
             enable_liquidity_checks: env::var("ENABLE_LIQUIDITY_CHECKS").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
             min_liquidity_lock_percentage: env::var("MIN_LIQUIDITY_LOCK_PERCENTAGE").unwrap_or_else(|_| "80.0".to_string()).parse().unwrap_or(80.0),
             min_total_liquidity_usd: env::var("MIN_TOTAL_LIQUIDITY_USD").unwrap_or_else(|_| "10000.0".to_string()).parse().unwrap_or(10000.0),
             max_dev_wallet_percentage: env::var("MAX_DEV_WALLET_PERCENTAGE").unwrap_or_else(|_| "10.0".to_string()).parse().unwrap_or(10.0),
+            max_liquidity_disagreement_factor: env::var("MAX_LIQUIDITY_DISAGREEMENT_FACTOR").unwrap_or_else(|_| "0".to_string()).parse().unwrap_or(0.0),
+            max_pools_per_token: env::var("MAX_POOLS_PER_TOKEN").unwrap_or_else(|_| "0".to_string()).parse().unwrap_or(0),
+            min_main_pool_liquidity_usd: env::var("MIN_MAIN_POOL_LIQUIDITY_USD").unwrap_or_else(|_| "5000".to_string()).parse().unwrap_or(5000.0),
+            min_sol_pairs: env::var("MIN_SOL_PAIRS").unwrap_or_else(|_| "1".to_string()).parse().unwrap_or(1),
+            min_primary_sol_pair_liquidity_usd: env::var("MIN_PRIMARY_SOL_PAIR_LIQUIDITY_USD").unwrap_or_else(|_| "0".to_string()).parse().unwrap_or(0.0),
             
             enable_authority_checks: env::var("ENABLE_AUTHORITY_CHECKS").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
             reject_mint_authority: env::var("REJECT_MINT_AUTHORITY").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
             reject_freeze_authority: env::var("REJECT_FREEZE_AUTHORITY").unwrap_or_else(|_| "false".to_string()).parse().unwrap_or(false),
             allow_mutable_metadata: env::var("ALLOW_MUTABLE_METADATA").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
             
+            post_buy_sell_probe: env::var("POST_BUY_SELL_PROBE").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
+            position_recheck_interval_secs: env::var("POSITION_RECHECK_INTERVAL_SECS").unwrap_or_else(|_| "0".to_string()).parse().unwrap_or(0),
+            position_recheck_max_liquidity_drop_percent: env::var("POSITION_RECHECK_MAX_LIQUIDITY_DROP_PERCENT").unwrap_or_else(|_| "50.0".to_string()).parse().unwrap_or(50.0),
+            
+            name_blacklist_patterns: env::var("NAME_BLACKLIST_PATTERNS")
+                .unwrap_or_else(|_| "scam,honeypot,rugpull".to_string())
+                .split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect(),
+            known_tickers: env::var("KNOWN_TICKERS")
+                .unwrap_or_else(|_| concat!(
+                    "SOL:So11111111111111111111111111111111111111112,",
+                    "USDC:EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v,",
+                    "BONK:DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263,",
+                    "WIF:EKpQGSJtjMFqKZ9KQanSqYXRcF8fBopzLHYxdM65zcjm",
+                ).to_string())
+                .split(',')
+                .filter_map(|entry| entry.split_once(':'))
+                .map(|(symbol, mint)| (symbol.trim().to_uppercase(), mint.trim().to_string()))
+                .collect(),
+            avoided_pair_mints: env::var("AVOIDED_PAIR_MINTS")
+                .unwrap_or_else(|_| concat!(
+                    "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v,", // USDC
+                    "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB,", // USDT
+                    "3NZ9JMVBmGAqocybic2c7LQCJScmgsAZ6vQqTDzcqmJh,", // wBTC (Wormhole)
+                    "7vfCXTUXx5WJV5JADk17DUJ4ksgau7utNKj4b963voxs",  // wETH (Wormhole)
+                ).to_string())
+                .split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect(),
+            
             enable_holder_checks: env::var("ENABLE_HOLDER_CHECKS").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
             max_top_holder_percentage: env::var("MAX_TOP_HOLDER_PERCENTAGE").unwrap_or_else(|_| "30.0".to_string()).parse().unwrap_or(30.0),
             min_holder_count: env::var("MIN_HOLDER_COUNT").unwrap_or_else(|_| "100".to_string()).parse().unwrap_or(100),
+            holder_check_min_pool_age_minutes: env::var("HOLDER_CHECK_MIN_POOL_AGE_MINUTES").unwrap_or_else(|_| "30".to_string()).parse().unwrap_or(30),
+            min_token_age_minutes: env::var("MIN_TOKEN_AGE_MINUTES").unwrap_or_else(|_| "0".to_string()).parse().unwrap_or(0),
+            max_token_age_minutes: env::var("MAX_TOKEN_AGE_MINUTES").unwrap_or_else(|_| "0".to_string()).parse().unwrap_or(0),
+            enable_creator_checks: env::var("ENABLE_CREATOR_CHECKS").unwrap_or_else(|_| "false".to_string()).parse().unwrap_or(false),
+            min_creator_wallet_age_minutes: env::var("MIN_CREATOR_WALLET_AGE_MINUTES").unwrap_or_else(|_| "1440".to_string()).parse().unwrap_or(1440),
+            creator_blacklist_path: env::var("CREATOR_BLACKLIST_PATH").unwrap_or_else(|_| "./data/creator_blacklist.txt".to_string()),
             
             auto_reject_critical_risks: env::var("AUTO_REJECT_CRITICAL_RISKS").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
             auto_reject_high_risks: env::var("AUTO_REJECT_HIGH_RISKS").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
@@ -475,7 +1338,7 @@ impl SecuritySettings {
 impl TelegramSettings {
     pub fn from_env() -> Self {
         Self {
-            bot_token: env::var("TELEGRAM_BOT_TOKEN").unwrap_or_else(|_| "8165724823:AAFE3Lg1IaD42AHIvO-AR4akLLoWpbA2X-E".to_string()),
+            bot_token: env::var("TELEGRAM_BOT_TOKEN").unwrap_or_default(),
             chat_id: env::var("TELEGRAM_CHAT_ID").unwrap_or_default(),
             notifications_enabled: env::var("TELEGRAM_NOTIFICATIONS_ENABLED").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
             send_buy_alerts: env::var("TELEGRAM_SEND_BUY_ALERTS").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
@@ -483,12 +1346,19 @@ impl TelegramSettings {
             send_profit_summaries: env::var("TELEGRAM_SEND_PROFIT_SUMMARIES").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
             send_error_alerts: env::var("TELEGRAM_SEND_ERROR_ALERTS").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
             send_rugcheck_alerts: env::var("TELEGRAM_SEND_RUGCHECK_ALERTS").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
+            error_alert_dedup_window_secs: env::var("TELEGRAM_ERROR_DEDUP_WINDOW_SECS").unwrap_or_else(|_| "300".to_string()).parse().unwrap_or(300),
+            // Literal "\n" in the env value becomes a line break
+            buy_template: env::var("TELEGRAM_BUY_TEMPLATE").unwrap_or_default().replace("\\n", "\n"),
+            sell_template: env::var("TELEGRAM_SELL_TEMPLATE").unwrap_or_default().replace("\\n", "\n"),
+            profit_template: env::var("TELEGRAM_PROFIT_TEMPLATE").unwrap_or_default().replace("\\n", "\n"),
+            explorer_links: parse_explorer_links(&env::var("TELEGRAM_EXPLORER_LINKS").unwrap_or_else(|_| DEFAULT_EXPLORER_LINKS.to_string())),
         }
     }
 }
 
 impl WorkingApiSettings {
     pub fn from_env() -> Self {
+        let jupiter_tier = env::var("JUPITER_TIER").unwrap_or_else(|_| "lite".to_string()).to_lowercase();
         Self {
             // DexScreener (CONFIRMED WORKING)
             dexscreener_enabled: env::var("ENABLE_DEXSCREENER").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
@@ -499,8 +1369,15 @@ impl WorkingApiSettings {
             jupiter_enabled: env::var("ENABLE_JUPITER").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
             jupiter_v2_recent_url: env::var("JUPITER_V2_RECENT_URL").unwrap_or_else(|_| "https://api.jup.ag/tokens/v2/recent".to_string()),
             jupiter_v1_all_url: env::var("JUPITER_V1_ALL_URL").unwrap_or_else(|_| "https://token.jup.ag/all".to_string()),
-            jupiter_quote_api: env::var("JUPITER_QUOTE_API").unwrap_or_else(|_| "https://quote-api.jup.ag/v6/quote".to_string()),
+            jupiter_tier: jupiter_tier.clone(),
+            jupiter_base_url: env::var("JUPITER_BASE_URL").unwrap_or_default(),
+            jupiter_api_key: env::var("JUPITER_API_KEY").unwrap_or_default(),
+            jupiter_send_api_key: env::var("JUPITER_SEND_API_KEY").ok().and_then(|v| v.parse().ok()).unwrap_or(jupiter_tier == "paid"),
+            jupiter_quote_api: env::var("JUPITER_QUOTE_API").unwrap_or_default(),
+            jupiter_swap_api: env::var("JUPITER_SWAP_API").unwrap_or_default(),
             jupiter_timeout_ms: env::var("JUPITER_TIMEOUT_MS").unwrap_or_else(|_| "10000".to_string()).parse().unwrap_or(10000),
+            jupiter_fee_account: env::var("JUPITER_FEE_ACCOUNT").ok().filter(|s| !s.trim().is_empty()),
+            jupiter_platform_fee_bps: env::var("JUPITER_PLATFORM_FEE_BPS").unwrap_or_else(|_| "0".to_string()).parse().unwrap_or(0),
             
             // RugCheck API (CONFIRMED WORKING)
             rugcheck_enabled: env::var("ENABLE_RUGCHECK").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
@@ -543,6 +1420,43 @@ impl PremiumEndpointSettings {
             grpc_enabled: env::var("GRPC_ENABLED").unwrap_or_else(|_| "false".to_string()).parse().unwrap_or(false),
             grpc_endpoint: env::var("GRPC_ENDPOINT").unwrap_or_else(|_| "grpc://api.mainnet-beta.solana.com:10015".to_string()),
             grpc_token: env::var("GRPCTOKEN").unwrap_or_default(),
+            
+            // Birdeye (API key required)
+            birdeye_enabled: env::var("BIRDEYE_ENABLED").unwrap_or_else(|_| "false".to_string()).parse().unwrap_or(false),
+            birdeye_api_key: env::var("BIRDEYE_API_KEY").unwrap_or_default(),
+            
+            // Jito bundles (no key required)
+            jito_enabled: env::var("JITO_ENABLED").unwrap_or_else(|_| "false".to_string()).parse().unwrap_or(false),
+            jito_block_engine_url: env::var("JITO_BLOCK_ENGINE_URL").unwrap_or_else(|_| "https://mainnet.block-engine.jito.wtf".to_string()),
+            jito_tip_lamports: env::var("JITO_TIP_LAMPORTS").unwrap_or_else(|_| "100000".to_string()).parse().unwrap_or(100_000),
+            
+            // Private submission (uses the provider's credentials above)
+            private_tx_enabled: env::var("PRIVATE_TX_ENABLED").unwrap_or_else(|_| "false".to_string()).parse().unwrap_or(false),
+            private_tx_provider: env::var("PRIVATE_TX_PROVIDER").unwrap_or_else(|_| "zeroslot".to_string()).to_lowercase(),
+        }
+    }
+
+    /// ZERO_SLOT_TIP_VALUE (SOL) as lamports
+    pub fn zeroslot_tip(&self) -> Lamports {
+        Lamports::from_sol(self.zeroslot_tip_value)
+    }
+
+    /// NOZOMI_TIP_AMOUNT (SOL) as lamports
+    pub fn nozomi_tip(&self) -> Lamports {
+        Lamports::from_sol(self.nozomi_tip_amount)
+    }
+
+    /// JITO_TIP_LAMPORTS
+    pub fn jito_tip(&self) -> Lamports {
+        Lamports::new(self.jito_tip_lamports)
+    }
+
+    /// Tip the enabled private relay adds to every swap (zero when private submission is off)
+    pub fn private_tx_tip(&self) -> Lamports {
+        match (self.private_tx_enabled, self.private_tx_provider.as_str()) {
+            (true, "zeroslot") => self.zeroslot_tip(),
+            (true, "nozomi") => self.nozomi_tip(),
+            _ => Lamports::ZERO,
         }
     }
 }
@@ -571,20 +1485,104 @@ impl PerformanceSettings {
 
 impl MonitoringSettings {
     pub fn from_env() -> Self {
+        let storage_backend = env::var("STORAGE_BACKEND").unwrap_or_else(|_| "json".to_string()).to_lowercase();
         Self {
             scan_interval_seconds: env::var("SCAN_INTERVAL_SECONDS").unwrap_or_else(|_| "30".to_string()).parse().unwrap_or(30),
             price_check_interval_ms: env::var("PRICE_CHECK_INTERVAL_MS").unwrap_or_else(|_| "1000".to_string()).parse().unwrap_or(1000),
+            concurrent_position_monitoring: env::var("CONCURRENT_POSITION_MONITORING").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
+            dexscreener_interval_ms: env::var("DEXSCREENER_INTERVAL_MS").unwrap_or_else(|_| "1000".to_string()).parse().unwrap_or(1000),
+            scan_error_backoff_base_ms: env::var("SCAN_ERROR_BACKOFF_BASE_MS").unwrap_or_else(|_| "5000".to_string()).parse().unwrap_or(5000),
+            scan_error_backoff_max_ms: env::var("SCAN_ERROR_BACKOFF_MAX_MS").unwrap_or_else(|_| "120000".to_string()).parse().unwrap_or(120000),
+            scan_degraded_alert_threshold: env::var("SCAN_DEGRADED_ALERT_THRESHOLD").unwrap_or_else(|_| "5".to_string()).parse().unwrap_or(5),
+            sol_price_interval_ms: env::var("SOL_PRICE_INTERVAL_MS").unwrap_or_else(|_| "300000".to_string()).parse().unwrap_or(300000),
+            sol_price_retries: env::var("SOL_PRICE_RETRIES").unwrap_or_else(|_| "1".to_string()).parse().unwrap_or(1),
+            fallback_sol_price_usd: env::var("FALLBACK_SOL_PRICE_USD").ok().and_then(|v| v.parse().ok()).unwrap_or(FALLBACK_SOL_PRICE_USD),
             position_update_interval_ms: env::var("POSITION_UPDATE_INTERVAL_MS").unwrap_or_else(|_| "5000".to_string()).parse().unwrap_or(5000),
             health_check_interval_minutes: env::var("HEALTH_CHECK_INTERVAL_MINUTES").unwrap_or_else(|_| "5".to_string()).parse().unwrap_or(5),
+            price_cache_ttl_secs: env::var("PRICE_CACHE_TTL_SECS").unwrap_or_else(|_| "600".to_string()).parse().unwrap_or(600),
+            max_alert_history: env::var("MAX_ALERT_HISTORY").unwrap_or_else(|_| "100".to_string()).parse().unwrap_or(100),
+            stale_pricing_alert_secs: env::var("STALE_PRICING_ALERT_SECS").unwrap_or_else(|_| "300".to_string()).parse().unwrap_or(300),
+            stale_pricing_alert_fraction: env::var("STALE_PRICING_ALERT_FRACTION").unwrap_or_else(|_| "0.5".to_string()).parse().unwrap_or(0.5),
+            decision_trace_enabled: env::var("DECISION_TRACE").unwrap_or_else(|_| "false".to_string()).parse().unwrap_or(false),
+            decision_trace_path: env::var("DECISION_TRACE_PATH").unwrap_or_else(|_| "./data/decision_trace.jsonl".to_string()),
+            decision_trace_mints: env::var("DECISION_TRACE_MINTS").unwrap_or_default()
+                .split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect(),
             
             log_level: env::var("LOG_LEVEL").unwrap_or_else(|_| "info".to_string()),
             log_to_file: env::var("LOG_TO_FILE").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
             log_file_path: env::var("LOG_FILE_PATH").unwrap_or_else(|_| "./logs/sniper.log".to_string()),
             save_analysis_results: env::var("SAVE_ANALYSIS_RESULTS").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
             analysis_results_path: env::var("ANALYSIS_RESULTS_PATH").unwrap_or_else(|_| "./data/analysis".to_string()),
+            health_snapshot_path: env::var("HEALTH_SNAPSHOT_PATH").unwrap_or_else(|_| "./data/health.json".to_string()),
+            health_snapshot_interval_secs: env::var("HEALTH_SNAPSHOT_INTERVAL_SECS").unwrap_or_else(|_| "60".to_string()).parse().unwrap_or(60),
+            trade_journal_path: env::var("TRADE_JOURNAL_PATH").unwrap_or_else(|_| "./data/pending_trades.json".to_string()),
+            daily_trades_path: env::var("DAILY_TRADES_PATH").unwrap_or_else(|_| "./data/daily_trades.json".to_string()),
+            compound_state_path: env::var("COMPOUND_STATE_PATH").unwrap_or_else(|_| "./data/compound_state.json".to_string()),
+            portfolio_history_path: env::var("PORTFOLIO_HISTORY_PATH").unwrap_or_else(|_| "./data/portfolio_history.jsonl".to_string()),
+            storage_backend: storage_backend.clone(),
+            storage_path: env::var("STORAGE_PATH").unwrap_or_else(|_| {
+                if storage_backend == "sqlite" { "./data/sniper.db" } else { "./data/storage" }.to_string()
+            }),
+            create_data_dirs: env::var("CREATE_DATA_DIRS").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
+            webhook_url: env::var("WEBHOOK_URL").unwrap_or_default(),
+            webhook_bearer_token: env::var("WEBHOOK_BEARER_TOKEN").unwrap_or_default(),
             
             enable_real_time_alerts: env::var("ENABLE_REAL_TIME_ALERTS").unwrap_or_else(|_| "true".to_string()).parse().unwrap_or(true),
             max_new_tokens_per_scan: env::var("MAX_NEW_TOKENS_PER_SCAN").unwrap_or_else(|_| "10".to_string()).parse().unwrap_or(10),
+            max_candidates_per_scan: env::var("MAX_CANDIDATES_PER_SCAN").unwrap_or_else(|_| "10".to_string()).parse().unwrap_or(10),
+            allow_missing_creation_time: env::var("ALLOW_MISSING_CREATION_TIME").unwrap_or_else(|_| "false".to_string()).parse().unwrap_or(false),
+            skip_inactive_pools: env::var("SKIP_INACTIVE_POOLS").unwrap_or_else(|_| "false".to_string()).parse().unwrap_or(false),
+            inactive_pool_min_age_minutes: env::var("INACTIVE_POOL_MIN_AGE_MINUTES").unwrap_or_else(|_| "10".to_string()).parse().unwrap_or(10),
         }
     }
-}
\ No newline at end of file
+}
+/// JUPITER_TIER is lite or paid, and paid has a key it actually sends
+fn validate_jupiter_tier(tier: &str, api_key: &str, send_api_key: bool) -> Result<(), String> {
+    match tier {
+        "lite" => Ok(()),
+        "paid" if api_key.is_empty() => Err("❌ JUPITER_API_KEY is required when JUPITER_TIER=paid".to_string()),
+        "paid" if !send_api_key => Err("❌ JUPITER_SEND_API_KEY=false would strip the key the paid tier needs".to_string()),
+        "paid" => Ok(()),
+        other => Err(format!("❌ JUPITER_TIER must be lite or paid, got: {}", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tips_and_fees_convert_to_the_expected_lamports() {
+        let mut settings = BotSettings::from_env().unwrap();
+        let premium = &mut settings.apis.premium_endpoints;
+        premium.zeroslot_tip_value = 0.0015;
+        premium.nozomi_tip_amount = 0.001;
+        premium.jito_tip_lamports = 100_000;
+        assert_eq!(premium.zeroslot_tip(), Lamports::new(1_500_000));
+        assert_eq!(premium.nozomi_tip(), Lamports::new(1_000_000));
+        assert_eq!(premium.jito_tip(), Lamports::new(100_000));
+
+        premium.private_tx_enabled = true;
+        premium.private_tx_provider = "nozomi".to_string();
+        assert_eq!(premium.private_tx_tip(), Lamports::new(1_000_000));
+        premium.private_tx_enabled = false;
+        assert_eq!(premium.private_tx_tip(), Lamports::ZERO);
+
+        let wallet = &mut settings.wallet;
+        wallet.priority_fee_micro_lamports = 100_000;
+        wallet.compute_unit_limit = 300_000;
+        wallet.compute_unit_price = 2_000;
+        assert_eq!(wallet.priority_fee(), Lamports::new(1));
+        assert_eq!(wallet.compute_budget_fee(), Lamports::new(600));
+    }
+
+    #[test]
+    fn paid_jupiter_tier_needs_a_key_it_sends() {
+        assert_eq!(validate_jupiter_tier("paid", "", true).unwrap_err(), "❌ JUPITER_API_KEY is required when JUPITER_TIER=paid");
+        assert!(validate_jupiter_tier("paid", "secret", false).unwrap_err().contains("JUPITER_SEND_API_KEY=false"));
+        assert!(validate_jupiter_tier("paid", "secret", true).is_ok());
+        // Lite needs no key at all
+        assert!(validate_jupiter_tier("lite", "", false).is_ok());
+        assert_eq!(validate_jupiter_tier("pro", "secret", true).unwrap_err(), "❌ JUPITER_TIER must be lite or paid, got: pro");
+    }
+}
@@ -0,0 +1,425 @@
+// Settings Schema - every env var BotSettings reads, with its type, default and a short
+// description, for `config schema`. Entries name their BotSettings field so fields added without
+// an entry (or entries left behind by removed fields) show up in `undocumented_settings`.
+use crate::settings::BotSettings;
+
+pub struct SettingSpec {
+    pub field: &'static str,       // BotSettings field within the section
+    pub env_var: &'static str,
+    pub kind: &'static str,        // bool, integer, number, text, list or map
+    pub default: &'static str,     // Value used when the env var is unset; empty = none
+    pub description: &'static str,
+}
+
+/// One BotSettings section; `name` is its path in the exported JSON ("apis.premium_endpoints")
+pub struct SchemaSection {
+    pub name: &'static str,
+    pub settings: &'static [SettingSpec],
+}
+
+const fn spec(
+    field: &'static str,
+    env_var: &'static str,
+    kind: &'static str,
+    default: &'static str,
+    description: &'static str,
+) -> SettingSpec {
+    SettingSpec { field, env_var, kind, default, description }
+}
+
+pub const SETTINGS_SCHEMA: &[SchemaSection] = &[
+    SchemaSection {
+        name: "wallet",
+        settings: &[
+            spec("private_key", "SOLANA_PRIVATE_KEY", "text", "", "Wallet secret key (base58 or JSON byte array); required"),
+            spec("min_balance_sol", "MIN_WALLET_BALANCE_SOL", "number", "0.1", "Buys stop when the wallet balance falls below this reserve"),
+            spec("reserve_fee_buffer_sol", "RESERVE_FEE_BUFFER_SOL", "number", "0.01", "Kept liquid for fees on top of the minimum balance; SOL-spending sends that would dip into it are refused"),
+            spec("rpc_url", "RPC_URL", "text", "https://api.mainnet-beta.solana.com", "Primary Solana RPC endpoint"),
+            spec("backup_rpc_urls", "BACKUP_RPC_URLS", "list", "https://solana-rpc.publicnode.com,https://rpc.ankr.com/solana", "Comma-separated fallback RPC endpoints"),
+            spec("rpc_max_requests_per_second", "RPC_MAX_REQUESTS_PER_SECOND", "integer", "10", "Shared by trader, wallet and RugCheck; 0 = unlimited"),
+            spec("commitment", "COMMITMENT", "text", "confirmed", "Commitment for transactions: processed, confirmed or finalized"),
+            spec("balance_commitment", "BALANCE_COMMITMENT", "text", "confirmed", "Balance guard reads: processed, confirmed or finalized"),
+            spec("confirmation_timeout_ms", "CONFIRMATION_TIMEOUT_MS", "integer", "15000", "Wait per swap send before checking whether its blockhash expired"),
+            spec("max_retries", "MAX_RETRIES", "integer", "10", "RPC send retries"),
+            spec("priority_fee_micro_lamports", "PRIORITY_FEE_MICRO_LAMPORTS", "integer", "100000", "Priority fee per compute unit for swaps"),
+            spec("compute_unit_limit", "COMPUTE_UNIT_LIMIT", "integer", "300000", "Compute units requested per swap"),
+            spec("compute_unit_price", "COMPUTE_UNIT_PRICE", "integer", "2000", "Compute unit price in micro-lamports"),
+            spec("sell_priority_fee_micro_lamports", "SELL_PRIORITY_FEE_MICRO_LAMPORTS", "integer", "0", "Compute unit price for sells, so exits outbid entries; 0 = COMPUTE_UNIT_PRICE"),
+            spec("persistent_wsol", "PERSISTENT_WSOL", "bool", "false", "Keep one WSOL account instead of wrapping/unwrapping per swap"),
+            spec("wsol_top_up_sol", "WSOL_TOP_UP_SOL", "number", "1.0", "Wrap at least this much whenever the WSOL account runs short"),
+        ],
+    },
+    SchemaSection {
+        name: "trading",
+        settings: &[
+            spec("position_size_sol", "POSITION_SIZE_SOL", "number", "1.0", "SOL spent per buy"),
+            spec("min_position_size_sol", "MIN_POSITION_SIZE_SOL", "number", "0.05", "Buys shrink to fit the balance after reserve and fees, but never below this"),
+            spec("funding_mint", "FUNDING_MINT", "text", "So11111111111111111111111111111111111111112", "Mint buys are paid in (wrapped SOL by default; e.g. USDC), sized at position_size_sol worth"),
+            spec("sell_to_funding_mint", "SELL_TO_FUNDING_MINT", "bool", "false", "Sell back into the funding mint instead of SOL"),
+            spec("max_positions", "MAX_ACTIVE_POSITIONS", "integer", "5", "Open positions the allocation budget is sized for"),
+            spec("max_token_allocation_percent", "MAX_TOKEN_ALLOCATION_PERCENT", "number", "0", "One token's share of capital (at least max_positions x position size); 0 = off"),
+            spec("min_position_tokens", "MIN_POSITION_TOKENS", "integer", "1", "Fills below this many raw tokens aren't tracked (a zero fill would poison P&L)"),
+            spec("min_seconds_between_buys", "MIN_SECONDS_BETWEEN_BUYS", "integer", "0", "Pools arriving sooner after the last buy are deferred; 0 = no spacing"),
+            spec("max_concurrent_buys", "MAX_CONCURRENT_BUYS", "integer", "1", "Buys allowed in flight at once; more are skipped until one resolves"),
+            spec("sell_cooldown_secs", "SELL_COOLDOWN_SECS", "integer", "0", "A mint is ignored this long after any sell of it, win or loss; 0 = off"),
+            spec("buy_spacing_per_source", "BUY_SPACING_PER_SOURCE", "bool", "false", "Space buys per detection source instead of globally"),
+            spec("max_daily_trades", "MAX_DAILY_TRADES", "integer", "0", "Buys per UTC day (sells don't count); 0 = unlimited"),
+            spec("compound_enabled", "COMPOUND_ENABLED", "bool", "false", "Grow the buy size from POSITION_SIZE_SOL as realized profits accumulate"),
+            spec("compound_step_percent", "COMPOUND_STEP_PERCENT", "number", "10.0", "Buy size increase per compounding step"),
+            spec("compound_wins_per_step", "COMPOUND_WINS_PER_STEP", "integer", "3", "Profitable closes that earn a step (a step is undone once realized P&L falls below where its run began)"),
+            spec("max_position_size_sol", "MAX_POSITION_SIZE_SOL", "number", "2.0", "Compounding never grows the buy size past this"),
+            spec("trading_hours", "TRADING_HOURS", "text", "", "Hours new buys are allowed, e.g. \"9-17,22-2\"; empty = always (positions are managed 24/7)"),
+            spec("trading_hours_utc_offset", "TRADING_HOURS_UTC_OFFSET", "number", "0", "Hours TRADING_HOURS is ahead of UTC (e.g. -5, 5.5); 0 = UTC"),
+            spec("min_liquidity_sol", "MIN_LIQUIDITY_SOL", "number", "10.0", "Skip pools with less SOL liquidity"),
+            spec("max_slippage_percent", "MAX_SLIPPAGE_PERCENT", "number", "15.0", "Highest slippage a buy may widen to"),
+            spec("initial_slippage_percent", "INITIAL_SLIPPAGE_PERCENT", "number", "15.0", "Buys start here and widen toward max_slippage_percent on slippage failures"),
+            spec("slippage_escalation_attempts", "SLIPPAGE_ESCALATION_ATTEMPTS", "integer", "3", "Total buy attempts including the first"),
+            spec("confirm_attempts", "CONFIRM_ATTEMPTS", "integer", "3", "Sends per swap; an expired send is rebuilt with a fresh blockhash"),
+            spec("quote_attempts", "QUOTE_ATTEMPTS", "integer", "3", "Buy quotes while Jupiter is temporarily unavailable (\"no route\" follows NO_ROUTE_RETRY_SECS)"),
+            spec("quote_retry_delay_ms", "QUOTE_RETRY_DELAY_MS", "integer", "150", "First re-quote wait; the Nth waits N times this"),
+            spec("no_route_retry_secs", "NO_ROUTE_RETRY_SECS", "integer", "0", "Keep re-quoting a buy Jupiter can't route yet for this long; 0 = skip at once"),
+            spec("no_route_retry_interval_ms", "NO_ROUTE_RETRY_INTERVAL_MS", "integer", "2000", "Wait between no-route re-quotes"),
+            spec("require_manual_approval", "REQUIRE_MANUAL_APPROVAL", "bool", "false", "Ask via Telegram Approve/Reject buttons before every buy"),
+            spec("manual_approval_timeout_secs", "MANUAL_APPROVAL_TIMEOUT_SECS", "integer", "120", "Unanswered approval requests are rejected after this long"),
+            spec("enable_auto_trading", "ENABLE_AUTO_SNIPE", "bool", "false", "Buy automatically; false = scan and alert only"),
+            spec("signals_only", "SIGNALS_ONLY", "bool", "false", "With auto-trading off: alert tokens that pass every check instead of buying"),
+            spec("dry_run_paper_positions", "DRY_RUN_PAPER_POSITIONS", "bool", "false", "start --dry-run also tracks would-be buys as in-memory paper positions"),
+            spec("max_round_trip_cost_percent", "MAX_ROUND_TRIP_COST_PERCENT", "number", "0", "Reject when buying then instantly selling would lose more than this; 0 = off"),
+            spec("copy_trade_wallets", "COPY_TRADE_WALLETS", "list", "", "Wallets whose buys are copied (source \"copy:<wallet>\"); empty = off"),
+            spec("copy_trade_poll_interval_ms", "COPY_TRADE_POLL_INTERVAL_MS", "integer", "2000", "How often followed wallets are checked for new buys"),
+            spec("stop_loss_percent", "STOP_LOSS_PERCENT", "number", "50.0", "Sell everything once down this much"),
+            spec("trailing_stop_enabled", "TRAILING_STOP_ENABLED", "bool", "true", "Use a trailing stop once the position is up TRAILING_ACTIVATION_PERCENT"),
+            spec("trailing_stop_percent", "TRAILING_STOP_PERCENT", "number", "30.0", "Trailing stop distance below the highest price"),
+            spec("trailing_activation_percent", "TRAILING_ACTIVATION_PERCENT", "number", "20.0", "Trailing stop arms once the position is up this much"),
+            spec("trailing_stop_rungs", "TRAILING_STOP_RUNGS", "list", "", "Tighter trails past higher peak gains as profit:trail, e.g. \"100:15,300:8\"; empty = TRAILING_STOP_PERCENT throughout"),
+            spec("exit_mode", "EXIT_MODE", "text", "trailing", "\"trailing\" (trailing stop) or \"momentum\" (scale out when a rally stalls)"),
+            spec("momentum_lower_updates", "MOMENTUM_LOWER_UPDATES", "integer", "3", "Momentum: consecutive lower price updates after a new high that trigger a scale-out"),
+            spec("momentum_sell_percent", "MOMENTUM_SELL_PERCENT", "number", "50.0", "Momentum: share of the position sold per scale-out"),
+            spec("profit_threshold_percent", "PROFIT_THRESHOLD_PERCENT", "number", "50.0", "Take profit once up this much"),
+            spec("sell_percentage", "SELL_PERCENTAGE", "number", "75.0", "Share of the position sold at the take-profit"),
+            spec("max_hold_time_hours", "MAX_HOLD_TIME_HOURS", "integer", "24", "Sell positions held longer than this"),
+            spec("source_max_hold_minutes", "SOURCE_MAX_HOLD_MINUTES", "map", "", "Per-source hold time overrides as source:minutes, e.g. \"copy:45\""),
+            spec("min_timeout_sell_profit_sol", "MIN_TIMEOUT_SELL_PROFIT_SOL", "number", "0.0", "Max-hold sells projected to net less than this after fees are deferred"),
+            spec("timeout_grace_minutes", "TIMEOUT_GRACE_MINUTES", "integer", "5", "How much longer a deferred max-hold sell waits before deciding again"),
+            spec("max_timeout_extension_minutes", "MAX_TIMEOUT_EXTENSION_MINUTES", "integer", "0", "Longest a max-hold sell can be deferred in total; 0 = always sell on time"),
+            spec("portfolio_take_profit_sol", "PORTFOLIO_TAKE_PROFIT_SOL", "number", "0.0", "Day's P&L (realized + open) at which everything is sold and buys pause; 0 = off"),
+            spec("portfolio_take_profit_percent", "PORTFOLIO_TAKE_PROFIT_PERCENT", "number", "0.0", "Sell everything and pause buys once open positions are up this much in total; 0 = off"),
+            spec("entry_delay_ms", "ENTRY_DELAY_MS", "integer", "0", "Wait before buying to confirm the price; 0 = buy immediately"),
+            spec("entry_max_price_rise_percent", "ENTRY_MAX_PRICE_RISE_PERCENT", "number", "20.0", "Abort if price rose more than this during the delay"),
+            spec("max_entry_price_usd", "MAX_ENTRY_PRICE_USD", "number", "0.0", "Don't buy above this price; 0 = no limit"),
+            spec("token_max_entry_price_usd", "TOKEN_MAX_ENTRY_PRICES_USD", "map", "", "Per-mint price limits as mint:price, overriding MAX_ENTRY_PRICE_USD"),
+            spec("min_out_tolerance_percent", "MIN_OUT_TOLERANCE_PERCENT", "number", "20.0", "Reject quotes guaranteeing less than expected minus this; 0 = disabled"),
+            spec("min_routes", "MIN_ROUTES", "integer", "1", "Skip buys whose best Jupiter route has fewer legs (1 = any route)"),
+            spec("max_price_impact_percent", "MAX_PRICE_IMPACT_PERCENT", "number", "0.0", "Skip buys whose quote moves the price more than this (0 = no limit)"),
+            spec("requote_before_send_min_sol", "REQUOTE_BEFORE_SEND_MIN_SOL", "number", "0.0", "Buys at least this size re-check price impact on a fresh quote right before sending (0 = off)"),
+            spec("min_sell_liquidity_sol", "MIN_SELL_LIQUIDITY_SOL", "number", "0.0", "Auto-sells check pool liquidity first; 0 = disabled"),
+            spec("dust_threshold_usd", "DUST_THRESHOLD_USD", "number", "0.05", "Balances worth less count as sold: no swap, no scale-out remainder; 0 = only empty balances"),
+            spec("hold_on_low_liquidity", "HOLD_ON_LOW_LIQUIDITY", "bool", "true", "true = hold and retry, then write off; false = sell anyway"),
+            spec("low_liquidity_hold_minutes", "LOW_LIQUIDITY_HOLD_MINUTES", "integer", "5", "How long a held position waits for liquidity before it's written off as rugged"),
+        ],
+    },
+    SchemaSection {
+        name: "security",
+        settings: &[
+            spec("min_acceptable_score", "MIN_ACCEPTABLE_SCORE", "integer", "70", "Lowest RugCheck score (0-100) that may be bought"),
+            spec("high_confidence_score", "HIGH_CONFIDENCE_SCORE", "integer", "85", "Score reported as high confidence"),
+            spec("medium_confidence_score", "MEDIUM_CONFIDENCE_SCORE", "integer", "70", "Score reported as medium confidence"),
+            spec("require_rugcheck_success", "REQUIRE_RUGCHECK_SUCCESS", "bool", "true", "Reject tokens whose RugCheck report could not be fetched"),
+            spec("require_all_criteria", "REQUIRE_ALL_CRITERIA", "bool", "true", "Buy only when every criterion passes (the weighted score is 100 only then)"),
+            spec("score_weight_mint_authority", "SCORE_WEIGHT_MINT_AUTHORITY", "number", "20", "Weight of mint authority in the 0-100 score"),
+            spec("score_weight_freeze_authority", "SCORE_WEIGHT_FREEZE_AUTHORITY", "number", "20", "Weight of freeze authority in the 0-100 score"),
+            spec("score_weight_lp", "SCORE_WEIGHT_LP", "number", "20", "Weight of LP burned/locked in the 0-100 score"),
+            spec("score_weight_taxes", "SCORE_WEIGHT_TAXES", "number", "15", "Weight of buy/sell taxes in the 0-100 score"),
+            spec("score_weight_top_holders", "SCORE_WEIGHT_TOP_HOLDERS", "number", "15", "Weight of holder concentration in the 0-100 score"),
+            spec("score_weight_can_sell", "SCORE_WEIGHT_CAN_SELL", "number", "10", "Weight of the sellability test in the 0-100 score"),
+            spec("production_mode", "PRODUCTION_MODE", "bool", "true", "No sample tokens or random test scenarios - missing data = reject"),
+            spec("rugcheck_fetch_max_attempts", "RUGCHECK_FETCH_MAX_ATTEMPTS", "integer", "3", "RugCheck report fetches before giving up"),
+            spec("rugcheck_fetch_retry_delay_ms", "RUGCHECK_FETCH_RETRY_DELAY_MS", "integer", "500", "First wait between RugCheck fetch attempts, doubled after each failure"),
+            spec("secondary_rugcheck_mode", "SECONDARY_RUGCHECK_MODE", "text", "off", "rugcheck.xyz second opinion: \"off\", \"both\" (it must reach the min score too) or \"combined\" (the average must, instead of the primary bar)"),
+            spec("secondary_rugcheck_min_score", "SECONDARY_RUGCHECK_MIN_SCORE", "integer", "70", "Minimum rugcheck.xyz score for the second opinion"),
+            spec("enable_liquidity_checks", "ENABLE_LIQUIDITY_CHECKS", "bool", "true", "Run the liquidity checks"),
+            spec("min_liquidity_lock_percentage", "MIN_LIQUIDITY_LOCK_PERCENTAGE", "number", "80.0", "Share of LP that must be locked or burned"),
+            spec("min_total_liquidity_usd", "MIN_TOTAL_LIQUIDITY_USD", "number", "10000.0", "Skip pools with less total liquidity"),
+            spec("max_dev_wallet_percentage", "MAX_DEV_WALLET_PERCENTAGE", "number", "10.0", "Largest supply share the developer wallet may hold"),
+            spec("max_liquidity_disagreement_factor", "MAX_LIQUIDITY_DISAGREEMENT_FACTOR", "number", "0", "Reject when indexer and on-chain SOL reserves differ by more than this (0 = off)"),
+            spec("max_pools_per_token", "MAX_POOLS_PER_TOKEN", "integer", "0", "Reject tokens spread over more pools than this, unless MIN_MAIN_POOL_LIQUIDITY_USD exempts them; 0 = off"),
+            spec("min_main_pool_liquidity_usd", "MIN_MAIN_POOL_LIQUIDITY_USD", "number", "5000", "A pool this deep exempts its token from MAX_POOLS_PER_TOKEN"),
+            spec("min_sol_pairs", "MIN_SOL_PAIRS", "integer", "1", "SOL-paired pools with liquidity a token needs (1 = the detected pool is enough)"),
+            spec("min_primary_sol_pair_liquidity_usd", "MIN_PRIMARY_SOL_PAIR_LIQUIDITY_USD", "number", "0", "One SOL pair this deep satisfies MIN_SOL_PAIRS on its own; 0 = no exemption"),
+            spec("enable_authority_checks", "ENABLE_AUTHORITY_CHECKS", "bool", "true", "Run the mint/freeze authority checks"),
+            spec("reject_mint_authority", "REJECT_MINT_AUTHORITY", "bool", "true", "Reject tokens whose mint authority is still set"),
+            spec("reject_freeze_authority", "REJECT_FREEZE_AUTHORITY", "bool", "false", "Reject tokens whose freeze authority is still set"),
+            spec("allow_mutable_metadata", "ALLOW_MUTABLE_METADATA", "bool", "true", "Accept tokens whose metadata can still change"),
+            spec("post_buy_sell_probe", "POST_BUY_SELL_PROBE", "bool", "true", "Quote a sell right after each buy to catch honeypots"),
+            spec("position_recheck_interval_secs", "POSITION_RECHECK_INTERVAL_SECS", "integer", "0", "Re-check open positions' pool liquidity this often; 0 = disabled"),
+            spec("position_recheck_max_liquidity_drop_percent", "POSITION_RECHECK_MAX_LIQUIDITY_DROP_PERCENT", "number", "50.0", "Exit when liquidity falls this far below the first re-check"),
+            spec("name_blacklist_patterns", "NAME_BLACKLIST_PATTERNS", "list", "scam,honeypot,rugpull", "Rejected name/symbol fragments (\"re:\" prefix = regex), case-insensitive"),
+            spec("known_tickers", "KNOWN_TICKERS", "map", "SOL:So11111111111111111111111111111111111111112,USDC:EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v,BONK:DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263,WIF:EKpQGSJtjMFqKZ9KQanSqYXRcF8fBopzLHYxdM65zcjm", "SYMBOL:mint pairs; other tokens using these symbols are rejected as impersonations"),
+            spec("avoided_pair_mints", "AVOIDED_PAIR_MINTS", "list", "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v,Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB,3NZ9JMVBmGAqocybic2c7LQCJScmgsAZ6vQqTDzcqmJh,7vfCXTUXx5WJV5JADk17DUJ4ksgau7utNKj4b963voxs", "Stablecoins / wrapped majors never worth sniping (USDC, USDT, wBTC, wETH)"),
+            spec("enable_holder_checks", "ENABLE_HOLDER_CHECKS", "bool", "true", "Run the holder distribution checks"),
+            spec("max_top_holder_percentage", "MAX_TOP_HOLDER_PERCENTAGE", "number", "30.0", "Largest supply share a single holder may have"),
+            spec("min_holder_count", "MIN_HOLDER_COUNT", "integer", "100", "Fewest holders a token may have"),
+            spec("holder_check_min_pool_age_minutes", "HOLDER_CHECK_MIN_POOL_AGE_MINUTES", "integer", "30", "Holder checks skip pools younger than this (new pools legitimately have few holders)"),
+            spec("min_token_age_minutes", "MIN_TOKEN_AGE_MINUTES", "integer", "0", "Skip tokens younger than this; 0 = off"),
+            spec("max_token_age_minutes", "MAX_TOKEN_AGE_MINUTES", "integer", "0", "Skip tokens older than this; 0 = off"),
+            spec("enable_creator_checks", "ENABLE_CREATOR_CHECKS", "bool", "false", "Check the creator wallet age and blacklist before buying"),
+            spec("min_creator_wallet_age_minutes", "MIN_CREATOR_WALLET_AGE_MINUTES", "integer", "1440", "Reject creators whose wallet was younger than this at launch"),
+            spec("creator_blacklist_path", "CREATOR_BLACKLIST_PATH", "text", "./data/creator_blacklist.txt", "Known rugger wallets, one per line (# comments); missing file = empty"),
+            spec("auto_reject_critical_risks", "AUTO_REJECT_CRITICAL_RISKS", "bool", "true", "Reject tokens with any critical risk"),
+            spec("auto_reject_high_risks", "AUTO_REJECT_HIGH_RISKS", "bool", "true", "Reject tokens with any high risk"),
+            spec("max_allowed_medium_risks", "MAX_ALLOWED_MEDIUM_RISKS", "integer", "2", "Medium risks tolerated before rejecting"),
+        ],
+    },
+    SchemaSection {
+        name: "telegram",
+        settings: &[
+            spec("bot_token", "TELEGRAM_BOT_TOKEN", "text", "", "Bot API token from @BotFather"),
+            spec("chat_id", "TELEGRAM_CHAT_ID", "text", "", "Chat that receives alerts"),
+            spec("notifications_enabled", "TELEGRAM_NOTIFICATIONS_ENABLED", "bool", "true", "Send Telegram notifications"),
+            spec("send_buy_alerts", "TELEGRAM_SEND_BUY_ALERTS", "bool", "true", "Send buy alerts"),
+            spec("send_sell_alerts", "TELEGRAM_SEND_SELL_ALERTS", "bool", "true", "Send sell alerts"),
+            spec("send_profit_summaries", "TELEGRAM_SEND_PROFIT_SUMMARIES", "bool", "true", "Send periodic profit summaries"),
+            spec("send_error_alerts", "TELEGRAM_SEND_ERROR_ALERTS", "bool", "true", "Send error alerts"),
+            spec("send_rugcheck_alerts", "TELEGRAM_SEND_RUGCHECK_ALERTS", "bool", "true", "Send RugCheck rejection alerts"),
+            spec("error_alert_dedup_window_secs", "TELEGRAM_ERROR_DEDUP_WINDOW_SECS", "integer", "300", "Identical errors sent at most once per window"),
+            spec("buy_template", "TELEGRAM_BUY_TEMPLATE", "text", "", "Custom buy alert text; empty = built-in"),
+            spec("sell_template", "TELEGRAM_SELL_TEMPLATE", "text", "", "Custom sell alert text; empty = built-in"),
+            spec("profit_template", "TELEGRAM_PROFIT_TEMPLATE", "text", "", "Custom profit alert text; empty = built-in"),
+            spec("explorer_links", "TELEGRAM_EXPLORER_LINKS", "list", "DexScreener:https://dexscreener.com/solana/{mint}", "name:url pairs linked from buy, sell and signal alerts ({mint} is replaced)"),
+        ],
+    },
+    SchemaSection {
+        name: "apis",
+        settings: &[
+            spec("dexscreener_enabled", "ENABLE_DEXSCREENER", "bool", "true", "Detect new pools from DexScreener"),
+            spec("dexscreener_api_url", "DEXSCREENER_API_URL", "text", "https://api.dexscreener.com/latest/dex/search/?q=SOL", "DexScreener search endpoint"),
+            spec("dexscreener_timeout_ms", "DEXSCREENER_TIMEOUT_MS", "integer", "10000", "DexScreener request timeout"),
+            spec("jupiter_enabled", "ENABLE_JUPITER", "bool", "true", "Detect new tokens from Jupiter"),
+            spec("jupiter_v2_recent_url", "JUPITER_V2_RECENT_URL", "text", "https://api.jup.ag/tokens/v2/recent", "Jupiter recent tokens endpoint"),
+            spec("jupiter_v1_all_url", "JUPITER_V1_ALL_URL", "text", "https://token.jup.ag/all", "Jupiter token list endpoint"),
+            spec("jupiter_tier", "JUPITER_TIER", "text", "lite", "\"lite\" (free, rate limited) or \"paid\" (api.jup.ag, needs JUPITER_API_KEY)"),
+            spec("jupiter_base_url", "JUPITER_BASE_URL", "text", "", "Jupiter API host; empty = the tier's default host"),
+            spec("jupiter_api_key", "JUPITER_API_KEY", "text", "", "Jupiter API key; required on the paid tier"),
+            spec("jupiter_send_api_key", "JUPITER_SEND_API_KEY", "bool", "", "Send X-API-KEY; unset = on for paid, off for lite"),
+            spec("jupiter_quote_api", "JUPITER_QUOTE_API", "text", "", "Full quote URL override; empty = derived from the base URL"),
+            spec("jupiter_swap_api", "JUPITER_SWAP_API", "text", "", "Full swap URL override; empty = derived from the base URL"),
+            spec("jupiter_timeout_ms", "JUPITER_TIMEOUT_MS", "integer", "10000", "Jupiter request timeout"),
+            spec("jupiter_fee_account", "JUPITER_FEE_ACCOUNT", "text", "", "Referral wSOL token account for platform fees; empty = off"),
+            spec("jupiter_platform_fee_bps", "JUPITER_PLATFORM_FEE_BPS", "integer", "0", "Platform fee charged when JUPITER_FEE_ACCOUNT is set (1-255)"),
+            spec("rugcheck_enabled", "ENABLE_RUGCHECK", "bool", "true", "Use the RugCheck API"),
+            spec("rugcheck_api_url", "RUGCHECK_API_URL", "text", "https://api.rugcheck.xyz", "RugCheck API base URL"),
+            spec("rugcheck_timeout_ms", "RUGCHECK_TIMEOUT_MS", "integer", "15000", "RugCheck request timeout"),
+            spec("telegram_enabled", "ENABLE_TELEGRAM", "bool", "true", "Use the Telegram API"),
+            spec("telegram_api_url", "TELEGRAM_API_URL", "text", "https://api.telegram.org", "Telegram API base URL"),
+            spec("telegram_timeout_ms", "TELEGRAM_TIMEOUT_MS", "integer", "5000", "Telegram request timeout"),
+        ],
+    },
+    SchemaSection {
+        name: "apis.premium_endpoints",
+        settings: &[
+            spec("zeroslot_enabled", "ZEROSLOT_ENABLED", "bool", "false", "Enable the 0slot endpoint"),
+            spec("zeroslot_rpc_url", "ZEROSLOT_RPC_URL", "text", "https://ny1.0slot.trade/rpc", "0slot RPC endpoint"),
+            spec("zeroslot_api_key", "ZEROSLOT_API_KEY", "text", "", "0slot API key"),
+            spec("zeroslot_tip_account", "SLOT_TIP_ACCOUNT", "text", "9n3dWQaJF7FQtqCaQKRvpKqB4k4BLAWwQjZSEgN8DqNv", "0slot tip account"),
+            spec("zeroslot_tip_value", "ZERO_SLOT_TIP_VALUE", "number", "0.0015", "0slot tip in SOL"),
+            spec("nozomi_enabled", "NOZOMI_ENABLED", "bool", "false", "Enable the Nozomi endpoint"),
+            spec("nozomi_url", "NOZOMI_URL", "text", "https://ewr1.nozomi.temporal.xyz/rpc", "Nozomi RPC endpoint"),
+            spec("nozomi_uuid", "NOZOMI_UUID", "text", "", "Nozomi client UUID"),
+            spec("nozomi_tip_account", "NOZOMI_TIP_ACCOUNT", "text", "9n3dWQaJF7FQtqCaQKRvpKqB4k4BLAWwQjZSEgN8DqNv", "Nozomi tip account"),
+            spec("nozomi_tip_amount", "NOZOMI_TIP_AMOUNT", "number", "0.001", "Nozomi tip in SOL"),
+            spec("nextblock_enabled", "NEXTBLOCK_ENABLED", "bool", "false", "Enable the NextBlock endpoint"),
+            spec("nextblock_url", "NEXT_BLOCK_URL", "text", "https://api.nextblock.xyz", "NextBlock endpoint"),
+            spec("nextblock_api_key", "NEXTBLOCK_API_KEY", "text", "", "NextBlock API key"),
+            spec("grpc_enabled", "GRPC_ENABLED", "bool", "false", "Enable the gRPC stream"),
+            spec("grpc_endpoint", "GRPC_ENDPOINT", "text", "grpc://api.mainnet-beta.solana.com:10015", "gRPC endpoint"),
+            spec("grpc_token", "GRPCTOKEN", "text", "", "gRPC auth token"),
+            spec("birdeye_enabled", "BIRDEYE_ENABLED", "bool", "false", "Use Birdeye for prices (needs BIRDEYE_API_KEY)"),
+            spec("birdeye_api_key", "BIRDEYE_API_KEY", "text", "", "Birdeye API key"),
+            spec("jito_enabled", "JITO_ENABLED", "bool", "false", "Send swaps as Jito bundles"),
+            spec("jito_block_engine_url", "JITO_BLOCK_ENGINE_URL", "text", "https://mainnet.block-engine.jito.wtf", "Jito block engine endpoint"),
+            spec("jito_tip_lamports", "JITO_TIP_LAMPORTS", "integer", "100000", "Jito tip per bundle (at least 1000)"),
+            spec("private_tx_enabled", "PRIVATE_TX_ENABLED", "bool", "false", "Send swaps through a private relay"),
+            spec("private_tx_provider", "PRIVATE_TX_PROVIDER", "text", "zeroslot", "Private relay: zeroslot or nozomi"),
+        ],
+    },
+    SchemaSection {
+        name: "performance",
+        settings: &[
+            spec("concurrent_requests", "CONCURRENT_REQUESTS", "integer", "10", "Concurrent API requests"),
+            spec("request_timeout_ms", "REQUEST_TIMEOUT_MS", "integer", "3000", "Default request timeout"),
+            spec("retry_attempts", "RETRY_ATTEMPTS", "integer", "5", "Default request retries"),
+            spec("retry_delay_ms", "RETRY_DELAY_MS", "integer", "50", "Delay between request retries"),
+            spec("use_parallel_analysis", "USE_PARALLEL_ANALYSIS", "bool", "true", "Analyze candidates in parallel"),
+            spec("enable_precompute", "ENABLE_PRECOMPUTE", "bool", "true", "Precompute analysis inputs"),
+            spec("max_analysis_threads", "MAX_ANALYSIS_THREADS", "integer", "8", "Analysis worker threads"),
+            spec("cache_size_mb", "CACHE_SIZE_MB", "integer", "256", "Cache size"),
+            spec("cleanup_interval_minutes", "CLEANUP_INTERVAL_MINUTES", "integer", "15", "Cache cleanup interval"),
+            spec("health_check_interval_seconds", "HEALTH_CHECK_INTERVAL_SECONDS", "integer", "30", "Performance health check interval"),
+            spec("max_error_rate", "MAX_ERROR_RATE", "number", "0.1", "Error rate (0-1) considered unhealthy"),
+            spec("min_success_rate", "MIN_SUCCESS_RATE", "number", "0.95", "Success rate (0-1) considered healthy"),
+        ],
+    },
+    SchemaSection {
+        name: "monitoring",
+        settings: &[
+            spec("scan_interval_seconds", "SCAN_INTERVAL_SECONDS", "integer", "30", "Pool scanning frequency"),
+            spec("price_check_interval_ms", "PRICE_CHECK_INTERVAL_MS", "integer", "1000", "Open position price check frequency"),
+            spec("concurrent_position_monitoring", "CONCURRENT_POSITION_MONITORING", "bool", "true", "Check positions on a dedicated task instead of between scans"),
+            spec("dexscreener_interval_ms", "DEXSCREENER_INTERVAL_MS", "integer", "1000", "New pool discovery (tolerates fast polling)"),
+            spec("scan_error_backoff_base_ms", "SCAN_ERROR_BACKOFF_BASE_MS", "integer", "5000", "First retry delay after a failed scan, doubled per failure"),
+            spec("scan_error_backoff_max_ms", "SCAN_ERROR_BACKOFF_MAX_MS", "integer", "120000", "Longest retry delay after failed scans"),
+            spec("scan_degraded_alert_threshold", "SCAN_DEGRADED_ALERT_THRESHOLD", "integer", "5", "Consecutive failures before a \"scanner degraded\" alert"),
+            spec("sol_price_interval_ms", "SOL_PRICE_INTERVAL_MS", "integer", "300000", "CoinGecko SOL price refresh (rate limited)"),
+            spec("sol_price_retries", "SOL_PRICE_RETRIES", "integer", "1", "Extra CoinGecko attempts before falling back to Jupiter"),
+            spec("fallback_sol_price_usd", "FALLBACK_SOL_PRICE_USD", "number", "235", "Last resort SOL price when none was ever fetched (logged loudly)"),
+            spec("position_update_interval_ms", "POSITION_UPDATE_INTERVAL_MS", "integer", "5000", "Position update frequency"),
+            spec("health_check_interval_minutes", "HEALTH_CHECK_INTERVAL_MINUTES", "integer", "5", "Health check frequency"),
+            spec("price_cache_ttl_secs", "PRICE_CACHE_TTL_SECS", "integer", "600", "Cached token prices older than this are evicted (and no longer a stale fallback)"),
+            spec("max_alert_history", "MAX_ALERT_HISTORY", "integer", "100", "Profit alerts kept in memory for the status report"),
+            spec("stale_pricing_alert_secs", "STALE_PRICING_ALERT_SECS", "integer", "300", "Positions without a fresh price for this long count as stale; 0 = no watchdog"),
+            spec("stale_pricing_alert_fraction", "STALE_PRICING_ALERT_FRACTION", "number", "0.5", "Alert once more than this share of positions is stale"),
+            spec("decision_trace_enabled", "DECISION_TRACE", "bool", "false", "Log why every scanned pool was or wasn't bought"),
+            spec("decision_trace_path", "DECISION_TRACE_PATH", "text", "./data/decision_trace.jsonl", "Where DECISION_TRACE writes its JSON lines"),
+            spec("decision_trace_mints", "DECISION_TRACE_MINTS", "list", "", "Mints to report as \"never detected\" if no source sees them"),
+            spec("log_level", "LOG_LEVEL", "text", "info", "Log verbosity"),
+            spec("log_to_file", "LOG_TO_FILE", "bool", "true", "Also write logs to LOG_FILE_PATH"),
+            spec("log_file_path", "LOG_FILE_PATH", "text", "./logs/sniper.log", "Log file location"),
+            spec("save_analysis_results", "SAVE_ANALYSIS_RESULTS", "bool", "true", "Save analysis results to ANALYSIS_RESULTS_PATH"),
+            spec("analysis_results_path", "ANALYSIS_RESULTS_PATH", "text", "./data/analysis", "Analysis results directory"),
+            spec("health_snapshot_path", "HEALTH_SNAPSHOT_PATH", "text", "./data/health.json", "Health snapshot file"),
+            spec("health_snapshot_interval_secs", "HEALTH_SNAPSHOT_INTERVAL_SECS", "integer", "60", "Health snapshot write frequency"),
+            spec("trade_journal_path", "TRADE_JOURNAL_PATH", "text", "./data/pending_trades.json", "Pending buys, reconciled on startup"),
+            spec("daily_trades_path", "DAILY_TRADES_PATH", "text", "./data/daily_trades.json", "Today's buy count for MAX_DAILY_TRADES"),
+            spec("compound_state_path", "COMPOUND_STATE_PATH", "text", "./data/compound_state.json", "Auto-compounding steps, so a restart keeps the grown buy size"),
+            spec("portfolio_history_path", "PORTFOLIO_HISTORY_PATH", "text", "./data/portfolio_history.jsonl", "Append-only JSON lines, one portfolio summary per 15 minutes"),
+            spec("storage_backend", "STORAGE_BACKEND", "text", "json", "\"json\" or \"sqlite\" (needs --features sqlite): open positions, trades, processed pools"),
+            spec("storage_path", "STORAGE_PATH", "text", "", "Directory for json, database file for sqlite; unset = ./data/storage or ./data/sniper.db"),
+            spec("create_data_dirs", "CREATE_DATA_DIRS", "bool", "true", "Create missing log/data directories on startup"),
+            spec("webhook_url", "WEBHOOK_URL", "text", "", "POST a JSON event on every buy/sell/write-off/reject; empty = disabled"),
+            spec("webhook_bearer_token", "WEBHOOK_BEARER_TOKEN", "text", "", "Optional Authorization: Bearer token for WEBHOOK_URL"),
+            spec("enable_real_time_alerts", "ENABLE_REAL_TIME_ALERTS", "bool", "true", "Send alerts as events happen"),
+            spec("max_new_tokens_per_scan", "MAX_NEW_TOKENS_PER_SCAN", "integer", "10", "New tokens analyzed per scan"),
+            spec("max_candidates_per_scan", "MAX_CANDIDATES_PER_SCAN", "integer", "10", "Qualifying pools each detection source returns per scan, newest first"),
+            spec("allow_missing_creation_time", "ALLOW_MISSING_CREATION_TIME", "bool", "false", "Keep DexScreener pairs without pairCreatedAt as unknown age instead of dropping them"),
+            spec("skip_inactive_pools", "SKIP_INACTIVE_POOLS", "bool", "false", "Drop DexScreener pairs with zero 5m and 1h volume - liquidity but nobody trading"),
+            spec("inactive_pool_min_age_minutes", "INACTIVE_POOL_MIN_AGE_MINUTES", "integer", "10", "Pairs younger than this haven't had time to trade and are never judged inactive"),
+        ],
+    },
+];
+
+/// Fields present in `settings` but missing from the schema, and schema entries whose field no
+/// longer exists, as "section.field"
+pub fn undocumented_settings(settings: &BotSettings) -> Vec<String> {
+    let exported = serde_json::to_value(settings).unwrap_or_default();
+    let mut problems = Vec::new();
+    for section in SETTINGS_SCHEMA {
+        let fields = section.name.split('.')
+            .try_fold(&exported, |value, key| value.get(key))
+            .and_then(|value| value.as_object());
+        let Some(fields) = fields else {
+            problems.push(format!("{} (no such section)", section.name));
+            continue;
+        };
+        let nested = |field: &str| SETTINGS_SCHEMA.iter()
+            .any(|other| other.name == format!("{}.{}", section.name, field));
+        for field in fields.keys().filter(|field| !nested(field)) {
+            if !section.settings.iter().any(|setting| setting.field == field) {
+                problems.push(format!("{}.{}", section.name, field));
+            }
+        }
+        for setting in section.settings.iter().filter(|setting| !fields.contains_key(setting.field)) {
+            problems.push(format!("{}.{} (field removed)", section.name, setting.field));
+        }
+    }
+    problems
+}
+
+/// The schema as a commented .env file: every variable is listed, commented out, at its default
+pub fn env_template() -> String {
+    let mut template = String::from("# Solana Token Sniper settings - uncomment and edit the ones you need\n");
+    for section in SETTINGS_SCHEMA {
+        template.push_str(&format!("\n# ===== {} =====\n", section.name));
+        for setting in section.settings {
+            template.push_str(&format!("# {} ({})\n# {}={}\n", setting.description, setting.kind, setting.env_var, setting.default));
+        }
+    }
+    template
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::{
+        MonitoringSettings, PerformanceSettings, SecuritySettings, TelegramSettings, TradingSettings, WalletSettings,
+        WorkingApiSettings,
+    };
+    use serde_json::Value;
+
+    /// Settings as they load with none of the schema's variables set. Built from the section
+    /// loaders directly so a developer's .env can't leak in.
+    fn settings_from_empty_env() -> BotSettings {
+        for setting in SETTINGS_SCHEMA.iter().flat_map(|section| section.settings) {
+            std::env::remove_var(setting.env_var);
+        }
+        BotSettings {
+            wallet: WalletSettings::from_env(),
+            trading: TradingSettings::from_env(),
+            security: SecuritySettings::from_env(),
+            telegram: TelegramSettings::from_env(),
+            apis: WorkingApiSettings::from_env(),
+            performance: PerformanceSettings::from_env(),
+            monitoring: MonitoringSettings::from_env(),
+        }
+    }
+
+    /// A list item as it's written in the env var: plain strings as-is, explorer links as name:url
+    fn item_text(item: &Value) -> Option<String> {
+        item.as_str().map(str::to_string)
+            .or_else(|| Some(format!("{}:{}", item["name"].as_str()?, item["url_template"].as_str()?)))
+    }
+
+    /// Whether the exported `value` is what the schema says loads when `setting` is unset
+    fn matches_default(setting: &SettingSpec, value: &Value) -> bool {
+        let (kind, default) = (setting.kind, setting.default);
+        // No fixed default - derived from other settings, as the description explains
+        if default.is_empty() && setting.description.contains("unset = ") {
+            return true;
+        }
+        match (kind, value) {
+            (_, Value::Null) => default.is_empty(),
+            ("bool", Value::Bool(value)) => default.parse() == Ok(*value),
+            ("integer" | "number", Value::Number(value)) => default.parse::<f64>().ok() == value.as_f64(),
+            ("text", Value::String(value)) => default == value,
+            ("list", Value::Array(items)) => {
+                let expected: Vec<&str> = default.split(',').map(str::trim).filter(|item| !item.is_empty()).collect();
+                items.len() == expected.len() && items.iter().zip(&expected).all(|(item, expected)| item_text(item).as_deref() == Some(expected))
+            }
+            ("map", Value::Object(entries)) => {
+                let expected: Vec<(&str, &str)> = default.split(',').filter_map(|entry| entry.split_once(':')).collect();
+                entries.len() == expected.len() && expected.iter().all(|(key, expected)| entries.get(*key).and_then(Value::as_str) == Some(expected))
+            }
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn every_field_has_a_schema_entry() {
+        assert_eq!(undocumented_settings(&settings_from_empty_env()), Vec::<String>::new());
+    }
+
+    #[test]
+    fn schema_defaults_match_what_an_empty_env_loads() {
+        let exported = serde_json::to_value(settings_from_empty_env()).unwrap();
+        let mismatches: Vec<String> = SETTINGS_SCHEMA.iter()
+            .flat_map(|section| section.settings.iter().map(move |setting| (section, setting)))
+            .filter_map(|(section, setting)| {
+                let value = section.name.split('.')
+                    .chain([setting.field])
+                    .try_fold(&exported, |value, key| value.get(key))?;
+                (!matches_default(setting, value))
+                    .then(|| format!("{} ({}): schema says {:?}, loads {}", setting.env_var, setting.kind, setting.default, value))
+            })
+            .collect();
+        assert!(mismatches.is_empty(), "schema defaults out of date:\n{}", mismatches.join("\n"));
+    }
+}
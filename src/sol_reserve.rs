@@ -0,0 +1,129 @@
+// SOL Reserve - the backstop every SOL-spending transaction passes right before it is sent.
+// Whatever the per-trade guards decided, native SOL must stay above MIN_WALLET_BALANCE_SOL plus
+// RESERVE_FEE_BUFFER_SOL once every spend already in flight has landed. Sells are never checked:
+// they bring SOL back.
+use std::sync::{Arc, Mutex};
+use crate::lamports::Lamports;
+
+/// A spend the reserve refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReserveBreachError {
+    pub balance: Lamports,
+    pub pending: Lamports, // Claimed by spends still in flight
+    pub spend: Lamports,
+    pub floor: Lamports,
+}
+
+impl std::fmt::Display for ReserveBreachError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "spending {} would take the wallet ({}, {} already in flight) below the {} reserve",
+            self.spend, self.balance, self.pending, self.floor)
+    }
+}
+
+impl std::error::Error for ReserveBreachError {}
+
+/// Spends claimed against the wallet but not landed yet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReserveLedger {
+    floor: Lamports,
+    pending: Lamports,
+}
+
+impl ReserveLedger {
+    pub fn new(floor: Lamports) -> Self {
+        Self { floor, pending: Lamports::ZERO }
+    }
+
+    /// Claim `spend` when `balance` still covers it on top of the pending claims and the floor
+    pub fn claim(&mut self, balance: Lamports, spend: Lamports) -> Result<(), ReserveBreachError> {
+        let committed = self.pending + spend + self.floor;
+        if balance < committed {
+            return Err(ReserveBreachError { balance, pending: self.pending, spend, floor: self.floor });
+        }
+        self.pending = self.pending + spend;
+        Ok(())
+    }
+
+    /// A claimed spend landed (or was abandoned)
+    pub fn release(&mut self, spend: Lamports) {
+        self.pending = self.pending.saturating_sub(spend);
+    }
+}
+
+/// Shared handle to the ledger
+#[derive(Debug, Clone)]
+pub struct SolReserve(Arc<Mutex<ReserveLedger>>);
+
+impl SolReserve {
+    pub fn new(floor: Lamports) -> Self {
+        Self(Arc::new(Mutex::new(ReserveLedger::new(floor))))
+    }
+
+    /// Claim `spend` against `balance`; the claim is released when the returned guard drops
+    pub fn claim(&self, balance: Lamports, spend: Lamports) -> Result<ReserveClaim, ReserveBreachError> {
+        self.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).claim(balance, spend)?;
+        Ok(ReserveClaim { reserve: self.clone(), spend })
+    }
+}
+
+/// A spend in flight; hold it until the transaction has landed or failed
+pub struct ReserveClaim {
+    reserve: SolReserve,
+    spend: Lamports,
+}
+
+impl Drop for ReserveClaim {
+    fn drop(&mut self) {
+        self.reserve.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).release(self.spend);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sol(amount: f64) -> Lamports {
+        Lamports::from_sol(amount)
+    }
+
+    #[test]
+    fn buys_in_flight_throttle_the_next_one() {
+        // 1 SOL wallet, 0.15 SOL floor: three 0.25 SOL buys fit, the fourth would dig into the floor
+        let mut ledger = ReserveLedger::new(sol(0.15));
+        let balance = sol(1.0);
+        for _ in 0..3 {
+            assert!(ledger.claim(balance, sol(0.25)).is_ok());
+        }
+        let breach = ledger.claim(balance, sol(0.25)).unwrap_err();
+        assert_eq!(breach, ReserveBreachError { balance, pending: sol(0.75), spend: sol(0.25), floor: sol(0.15) });
+        // A smaller spend that still leaves the floor goes through
+        assert!(ledger.claim(balance, sol(0.1)).is_ok());
+    }
+
+    #[test]
+    fn landed_buys_are_read_from_the_balance_instead() {
+        let mut ledger = ReserveLedger::new(sol(0.15));
+        ledger.claim(sol(1.0), sol(0.5)).unwrap();
+        assert!(ledger.claim(sol(1.0), sol(0.5)).is_err());
+        // The first buy landed: the balance dropped and its claim is released
+        ledger.release(sol(0.5));
+        assert!(ledger.claim(sol(0.5), sol(0.35)).is_ok());
+        assert!(ledger.claim(sol(0.5), sol(0.01)).is_err());
+    }
+
+    #[test]
+    fn spending_down_to_the_floor_exactly_is_allowed() {
+        let mut ledger = ReserveLedger::new(sol(0.15));
+        assert!(ledger.claim(sol(0.4), sol(0.25)).is_ok());
+    }
+
+    #[test]
+    fn dropped_claims_free_the_reserve() {
+        let reserve = SolReserve::new(sol(0.15));
+        let first = reserve.claim(sol(1.0), sol(0.5)).unwrap();
+        assert!(reserve.claim(sol(1.0), sol(0.5)).is_err());
+        drop(first); // The buy failed to land
+        assert!(reserve.claim(sol(1.0), sol(0.5)).is_ok());
+    }
+}
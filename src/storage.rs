@@ -0,0 +1,617 @@
+// Storage - open positions, trade history and processed pools behind one backend (JSON files or SQLite)
+use crate::settings::BotSettings;
+use crate::pool_scanner::{TokenPosition, PositionKind};
+use crate::health::unix_secs;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+use anyhow::{Result, anyhow};
+
+/// The persisted part of an open position - enough to resume its max-hold and auto-sell after a restart
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredPosition {
+    pub token_address: String,
+    pub purchase_unix_secs: u64,
+    pub sol_amount: f64,
+    pub estimated_tokens: u64,
+    pub kind: PositionKind,
+    pub max_hold_secs: u64,
+    #[serde(default)]
+    pub timeout_extension_secs: u64, // Part of max_hold_secs added by deferred timeout sells
+    pub signature: Option<String>, // Buy transaction, real positions only
+}
+
+impl From<&TokenPosition> for StoredPosition {
+    fn from(position: &TokenPosition) -> Self {
+        Self {
+            token_address: position.token_address.clone(),
+            purchase_unix_secs: unix_secs(position.purchase_time),
+            sol_amount: position.sol_amount,
+            estimated_tokens: position.estimated_tokens,
+            kind: position.kind,
+            max_hold_secs: position.max_hold.as_secs(),
+            timeout_extension_secs: position.timeout_extension.as_secs(),
+            signature: position.trade_result.as_ref().map(|result| result.transaction_signature.clone()),
+        }
+    }
+}
+
+impl StoredPosition {
+    /// Rebuild the in-memory position (fill stats and the sell probe aren't kept)
+    pub fn to_position(&self) -> TokenPosition {
+        TokenPosition {
+            token_address: self.token_address.clone(),
+            purchase_time: UNIX_EPOCH + Duration::from_secs(self.purchase_unix_secs),
+            sol_amount: self.sol_amount,
+            estimated_tokens: self.estimated_tokens,
+            entry_price: crate::profit_monitor::safe_ratio(self.sol_amount, self.estimated_tokens as f64),
+            kind: self.kind,
+            trade_result: self.signature.as_ref().map(|signature| crate::jupiter_trader::TradeResult {
+                transaction_signature: signature.clone(),
+                tokens_received: self.estimated_tokens,
+                sol_received: 0.0,
+                landed_via: None,
+                time_to_fill: None,
+                realized_slippage_percent: None,
+            }),
+            max_hold: Duration::from_secs(self.max_hold_secs),
+            timeout_extension: Duration::from_secs(self.timeout_extension_secs),
+            sell_probe: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TradeSide {
+    Buy,
+    Sell,
+    WriteOff, // Rugged position closed at a total loss without a swap
+}
+
+/// One line of the trade history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TradeRecord {
+    pub timestamp_unix_secs: u64,
+    pub token_address: String,
+    pub side: TradeSide,
+    pub kind: PositionKind,
+    pub sol_amount: f64,   // Spent on a buy, received on a sell
+    pub token_amount: u64,
+    pub pnl_sol: Option<f64>, // Realized on sells and write-offs
+    pub signature: Option<String>,
+}
+
+/// Totals over a set of trades for the `history trades` command
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TradeStats {
+    pub buys: usize,
+    pub closes: usize, // Sells and write-offs
+    pub wins: usize,
+    pub realized_pnl_sol: f64,
+}
+
+pub fn trade_stats(trades: &[TradeRecord]) -> TradeStats {
+    let mut stats = TradeStats::default();
+    for trade in trades {
+        match trade.side {
+            TradeSide::Buy => stats.buys += 1,
+            TradeSide::Sell | TradeSide::WriteOff => {
+                stats.closes += 1;
+                let pnl = trade.pnl_sol.unwrap_or_default();
+                if pnl > 0.0 {
+                    stats.wins += 1;
+                }
+                stats.realized_pnl_sol += pnl;
+            }
+        }
+    }
+    stats
+}
+
+/// Persistence used by the scanner, the position monitor and the `history`/`positions` commands.
+/// Calls are blocking and small; every write is all-or-nothing so a crash can't leave a torn file.
+pub trait Storage: Send + Sync {
+    fn backend_name(&self) -> &'static str;
+
+    /// Replace the stored open positions with `positions`
+    fn save_positions(&self, positions: &[StoredPosition]) -> Result<()>;
+    fn load_positions(&self) -> Result<Vec<StoredPosition>>;
+
+    fn append_trade(&self, trade: &TradeRecord) -> Result<()>;
+    /// Every trade, oldest first
+    fn load_trades(&self) -> Result<Vec<TradeRecord>>;
+
+    /// The newest `limit` trades (oldest first), optionally for a single token
+    fn recent_trades(&self, token_address: Option<&str>, limit: usize) -> Result<Vec<TradeRecord>> {
+        let mut trades: Vec<TradeRecord> = self.load_trades()?.into_iter()
+            .filter(|trade| token_address.is_none_or(|token| trade.token_address == token))
+            .collect();
+        let skip = trades.len().saturating_sub(limit);
+        Ok(trades.split_off(skip))
+    }
+
+    fn mark_pool_processed(&self, pool_address: &str) -> Result<()>;
+    fn load_processed_pools(&self) -> Result<HashSet<String>>;
+}
+
+/// Open the backend chosen by STORAGE_BACKEND at STORAGE_PATH
+pub fn open_storage(settings: &BotSettings) -> Result<Arc<dyn Storage>> {
+    let path = &settings.monitoring.storage_path;
+    match settings.monitoring.storage_backend.as_str() {
+        "json" => Ok(Arc::new(JsonStorage::open(path)?)),
+        #[cfg(feature = "sqlite")]
+        "sqlite" => Ok(Arc::new(sqlite::SqliteStorage::open(path)?)),
+        #[cfg(not(feature = "sqlite"))]
+        "sqlite" => Err(anyhow!("STORAGE_BACKEND=sqlite needs a build with --features sqlite")),
+        other => Err(anyhow!("Unknown storage backend: {}", other)),
+    }
+}
+
+/// Processed pools kept across restarts; older ones are dropped when the list is loaded
+const MAX_PROCESSED_POOLS: usize = 50_000;
+
+/// Plain files in one directory: positions.json (rewritten via a temp file and rename),
+/// trades.jsonl and processed_pools.txt (append-only; a torn last line is skipped on load,
+/// and the file is compacted on load once it holds duplicates or more than MAX_PROCESSED_POOLS)
+pub struct JsonStorage {
+    dir: PathBuf,
+}
+
+impl JsonStorage {
+    pub fn open(dir: &str) -> Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        Ok(Self { dir: PathBuf::from(dir) })
+    }
+
+    fn file(&self, name: &str) -> PathBuf {
+        self.dir.join(name)
+    }
+}
+
+impl Storage for JsonStorage {
+    fn backend_name(&self) -> &'static str {
+        "json"
+    }
+
+    fn save_positions(&self, positions: &[StoredPosition]) -> Result<()> {
+        write_atomic(&self.file("positions.json"), &serde_json::to_string_pretty(positions)?)
+    }
+
+    fn load_positions(&self) -> Result<Vec<StoredPosition>> {
+        match std::fs::read_to_string(self.file("positions.json")) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn append_trade(&self, trade: &TradeRecord) -> Result<()> {
+        append_line(&self.file("trades.jsonl"), &serde_json::to_string(trade)?)
+    }
+
+    fn load_trades(&self) -> Result<Vec<TradeRecord>> {
+        Ok(read_lines(&self.file("trades.jsonl"))?.iter()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    fn mark_pool_processed(&self, pool_address: &str) -> Result<()> {
+        append_line(&self.file("processed_pools.txt"), pool_address)
+    }
+
+    fn load_processed_pools(&self) -> Result<HashSet<String>> {
+        let path = self.file("processed_pools.txt");
+        let lines = read_lines(&path)?;
+        let read = lines.len();
+        let pools = newest_unique(lines, MAX_PROCESSED_POOLS);
+        if pools.len() < read {
+            let mut content = pools.join("\n");
+            content.push('\n');
+            write_atomic(&path, &content)?;
+        }
+        Ok(pools.into_iter().collect())
+    }
+}
+
+/// The last `keep` distinct entries of `lines`, in their original order (a repeat counts at its last position)
+fn newest_unique(lines: Vec<String>, keep: usize) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut newest: Vec<String> = lines.into_iter().rev()
+        .filter(|line| seen.insert(line.clone()))
+        .take(keep)
+        .collect();
+    newest.reverse();
+    newest
+}
+
+/// Write to a sibling temp file and rename it over the target, so readers see the old or the new content
+fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    {
+        let mut file = std::fs::File::create(&tmp)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+    }
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+fn append_line(path: &Path, line: &str) -> Result<()> {
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", line)?;
+    Ok(())
+}
+
+/// Non-empty lines of `path` (none if it doesn't exist yet)
+fn read_lines(path: &Path) -> Result<Vec<String>> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(content.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use super::{MAX_PROCESSED_POOLS, Storage, StoredPosition, TradeRecord};
+
+    use rusqlite::{Connection, params};
+    use serde::{Serialize, de::DeserializeOwned};
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+    use anyhow::{Result, anyhow};
+
+    /// One SQLite database file; every write is a single statement or transaction
+    pub struct SqliteStorage {
+        conn: Mutex<Connection>,
+    }
+
+    impl SqliteStorage {
+        pub fn open(path: &str) -> Result<Self> {
+            if let Some(parent) = std::path::Path::new(path).parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let conn = Connection::open(path)?;
+            conn.execute_batch(
+                "PRAGMA journal_mode = WAL;
+                 CREATE TABLE IF NOT EXISTS positions (
+                     token_address TEXT PRIMARY KEY,
+                     purchase_unix_secs INTEGER NOT NULL,
+                     sol_amount REAL NOT NULL,
+                     estimated_tokens INTEGER NOT NULL,
+                     kind TEXT NOT NULL,
+                     max_hold_secs INTEGER NOT NULL,
+                     signature TEXT,
+                     timeout_extension_secs INTEGER NOT NULL DEFAULT 0
+                 );
+                 CREATE TABLE IF NOT EXISTS trades (
+                     id INTEGER PRIMARY KEY AUTOINCREMENT,
+                     timestamp_unix_secs INTEGER NOT NULL,
+                     token_address TEXT NOT NULL,
+                     side TEXT NOT NULL,
+                     kind TEXT NOT NULL,
+                     sol_amount REAL NOT NULL,
+                     token_amount INTEGER NOT NULL,
+                     pnl_sol REAL,
+                     signature TEXT
+                 );
+                 CREATE INDEX IF NOT EXISTS trades_by_token ON trades (token_address);
+                 CREATE TABLE IF NOT EXISTS processed_pools (pool_address TEXT PRIMARY KEY);",
+            )?;
+            // Databases created before timeout extensions were persisted
+            let has_extensions = conn
+                .prepare("SELECT 1 FROM pragma_table_info('positions') WHERE name = 'timeout_extension_secs'")?
+                .exists([])?;
+            if !has_extensions {
+                conn.execute("ALTER TABLE positions ADD COLUMN timeout_extension_secs INTEGER NOT NULL DEFAULT 0", [])?;
+            }
+            Ok(Self { conn: Mutex::new(conn) })
+        }
+
+        fn conn(&self) -> Result<std::sync::MutexGuard<'_, Connection>> {
+            self.conn.lock().map_err(|_| anyhow!("SQLite connection lock poisoned"))
+        }
+    }
+
+    /// Enums are stored as their serde name so the columns read the same as the JSON backend
+    fn to_text<T: Serialize>(value: &T) -> Result<String> {
+        serde_json::to_value(value)?.as_str()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("expected a unit enum"))
+    }
+
+    fn from_text<T: DeserializeOwned>(text: String) -> Result<T> {
+        Ok(serde_json::from_value(serde_json::Value::String(text))?)
+    }
+
+    const TRADE_COLUMNS: &str =
+        "timestamp_unix_secs, token_address, side, kind, sol_amount, token_amount, pnl_sol, signature";
+
+    type TradeRow = (i64, String, String, String, f64, i64, Option<f64>, Option<String>);
+
+    fn read_trade(row: &rusqlite::Row) -> rusqlite::Result<TradeRow> {
+        Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?, row.get(7)?))
+    }
+
+    fn trade_from_row(row: TradeRow) -> Result<TradeRecord> {
+        let (timestamp, token_address, side, kind, sol_amount, token_amount, pnl_sol, signature) = row;
+        Ok(TradeRecord {
+            timestamp_unix_secs: timestamp as u64,
+            token_address,
+            side: from_text(side)?,
+            kind: from_text(kind)?,
+            sol_amount,
+            token_amount: token_amount as u64,
+            pnl_sol,
+            signature,
+        })
+    }
+
+    impl Storage for SqliteStorage {
+        fn backend_name(&self) -> &'static str {
+            "sqlite"
+        }
+
+        fn save_positions(&self, positions: &[StoredPosition]) -> Result<()> {
+            let mut conn = self.conn()?;
+            let tx = conn.transaction()?;
+            tx.execute("DELETE FROM positions", [])?;
+            for position in positions {
+                tx.execute(
+                    "INSERT INTO positions (token_address, purchase_unix_secs, sol_amount, estimated_tokens, kind, max_hold_secs, signature, timeout_extension_secs)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        position.token_address,
+                        position.purchase_unix_secs as i64,
+                        position.sol_amount,
+                        position.estimated_tokens as i64,
+                        to_text(&position.kind)?,
+                        position.max_hold_secs as i64,
+                        position.signature,
+                        position.timeout_extension_secs as i64,
+                    ],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        }
+
+        fn load_positions(&self) -> Result<Vec<StoredPosition>> {
+            let conn = self.conn()?;
+            let mut stmt = conn.prepare(
+                "SELECT token_address, purchase_unix_secs, sol_amount, estimated_tokens, kind, max_hold_secs, signature, timeout_extension_secs
+                 FROM positions ORDER BY purchase_unix_secs",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?, row.get::<_, f64>(2)?, row.get::<_, i64>(3)?,
+                    row.get::<_, String>(4)?, row.get::<_, i64>(5)?, row.get::<_, Option<String>>(6)?, row.get::<_, i64>(7)?))
+            })?;
+            rows.map(|row| {
+                let (token_address, purchase, sol_amount, tokens, kind, max_hold, signature, extension) = row?;
+                Ok(StoredPosition {
+                    token_address,
+                    purchase_unix_secs: purchase as u64,
+                    sol_amount,
+                    estimated_tokens: tokens as u64,
+                    kind: from_text(kind)?,
+                    max_hold_secs: max_hold as u64,
+                    timeout_extension_secs: extension as u64,
+                    signature,
+                })
+            }).collect()
+        }
+
+        fn append_trade(&self, trade: &TradeRecord) -> Result<()> {
+            self.conn()?.execute(
+                &format!("INSERT INTO trades ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)", TRADE_COLUMNS),
+                params![
+                    trade.timestamp_unix_secs as i64,
+                    trade.token_address,
+                    to_text(&trade.side)?,
+                    to_text(&trade.kind)?,
+                    trade.sol_amount,
+                    trade.token_amount as i64,
+                    trade.pnl_sol,
+                    trade.signature,
+                ],
+            )?;
+            Ok(())
+        }
+
+        fn load_trades(&self) -> Result<Vec<TradeRecord>> {
+            let conn = self.conn()?;
+            let mut stmt = conn.prepare(&format!("SELECT {} FROM trades ORDER BY id", TRADE_COLUMNS))?;
+            let rows = stmt.query_map([], read_trade)?;
+            rows.map(|row| trade_from_row(row?)).collect()
+        }
+
+        /// Filtered and limited in SQL instead of loading the whole history
+        fn recent_trades(&self, token_address: Option<&str>, limit: usize) -> Result<Vec<TradeRecord>> {
+            let conn = self.conn()?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM trades WHERE ?1 IS NULL OR token_address = ?1 ORDER BY id DESC LIMIT ?2",
+                TRADE_COLUMNS,
+            ))?;
+            let rows = stmt.query_map(params![token_address, limit as i64], read_trade)?;
+            let mut trades = rows.map(|row| trade_from_row(row?)).collect::<Result<Vec<_>>>()?;
+            trades.reverse();
+            Ok(trades)
+        }
+
+        fn mark_pool_processed(&self, pool_address: &str) -> Result<()> {
+            self.conn()?.execute("INSERT OR IGNORE INTO processed_pools (pool_address) VALUES (?1)", [pool_address])?;
+            Ok(())
+        }
+
+        fn load_processed_pools(&self) -> Result<HashSet<String>> {
+            let conn = self.conn()?;
+            conn.execute(
+                "DELETE FROM processed_pools WHERE rowid NOT IN
+                     (SELECT rowid FROM processed_pools ORDER BY rowid DESC LIMIT ?1)",
+                [MAX_PROCESSED_POOLS as i64],
+            )?;
+            let mut stmt = conn.prepare("SELECT pool_address FROM processed_pools")?;
+            let pools = stmt.query_map([], |row| row.get::<_, String>(0))?
+                .collect::<rusqlite::Result<HashSet<String>>>()?;
+            Ok(pools)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh directory per test so parallel tests don't share files
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("storage_test_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn position(token: &str, kind: PositionKind) -> StoredPosition {
+        StoredPosition {
+            token_address: token.to_string(),
+            purchase_unix_secs: 1_700_000_000,
+            sol_amount: 0.25,
+            estimated_tokens: 1_000_000,
+            kind,
+            max_hold_secs: 600,
+            timeout_extension_secs: 0,
+            signature: (kind == PositionKind::Real).then(|| format!("sig-{}", token)),
+        }
+    }
+
+    fn trade(token: &str, side: TradeSide, pnl_sol: Option<f64>) -> TradeRecord {
+        TradeRecord {
+            timestamp_unix_secs: 1_700_000_100,
+            token_address: token.to_string(),
+            side,
+            kind: PositionKind::Real,
+            sol_amount: 0.3,
+            token_amount: 1_000_000,
+            pnl_sol,
+            signature: Some(format!("sig-{}", token)),
+        }
+    }
+
+    fn assert_round_trips(storage: &dyn Storage) {
+        let mut positions = vec![position("mintA", PositionKind::Real), position("mintB", PositionKind::Paper)];
+        positions[1].purchase_unix_secs += 60; // SQLite loads them by purchase time
+        positions[1].max_hold_secs += 300;
+        positions[1].timeout_extension_secs = 300;
+        storage.save_positions(&positions).unwrap();
+        assert_eq!(storage.load_positions().unwrap(), positions);
+        storage.save_positions(&positions[1..]).unwrap();
+        assert_eq!(storage.load_positions().unwrap(), positions[1..].to_vec());
+
+        let trades = vec![
+            trade("mintA", TradeSide::Buy, None),
+            trade("mintB", TradeSide::Buy, None),
+            trade("mintA", TradeSide::Sell, Some(0.05)),
+            trade("mintB", TradeSide::WriteOff, Some(-0.25)),
+        ];
+        for trade in &trades {
+            storage.append_trade(trade).unwrap();
+        }
+        assert_eq!(storage.load_trades().unwrap(), trades);
+        assert_eq!(storage.recent_trades(None, 2).unwrap(), trades[2..].to_vec());
+        assert_eq!(storage.recent_trades(Some("mintA"), 10).unwrap(), vec![trades[0].clone(), trades[2].clone()]);
+
+        storage.mark_pool_processed("poolA").unwrap();
+        storage.mark_pool_processed("poolB").unwrap();
+        storage.mark_pool_processed("poolA").unwrap();
+        let expected: HashSet<String> = ["poolA", "poolB"].iter().map(|pool| pool.to_string()).collect();
+        assert_eq!(storage.load_processed_pools().unwrap(), expected);
+    }
+
+    #[test]
+    fn json_storage_round_trips() {
+        let dir = test_dir("json");
+        assert_round_trips(&JsonStorage::open(dir.to_str().unwrap()).unwrap());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn json_storage_starts_empty_and_skips_a_torn_trade_line() {
+        let dir = test_dir("json_torn");
+        let storage = JsonStorage::open(dir.to_str().unwrap()).unwrap();
+        assert!(storage.load_positions().unwrap().is_empty());
+        assert!(storage.load_processed_pools().unwrap().is_empty());
+
+        storage.append_trade(&trade("mintA", TradeSide::Buy, None)).unwrap();
+        append_line(&dir.join("trades.jsonl"), "{\"timestamp_unix_secs\": 17").unwrap();
+        assert_eq!(storage.load_trades().unwrap(), vec![trade("mintA", TradeSide::Buy, None)]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn positions_saved_before_timeout_extensions_load_without_one() {
+        let dir = test_dir("json_old_positions");
+        let storage = JsonStorage::open(dir.to_str().unwrap()).unwrap();
+        let saved = r#"[{"token_address": "mintA", "purchase_unix_secs": 1700000000, "sol_amount": 0.25,
+            "estimated_tokens": 1000000, "kind": "Real", "max_hold_secs": 600, "signature": "sig-mintA"}]"#;
+        write_atomic(&dir.join("positions.json"), saved).unwrap();
+        assert_eq!(storage.load_positions().unwrap(), vec![position("mintA", PositionKind::Real)]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn processed_pools_file_is_compacted_on_load() {
+        let dir = test_dir("json_compact");
+        let storage = JsonStorage::open(dir.to_str().unwrap()).unwrap();
+        for pool in ["poolA", "poolB", "poolA", "poolC", "poolB"] {
+            storage.mark_pool_processed(pool).unwrap();
+        }
+        assert_eq!(storage.load_processed_pools().unwrap().len(), 3);
+        assert_eq!(read_lines(&dir.join("processed_pools.txt")).unwrap(), vec!["poolA", "poolC", "poolB"]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn newest_unique_keeps_the_latest_distinct_entries_in_order() {
+        let lines = ["a", "b", "a", "c", "d"].iter().map(|line| line.to_string()).collect::<Vec<_>>();
+        assert_eq!(newest_unique(lines.clone(), 10), vec!["b", "a", "c", "d"]);
+        assert_eq!(newest_unique(lines, 2), vec!["c", "d"]);
+        assert!(newest_unique(Vec::new(), 2).is_empty());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite_storage_round_trips() {
+        let dir = test_dir("sqlite");
+        let path = dir.join("sniper.db");
+        assert_round_trips(&sqlite::SqliteStorage::open(path.to_str().unwrap()).unwrap());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite_storage_adds_the_timeout_extension_column_to_old_databases() {
+        let dir = test_dir("sqlite_old");
+        let path = dir.join("sniper.db");
+        std::fs::create_dir_all(&dir).unwrap();
+        rusqlite::Connection::open(&path).unwrap().execute_batch(
+            "CREATE TABLE positions (token_address TEXT PRIMARY KEY, purchase_unix_secs INTEGER NOT NULL,
+                 sol_amount REAL NOT NULL, estimated_tokens INTEGER NOT NULL, kind TEXT NOT NULL,
+                 max_hold_secs INTEGER NOT NULL, signature TEXT);
+             INSERT INTO positions VALUES ('mintA', 1700000000, 0.25, 1000000, 'Real', 600, 'sig-mintA');",
+        ).unwrap();
+
+        let storage = sqlite::SqliteStorage::open(path.to_str().unwrap()).unwrap();
+        assert_eq!(storage.load_positions().unwrap(), vec![position("mintA", PositionKind::Real)]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
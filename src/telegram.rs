@@ -1,8 +1,212 @@
 // Telegram Bot Integration for Token Sniper Notifications
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
 use tokio::time::Duration;
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use chrono;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use crate::settings::TelegramSettings;
+
+// Retry policy for Telegram HTTP calls
+const MAX_SEND_ATTEMPTS: u32 = 3;
+const BASE_RETRY_DELAY_MS: u64 = 500;
+
+/// Non-2xx response from the Telegram Bot API
+#[derive(Debug)]
+pub struct TelegramApiError {
+    pub status: StatusCode,
+    pub body: String,
+}
+
+impl TelegramApiError {
+    /// Rate limits and server-side failures are worth retrying; 400/401/403/404 mean
+    /// a bad token, blocked bot or wrong chat ID and need user action instead
+    pub fn is_retryable(&self) -> bool {
+        self.status == StatusCode::TOO_MANY_REQUESTS || self.status.is_server_error()
+    }
+
+    /// Telegram's suggested wait for 429 responses, if present
+    fn retry_after(&self) -> Option<Duration> {
+        let body: serde_json::Value = serde_json::from_str(&self.body).ok()?;
+        body["parameters"]["retry_after"].as_u64().map(Duration::from_secs)
+    }
+}
+
+impl std::fmt::Display for TelegramApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Telegram API error: {} - {}", self.status, self.body)
+    }
+}
+
+impl std::error::Error for TelegramApiError {}
+
+/// True when an error came from a transient outage (network, 429, 5xx) rather than
+/// an auth/chat-ID problem the user needs to fix
+pub fn is_transient_error(error: &anyhow::Error) -> bool {
+    if let Some(api_error) = error.downcast_ref::<TelegramApiError>() {
+        return api_error.is_retryable();
+    }
+    error.downcast_ref::<reqwest::Error>().is_some()
+}
+
+/// Outcome of a Telegram approval request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApprovalDecision {
+    Approved { by: String },
+    Rejected { by: String },
+    TimedOut, // Treated as a rejection
+}
+
+/// Match inline-button callback data ("approve:<id>" / "reject:<id>") against a request id.
+/// Some(true) = approve, Some(false) = reject, None = not for this request.
+pub fn parse_approval_callback(data: &str, request_id: &str) -> Option<bool> {
+    let (action, id) = data.split_once(':')?;
+    if id != request_id {
+        return None;
+    }
+    match action {
+        "approve" => Some(true),
+        "reject" => Some(false),
+        _ => None,
+    }
+}
+
+/// Which alert a user template replaces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateKind {
+    Buy,
+    Sell,
+    Profit,
+}
+
+impl TemplateKind {
+    /// Placeholders this alert can fill
+    pub fn placeholders(self) -> &'static [&'static str] {
+        match self {
+            TemplateKind::Buy => &["mint", "name", "sol_amount", "price", "time", "links"],
+            TemplateKind::Sell => &["mint", "name", "sol_amount", "pnl", "pnl_pct", "time", "links"],
+            TemplateKind::Profit => &["mint", "name", "sol_amount", "pnl", "pnl_pct", "time", "alert"],
+        }
+    }
+}
+
+/// User-configured alert templates; None keeps the built-in layout
+#[derive(Debug, Clone, Default)]
+pub struct MessageTemplates {
+    pub buy: Option<String>,
+    pub sell: Option<String>,
+    pub profit: Option<String>,
+}
+
+impl MessageTemplates {
+    /// Templates from settings; an empty setting keeps the built-in layout
+    pub fn from_settings(settings: &TelegramSettings) -> Self {
+        let non_empty = |template: &str| Some(template.to_string()).filter(|t| !t.trim().is_empty());
+        Self {
+            buy: non_empty(&settings.buy_template),
+            sell: non_empty(&settings.sell_template),
+            profit: non_empty(&settings.profit_template),
+        }
+    }
+
+    fn get(&self, kind: TemplateKind) -> Option<&str> {
+        match kind {
+            TemplateKind::Buy => self.buy.as_deref(),
+            TemplateKind::Sell => self.sell.as_deref(),
+            TemplateKind::Profit => self.profit.as_deref(),
+        }
+    }
+}
+
+/// `{name}` placeholders in a template that the alert kind can't fill
+pub fn unknown_placeholders(template: &str, kind: TemplateKind) -> Vec<String> {
+    let mut unknown = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start + 1..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 1..start + 1 + len];
+        if !kind.placeholders().contains(&name) {
+            unknown.push(name.to_string());
+        }
+        rest = &rest[start + 1 + len + 1..];
+    }
+    unknown
+}
+
+/// Escape text for a message sent with parse_mode HTML (token names are user-chosen)
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// An error alert. Errors often quote request URLs, whose `&` Telegram's HTML mode rejects.
+fn error_alert_message(category: &str, error: &str, suppressed: u32) -> String {
+    let mut message = format!("⚠️ <b>ERROR</b> ({})\n{}", escape_html(category), escape_html(error));
+    if suppressed > 0 {
+        message.push_str(&format!("\n(suppressed {} repeats)", suppressed));
+    }
+    message
+}
+
+/// Replace each `{key}` in a template with its HTML-escaped value. `{links}` is already
+/// markup (render_explorer_links) and goes in as is.
+pub fn render_template(template: &str, values: &[(&str, String)]) -> String {
+    values.iter().fold(template.to_string(), |rendered, (key, value)| {
+        let value = if *key == "links" { value.clone() } else { escape_html(value) };
+        rendered.replace(&format!("{{{}}}", key), &value)
+    })
+}
+
+/// Built-in explorer links: DexScreener only, as alerts always had
+pub const DEFAULT_EXPLORER_LINKS: &str = "DexScreener:https://dexscreener.com/solana/{mint}";
+
+/// An explorer linked from alerts; `url_template` has a `{mint}` placeholder
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExplorerLink {
+    pub name: String,
+    pub url_template: String,
+}
+
+/// Parse TELEGRAM_EXPLORER_LINKS: comma-separated `name:url_template` (the URL keeps its own colons)
+pub fn parse_explorer_links(raw: &str) -> Vec<ExplorerLink> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| match entry.split_once(':') {
+            Some((name, url_template)) => ExplorerLink { name: name.trim().to_string(), url_template: url_template.trim().to_string() },
+            None => ExplorerLink { name: String::new(), url_template: entry.to_string() },
+        })
+        .collect()
+}
+
+/// One HTML line linking `mint` on every explorer, or empty when none are configured.
+/// Names and URLs are escaped, so an `&` in a query string or a `<` in a name can't break the message.
+pub fn render_explorer_links(links: &[ExplorerLink], mint: &str) -> String {
+    if links.is_empty() {
+        return String::new();
+    }
+    let anchors: Vec<String> = links.iter()
+        .map(|link| format!("<a href=\"{}\">View on {}</a>",
+            escape_html(&link.url_template.replace("{mint}", mint)), escape_html(&link.name)))
+        .collect();
+    format!("🔗 {}", anchors.join(" | "))
+}
+
+// Longest single getUpdates long-poll while waiting for an approval
+const APPROVAL_POLL_SECS: u64 = 10;
+
+// Dedup state for one (category, message) error alert
+#[derive(Debug)]
+struct ErrorAlertState {
+    last_sent: Instant,
+    suppressed: u32,
+}
 
 #[derive(Debug, Clone)]
 pub struct TelegramNotifier {
@@ -10,6 +214,11 @@ pub struct TelegramNotifier {
     chat_id: String,
     client: Client,
     enabled: bool,
+    error_dedup_window: Duration,
+    error_alerts: Arc<Mutex<HashMap<(String, String), ErrorAlertState>>>, // Shared across clones
+    update_offset: Arc<Mutex<i64>>, // Next getUpdates offset, so handled callbacks aren't re-read
+    templates: MessageTemplates,
+    explorer_links: Vec<ExplorerLink>,
 }
 
 impl TelegramNotifier {
@@ -24,9 +233,92 @@ impl TelegramNotifier {
             chat_id: chat_id.to_string(),
             client,
             enabled: !chat_id.is_empty(),
+            error_dedup_window: Duration::from_secs(300),
+            error_alerts: Arc::new(Mutex::new(HashMap::new())),
+            update_offset: Arc::new(Mutex::new(0)),
+            templates: MessageTemplates::default(),
+            explorer_links: parse_explorer_links(DEFAULT_EXPLORER_LINKS),
         }
     }
 
+    /// Replace the built-in buy/sell/profit layouts with user templates
+    pub fn with_templates(mut self, templates: MessageTemplates) -> Self {
+        self.templates = templates;
+        self
+    }
+
+    /// Explorers linked from buy, sell and signal alerts (DexScreener by default)
+    pub fn with_explorer_links(mut self, explorer_links: Vec<ExplorerLink>) -> Self {
+        self.explorer_links = explorer_links;
+        self
+    }
+
+    /// The configured explorer links for a mint, as one HTML line
+    pub fn explorer_links_html(&self, mint: &str) -> String {
+        render_explorer_links(&self.explorer_links, mint)
+    }
+
+    /// Render the user's template for an alert, or None to use the built-in layout
+    pub fn render(&self, kind: TemplateKind, values: &[(&str, String)]) -> Option<String> {
+        self.templates.get(kind).map(|template| render_template(template, values))
+    }
+
+    /// Set how long an identical error alert is suppressed after being sent
+    pub fn with_error_dedup_window(mut self, window: Duration) -> Self {
+        self.error_dedup_window = window;
+        self
+    }
+
+    /// Send an error alert at most once per dedup window for the same category and text.
+    /// Repeats inside the window are counted and reported with the next send or on recovery.
+    pub async fn send_error_alert(&self, category: &str, error: &str) -> Result<()> {
+        let key = (category.to_string(), error.to_string());
+        let suppressed = {
+            let mut alerts = self.error_alerts.lock().unwrap_or_else(|e| e.into_inner());
+            match alerts.get_mut(&key) {
+                Some(state) if state.last_sent.elapsed() < self.error_dedup_window => {
+                    state.suppressed += 1;
+                    return Ok(());
+                }
+                Some(state) => {
+                    let suppressed = state.suppressed;
+                    state.last_sent = Instant::now();
+                    state.suppressed = 0;
+                    suppressed
+                }
+                None => {
+                    alerts.insert(key, ErrorAlertState { last_sent: Instant::now(), suppressed: 0 });
+                    0
+                }
+            }
+        };
+
+        self.send_message(&error_alert_message(category, error, suppressed)).await
+    }
+
+    /// Clear error alerts for a category once it succeeds again, sending a recovery
+    /// summary if any repeats were suppressed
+    pub async fn resolve_error_alerts(&self, category: &str) -> Result<()> {
+        let suppressed: u32 = {
+            let mut alerts = self.error_alerts.lock().unwrap_or_else(|e| e.into_inner());
+            let mut suppressed = 0;
+            alerts.retain(|(alert_category, _), state| {
+                if alert_category == category {
+                    suppressed += state.suppressed;
+                    false
+                } else {
+                    true
+                }
+            });
+            suppressed
+        };
+
+        if suppressed > 0 {
+            self.send_message(&format!("✅ <b>RECOVERED</b> ({})\n(suppressed {} repeats)", escape_html(category), suppressed)).await?;
+        }
+        Ok(())
+    }
+
     pub async fn send_message(&self, message: &str) -> Result<()> {
         if !self.enabled || self.chat_id.is_empty() {
             return Ok(());
@@ -39,25 +331,123 @@ impl TelegramNotifier {
             "parse_mode": "HTML"
         });
 
-        let response = self.client
-            .post(&url)
-            .json(&payload)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(anyhow::anyhow!(
-                "Failed to send Telegram message: {} - {}. Please check: 1) Start chat with @snipercheck_bot 2) Get your chat ID from @userinfobot 3) Update TELEGRAM_CHAT_ID in .env", 
-                status, 
-                error_text
-            ));
-        }
+        self.send_with_retry(|| self.client.post(&url).json(&payload)).await?;
 
         Ok(())
     }
 
+    /// Send `message` with Approve/Reject buttons and wait up to `timeout` for a press from the
+    /// configured chat. Presses from other chats are ignored; no press in time means TimedOut.
+    pub async fn request_approval(&self, message: &str, request_id: &str, timeout: Duration) -> Result<ApprovalDecision> {
+        if !self.enabled || self.chat_id.is_empty() {
+            return Err(anyhow::anyhow!("Telegram not enabled - cannot request approval"));
+        }
+
+        let send_url = format!("https://api.telegram.org/bot{}/sendMessage", self.token);
+        let payload = serde_json::json!({
+            "chat_id": self.chat_id,
+            "text": message,
+            "parse_mode": "HTML",
+            "reply_markup": {
+                "inline_keyboard": [[
+                    { "text": "✅ Approve", "callback_data": format!("approve:{}", request_id) },
+                    { "text": "❌ Reject", "callback_data": format!("reject:{}", request_id) },
+                ]]
+            }
+        });
+        self.send_with_retry(|| self.client.post(&send_url).json(&payload)).await?;
+
+        let updates_url = format!("https://api.telegram.org/bot{}/getUpdates", self.token);
+        let deadline = Instant::now() + timeout;
+
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            let offset = *self.update_offset.lock().unwrap_or_else(|e| e.into_inner());
+            let poll = serde_json::json!({
+                "offset": offset,
+                "timeout": remaining.as_secs().clamp(1, APPROVAL_POLL_SECS),
+                "allowed_updates": ["callback_query"],
+            });
+            let response = self.send_with_retry(|| self.client.post(&updates_url).json(&poll)).await?;
+            let body: serde_json::Value = response.json().await?;
+
+            for update in body["result"].as_array().cloned().unwrap_or_default() {
+                if let Some(update_id) = update["update_id"].as_i64() {
+                    let mut next_offset = self.update_offset.lock().unwrap_or_else(|e| e.into_inner());
+                    *next_offset = (*next_offset).max(update_id + 1);
+                }
+
+                let callback = &update["callback_query"];
+                let from_our_chat = callback["message"]["chat"]["id"].as_i64().is_some()
+                    && callback["message"]["chat"]["id"].as_i64() == self.chat_id.parse().ok();
+                let approved = match callback["data"].as_str() {
+                    Some(data) if from_our_chat => parse_approval_callback(data, request_id),
+                    _ => None,
+                };
+                let Some(approved) = approved else {
+                    continue;
+                };
+
+                let by = callback["from"]["username"].as_str()
+                    .map(|username| format!("@{}", username))
+                    .unwrap_or_else(|| callback["from"]["id"].to_string());
+
+                // Stop the button's loading spinner; failure here doesn't change the decision
+                let answer_url = format!("https://api.telegram.org/bot{}/answerCallbackQuery", self.token);
+                let answer = serde_json::json!({
+                    "callback_query_id": callback["id"],
+                    "text": if approved { "Approved" } else { "Rejected" },
+                });
+                let _ = self.client.post(&answer_url).json(&answer).send().await;
+
+                return Ok(if approved {
+                    ApprovalDecision::Approved { by }
+                } else {
+                    ApprovalDecision::Rejected { by }
+                });
+            }
+        }
+
+        Ok(ApprovalDecision::TimedOut)
+    }
+
+    /// Send a request, retrying network errors, 429s and 5xx with exponential backoff.
+    /// Permanent failures (bad token, bad chat ID) are returned immediately as TelegramApiError.
+    async fn send_with_retry<F>(&self, build_request: F) -> Result<Response>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let mut attempt = 1;
+        loop {
+            let delay = Duration::from_millis(BASE_RETRY_DELAY_MS * 2u64.pow(attempt - 1));
+
+            let error = match build_request().send().await {
+                Ok(response) if response.status().is_success() => return Ok(response),
+                Ok(response) => {
+                    let api_error = TelegramApiError {
+                        status: response.status(),
+                        body: response.text().await.unwrap_or_default(),
+                    };
+                    if !api_error.is_retryable() || attempt >= MAX_SEND_ATTEMPTS {
+                        return Err(api_error.into());
+                    }
+                    let wait = api_error.retry_after().unwrap_or(delay);
+                    println!("⚠️  Telegram {} (attempt {}/{}), retrying in {}ms", api_error.status, attempt, MAX_SEND_ATTEMPTS, wait.as_millis());
+                    tokio::time::sleep(wait).await;
+                    attempt += 1;
+                    continue;
+                }
+                Err(e) => e,
+            };
+
+            if attempt >= MAX_SEND_ATTEMPTS {
+                return Err(error.into());
+            }
+            println!("⚠️  Telegram request failed (attempt {}/{}): {}, retrying in {}ms", attempt, MAX_SEND_ATTEMPTS, error, delay.as_millis());
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
     // Test method to verify bot setup
     pub async fn test_connection(&self) -> Result<String> {
         if !self.enabled || self.chat_id.is_empty() {
@@ -66,38 +456,44 @@ impl TelegramNotifier {
 
         // First test if bot token is valid
         let bot_url = format!("https://api.telegram.org/bot{}/getMe", self.token);
-        let bot_response = self.client.get(&bot_url).send().await?;
-        
-        if !bot_response.status().is_success() {
-            return Err(anyhow::anyhow!("Invalid bot token"));
-        }
+        self.send_with_retry(|| self.client.get(&bot_url))
+            .await
+            .map_err(|e| e.context("Bot token check (getMe) failed"))?;
 
         // Try to send a test message
         let test_result = self.send_message("🧪 Telegram setup test successful! ✅").await;
         
         match test_result {
             Ok(_) => Ok("✅ Telegram connection successful!".to_string()),
-            Err(e) => Err(anyhow::anyhow!("❌ Telegram test failed: {}", e)),
+            Err(e) => Err(e.context("Test message failed")),
         }
     }
 
     // Buy alert with rich formatting
     pub async fn send_buy_alert(&self, token_address: &str, token_name: &str, amount_sol: f64, price: f64) -> Result<()> {
-        let message = format!(
+        let custom = self.render(TemplateKind::Buy, &[
+            ("mint", token_address.to_string()),
+            ("name", token_name.to_string()),
+            ("sol_amount", amount_sol.to_string()),
+            ("price", format!("{:.8}", price)),
+            ("time", chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string()),
+            ("links", self.explorer_links_html(token_address)),
+        ]);
+        let message = custom.unwrap_or_else(|| format!(
             "🎉 <b>BUY ALERT</b> 🎉\n\n\
             💎 <b>Token:</b> {}\n\
             📍 <b>Address:</b> <code>{}</code>\n\
             💰 <b>Amount:</b> {} SOL\n\
             💵 <b>Price:</b> ${:.8}\n\
             ⏰ <b>Time:</b> {}\n\n\
-            🔗 <a href=\"https://dexscreener.com/solana/{}\">View on DexScreener</a>",
+            {}",
             token_name,
             token_address,
             amount_sol,
             price,
             chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-            token_address
-        );
+            self.explorer_links_html(token_address)
+        ));
 
         self.send_message(&message).await
     }
@@ -107,23 +503,77 @@ impl TelegramNotifier {
         let emoji = if profit_loss > 0.0 { "🟢" } else { "🔴" };
         let status = if profit_loss > 0.0 { "PROFIT" } else { "LOSS" };
         
-        let message = format!(
+        let custom = self.render(TemplateKind::Sell, &[
+            ("mint", token_address.to_string()),
+            ("name", token_name.to_string()),
+            ("sol_amount", amount_sol.to_string()),
+            ("pnl", format!("{:.4}", profit_loss)),
+            ("pnl_pct", format!("{:.2}", percentage)),
+            ("time", chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string()),
+            ("links", self.explorer_links_html(token_address)),
+        ]);
+        let message = custom.unwrap_or_else(|| format!(
             "{} <b>SELL ALERT</b> {}\n\n\
             💎 <b>Token:</b> {}\n\
             📍 <b>Address:</b> <code>{}</code>\n\
             💰 <b>Amount:</b> {} SOL\n\
             {} <b>{}:</b> {} SOL ({:.2}%)\n\
             ⏰ <b>Time:</b> {}\n\n\
-            🔗 <a href=\"https://dexscreener.com/solana/{}\">View on DexScreener</a>",
+            {}",
             emoji, emoji,
             token_name,
             token_address,
             amount_sol,
             emoji, status, profit_loss, percentage,
             chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-            token_address
-        );
+            self.explorer_links_html(token_address)
+        ));
 
         self.send_message(&message).await
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template_values_are_html_escaped() {
+        let rendered = render_template("Bought {name} ({mint})", &[
+            ("name", "<b>PEPE</b> & \"friends\"".to_string()),
+            ("mint", "TokenMint111".to_string()),
+        ]);
+        assert_eq!(rendered, "Bought &lt;b&gt;PEPE&lt;/b&gt; &amp; &quot;friends&quot; (TokenMint111)");
+    }
+
+    #[test]
+    fn template_links_stay_markup() {
+        let links = render_explorer_links(&parse_explorer_links(DEFAULT_EXPLORER_LINKS), "TokenMint111");
+        let rendered = render_template("{links}", &[("links", links.clone())]);
+        assert_eq!(rendered, links);
+        assert!(rendered.contains("<a href="));
+    }
+
+    #[test]
+    fn unknown_placeholders_are_reported() {
+        assert_eq!(unknown_placeholders("{mint} {pnl}", TemplateKind::Buy), vec!["pnl".to_string()]);
+        assert!(unknown_placeholders("{mint} {pnl}", TemplateKind::Sell).is_empty());
+    }
+
+    #[test]
+    fn configured_explorer_links_are_rendered_and_escaped() {
+        let links = parse_explorer_links("Solscan:https://solscan.io/token/{mint},B<ird>eye:https://birdeye.so/token/{mint}?chain=solana&tab=1");
+        let rendered = render_explorer_links(&links, "TokenMint111");
+        assert_eq!(rendered, "🔗 <a href=\"https://solscan.io/token/TokenMint111\">View on Solscan</a> | \
+            <a href=\"https://birdeye.so/token/TokenMint111?chain=solana&amp;tab=1\">View on B&lt;ird&gt;eye</a>");
+        assert!(render_explorer_links(&[], "TokenMint111").is_empty());
+    }
+
+    #[test]
+    fn error_alerts_escape_urls_in_the_error() {
+        let message = error_alert_message("quote", "error sending request for url (https://lite-api.jup.ag/v6/quote?inputMint=a&outputMint=<b>)", 0);
+        assert_eq!(message, "⚠️ <b>ERROR</b> (quote)\nerror sending request for url \
+            (https://lite-api.jup.ag/v6/quote?inputMint=a&amp;outputMint=&lt;b&gt;)");
+        assert!(error_alert_message("R&D", "boom", 3).starts_with("⚠️ <b>ERROR</b> (R&amp;D)\nboom\n(suppressed 3 repeats)"));
+    }
+}
@@ -0,0 +1,163 @@
+// Trade Journal - pending buy intents persisted so a crash mid-trade can be reconciled on restart
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use anyhow::Result;
+
+/// Seconds after which an unseen transaction's blockhash has certainly expired
+pub const BLOCKHASH_EXPIRY_SECS: u64 = 150;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingTrade {
+    pub token_address: String,
+    pub sol_amount: f64,
+    pub expected_tokens: u64,
+    pub signature: Option<String>, // Set once signed, before the transaction is sent
+    pub created_at_unix_secs: u64,
+}
+
+/// What a restart should do with a journaled trade
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReconcileAction {
+    Confirm, // Landed on-chain - restore it as a position
+    Discard, // Never sent, failed, or expired
+    Keep,    // Still unknown - check again next start
+}
+
+/// Decide a journaled trade's fate from its on-chain status (None = not found, Some(ok) = executed)
+pub fn reconcile_action(trade: &PendingTrade, status: Option<bool>, now_unix_secs: u64) -> ReconcileAction {
+    if trade.signature.is_none() {
+        return ReconcileAction::Discard;
+    }
+    match status {
+        Some(true) => ReconcileAction::Confirm,
+        Some(false) => ReconcileAction::Discard,
+        None if now_unix_secs.saturating_sub(trade.created_at_unix_secs) >= BLOCKHASH_EXPIRY_SECS => ReconcileAction::Discard,
+        None => ReconcileAction::Keep,
+    }
+}
+
+/// Cheap to clone - every operation re-reads and rewrites the file so clones never diverge
+#[derive(Debug, Clone)]
+pub struct TradeJournal {
+    file_path: String,
+}
+
+impl TradeJournal {
+    pub fn new(file_path: &str) -> Self {
+        Self { file_path: file_path.to_string() }
+    }
+
+    /// All journaled trades, keyed by mint
+    pub fn load(&self) -> Result<HashMap<String, PendingTrade>> {
+        if !Path::new(&self.file_path).exists() {
+            return Ok(HashMap::new());
+        }
+        let content = std::fs::read_to_string(&self.file_path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Record a buy we are about to send
+    pub fn record_intent(&self, trade: PendingTrade) -> Result<()> {
+        let mut trades = self.load()?;
+        trades.insert(trade.token_address.clone(), trade);
+        self.save(&trades)
+    }
+
+    /// Attach the signature of the signed transaction before it goes out
+    pub fn attach_signature(&self, token_address: &str, signature: &str) -> Result<()> {
+        let mut trades = self.load()?;
+        if let Some(trade) = trades.get_mut(token_address) {
+            trade.signature = Some(signature.to_string());
+        }
+        self.save(&trades)
+    }
+
+    /// Drop a trade once it is recorded as a position or known not to have landed
+    pub fn clear(&self, token_address: &str) -> Result<()> {
+        let mut trades = self.load()?;
+        if trades.remove(token_address).is_some() {
+            self.save(&trades)?;
+        }
+        Ok(())
+    }
+
+    fn save(&self, trades: &HashMap<String, PendingTrade>) -> Result<()> {
+        if let Some(parent) = Path::new(&self.file_path).parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(trades)?;
+        std::fs::write(&self.file_path, json)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINT: &str = "TokenMint1111111111111111111111111111111111";
+    const SENT_AT: u64 = 1_800_000_000;
+
+    fn journal(name: &str) -> TradeJournal {
+        let path = std::env::temp_dir().join(format!("trade_journal_{}_{}.json", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        TradeJournal::new(&path.to_string_lossy())
+    }
+
+    fn intent() -> PendingTrade {
+        PendingTrade {
+            token_address: MINT.to_string(),
+            sol_amount: 0.1,
+            expected_tokens: 5_000_000,
+            signature: None,
+            created_at_unix_secs: SENT_AT,
+        }
+    }
+
+    /// What a restart finds in the journal and decides, given the transaction's on-chain status
+    fn restart(journal: &TradeJournal, status: Option<bool>, now_unix_secs: u64) -> Option<ReconcileAction> {
+        journal.load().unwrap().get(MINT).map(|trade| reconcile_action(trade, status, now_unix_secs))
+    }
+
+    #[test]
+    fn crash_before_signing_discards_the_intent() {
+        let journal = journal("unsigned");
+        journal.record_intent(intent()).unwrap();
+        assert_eq!(restart(&journal, None, SENT_AT + 5), Some(ReconcileAction::Discard));
+    }
+
+    #[test]
+    fn crash_after_signing_waits_for_the_blockhash_to_expire() {
+        let journal = journal("signed");
+        journal.record_intent(intent()).unwrap();
+        journal.attach_signature(MINT, "5igSig").unwrap();
+        assert_eq!(journal.load().unwrap()[MINT].signature.as_deref(), Some("5igSig"));
+        // Not seen on-chain yet: it may still land
+        assert_eq!(restart(&journal, None, SENT_AT + 30), Some(ReconcileAction::Keep));
+        assert_eq!(restart(&journal, None, SENT_AT + BLOCKHASH_EXPIRY_SECS), Some(ReconcileAction::Discard));
+    }
+
+    #[test]
+    fn crash_after_sending_confirms_a_landed_buy_and_discards_a_failed_one() {
+        let journal = journal("sent");
+        journal.record_intent(intent()).unwrap();
+        journal.attach_signature(MINT, "5igSig").unwrap();
+        assert_eq!(restart(&journal, Some(true), SENT_AT + 5), Some(ReconcileAction::Confirm));
+        assert_eq!(restart(&journal, Some(false), SENT_AT + 5), Some(ReconcileAction::Discard));
+    }
+
+    #[test]
+    fn crash_after_tracking_the_position_leaves_nothing_to_reconcile() {
+        let journal = journal("tracked");
+        journal.record_intent(intent()).unwrap();
+        journal.attach_signature(MINT, "5igSig").unwrap();
+        journal.clear(MINT).unwrap();
+        assert_eq!(restart(&journal, Some(true), SENT_AT + 5), None);
+    }
+
+    #[test]
+    fn missing_journal_loads_empty() {
+        assert!(journal("missing").load().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+// Trading Schedule - hours of the day new buys are allowed (TRADING_HOURS). Positions are
+// managed around the clock; only buying stops outside the windows.
+
+/// One allowed window, `start` up to (not including) `end`, in whole hours 0-24. A window whose
+/// end is before its start wraps past midnight (22-6 = 22:00 to 06:00).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HourRange {
+    pub start: u8,
+    pub end: u8,
+}
+
+impl HourRange {
+    pub fn contains(&self, hour: u8) -> bool {
+        if self.start <= self.end {
+            (self.start..self.end).contains(&hour)
+        } else {
+            hour >= self.start || hour < self.end
+        }
+    }
+}
+
+/// Parse "9-17,20-23" into windows; empty = no schedule (always open)
+pub fn parse_trading_hours(spec: &str) -> Result<Vec<HourRange>, String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (start, end) = entry.split_once('-')
+                .ok_or_else(|| format!("'{}' is not a start-end hour range", entry))?;
+            let hour = |value: &str| value.trim().parse::<u8>().ok().filter(|&hour| hour <= 24)
+                .ok_or_else(|| format!("'{}' in '{}' is not an hour from 0 to 24", value.trim(), entry));
+            let (start, end) = (hour(start)?, hour(end)?);
+            if start == end {
+                return Err(format!("'{}' is an empty range", entry));
+            }
+            Ok(HourRange { start: start % 24, end })
+        })
+        .collect()
+}
+
+/// Hour of the day (0-23) at `unix_secs` in the zone `utc_offset_hours` from UTC
+pub fn local_hour(unix_secs: u64, utc_offset_hours: f64) -> u8 {
+    let local_secs = unix_secs as i64 + (utc_offset_hours * 3600.0).round() as i64;
+    (local_secs.rem_euclid(86_400) / 3600) as u8
+}
+
+/// Whether buys are allowed at `unix_secs`. No windows = always.
+pub fn within_trading_hours(windows: &[HourRange], unix_secs: u64, utc_offset_hours: f64) -> bool {
+    if windows.is_empty() {
+        return true;
+    }
+    let hour = local_hour(unix_secs, utc_offset_hours);
+    windows.iter().any(|window| window.contains(hour))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unix time at `hour`:`minute` UTC on some day
+    fn at(hour: u64, minute: u64) -> u64 {
+        1_700_006_400 + hour * 3600 + minute * 60 // 2023-11-15 00:00 UTC
+    }
+
+    #[test]
+    fn parses_ranges_and_an_empty_schedule() {
+        assert_eq!(parse_trading_hours("").unwrap(), Vec::new());
+        assert_eq!(parse_trading_hours(" 9-17, 22-2 ").unwrap(), vec![HourRange { start: 9, end: 17 }, HourRange { start: 22, end: 2 }]);
+        assert_eq!(parse_trading_hours("24-6").unwrap(), vec![HourRange { start: 0, end: 6 }]);
+    }
+
+    #[test]
+    fn rejects_malformed_ranges() {
+        assert!(parse_trading_hours("9").is_err());
+        assert!(parse_trading_hours("9-25").is_err());
+        assert!(parse_trading_hours("9-9").is_err());
+        assert!(parse_trading_hours("nine-17").is_err());
+    }
+
+    #[test]
+    fn buys_are_allowed_inside_the_window_and_blocked_outside() {
+        let windows = parse_trading_hours("9-17").unwrap();
+        assert!(!within_trading_hours(&windows, at(8, 59), 0.0));
+        assert!(within_trading_hours(&windows, at(9, 0), 0.0));
+        assert!(within_trading_hours(&windows, at(16, 59), 0.0));
+        assert!(!within_trading_hours(&windows, at(17, 0), 0.0)); // End hour is exclusive
+    }
+
+    #[test]
+    fn windows_wrap_past_midnight() {
+        let windows = parse_trading_hours("22-2").unwrap();
+        assert!(within_trading_hours(&windows, at(23, 30), 0.0));
+        assert!(within_trading_hours(&windows, at(1, 59), 0.0));
+        assert!(!within_trading_hours(&windows, at(2, 0), 0.0));
+        assert!(!within_trading_hours(&windows, at(12, 0), 0.0));
+    }
+
+    #[test]
+    fn offset_reads_the_windows_in_local_time() {
+        let windows = parse_trading_hours("9-17").unwrap();
+        // 14:00 UTC is 09:00 at UTC-5 and 19:30 at UTC+5.5
+        assert!(within_trading_hours(&windows, at(14, 0), -5.0));
+        assert!(!within_trading_hours(&windows, at(14, 0), 5.5));
+        assert_eq!(local_hour(at(0, 30), -5.0), 19); // Wraps back to the previous day
+    }
+
+    #[test]
+    fn no_schedule_always_trades() {
+        assert!(within_trading_hours(&[], at(3, 0), 0.0));
+    }
+}
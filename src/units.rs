@@ -0,0 +1,77 @@
+// Units - every USD <-> SOL <-> lamports conversion goes through here, so the rate and the
+// rounding are decided once instead of at each call site
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// SOL price (USD) used where no live price is available: turning DexScreener/Birdeye USD
+/// liquidity into SOL, the USD liquidity floor, and P&L before the first SOL price fetch.
+/// Replaces the old per-site rates (the liquidity floor used 200, the P&L fallback 150).
+pub const FALLBACK_SOL_PRICE_USD: f64 = 235.0;
+
+/// SOL to lamports, rounded to the nearest lamport so float error can't shave one off.
+/// Negative or non-finite amounts are zero.
+pub fn sol_to_lamports(sol: f64) -> u64 {
+    if !sol.is_finite() || sol <= 0.0 {
+        return 0;
+    }
+    (sol * LAMPORTS_PER_SOL as f64).round() as u64
+}
+
+pub fn lamports_to_sol(lamports: u64) -> f64 {
+    lamports as f64 / LAMPORTS_PER_SOL as f64
+}
+
+/// USD amount in SOL at `sol_price_usd`; zero when the price is unknown (0 or negative)
+pub fn usd_to_sol(usd: f64, sol_price_usd: f64) -> f64 {
+    if sol_price_usd <= 0.0 {
+        return 0.0;
+    }
+    usd / sol_price_usd
+}
+
+/// SOL amount in USD at `sol_price_usd`
+pub fn sol_to_usd(sol: f64, sol_price_usd: f64) -> f64 {
+    sol * sol_price_usd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sol_to_lamports_rounds_to_the_nearest_lamport() {
+        assert_eq!(sol_to_lamports(1.0), 1_000_000_000);
+        assert_eq!(sol_to_lamports(0.1 + 0.2), 300_000_000);
+        // 0.001971831 * 1e9 is 1971830.9999999998 in floating point: truncating lost a lamport
+        assert_eq!(sol_to_lamports(0.001_971_831), 1_971_831);
+        assert_eq!(sol_to_lamports(0.000_000_000_4), 0);
+        assert_eq!(sol_to_lamports(0.000_000_000_6), 1);
+        assert_eq!(sol_to_lamports(0.000_000_001_5), 2); // Half a lamport rounds away from zero
+    }
+
+    #[test]
+    fn sol_to_lamports_treats_invalid_amounts_as_zero() {
+        assert_eq!(sol_to_lamports(-0.5), 0);
+        assert_eq!(sol_to_lamports(f64::NAN), 0);
+        assert_eq!(sol_to_lamports(f64::INFINITY), 0);
+    }
+
+    #[test]
+    fn lamports_round_trip_through_sol() {
+        assert_eq!(lamports_to_sol(1), 0.000_000_001);
+        assert_eq!(lamports_to_sol(2_500_000_000), 2.5);
+        for lamports in [0, 1, 999_999_999, 1_000_000_001, 123_456_789_012] {
+            assert_eq!(sol_to_lamports(lamports_to_sol(lamports)), lamports);
+        }
+    }
+
+    #[test]
+    fn usd_and_sol_convert_at_the_given_price() {
+        assert_eq!(usd_to_sol(470.0, 235.0), 2.0);
+        assert_eq!(sol_to_usd(2.0, 235.0), 470.0);
+        assert_eq!(usd_to_sol(470.0, 0.0), 0.0);
+        assert_eq!(usd_to_sol(470.0, -1.0), 0.0);
+        // The liquidity floor and the liquidity conversion agree at the fallback price
+        let floor_usd = sol_to_usd(10.0, FALLBACK_SOL_PRICE_USD);
+        assert!((usd_to_sol(floor_usd, FALLBACK_SOL_PRICE_USD) - 10.0).abs() < 1e-12);
+    }
+}
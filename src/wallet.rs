@@ -7,26 +7,115 @@ use solana_sdk::{
 };
 use solana_client::rpc_client::RpcClient;
 use std::env;
+use anyhow::anyhow;
+use crate::rpc_limiter::RpcRateLimiter;
+use crate::units::lamports_to_sol;
+
+/// Bytes in a full Solana keypair (32-byte secret + 32-byte public key)
+const KEYPAIR_LENGTH: usize = 64;
+
+/// Parse a private key in any of the formats wallets export: a base58 string (Phantom,
+/// Solflare), a JSON byte array (solana-keygen id.json) or hex (optionally 0x-prefixed).
+/// Errors name the detected format and never echo the key itself.
+pub fn parse_keypair(input: &str) -> Result<Keypair, anyhow::Error> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(anyhow!("Private key is empty - expected base58, a JSON byte array or hex"));
+    }
+
+    let (format, bytes) = if input.starts_with('[') {
+        let bytes: Vec<u8> = serde_json::from_str(input)
+            .map_err(|e| anyhow!("Private key looks like a JSON byte array but doesn't parse: {}", e))?;
+        ("JSON byte array", bytes)
+    } else if let Some(hex) = hex_key(input) {
+        ("hex", decode_hex(hex).ok_or_else(|| anyhow!("Private key looks like hex but has an odd number of digits"))?)
+    } else {
+        let bytes = bs58::decode(input)
+            .into_vec()
+            .map_err(|e| anyhow!("Private key is not valid base58 (or a JSON byte array / hex): {}", e))?;
+        ("base58", bytes)
+    };
+
+    if bytes.len() != KEYPAIR_LENGTH {
+        return Err(anyhow!("Private key ({}) decodes to {} bytes - expected {}", format, bytes.len(), KEYPAIR_LENGTH));
+    }
+    Keypair::from_bytes(&bytes).map_err(|e| anyhow!("Private key ({}) is not a valid keypair: {}", format, e))
+}
+
+/// The hex digits of a hex-encoded key, or None if `input` isn't hex. Without a 0x prefix only
+/// a full-length key counts, since shorter all-hex strings are also valid base58.
+fn hex_key(input: &str) -> Option<&str> {
+    let (digits, prefixed) = match input.strip_prefix("0x").or_else(|| input.strip_prefix("0X")) {
+        Some(digits) => (digits, true),
+        None => (input, false),
+    };
+    let is_hex = !digits.is_empty() && digits.chars().all(|c| c.is_ascii_hexdigit());
+    (is_hex && (prefixed || digits.len() == KEYPAIR_LENGTH * 2)).then_some(digits)
+}
+
+/// Commitment level from its name (processed, confirmed or finalized), case-insensitive
+pub fn parse_commitment(level: &str) -> Option<CommitmentConfig> {
+    match level.trim().to_lowercase().as_str() {
+        "processed" => Some(CommitmentConfig::processed()),
+        "confirmed" => Some(CommitmentConfig::confirmed()),
+        "finalized" => Some(CommitmentConfig::finalized()),
+        _ => None,
+    }
+}
+
+fn decode_hex(digits: &str) -> Option<Vec<u8>> {
+    if !digits.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).ok())
+        .collect()
+}
 
 pub struct SolanaWallet {
     pub keypair: Keypair,
     pub rpc_client: RpcClient,
     pub commitment: CommitmentConfig,
+    rpc_limiter: RpcRateLimiter,
 }
 
 impl SolanaWallet {
     /// Create a new wallet from private key
     pub fn new(private_key: &str) -> Result<Self, anyhow::Error> {
-        let keypair = solana_sdk::signature::Keypair::from_base58_string(private_key);
+        let keypair = parse_keypair(private_key)?;
         let rpc_client = solana_client::rpc_client::RpcClient::new("https://api.mainnet-beta.solana.com".to_string());
         
         Ok(Self {
             keypair,
             rpc_client,
             commitment: CommitmentConfig::confirmed(),
+            rpc_limiter: RpcRateLimiter::unlimited(),
         })
     }
 
+    /// Share an RPC rate limiter with the other components using the same provider
+    pub fn set_rpc_limiter(&mut self, rpc_limiter: RpcRateLimiter) {
+        self.rpc_limiter = rpc_limiter;
+    }
+
+    /// Commitment balance reads default to (BALANCE_COMMITMENT)
+    pub fn set_commitment(&mut self, commitment: CommitmentConfig) {
+        self.commitment = commitment;
+    }
+
+    /// Get wallet SOL balance at the wallet's commitment
+    pub async fn get_sol_balance(&self) -> Result<f64, anyhow::Error> {
+        self.get_sol_balance_with_commitment(self.commitment).await
+    }
+
+    /// Get wallet SOL balance at an explicit commitment
+    pub async fn get_sol_balance_with_commitment(&self, commitment: CommitmentConfig) -> Result<f64, anyhow::Error> {
+        self.rpc_limiter.acquire().await;
+        let lamports = self.rpc_client.get_balance_with_commitment(&self.keypair.pubkey(), commitment)?.value;
+        Ok(lamports_to_sol(lamports))
+    }
+
     /// Create wallet from environment variables
     pub fn from_env() -> Result<Self, anyhow::Error> {
         let private_key = env::var("PRIVATE_KEY").map_err(|_| anyhow::anyhow!("PRIVATE_KEY not found in environment"))?;
@@ -42,4 +131,115 @@ impl SolanaWallet {
     pub fn get_pubkey(&self) -> Pubkey {
         self.keypair.pubkey()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn base58_keys_parse() {
+        let keypair = Keypair::new();
+        assert_eq!(parse_keypair(&keypair.to_base58_string()).unwrap().pubkey(), keypair.pubkey());
+    }
+
+    #[test]
+    fn json_byte_array_keys_parse() {
+        let keypair = Keypair::new();
+        let json = serde_json::to_string(&keypair.to_bytes().to_vec()).unwrap();
+        assert_eq!(parse_keypair(&format!("  {}\n", json)).unwrap().pubkey(), keypair.pubkey());
+    }
+
+    #[test]
+    fn hex_keys_parse_with_or_without_a_prefix() {
+        let keypair = Keypair::new();
+        let digits = hex(&keypair.to_bytes());
+        assert_eq!(parse_keypair(&digits).unwrap().pubkey(), keypair.pubkey());
+        assert_eq!(parse_keypair(&format!("0x{}", digits.to_uppercase())).unwrap().pubkey(), keypair.pubkey());
+    }
+
+    #[test]
+    fn malformed_keys_name_the_format_without_echoing_the_key() {
+        let err = parse_keypair("[1, 2, 3").unwrap_err().to_string();
+        assert!(err.starts_with("Private key looks like a JSON byte array"));
+        let err = parse_keypair("[1, 2, 3]").unwrap_err().to_string();
+        assert_eq!(err, "Private key (JSON byte array) decodes to 3 bytes - expected 64");
+        let err = parse_keypair("0xabc").unwrap_err().to_string();
+        assert_eq!(err, "Private key looks like hex but has an odd number of digits");
+        let err = parse_keypair("not-a-key-0OIl").unwrap_err().to_string();
+        assert!(err.starts_with("Private key is not valid base58"));
+        assert!(!err.contains("not-a-key"));
+        assert!(parse_keypair("   ").unwrap_err().to_string().starts_with("Private key is empty"));
+    }
+
+    #[test]
+    fn commitment_levels_parse_by_name() {
+        assert_eq!(parse_commitment("processed"), Some(CommitmentConfig::processed()));
+        assert_eq!(parse_commitment(" Confirmed "), Some(CommitmentConfig::confirmed()));
+        assert_eq!(parse_commitment("FINALIZED"), Some(CommitmentConfig::finalized()));
+        assert_eq!(parse_commitment("max"), None);
+        assert_eq!(parse_commitment(""), None);
+    }
+
+    /// A JSON-RPC endpoint answering every call with `result`, handing back each request body.
+    /// The client's own getVersion probe is answered and not recorded.
+    fn recording_rpc(result: serde_json::Value) -> (String, std::sync::mpsc::Receiver<serde_json::Value>) {
+        use std::io::{BufRead, BufReader, Read, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut reader = BufReader::new(stream.unwrap());
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                let version = request["method"] == "getVersion";
+                let result = if version { serde_json::json!({ "solana-core": "2.0.0" }) } else { result.clone() };
+                let response = serde_json::json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }).to_string();
+                write!(reader.get_mut(), "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.len(), response).unwrap();
+                if !version {
+                    sender.send(request).unwrap();
+                }
+            }
+        });
+        (url, receiver)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn balance_reads_pass_their_commitment_to_the_rpc() {
+        let balance = serde_json::json!({ "context": { "slot": 1 }, "value": 1_500_000_000u64 });
+        let (url, requests) = recording_rpc(balance);
+        let mut wallet = SolanaWallet::new(&Keypair::new().to_base58_string()).unwrap();
+        wallet.rpc_client = RpcClient::new(url);
+
+        // The configured default...
+        wallet.set_commitment(CommitmentConfig::processed());
+        assert_eq!(wallet.get_sol_balance().await.unwrap(), 1.5);
+        let request = requests.recv().unwrap();
+        assert_eq!(request["method"], "getBalance");
+        assert_eq!(request["params"][0], wallet.keypair.pubkey().to_string());
+        assert_eq!(request["params"][1]["commitment"], "processed");
+
+        // ...or an explicit level
+        wallet.get_sol_balance_with_commitment(CommitmentConfig::finalized()).await.unwrap();
+        assert_eq!(requests.recv().unwrap()["params"][1]["commitment"], "finalized");
+    }
+}
@@ -0,0 +1,247 @@
+// Generic outbound webhook: POSTs a JSON event for every buy, sell, write-off and rejection
+use anyhow::Result;
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime};
+use crate::health::unix_secs;
+use crate::pool_scanner::PositionKind;
+use crate::settings::BotSettings;
+use crate::storage::{TradeRecord, TradeSide};
+
+// Retry policy for webhook deliveries
+const MAX_SEND_ATTEMPTS: u32 = 3;
+const BASE_RETRY_DELAY_MS: u64 = 500;
+
+/// Bumped only when a field is removed or changes meaning; new optional fields keep the version
+pub const WEBHOOK_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEventType {
+    Buy,
+    Sell,
+    WriteOff, // Position abandoned as rugged; sol_amount is 0 and pnl_sol the full loss
+    Reject,   // Pool evaluated and not bought; reason says why
+}
+
+/// Body of every webhook POST. Stable schema (version 1):
+///
+/// ```json
+/// {"schema_version":1,"type":"buy","mint":"<base58>","sol_amount":0.1,"token_amount":123456,
+///  "pnl_sol":null,"reason":null,"timestamp":1760000000,"signature":"<base58 or null>","paper":false}
+/// ```
+///
+/// Amount fields are null when they don't apply (rejects carry only mint, reason and timestamp).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookEvent {
+    pub schema_version: u32,
+    #[serde(rename = "type")]
+    pub event_type: WebhookEventType,
+    pub mint: String,
+    pub sol_amount: Option<f64>,   // SOL spent (buy) or received (sell)
+    pub token_amount: Option<u64>, // Raw token units bought or sold
+    pub pnl_sol: Option<f64>,      // Realized profit/loss; sells and write-offs only
+    pub reason: Option<String>,    // Rejects only
+    pub timestamp: u64,            // Unix seconds
+    pub signature: Option<String>, // Transaction signature; null for paper trades
+    pub paper: bool,               // Paper or simulated trade that never touched the chain
+}
+
+impl WebhookEvent {
+    pub fn from_trade(trade: &TradeRecord) -> Self {
+        Self {
+            schema_version: WEBHOOK_SCHEMA_VERSION,
+            event_type: match trade.side {
+                TradeSide::Buy => WebhookEventType::Buy,
+                TradeSide::Sell => WebhookEventType::Sell,
+                TradeSide::WriteOff => WebhookEventType::WriteOff,
+            },
+            mint: trade.token_address.clone(),
+            sol_amount: Some(trade.sol_amount),
+            token_amount: Some(trade.token_amount),
+            pnl_sol: trade.pnl_sol,
+            reason: None,
+            timestamp: trade.timestamp_unix_secs,
+            signature: trade.signature.clone(),
+            paper: trade.kind != PositionKind::Real,
+        }
+    }
+
+    pub fn reject(mint: &str, reason: &str) -> Self {
+        Self {
+            schema_version: WEBHOOK_SCHEMA_VERSION,
+            event_type: WebhookEventType::Reject,
+            mint: mint.to_string(),
+            sol_amount: None,
+            token_amount: None,
+            pnl_sol: None,
+            reason: Some(reason.to_string()),
+            timestamp: unix_secs(SystemTime::now()),
+            signature: None,
+            paper: false,
+        }
+    }
+
+    /// A reject event for a scanner outcome that didn't buy ("skipped: ..." or "rejected: ...")
+    pub fn from_outcome(mint: &str, outcome: &str) -> Option<Self> {
+        ["skipped: ", "rejected: "].iter()
+            .find_map(|prefix| outcome.strip_prefix(prefix))
+            .map(|reason| Self::reject(mint, reason))
+    }
+}
+
+/// Non-2xx response from the webhook endpoint
+#[derive(Debug)]
+pub struct WebhookError {
+    pub status: StatusCode,
+    pub body: String,
+}
+
+impl WebhookError {
+    /// Rate limits and server errors are retried; anything else is a misconfigured endpoint
+    pub fn is_retryable(&self) -> bool {
+        self.status == StatusCode::TOO_MANY_REQUESTS
+            || self.status == StatusCode::REQUEST_TIMEOUT
+            || self.status.is_server_error()
+    }
+}
+
+impl std::fmt::Display for WebhookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Webhook error: {} - {}", self.status, self.body)
+    }
+}
+
+impl std::error::Error for WebhookError {}
+
+fn is_transient_error(error: &anyhow::Error) -> bool {
+    if let Some(webhook_error) = error.downcast_ref::<WebhookError>() {
+        return webhook_error.is_retryable();
+    }
+    error.downcast_ref::<reqwest::Error>().is_some()
+}
+
+#[derive(Clone)]
+pub struct WebhookNotifier {
+    client: Client,
+    url: String,
+    bearer_token: Option<String>,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            url: url.to_string(),
+            bearer_token: None,
+        }
+    }
+
+    /// Sent as `Authorization: Bearer <token>`
+    pub fn with_bearer_token(mut self, token: &str) -> Self {
+        self.bearer_token = Some(token.to_string());
+        self
+    }
+
+    /// None unless WEBHOOK_URL is set
+    pub fn from_settings(settings: &BotSettings) -> Option<Self> {
+        let monitoring = &settings.monitoring;
+        if monitoring.webhook_url.is_empty() {
+            return None;
+        }
+        let notifier = Self::new(&monitoring.webhook_url);
+        Some(if monitoring.webhook_bearer_token.is_empty() {
+            notifier
+        } else {
+            notifier.with_bearer_token(&monitoring.webhook_bearer_token)
+        })
+    }
+
+    /// POST the event, retrying network errors, 408, 429 and 5xx with exponential backoff
+    pub async fn send(&self, event: &WebhookEvent) -> Result<()> {
+        let mut attempt = 1;
+        loop {
+            match self.post(event).await {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt < MAX_SEND_ATTEMPTS && is_transient_error(&e) => {
+                    let delay = Duration::from_millis(BASE_RETRY_DELAY_MS * 2u64.pow(attempt - 1));
+                    println!("⚠️  Webhook delivery failed (attempt {}/{}), retrying in {:?}: {}", attempt, MAX_SEND_ATTEMPTS, delay, e);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Deliver in the background so a slow endpoint never holds up trading
+    pub fn dispatch(&self, event: WebhookEvent) {
+        let notifier = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = notifier.send(&event).await {
+                println!("⚠️  Webhook {:?} event for {} not delivered: {}", event.event_type, event.mint, e);
+            }
+        });
+    }
+
+    async fn post(&self, event: &WebhookEvent) -> Result<()> {
+        let mut request = self.client.post(&self.url).json(event);
+        if let Some(token) = &self.bearer_token {
+            request = request.bearer_auth(token);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(WebhookError { status, body }.into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buy_event_payload_shape() {
+        let trade = TradeRecord {
+            timestamp_unix_secs: 1_760_000_000,
+            token_address: "TokenMint111".to_string(),
+            side: TradeSide::Buy,
+            kind: PositionKind::Real,
+            sol_amount: 0.1,
+            token_amount: 123_456,
+            pnl_sol: None,
+            signature: Some("sig111".to_string()),
+        };
+        let payload = serde_json::to_value(WebhookEvent::from_trade(&trade)).unwrap();
+        assert_eq!(payload, serde_json::json!({
+            "schema_version": 1,
+            "type": "buy",
+            "mint": "TokenMint111",
+            "sol_amount": 0.1,
+            "token_amount": 123456,
+            "pnl_sol": null,
+            "reason": null,
+            "timestamp": 1760000000,
+            "signature": "sig111",
+            "paper": false,
+        }));
+    }
+
+    #[test]
+    fn skips_and_rejections_become_reject_events() {
+        let skipped = WebhookEvent::from_outcome("mintA", "skipped: liquidity 2.0 SOL").unwrap();
+        assert_eq!(skipped.event_type, WebhookEventType::Reject);
+        assert_eq!(skipped.reason.as_deref(), Some("liquidity 2.0 SOL"));
+
+        let rejected = WebhookEvent::from_outcome("mintB", "rejected: security score 40/100 (needs 70)").unwrap();
+        assert_eq!(rejected.reason.as_deref(), Some("security score 40/100 (needs 70)"));
+
+        assert!(WebhookEvent::from_outcome("mintC", "bought 0.1000 SOL").is_none());
+    }
+}
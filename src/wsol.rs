@@ -0,0 +1,150 @@
+// Persistent Wrapped SOL - keep one WSOL account funded instead of wrapping/unwrapping on every swap
+use crate::lamports::Lamports;
+
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program,
+};
+
+pub const WRAPPED_SOL_MINT: Pubkey = solana_sdk::pubkey!("So11111111111111111111111111111111111111112");
+const TOKEN_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+// SPL Token instruction tags
+const CLOSE_ACCOUNT: u8 = 9;
+const SYNC_NATIVE: u8 = 17;
+// Associated Token Account instruction tag
+const CREATE_IDEMPOTENT: u8 = 1;
+
+/// The owner's WSOL associated token account - the one Jupiter swaps from and into
+/// when wrapAndUnwrapSol is off
+pub fn wsol_account(owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), TOKEN_PROGRAM_ID.as_ref(), WRAPPED_SOL_MINT.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    ).0
+}
+
+/// Token amount of an SPL token account (u64 at bytes 64..72 of the account data).
+/// For WSOL this is the wrapped balance, excluding the account's rent.
+pub fn token_account_amount(data: &[u8]) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(64..72)?.try_into().ok()?))
+}
+
+/// How much to wrap before a buy of `needed`: nothing if the WSOL account already covers it,
+/// otherwise at least the shortfall, rounded up to a `batch` so small buys don't each top up.
+/// Never wraps more than `available` (native SOL above the reserve); None = can't cover the buy.
+pub fn wsol_top_up(wsol_balance: Lamports, needed: Lamports, batch: Lamports, available: Lamports) -> Option<Lamports> {
+    if wsol_balance >= needed {
+        return Some(Lamports::ZERO);
+    }
+    let shortfall = needed.saturating_sub(wsol_balance);
+    if available < shortfall {
+        return None;
+    }
+    Some(shortfall.max(batch).min(available))
+}
+
+/// Instructions wrapping `amount` into the owner's WSOL account, creating it if needed
+pub fn wrap_instructions(owner: &Pubkey, amount: Lamports) -> Vec<Instruction> {
+    let account = wsol_account(owner);
+    vec![
+        Instruction::new_with_bytes(
+            ASSOCIATED_TOKEN_PROGRAM_ID,
+            &[CREATE_IDEMPOTENT],
+            vec![
+                AccountMeta::new(*owner, true),
+                AccountMeta::new(account, false),
+                AccountMeta::new_readonly(*owner, false),
+                AccountMeta::new_readonly(WRAPPED_SOL_MINT, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            ],
+        ),
+        solana_sdk::system_instruction::transfer(owner, &account, amount.as_u64()),
+        Instruction::new_with_bytes(TOKEN_PROGRAM_ID, &[SYNC_NATIVE], vec![AccountMeta::new(account, false)]),
+    ]
+}
+
+/// Instruction closing the owner's WSOL account - unwraps everything and refunds its rent
+pub fn unwrap_instruction(owner: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        TOKEN_PROGRAM_ID,
+        &[CLOSE_ACCOUNT],
+        vec![
+            AccountMeta::new(wsol_account(owner), false),
+            AccountMeta::new(*owner, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sol(amount: f64) -> Lamports {
+        Lamports::from_sol(amount)
+    }
+
+    #[test]
+    fn covered_buys_wrap_nothing() {
+        assert_eq!(wsol_top_up(sol(1.0), sol(0.25), sol(1.0), sol(5.0)), Some(Lamports::ZERO));
+        assert_eq!(wsol_top_up(sol(0.25), sol(0.25), sol(1.0), sol(5.0)), Some(Lamports::ZERO));
+    }
+
+    #[test]
+    fn shortfalls_wrap_a_whole_batch() {
+        // 0.1 wrapped, 0.25 needed: wrap the 1 SOL batch rather than the 0.15 shortfall
+        assert_eq!(wsol_top_up(sol(0.1), sol(0.25), sol(1.0), sol(5.0)), Some(sol(1.0)));
+        // A buy bigger than the batch wraps its whole shortfall
+        assert_eq!(wsol_top_up(sol(0.5), sol(3.0), sol(1.0), sol(5.0)), Some(sol(2.5)));
+    }
+
+    #[test]
+    fn top_ups_never_dig_into_the_reserve() {
+        // Only 0.4 SOL spare above the reserve: wrap that much, not the full batch
+        assert_eq!(wsol_top_up(sol(0.1), sol(0.25), sol(1.0), sol(0.4)), Some(sol(0.4)));
+        // Not even the shortfall fits
+        assert_eq!(wsol_top_up(sol(0.1), sol(0.25), sol(1.0), sol(0.1)), None);
+    }
+
+    #[test]
+    fn a_run_of_buys_tops_up_once_per_batch() {
+        let (buy, batch) = (sol(0.3), sol(1.0));
+        let (mut wsol, mut spare, mut top_ups) = (Lamports::ZERO, sol(10.0), 0);
+        for _ in 0..6 {
+            let top_up = wsol_top_up(wsol, buy, batch, spare).unwrap();
+            if top_up > Lamports::ZERO {
+                top_ups += 1;
+            }
+            wsol = wsol + top_up;
+            spare = spare.saturating_sub(top_up);
+            wsol = wsol.saturating_sub(buy); // The swap spends from the WSOL account
+        }
+        assert_eq!(top_ups, 2); // 6 x 0.3 SOL = 1.8 SOL: two 1 SOL batches
+        assert_eq!(wsol + spare, sol(10.0 - 1.8));
+    }
+
+    #[test]
+    fn wrapped_balance_is_read_from_the_token_account_data() {
+        let mut data = vec![0u8; 165];
+        data[64..72].copy_from_slice(&1_500_000u64.to_le_bytes());
+        assert_eq!(token_account_amount(&data), Some(1_500_000));
+        assert_eq!(token_account_amount(&data[..70]), None);
+    }
+
+    #[test]
+    fn wrap_funds_and_syncs_the_owners_wsol_account() {
+        let owner = Pubkey::new_unique();
+        let account = wsol_account(&owner);
+        let instructions = wrap_instructions(&owner, sol(1.0));
+        assert_eq!(instructions.len(), 3);
+        assert_eq!(instructions[1].accounts[1].pubkey, account);
+        assert_eq!(instructions[2].data, vec![SYNC_NATIVE]);
+        let unwrap = unwrap_instruction(&owner);
+        assert_eq!(unwrap.data, vec![CLOSE_ACCOUNT]);
+        assert_eq!(unwrap.accounts[0].pubkey, account);
+    }
+}